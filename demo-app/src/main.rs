@@ -1,6 +1,5 @@
-#![allow(dead_code)]
-
 use nexus_events::prelude::*;
+use nexus_events::demo_utils::{LogPanel, MetricsTracker};
 
 use crossterm::{
     event::{self, Event, KeyCode},
@@ -21,7 +20,6 @@ use std::{
     sync::{Arc, Mutex},
     thread,
     time::{Duration, Instant},
-    collections::HashMap,
 };
 
 ////////////////////////////////////////////////////////
@@ -66,111 +64,30 @@ enum MoveDirection {
     South, 
     East,
     West,
-    NorthEast,
-    NorthWest,
-    SouthEast,
-    SouthWest,
-}
-
-////////////////////////////////////////////////////////
-// 2) Performance Metrics
-////////////////////////////////////////////////////////
-#[derive(Debug)]
-struct MetricsTracker {
-    logs: Vec<String>,
-    events_processed: usize,
-    events_by_type: HashMap<String, usize>,
-    handler_calls: usize,
-    frame_times: Vec<Duration>,
-    max_frame_time: Duration,
-    min_frame_time: Duration,
-    start_time: Instant,
-    event_components: usize,
-}
-
-impl MetricsTracker {
-    fn new() -> Self {
-        Self {
-            logs: Vec::with_capacity(20),
-            events_processed: 0,
-            events_by_type: HashMap::new(),
-            handler_calls: 0,
-            frame_times: Vec::with_capacity(120),
-            max_frame_time: Duration::from_nanos(0),
-            min_frame_time: Duration::from_secs(1),
-            start_time: Instant::now(),
-            event_components: 0,
-        }
-    }
-    
-    fn record_event(&mut self, event_type: &str) {
-        self.events_processed += 1;
-        *self.events_by_type.entry(event_type.to_string()).or_insert(0) += 1;
-    }
-    
-    fn record_handler_call(&mut self) {
-        self.handler_calls += 1;
-    }
-    
-    fn add_frame_time(&mut self, dt: Duration) {
-        self.frame_times.push(dt);
-        if self.frame_times.len() > 120 {
-            self.frame_times.remove(0);
-        }
-        
-        if dt > self.max_frame_time {
-            self.max_frame_time = dt;
-        }
-        if dt < self.min_frame_time {
-            self.min_frame_time = dt;
-        }
-    }
-    
-    fn increment_components(&mut self, count: usize) {
-        self.event_components = count;
-    }
-
-    fn average_frame_time(&self) -> Duration {
-        if self.frame_times.is_empty() {
-            return Duration::from_nanos(0);
-        }
-        let total: Duration = self.frame_times.iter().sum();
-        total / self.frame_times.len() as u32
-    }
-    
-    fn push_log(&mut self, line: String) {
-        if self.logs.len() > 15 {
-            self.logs.remove(0);
-        }
-        self.logs.push(line);
-    }
 }
 
 ////////////////////////////////////////////////////////
-// 3) UI Model
+// 2) UI Model
 ////////////////////////////////////////////////////////
 #[derive(Debug)]
 struct UIModel {
-    game_logs: Vec<String>,
-    enemy_logs: Vec<String>,
-    event_logs: Vec<String>,
+    game_logs: LogPanel,
+    enemy_logs: LogPanel,
+    event_logs: LogPanel,
     metrics: Arc<Mutex<MetricsTracker>>,
 }
 
 impl UIModel {
     fn new(metrics: Arc<Mutex<MetricsTracker>>) -> Self {
         Self {
-            game_logs: Vec::new(),
-            enemy_logs: Vec::new(),
-            event_logs: Vec::new(),
+            game_logs: LogPanel::new(9),
+            enemy_logs: LogPanel::new(9),
+            event_logs: LogPanel::new(9),
             metrics,
         }
     }
 
     fn push_game_log(&mut self, line: String) {
-        if self.game_logs.len() > 8 {
-            self.game_logs.remove(0);
-        }
         self.game_logs.push(line.clone());
         
         if let Ok(mut metrics) = self.metrics.lock() {
@@ -179,9 +96,6 @@ impl UIModel {
     }
     
     fn push_enemy_log(&mut self, line: String) {
-        if self.enemy_logs.len() > 8 {
-            self.enemy_logs.remove(0);
-        }
         self.enemy_logs.push(line.clone());
         
         if let Ok(mut metrics) = self.metrics.lock() {
@@ -190,9 +104,6 @@ impl UIModel {
     }
     
     fn push_event_log(&mut self, line: String) {
-        if self.event_logs.len() > 8 {
-            self.event_logs.remove(0);
-        }
         self.event_logs.push(line.clone()); 
         
         if let Ok(mut metrics) = self.metrics.lock() {
@@ -201,42 +112,11 @@ impl UIModel {
     }
 
     fn draw<B: Backend>(&self, frame: &mut tui::Frame<B>) {
-        // Get performance metrics
-        let runtime;
-        let fps;
-        let avg_frame_ms;
-        let min_frame_ms;
-        let max_frame_ms;
-        let events_per_sec;
-        let handlers_per_sec;
-        let event_total;
-        let handler_total;
-        let evented_components;
-        
-        if let Ok(metrics) = self.metrics.lock() {
-            runtime = metrics.start_time.elapsed().as_secs_f32();
-            fps = 1.0 / metrics.average_frame_time().as_secs_f32().max(0.000001);
-            avg_frame_ms = metrics.average_frame_time().as_micros() as f32 / 1000.0;
-            min_frame_ms = metrics.min_frame_time.as_micros() as f32 / 1000.0;
-            max_frame_ms = metrics.max_frame_time.as_micros() as f32 / 1000.0;
-            events_per_sec = metrics.events_processed as f32 / runtime.max(0.001);
-            handlers_per_sec = metrics.handler_calls as f32 / runtime.max(0.001);
-            event_total = metrics.events_processed;
-            handler_total = metrics.handler_calls;
-            evented_components = metrics.event_components;
-        } else {
-            // Defaults if can't get lock
-            runtime = 0.0;
-            fps = 0.0;
-            avg_frame_ms = 0.0;
-            min_frame_ms = 0.0;
-            max_frame_ms = 0.0;
-            events_per_sec = 0.0;
-            handlers_per_sec = 0.0;
-            event_total = 0;
-            handler_total = 0;
-            evented_components = 0;
-        }
+        // Get performance metrics (zeroes if the lock is poisoned)
+        let [timing_line, events_line] = self.metrics.lock()
+            .map(|metrics| metrics.panel())
+            .unwrap_or_default()
+            .lines();
         
         // Create main layout
         let chunks = Layout::default()
@@ -254,14 +134,8 @@ impl UIModel {
             Spans::from(vec![
                 Span::styled("NEXUS EVENTS PERFORMANCE DEMO", Style::default().fg(Color::Yellow))
             ]),
-            Spans::from(vec![
-                Span::raw(format!("Runtime: {:.1}s | FPS: {:.1} | Frame: {:.2}ms (min: {:.2} max: {:.2})",
-                    runtime, fps, avg_frame_ms, min_frame_ms, max_frame_ms))
-            ]),
-            Spans::from(vec![
-                Span::raw(format!("Events: {} ({:.1}/sec) | Handler calls: {} ({:.1}/sec) | EventedComponents: {}",
-                    event_total, events_per_sec, handler_total, handlers_per_sec, evented_components))
-            ]),
+            Spans::from(vec![Span::raw(timing_line)]),
+            Spans::from(vec![Span::raw(events_line)]),
         ];
         
         let stats_para = Paragraph::new(stats_text)
@@ -288,6 +162,7 @@ impl UIModel {
         
         // Game logs
         let game_log_items: Vec<ListItem> = self.game_logs
+            .lines()
             .iter()
             .map(|s| ListItem::new(s.clone()))
             .collect();
@@ -298,6 +173,7 @@ impl UIModel {
         
         // Event logs
         let event_log_items: Vec<ListItem> = self.event_logs
+            .lines()
             .iter()
             .map(|s| ListItem::new(s.clone()))
             .collect();
@@ -308,6 +184,7 @@ impl UIModel {
         
         // Enemy logs
         let enemy_log_items: Vec<ListItem> = self.enemy_logs
+            .lines()
             .iter()
             .map(|s| ListItem::new(s.clone()))
             .collect();
@@ -330,7 +207,7 @@ impl UIModel {
 }
 
 ////////////////////////////////////////////////////////
-// 4) Game World
+// 3) Game World
 ////////////////////////////////////////////////////////
 #[event_component]
 struct World {
@@ -374,7 +251,7 @@ impl World {
         // Update the component count in metrics when adding an entity
         if let Ok(mut metrics) = self.metrics.lock() {
             // Base count is 2 (world + player) + current enemies count
-            metrics.increment_components(2 + self.entities_count);
+            metrics.set_components(2 + self.entities_count);
         }
     }
     
@@ -389,7 +266,7 @@ impl World {
         // Update the component count in metrics when removing an entity
         if let Ok(mut metrics) = self.metrics.lock() {
             // Base count is 2 (world + player) + current enemies count
-            metrics.increment_components(2 + self.entities_count);
+            metrics.set_components(2 + self.entities_count);
         }
     }
     
@@ -403,7 +280,7 @@ impl World {
     #[event_handler(GameTick)]
     fn on_tick(&mut self, evt: &GameTick) {
         if let Ok(mut ui) = self.ui.lock() {
-            if evt.frame_number % 60 == 0 {  // Only log every 60th frame to reduce spam
+            if evt.frame_number.is_multiple_of(60) {  // Only log every 60th frame to reduce spam
                 ui.push_game_log(format!("Tick frame #{} (dt={:.3}ms)", 
                     evt.frame_number, evt.dt * 1000.0));
             }
@@ -423,10 +300,6 @@ impl World {
             MoveDirection::South => "South",
             MoveDirection::East => "East",
             MoveDirection::West => "West",
-            MoveDirection::NorthEast => "North-East",
-            MoveDirection::NorthWest => "North-West",
-            MoveDirection::SouthEast => "South-East",
-            MoveDirection::SouthWest => "South-West",
         };
         
         if let Ok(mut ui) = self.ui.lock() {
@@ -457,7 +330,7 @@ impl World {
 }
 
 ////////////////////////////////////////////////////////
-// 5) Enemy
+// 4) Enemy
 ////////////////////////////////////////////////////////
 #[event_component]
 struct Enemy {
@@ -487,7 +360,7 @@ impl Enemy {
     #[event_handler(GameTick)]
     fn on_tick(&mut self, evt: &GameTick) {
         // Only log occasionally to reduce spam
-        if evt.frame_number % 60 == 0 {
+        if evt.frame_number.is_multiple_of(60) {
             if let Ok(mut ui) = self.ui.lock() {
                 ui.push_enemy_log(format!("({}) active at frame #{}", self.name, evt.frame_number));
             }
@@ -537,7 +410,7 @@ impl Enemy {
             if Instant::now() >= t {
                 // unsub if we had a handler
                 if let Some(hid) = self.tick_handler.take() {
-                    let _ = unsubscribe::<GameTick>(hid);
+                    unsubscribe::<GameTick>(hid);
                     if let Ok(mut ui) = self.ui.lock() {
                         ui.push_enemy_log(format!("({}) unsubscribed from Tick", self.name));
                     }
@@ -639,8 +512,8 @@ impl Enemy {
     #[event_handler(WorldUpdate)]
     fn on_world_update(&mut self, evt: &WorldUpdate) {
         if let Ok(mut ui) = self.ui.lock() {
-            ui.push_enemy_log(format!("({}) received world update: t={:.1}s", 
-                self.name, evt.elapsed_time));
+            ui.push_enemy_log(format!("({}) received world update: t={:.1}s, entities={}",
+                self.name, evt.elapsed_time, evt.active_entities));
         }
         
         // Record the event and handler call in metrics
@@ -667,14 +540,13 @@ impl Enemy {
 }
 
 ////////////////////////////////////////////////////////
-// 6) Player
+// 5) Player
 ////////////////////////////////////////////////////////
 
 #[event_component]
 struct Player {
     name: String,
     hp: i32,
-    position: (f32, f32),
     ui: Arc<Mutex<UIModel>>,
     metrics: Arc<Mutex<MetricsTracker>>,
 }
@@ -684,7 +556,6 @@ impl Player {
         Self {
             name: name.to_string(),
             hp: 100,
-            position: (0.0, 0.0),
             ui,
             metrics,
         }
@@ -705,7 +576,7 @@ impl Player {
         // Reset HP if defeated
         if self.hp <= 0 {
             if let Ok(mut ui) = self.ui.lock() {
-                ui.push_event_log(format!("Player was defeated! HP reset."));
+                ui.push_event_log("Player was defeated! HP reset.".to_string());
             }
             self.hp = 100;
         }
@@ -762,6 +633,35 @@ impl Player {
     }
 }
 
+////////////////////////////////////////////////////////
+// 6) Wiring
+////////////////////////////////////////////////////////
+
+/// Subscribes the handlers of the world, the player and every enemy on the
+/// global bus. Each enemy gets a set of its own, as a set subscribes a
+/// handler only once.
+fn subscribe_handlers(
+    world: &Arc<Mutex<World>>,
+    player: &Arc<Mutex<Player>>,
+    enemies: &[Arc<Mutex<Enemy>>],
+) -> EventResult<Vec<SubscriptionSet>> {
+    let bus = SharedEventBus::global();
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    World::subscribe_on_tick(world, &mut subscriptions)?;
+    World::subscribe_on_player_movement(world, &mut subscriptions)?;
+    Player::subscribe_on_attacked(player, &mut subscriptions)?;
+    let mut sets = vec![subscriptions];
+    for enemy in enemies {
+        let mut subscriptions = SubscriptionSet::new(&bus);
+        Enemy::subscribe_on_tick(enemy, &mut subscriptions)?;
+        Enemy::subscribe_on_targeted_attack(enemy, &mut subscriptions)?;
+        Enemy::subscribe_on_attacked(enemy, &mut subscriptions)?;
+        Enemy::subscribe_on_world_update(enemy, &mut subscriptions)?;
+        sets.push(subscriptions);
+    }
+    Ok(sets)
+}
+
 ////////////////////////////////////////////////////////
 fn main() -> io::Result<()> {
     eprintln!("Starting demo app...");
@@ -778,14 +678,17 @@ fn main() -> io::Result<()> {
     let metrics = Arc::new(Mutex::new(MetricsTracker::new()));
     let ui = Arc::new(Mutex::new(UIModel::new(metrics.clone())));
 
-    // Create game world and entities
-    let mut world = World::new(ui.clone(), metrics.clone());
-    let player = Player::new("Player1", ui.clone(), metrics.clone());
-    let mut enemies = Vec::new();
-    for i in 1..=3 {
-        enemies.push(Enemy::new(&format!("Enemy{}", i), ui.clone(), metrics.clone()));
-    }
-    world.entities_count = enemies.len();
+    // Create game world and entities, shared with their handlers
+    let world = Arc::new(Mutex::new(World::new(ui.clone(), metrics.clone())));
+    let player = Arc::new(Mutex::new(Player::new("Player1", ui.clone(), metrics.clone())));
+    let enemies: Vec<_> = (1..=3)
+        .map(|i| Arc::new(Mutex::new(Enemy::new(&format!("Enemy{}", i), ui.clone(), metrics.clone()))))
+        .collect();
+    if let Ok(mut world) = world.lock() {
+        world.entities_count = enemies.len();
+    }
+    // Kept until the end of `main`: dropping them unsubscribes the handlers.
+    let _subscriptions = subscribe_handlers(&world, &player, &enemies).map_err(io::Error::other)?;
     
     // Game loop variables
     let mut last_frame = Instant::now();
@@ -808,7 +711,7 @@ fn main() -> io::Result<()> {
         
         // Process input 
         if event::poll(std::time::Duration::from_millis(0))? {
-            if let Event::Key(key) = event::read()? {
+            if let (Event::Key(key), Ok(mut world), Ok(player)) = (event::read()?, world.lock(), player.lock()) {
                 match key.code {
                     KeyCode::Char('q') | KeyCode::Char('Q') => {
                         running = false;
@@ -820,12 +723,11 @@ fn main() -> io::Result<()> {
                         let critical = rng.gen_bool(0.2);
                         player.attack(player.name.clone(), damage, critical);
                     },
-                    KeyCode::Char('f') | KeyCode::Char('F') => {
+                    KeyCode::Char('f') | KeyCode::Char('F') if !enemies.is_empty() => {
                         // F: Targeted attack on a random enemy
-                        if !enemies.is_empty() {
-                            let mut rng = thread_rng();
-                            let target_idx = rng.gen_range(0..enemies.len());
-                            let target = &enemies[target_idx];
+                        let mut rng = thread_rng();
+                        let target_idx = rng.gen_range(0..enemies.len());
+                        if let Ok(target) = enemies[target_idx].lock() {
                             let damage = rng.gen_range(15..35);
                             player.target_attack(target.name.clone(), damage, player.name.clone());
                         }
@@ -864,19 +766,23 @@ fn main() -> io::Result<()> {
         }
         
         // Update game state 
-        world.update(dt_seconds);
+        if let Ok(mut world) = world.lock() {
+            world.update(dt_seconds);
+        }
         let tick = GameTick { dt: dt_seconds, frame_number };
         dispatch(tick);
         process_events();
 
         // Force enemies to do something visible occasionally
-        if frame_number % 30 == 0 {
+        if frame_number.is_multiple_of(30) {
             for enemy in &enemies {
                 // Trigger a random attack
                 let mut rng = thread_rng();
                 let damage = rng.gen_range(3..10);
                 let critical = rng.gen_bool(0.1);
-                enemy.send_attack(enemy.name.clone(), damage, critical);
+                if let Ok(enemy) = enemy.lock() {
+                    enemy.send_attack(enemy.name.clone(), damage, critical);
+                }
             }
         }
         
@@ -890,7 +796,7 @@ fn main() -> io::Result<()> {
             // Only count components once at startup, not every frame
             if frame_number == 1 {
                 // Use the already acquired lock instead of trying to get it again
-                m.increment_components(2 + enemies.len());
+                m.set_components(2 + enemies.len());
             }
         }
 
@@ -920,25 +826,25 @@ fn main() -> io::Result<()> {
     println!("========== FINAL STATISTICS ==========");
 
     if let Ok(m) = metrics.lock() {
-        let runtime = m.start_time.elapsed().as_secs_f32();
+        let runtime = m.runtime().as_secs_f32();
         println!("Runtime: {:.2} seconds", runtime);
         println!("Total frames: {}", frame_number);
         println!("Average FPS: {:.1}", frame_number as f32 / runtime);
         println!("Average frame time: {:.2}ms", m.average_frame_time().as_micros() as f32 / 1000.0);
-        println!("Min frame time: {:.2}ms", m.min_frame_time.as_micros() as f32 / 1000.0);
-        println!("Max frame time: {:.2}ms", m.max_frame_time.as_micros() as f32 / 1000.0);
+        println!("Min frame time: {:.2}ms", m.min_frame_time().as_micros() as f32 / 1000.0);
+        println!("Max frame time: {:.2}ms", m.max_frame_time().as_micros() as f32 / 1000.0);
         println!("\nEVENT STATISTICS:");
-        println!("Total events processed: {}", m.events_processed);
-        println!("Events per second: {:.1}", m.events_processed as f32 / runtime);
-        println!("Total handler calls: {}", m.handler_calls);
-        println!("Handler calls per second: {:.1}", m.handler_calls as f32 / runtime);
+        println!("Total events processed: {}", m.events_processed());
+        println!("Events per second: {:.1}", m.events_processed() as f32 / runtime);
+        println!("Total handler calls: {}", m.handler_calls());
+        println!("Handler calls per second: {:.1}", m.handler_calls() as f32 / runtime);
         
         println!("\nEVENTS BY TYPE:");
-        for (event_type, count) in &m.events_by_type {
+        for (event_type, count) in m.events_by_type() {
             println!("  {}: {} ({:.1}%)", 
                      event_type, 
                      count, 
-                     *count as f32 / m.events_processed as f32 * 100.0);
+                     *count as f32 / m.events_processed() as f32 * 100.0);
         }
     }
     
@@ -1,7 +1,9 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, ItemStruct, Fields, ItemFn, Type};
 
 /// Marks a struct so users can put `#[event_component]` above it.
@@ -25,70 +27,1236 @@ pub fn event_component(_attrs: TokenStream, input: TokenStream) -> TokenStream {
     TokenStream::from(quote! { #ast })
 }
 
-/// Marks a method as an event handler. On the first call,
-/// it uses a local `static ONCE: Once` to do the subscription.
+/// Field names given in `#[event_subscriber(...)]`.
+struct SubscriberFields {
+    id: Option<syn::LitStr>,
+    active: Option<syn::LitStr>,
+    subscriptions: Option<syn::LitStr>,
+    skip_active: bool,
+    // `lifecycle`, and the method it calls to register handlers.
+    lifecycle: bool,
+    register: Option<syn::LitStr>,
+    // `emitter`: derive `EventEmitter` as well.
+    emitter: bool,
+}
+
+impl SubscriberFields {
+    fn from_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut fields = SubscriberFields {
+            id: None,
+            active: None,
+            subscriptions: None,
+            skip_active: false,
+            lifecycle: false,
+            register: None,
+            emitter: false,
+        };
+        for attr in attrs.iter().filter(|a| a.path.is_ident("event_subscriber")) {
+            let list = match attr.parse_meta()? {
+                syn::Meta::List(list) => list,
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        "expected `#[event_subscriber(id = \"...\", active = \"...\", subscriptions = \"...\")]`"
+                    ));
+                }
+            };
+            for nested in list.nested {
+                match nested {
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip_active") => {
+                        fields.skip_active = true;
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("lifecycle") => {
+                        fields.lifecycle = true;
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("emitter") => {
+                        fields.emitter = true;
+                    }
+                    syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) => {
+                        let slot = if nv.path.is_ident("id") {
+                            &mut fields.id
+                        } else if nv.path.is_ident("active") {
+                            &mut fields.active
+                        } else if nv.path.is_ident("subscriptions") {
+                            &mut fields.subscriptions
+                        } else if nv.path.is_ident("register") {
+                            &mut fields.register
+                        } else {
+                            return Err(syn::Error::new_spanned(
+                                nv.path,
+                                "expected `id`, `active`, `subscriptions`, `skip_active`, `lifecycle`, `register` or `emitter`"
+                            ));
+                        };
+                        let syn::Lit::Str(name) = nv.lit else {
+                            return Err(syn::Error::new_spanned(nv.lit, "expected the field name as a string"));
+                        };
+                        if slot.replace(name.clone()).is_some() {
+                            return Err(syn::Error::new_spanned(name, "field given twice"));
+                        }
+                    }
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            other,
+                            "expected `id = \"...\"`, `active = \"...\"`, `subscriptions = \"...\"`, `skip_active`, `lifecycle`, `register = \"...\"` or `emitter`"
+                        ));
+                    }
+                }
+            }
+        }
+        if let (true, Some(active)) = (fields.skip_active, &fields.active) {
+            return Err(syn::Error::new_spanned(active, "`skip_active` and `active = ...` contradict each other"));
+        }
+        if let (false, Some(register)) = (fields.lifecycle, &fields.register) {
+            return Err(syn::Error::new_spanned(register, "`register = ...` is only used with `lifecycle`"));
+        }
+        Ok(fields)
+    }
+}
+
+/// Finds the field playing `role`, either under its default name or the one
+/// mapped with `#[event_subscriber(role = "...")]`.
+fn subscriber_field(
+    ast: &syn::DeriveInput,
+    fields: &syn::FieldsNamed,
+    role: &str,
+    mapped: &Option<syn::LitStr>,
+) -> syn::Result<syn::Ident> {
+    let wanted = mapped.as_ref().map_or_else(|| role.to_string(), |m| m.value());
+    if let Some(ident) = fields.named.iter().filter_map(|f| f.ident.as_ref()).find(|i| **i == wanted) {
+        return Ok(ident.clone());
+    }
+    Err(match mapped {
+        Some(m) => syn::Error::new_spanned(m, format!("`{}` has no field `{}`", ast.ident, wanted)),
+        None => syn::Error::new_spanned(
+            &ast.ident,
+            format!(
+                "`{}` has no field `{}`; name the field to use with `#[event_subscriber({} = \"...\")]`",
+                ast.ident, role, role
+            )
+        ),
+    })
+}
+
+/// Checks that `field` is declared as `expected` (judged by the last path
+/// segment, so `nexus_events::core::SubscriptionSet` is fine too).
+fn expect_field_type(fields: &syn::FieldsNamed, field: &syn::Ident, expected: &str) -> syn::Result<()> {
+    let ty = &fields.named.iter().find(|f| f.ident.as_ref() == Some(field)).unwrap().ty;
+    if last_segment(ty).is_some_and(|seg| seg.ident == expected) {
+        return Ok(());
+    }
+    Err(syn::Error::new_spanned(
+        ty,
+        format!("`{}` must be a `{}`, not `{}`", field, expected, pretty(ty))
+    ))
+}
+
+/// The last segment of a path type, `Tick` in `events::Tick`.
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last(),
+        _ => None,
+    }
+}
+
+/// Whether `a` and `b` name the same type as far as a macro can tell:
+/// equal last path segments (with their generics), or equal tokens.
+fn same_type(a: &Type, b: &Type) -> bool {
+    match (last_segment(a), last_segment(b)) {
+        (Some(a), Some(b)) => quote!(#a).to_string() == quote!(#b).to_string(),
+        _ => quote!(#a).to_string() == quote!(#b).to_string(),
+    }
+}
+
+/// `tokens` without the spaces `quote` puts between them, for messages.
+fn pretty(tokens: impl quote::ToTokens) -> String {
+    tokens.to_token_stream().to_string().replace(' ', "")
+}
+
+/// Parses the event or query type given to an attribute macro, with a
+/// usage hint if it is missing.
+fn parse_attr_type(attr: TokenStream, what: &str, usage: &str) -> syn::Result<Type> {
+    if attr.is_empty() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("expected the {} type: `{}`", what, usage)
+        ));
+    }
+    syn::parse(attr)
+}
+
+/// Implements `nexus_events::subscriber::EventSubscriber` from the struct's
+/// `id`, `active` and `subscriptions` fields (a `SubscriptionSet`).
+///
+/// Other field names are mapped with
+/// `#[event_subscriber(id = "entity_id", active = "enabled", subscriptions = "subs")]`;
+/// `#[event_subscriber(skip_active)]` is for structs without an `active`
+/// flag, which are then active unless paused. `set_active` writes the flag
+/// and pauses or resumes the subscriptions to match.
+///
+/// `#[event_subscriber(lifecycle, register = "subscribe_handlers")]` adds
+/// inherent `activate(&mut self, bus)` and `deactivate(&mut self)` methods
+/// (see `EventSubscriber`); `register` is optional, as `activate` also
+/// subscribes again whatever the `subscribe_<method>` companions of
+/// `#[event_handler]` registered with the set. The `register` method
+/// also implements `EventSubscriber::register_handlers`, which
+/// `SharedEventBus::rebind` calls.
+///
+/// `#[event_subscriber(emitter)]` derives `EventEmitter` too, as
+/// `#[derive(EventEmitter)]` would, `#[emitter(...)]` attributes included.
+#[proc_macro_derive(EventSubscriber, attributes(event_subscriber, emitter))]
+pub fn derive_event_subscriber(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    match expand_event_subscriber(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_event_subscriber(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mapping = SubscriberFields::from_attrs(&ast.attrs)?;
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct { fields: Fields::Named(fields), .. }) => fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "`EventSubscriber` can only be derived for structs with named fields"
+            ));
+        }
+    };
+
+    let id = subscriber_field(ast, fields, "id", &mapping.id)?;
+    let subscriptions = subscriber_field(ast, fields, "subscriptions", &mapping.subscriptions)?;
+    expect_field_type(fields, &subscriptions, "SubscriptionSet")?;
+    // Without an `active` field the paused state of the subscriptions is
+    // the flag, and the trait's `set_active` already maintains it.
+    let (is_active, set_active) = if mapping.skip_active {
+        (quote!(!self.#subscriptions.is_paused()), quote!())
+    } else {
+        let active = subscriber_field(ast, fields, "active", &mapping.active)?;
+        expect_field_type(fields, &active, "bool")?;
+        let set_active = quote! {
+            fn set_active(&mut self, active: bool) {
+                self.#active = active;
+                if active {
+                    self.#subscriptions.resume();
+                } else {
+                    self.#subscriptions.pause();
+                }
+            }
+        };
+        (quote!(self.#active), set_active)
+    };
+    let id_ty = &fields.named.iter().find(|f| f.ident.as_ref() == Some(&id)).unwrap().ty;
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let register_handlers = match &mapping.register {
+        Some(method) => {
+            let method: syn::Ident = method.parse()?;
+            quote! {
+                fn register_handlers(&mut self) {
+                    self.#method();
+                }
+            }
+        }
+        None => quote!(),
+    };
+    let emitter = if mapping.emitter { expand_event_emitter(ast)? } else { quote!() };
+    let lifecycle = if mapping.lifecycle {
+        let vis = &ast.vis;
+        let register = match &mapping.register {
+            Some(method) => {
+                let method: syn::Ident = method.parse()?;
+                quote!(self.#method();)
+            }
+            None => quote!(),
+        };
+        quote! {
+            #[allow(dead_code)]
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Subscribes the component's handlers on `bus`, marks it
+                /// active and publishes `ComponentActivated`.
+                #vis fn activate(&mut self, bus: &::nexus_events::core::SharedEventBus) -> ::nexus_events::core::EventResult<()>
+                where
+                    #id_ty: ::std::clone::Clone + ::std::marker::Send + ::std::marker::Sync + 'static
+                {
+                    self.#subscriptions.rebind(bus)?;
+                    ::nexus_events::subscriber::EventSubscriber::set_active(self, true);
+                    #register
+                    bus.publish(::nexus_events::subscriber::ComponentActivated {
+                        component: ::std::any::type_name::<Self>(),
+                        id: ::std::clone::Clone::clone(&self.#id),
+                    })
+                }
+
+                /// Unsubscribes the component's handlers, marks it
+                /// inactive and publishes `ComponentDeactivated`.
+                #vis fn deactivate(&mut self) -> ::nexus_events::core::EventResult<()>
+                where
+                    #id_ty: ::std::clone::Clone + ::std::marker::Send + ::std::marker::Sync + 'static
+                {
+                    self.#subscriptions.clear();
+                    ::nexus_events::subscriber::EventSubscriber::set_active(self, false);
+                    let bus = ::std::clone::Clone::clone(self.#subscriptions.bus());
+                    bus.publish(::nexus_events::subscriber::ComponentDeactivated {
+                        component: ::std::any::type_name::<Self>(),
+                        id: ::std::clone::Clone::clone(&self.#id),
+                    })
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+    Ok(quote! {
+        #lifecycle
+        #emitter
+
+        impl #impl_generics ::nexus_events::subscriber::EventSubscriber for #name #ty_generics #where_clause {
+            type Id = #id_ty;
+
+            fn subscriber_id(&self) -> &Self::Id {
+                &self.#id
+            }
+            fn is_active(&self) -> bool {
+                #is_active
+            }
+            fn subscriptions(&self) -> &::nexus_events::core::SubscriptionSet {
+                &self.#subscriptions
+            }
+            fn subscriptions_mut(&mut self) -> &mut ::nexus_events::core::SubscriptionSet {
+                &mut self.#subscriptions
+            }
+            #set_active
+            #register_handlers
+        }
+    })
+}
+
+/// Implements `nexus_events::core::EventEmitter` from the struct's bus
+/// fields: every field of type `SharedEventBus` or `LocalSharedEventBus`,
+/// which `#[event_sender(MyEvent, bus = "...")]` then names by the field's
+/// name.
+///
+/// `#[emitter(field = "scene_bus")]` picks the bus fields instead, one
+/// attribute per field, for buses behind another type name; `name =
+/// "scene"` next to it has senders use `bus = "scene"` for that field. All
+/// the bus fields must be of the same type, which becomes `Bus`.
+#[proc_macro_derive(EventEmitter, attributes(emitter))]
+pub fn derive_event_emitter(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    match expand_event_emitter(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_event_emitter(ast: &syn::DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct { fields: Fields::Named(fields), .. }) => fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "`EventEmitter` can only be derived for structs with named fields"
+            ));
+        }
+    };
+    let field_ty = |ident: &syn::Ident| &fields.named.iter().find(|f| f.ident.as_ref() == Some(ident)).unwrap().ty;
+
+    // (field, the name senders use for it)
+    let mut buses: Vec<(syn::Ident, String)> = Vec::new();
+    for attr in ast.attrs.iter().filter(|a| a.path.is_ident("emitter")) {
+        let usage = "expected `#[emitter(field = \"...\")]` or `#[emitter(field = \"...\", name = \"...\")]`";
+        let list = match attr.parse_meta()? {
+            syn::Meta::List(list) => list,
+            other => return Err(syn::Error::new_spanned(other, usage)),
+        };
+        let (mut field, mut name) = (None, None);
+        for nested in &list.nested {
+            let syn::NestedMeta::Meta(syn::Meta::NameValue(nv)) = nested else {
+                return Err(syn::Error::new_spanned(nested, usage));
+            };
+            let slot = if nv.path.is_ident("field") {
+                &mut field
+            } else if nv.path.is_ident("name") {
+                &mut name
+            } else {
+                return Err(syn::Error::new_spanned(&nv.path, "expected `field` or `name`"));
+            };
+            let syn::Lit::Str(value) = &nv.lit else {
+                return Err(syn::Error::new_spanned(&nv.lit, "expected a string"));
+            };
+            if slot.replace(value.clone()).is_some() {
+                return Err(syn::Error::new_spanned(&nv.path, "given twice"));
+            }
+        }
+        let Some(field) = field else {
+            return Err(syn::Error::new_spanned(&list, usage));
+        };
+        let Some(ident) = fields.named.iter().filter_map(|f| f.ident.as_ref()).find(|i| **i == field.value()) else {
+            return Err(syn::Error::new_spanned(&field, format!("`{}` has no field `{}`", ast.ident, field.value())));
+        };
+        let name = name.map_or_else(|| field.value(), |n| n.value());
+        if buses.iter().any(|(_, n)| *n == name) {
+            return Err(syn::Error::new_spanned(&list, format!("two buses named `{}`", name)));
+        }
+        buses.push((ident.clone(), name));
+    }
+    if buses.is_empty() {
+        buses = fields.named.iter()
+            .filter(|f| last_segment(&f.ty).is_some_and(|seg| seg.ident == "SharedEventBus" || seg.ident == "LocalSharedEventBus"))
+            .filter_map(|f| f.ident.clone())
+            .map(|ident| {
+                let name = ident.unraw().to_string();
+                (ident, name)
+            })
+            .collect();
+    }
+    let Some((first, _)) = buses.first() else {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            format!(
+                "`{}` has no `SharedEventBus` field; name the fields to send on with `#[emitter(field = \"...\")]`",
+                ast.ident
+            )
+        ));
+    };
+    let bus_ty = field_ty(first);
+    if let Some((other, _)) = buses.iter().find(|(f, _)| !same_type(field_ty(f), bus_ty)) {
+        return Err(syn::Error::new_spanned(
+            field_ty(other),
+            format!(
+                "`{}` is a `{}` but `{}` is a `{}`; every bus of an `EventEmitter` must have the same type",
+                other, pretty(field_ty(other)), first, pretty(bus_ty)
+            )
+        ));
+    }
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let arms = buses.iter().map(|(field, bus)| quote!(#bus => ::std::option::Option::Some(&self.#field),));
+    Ok(quote! {
+        impl #impl_generics ::nexus_events::core::EventEmitter for #name #ty_generics #where_clause {
+            type Bus = #bus_ty;
+
+            fn sender_named(&self, name: &str) -> ::std::option::Option<&Self::Bus> {
+                match name {
+                    #(#arms)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+/// Arguments of `#[event_handler(Event, ..., option, ...)]`.
+struct HandlerArgs {
+    event_tys: Vec<Type>,
+    throttle_ms: Option<syn::LitInt>,
+    debounce_ms: Option<syn::LitInt>,
+    respect_active: Option<syn::Ident>,
+    /// The fields of `uses(...)`, in the order given.
+    uses: Vec<syn::Ident>,
+}
+
+impl syn::parse::Parse for HandlerArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(input.error("expected the event type: `#[event_handler(MyEvent)]`"));
+        }
+        let mut args = HandlerArgs {
+            event_tys: vec![input.parse()?],
+            throttle_ms: None,
+            debounce_ms: None,
+            respect_active: None,
+            uses: Vec::new(),
+        };
+        let mut options = false;
+        while input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+            // Options are a name followed by `(` or `=`, or the flag
+            // `respect_active`; anything else is one more event type.
+            let is_flag = {
+                let ahead = input.fork();
+                ahead.parse::<syn::Ident>().is_ok_and(|i| i == "respect_active")
+                    && (ahead.is_empty() || ahead.peek(syn::Token![,]))
+            };
+            let is_option = is_flag
+                || input.peek(syn::Ident::peek_any) && (input.peek2(syn::token::Paren) || input.peek2(syn::Token![=]));
+            if !is_option {
+                if options {
+                    return Err(input.error("event types go before the options"));
+                }
+                let event_ty: Type = input.parse()?;
+                let name = quote!(#event_ty).to_string();
+                if args.event_tys.iter().any(|t| quote!(#t).to_string() == name) {
+                    return Err(syn::Error::new_spanned(&event_ty, format!("`{}` given twice", pretty(&event_ty))));
+                }
+                args.event_tys.push(event_ty);
+                continue;
+            }
+            options = true;
+            let option: syn::Ident = input.parse()?;
+            let duplicate = syn::Error::new_spanned(&option, format!("`{}` given twice", option));
+            if is_flag {
+                if args.respect_active.replace(option).is_some() {
+                    return Err(duplicate);
+                }
+            } else if option == "uses" {
+                if !args.uses.is_empty() {
+                    return Err(duplicate);
+                }
+                let content;
+                syn::parenthesized!(content in input);
+                let fields = content.parse_terminated::<syn::Ident, syn::Token![,]>(syn::Ident::parse_any)?;
+                if fields.is_empty() {
+                    return Err(syn::Error::new_spanned(option, "expected the fields the handler uses: `uses(position, velocity)`"));
+                }
+                for field in fields {
+                    if args.uses.contains(&field) {
+                        return Err(syn::Error::new_spanned(&field, format!("`{}` given twice", field)));
+                    }
+                    args.uses.push(field);
+                }
+            } else if option == "throttle_ms" || option == "debounce_ms" {
+                input.parse::<syn::Token![=]>()?;
+                let ms: syn::LitInt = input.parse()?;
+                ms.base10_parse::<u64>()?;
+                let slot = if option == "throttle_ms" { &mut args.throttle_ms } else { &mut args.debounce_ms };
+                if slot.replace(ms).is_some() {
+                    return Err(duplicate);
+                }
+            } else {
+                return Err(syn::Error::new_spanned(
+                    option,
+                    "expected `throttle_ms = N`, `debounce_ms = N`, `uses(...)` or `respect_active`"
+                ));
+            }
+        }
+        if let (Some(_), Some(debounce)) = (&args.throttle_ms, &args.debounce_ms) {
+            return Err(syn::Error::new_spanned(debounce, "use either `throttle_ms` or `debounce_ms`, not both"));
+        }
+        Ok(args)
+    }
+}
+
+/// Marks a method or function as an event handler.
+///
+/// On a method, it generates a companion
+/// `subscribe_<method>(this: &Arc<Mutex<Self>>, subscriptions: &mut SubscriptionSet)`
+/// that subscribes the method, through the set, to each of its event types.
+/// The subscriptions hold `this` weakly, lock it and call the method on it;
+/// they do nothing once the component is gone. Calling the companion again
+/// while they are still there fails with `EventError::DuplicateHandler`
+/// and subscribes nothing. The companion keeps its registration in the set
+/// (see `SubscriptionSet::register`), keyed by the type, name and
+/// definition site of the method. The method must be in an inherent `impl`
+/// and take `&self` or `&mut self`.
+///
+/// On a function without a receiver (and without `uses(...)`, below), the
+/// first call subscribes the
+/// function to the global bus, using a local `static ONCE: Once`.
+///
+/// The handler takes the event, and optionally the delivery context as a
+/// second argument: `fn on_hit(&mut self, evt: &Hit, ctx: &EventContext)`
+/// (or `&mut EventContext` to stop propagation).
+///
+/// `#[event_handler(PlayerJoined, PlayerLeft)]` handles several event types
+/// with one method, subscribed once per type. The method then takes a type
+/// every one of them turns into: a trait object they all implement
+/// (`evt: &dyn RosterEvent`), or an enum with a `From` impl for each
+/// (`evt: &RosterChange`, with `Clone` event types).
+///
+/// `throttle_ms = 16` lets at most one event through per 16 ms;
+/// `debounce_ms = 100` handles only the last event of a burst, once 100 ms
+/// have passed without another (the event type must be `Clone`). See
+/// `nexus_events::subscriber::decorators`. Neither works with a context
+/// argument. With several event types, each is throttled or debounced on
+/// its own.
+///
+/// `respect_active` makes the subscriptions skip the method while the
+/// component's `EventSubscriber::is_active` is false; calling the method
+/// directly still runs it. It needs a method taking `self` on an
+/// `EventSubscriber` and returning nothing.
+///
+/// `uses(position, velocity)` is for an associated function without `self`
+/// that only touches some fields of the component, each behind its own
+/// `Mutex` or `RwLock` (see `nexus_events::subscriber::FieldLock`). It takes
+/// the event, then a `&mut` to each listed field's value in the order given,
+/// then the optional context. Its companion takes `this: &Arc<Self>` and
+/// locks just those fields, in name order, so handlers using disjoint
+/// fields of one component can run at the same time:
+/// `fn on_tick(evt: &Tick, position: &mut Vec2, velocity: &mut Vec2)`.
 #[proc_macro_attribute]
 pub fn event_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let event_ty = parse_macro_input!(attr as Type);
+    let HandlerArgs { event_tys, throttle_ms, debounce_ms, respect_active, uses } = parse_macro_input!(attr as HandlerArgs);
     let method = parse_macro_input!(item as ItemFn);
 
-    let _fn_name = &method.sig.ident;
+    let fn_name = &method.sig.ident;
     let fn_vis = &method.vis;
     let fn_attrs = &method.attrs;
     let fn_block = &method.block;
     let fn_sig = &method.sig;
 
-    // We define a local static Once inside the user’s method body,
-    // so there's no associated static or nested module.
-    // The subscription is effectively a "type-level" broadcast approach (no per-instance).
-    let expanded = quote! {
-        #(#fn_attrs)*
-        #fn_vis #fn_sig {
-            // do the subscription once
-            {
-                use std::sync::Once;
-                static INIT: Once = Once::new();
-                INIT.call_once(|| {
-                    use ::nexus_events::core::{subscribe, Event};
-                    // Subscribe a broadcast closure. Right now, it's effectively no-op or a global approach.
-                    subscribe::<#event_ty, _>(move |_evt: &#event_ty| {
-                        // No instance-based logic – you might store a global list if needed
+    let receiver = match fn_sig.receiver() {
+        Some(syn::FnArg::Receiver(r)) if r.reference.is_some() => Some(r),
+        Some(other) => {
+            return syn::Error::new_spanned(other, "event handler methods take `&self` or `&mut self`")
+                .to_compile_error()
+                .into();
+        }
+        None => None,
+    };
+    if let (Some(r), Some(field)) = (receiver, uses.first()) {
+        let mut e = syn::Error::new_spanned(
+            r,
+            "`uses(...)` handlers take the fields they use instead of `self`: \
+             `fn on_tick(evt: &Tick, position: &mut Position, ..)`"
+        );
+        e.combine(syn::Error::new_spanned(field, "fields listed here"));
+        return e.to_compile_error().into();
+    }
+    // Methods, and associated functions with `uses(...)`, get a
+    // `subscribe_<method>` companion; other functions subscribe themselves.
+    let companion = receiver.is_some() || !uses.is_empty();
+
+    // A second `#[event_handler(SameEvent)]` on the method would subscribe
+    // under the same key and be dropped without a word.
+    let event_names: Vec<String> = event_tys.iter().map(|t| quote!(#t).to_string()).collect();
+    for attr in fn_attrs.iter().filter(|a| is_handler_attr(a)) {
+        if let Ok(other) = attr.parse_args::<HandlerArgs>() {
+            for other_ty in &other.event_tys {
+                let other_name = quote!(#other_ty).to_string();
+                if event_names.contains(&other_name) {
+                    return syn::Error::new_spanned(
+                        attr,
+                        format!("`{}` is already a handler for `{}`", fn_name, other_name)
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            }
+        }
+    }
+    // Each attribute on a method would generate its own
+    // `subscribe_<method>`.
+    if let (true, Some(attr)) = (companion, fn_attrs.iter().find(|a| is_handler_attr(a))) {
+        return syn::Error::new_spanned(
+            attr,
+            format!("`{}` already has an `#[event_handler]`; list all its events in one: `#[event_handler(A, B)]`", fn_name)
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let typed_args: Vec<&syn::PatType> = fn_sig.inputs.iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pt) => Some(pt),
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect();
+    // With `uses(...)`, the event is followed by a `&mut` for each field.
+    if typed_args.len() <= uses.len() {
+        return syn::Error::new_spanned(
+            &fn_sig.inputs,
+            "`uses(...)` handlers take the event, then a `&mut` argument for each field it lists"
+        )
+        .to_compile_error()
+        .into();
+    }
+    for (field, arg) in uses.iter().zip(&typed_args[1..]) {
+        if !matches!(&*arg.ty, Type::Reference(r) if r.mutability.is_some()) {
+            return syn::Error::new_spanned(&arg.ty, format!("`{}` is passed as `&mut`", field))
+                .to_compile_error()
+                .into();
+        }
+    }
+    let typed_args: Vec<&syn::PatType> = typed_args[..1].iter()
+        .chain(&typed_args[1 + uses.len()..])
+        .copied()
+        .collect();
+    if let Err(e) = check_handler_signature(fn_sig, &typed_args, &event_tys) {
+        return e.to_compile_error().into();
+    }
+    let active_check = match &respect_active {
+        Some(flag) if !companion => {
+            return syn::Error::new_spanned(flag, "`respect_active` needs a method taking `self` on an `EventSubscriber`")
+                .to_compile_error()
+                .into();
+        }
+        Some(_) if !matches!(fn_sig.output, syn::ReturnType::Default) => {
+            return syn::Error::new_spanned(&fn_sig.output, "`respect_active` handlers can't return a value")
+                .to_compile_error()
+                .into();
+        }
+        Some(_) => quote! {
+            if !::nexus_events::subscriber::EventSubscriber::is_active(&*this) {
+                return;
+            }
+        },
+        None => quote!(),
+    };
+    let with_context = typed_args.len() == 2;
+    if with_context {
+        if let Some(ms) = throttle_ms.as_ref().or(debounce_ms.as_ref()) {
+            return syn::Error::new_spanned(ms, "throttled or debounced handlers cannot take an `EventContext`")
+                .to_compile_error()
+                .into();
+        }
+    }
+    // With several events, the type the method takes them as.
+    let shared_ty = match (&*typed_args[0].ty, event_tys.len()) {
+        (Type::Reference(r), n) if n > 1 => Some(&*r.elem),
+        _ => None,
+    };
+    // The bus a debounced handler waits on.
+    let bus = if companion {
+        quote!(subscriptions.bus())
+    } else {
+        quote!(&::nexus_events::core::SharedEventBus::global())
+    };
+    // Field guards, taken in field name order so handlers sharing fields
+    // can't deadlock each other.
+    let guard = |field: &syn::Ident| syn::Ident::new(&format!("__{}", field), field.span());
+    let mut locked = uses.clone();
+    locked.sort();
+    let locks = locked.iter().map(|field| {
+        let guard = guard(field);
+        quote_spanned! {field.span()=>
+            let ::std::option::Option::Some(mut #guard) =
+                ::nexus_events::subscriber::FieldLock::lock_field(&this.#field) else { return };
+        }
+    });
+    let locks = quote!(#(#locks)*);
+    let fields = uses.iter().map(|field| {
+        let guard = guard(field);
+        quote_spanned!(field.span()=> &mut *#guard)
+    });
+    let fields = quote!(#(#fields,)*);
+
+    let subscriptions = event_tys.iter().map(|event_ty| {
+        // How each event reaches the method: coerced to the trait object,
+        // or converted into the enum.
+        let convert = match shared_ty {
+            Some(shared @ Type::TraitObject(_)) => quote_spanned! {event_ty.span()=>
+                let evt: &#shared = evt;
+            },
+            Some(shared) => quote_spanned! {event_ty.span()=>
+                let evt: &#shared = &<#shared as ::std::convert::From<#event_ty>>::from(::std::clone::Clone::clone(evt));
+            },
+            None => quote!(),
+        };
+        let args = if with_context { quote!(evt, #fields ctx) } else { quote!(evt, #fields) };
+        let call = match (receiver, companion) {
+            (Some(_), _) => quote!(this.#fn_name(#args)),
+            (None, true) => quote!(Self::#fn_name(#args)),
+            (None, false) => quote!(__handler(#args)),
+        };
+        let call = match &fn_sig.output {
+            syn::ReturnType::Default => quote!(#call;),
+            _ => quote!(let _ = #call;),
+        };
+        let call = match receiver {
+            Some(r) => {
+                let mutability = &r.mutability;
+                quote! {
+                    let ::std::option::Option::Some(this) = this.upgrade() else { return };
+                    let ::std::result::Result::Ok(#mutability this) = this.lock() else { return };
+                    #active_check
+                    #call
+                }
+            }
+            None if companion => quote! {
+                let ::std::option::Option::Some(this) = this.upgrade() else { return };
+                #active_check
+                #locks
+                #call
+            },
+            None => call,
+        };
+        let handler = if with_context {
+            quote! {
+                move |evt: &#event_ty, ctx: &mut ::nexus_events::core::EventContext| {
+                    #convert
+                    #call
+                }
+            }
+        } else {
+            quote! {
+                move |evt: &#event_ty| {
+                    #convert
+                    #call
+                }
+            }
+        };
+        let handler = match (&throttle_ms, &debounce_ms) {
+            (Some(ms), _) => quote! {
+                ::nexus_events::subscriber::decorators::throttle(::std::time::Duration::from_millis(#ms), #handler)
+            },
+            (_, Some(ms)) => quote! {
+                ::nexus_events::subscriber::decorators::debounce(
+                    #bus,
+                    ::std::time::Duration::from_millis(#ms),
+                    #handler
+                )
+            },
+            _ => handler,
+        };
+        if companion {
+            let subscribe = if with_context {
+                quote!(subscriptions.try_subscribe_in_phase::<#event_ty, _>(::nexus_events::core::Phase::PreInit, 0, handler)?)
+            } else {
+                quote!(subscriptions.try_subscribe::<#event_ty, _>(handler)?)
+            };
+            return quote! {
+                {
+                    let this = ::std::clone::Clone::clone(&this);
+                    let handler = #handler;
+                    #subscribe;
+                }
+            };
+        }
+        // Handler ids are derived from module and function name, so they
+        // are the same in every run (see `SharedEventBus::subscribe_keyed`).
+        // Functions handling several events carry the event too.
+        let key = if shared_ty.is_some() {
+            quote! {
+                &::std::format!(
+                    "{}::{}<{}>",
+                    module_path!(),
+                    stringify!(#fn_name),
+                    ::std::any::type_name::<#event_ty>()
+                )
+            }
+        } else {
+            quote!(concat!(module_path!(), "::", stringify!(#fn_name)))
+        };
+        if with_context {
+            quote! {
+                let _ = ::nexus_events::core::SharedEventBus::global().subscribe_keyed_in_phase::<#event_ty, _>(
+                    #key,
+                    ::nexus_events::core::Phase::PreInit,
+                    0,
+                    #handler
+                );
+            }
+        } else {
+            quote! {
+                let _ = ::nexus_events::core::SharedEventBus::global().subscribe_keyed::<#event_ty, _>(#key, #handler);
+            }
+        }
+    });
+
+    let expanded = if companion {
+        let subscribe_name = syn::Ident::new(&format!("subscribe_{}", fn_name), fn_name.span());
+        // With `uses(...)` the component is shared as is: its fields do
+        // their own locking.
+        let this_ty = if receiver.is_some() {
+            quote!(::std::sync::Arc<::std::sync::Mutex<Self>>)
+        } else {
+            quote!(::std::sync::Arc<Self>)
+        };
+        // The registration is keyed by where the method is defined as well:
+        // impl blocks of a generic type for different parameters may each
+        // have a handler of the same name.
+        let site = quote_spanned!(fn_name.span()=> concat!(file!(), ":", line!(), ":", column!()));
+        quote! {
+            #(#fn_attrs)*
+            #fn_vis #fn_sig #fn_block
+
+            /// Subscribes the handler, through `subscriptions`, to call it
+            /// on `this`, and keeps the registration in the set.
+            #fn_vis fn #subscribe_name(
+                this: &#this_ty,
+                subscriptions: &mut ::nexus_events::core::SubscriptionSet
+            ) -> ::nexus_events::core::EventResult<()> {
+                let this = ::std::sync::Arc::downgrade(this);
+                subscriptions.register(
+                    &::std::format!("{}::{}@{}", ::std::any::type_name::<Self>(), stringify!(#fn_name), #site),
+                    move |subscriptions| {
+                        #(#subscriptions)*
+                        ::std::result::Result::Ok(())
+                    }
+                )
+            }
+        }
+    } else {
+        // The body goes into a closure, called both by the function and
+        // by its subscriptions; the function's own arguments are renamed
+        // so any pattern can be passed on.
+        let params = typed_args.iter().map(|pt| {
+            let (pat, ty) = (&pt.pat, &pt.ty);
+            quote!(#pat: #ty)
+        });
+        let output = &fn_sig.output;
+        let mut outer_sig = fn_sig.clone();
+        let mut arg_names = Vec::new();
+        for (i, arg) in outer_sig.inputs.iter_mut().enumerate() {
+            if let syn::FnArg::Typed(pt) = arg {
+                let name = syn::Ident::new(&format!("__arg{}", i), proc_macro2::Span::call_site());
+                *pt.pat = syn::parse_quote!(#name);
+                arg_names.push(name);
+            }
+        }
+        quote! {
+            #(#fn_attrs)*
+            #fn_vis #outer_sig {
+                let __handler = |#(#params),*| #output #fn_block;
+                {
+                    static INIT: ::std::sync::Once = ::std::sync::Once::new();
+                    INIT.call_once(|| {
+                        #(#subscriptions)*
                     });
-                });
+                }
+                __handler(#(#arg_names),*)
             }
-            // Now run the user’s actual method body
-            #fn_block
         }
     };
     TokenStream::from(expanded)
 }
 
+/// Checks that a handler takes `&Event` (for several events, a reference to
+/// a type they share) and optionally an `EventContext` reference, and isn't
+/// async.
+fn check_handler_signature(sig: &syn::Signature, typed_args: &[&syn::PatType], event_tys: &[Type]) -> syn::Result<()> {
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(
+            asyncness,
+            "event handlers can't be `async`; start the async work from a regular handler"
+        ));
+    }
+    let (event, ctx) = match typed_args {
+        [event] => (event, None),
+        [event, ctx] => (event, Some(ctx)),
+        _ => {
+            let event = match event_tys {
+                [event_ty] => format!("`&{}`", pretty(event_ty)),
+                _ => "the event by reference".to_string(),
+            };
+            return Err(syn::Error::new_spanned(
+                &sig.inputs,
+                format!("event handlers take {} and, optionally, an `&EventContext`", event)
+            ));
+        }
+    };
+    match (&*event.ty, event_tys) {
+        (Type::Reference(r), [event_ty]) if r.mutability.is_some() => {
+            return Err(syn::Error::new_spanned(
+                &event.ty,
+                format!("handlers get the event by shared reference: `&{}`, not `&mut`", pretty(event_ty))
+            ));
+        }
+        (Type::Reference(r), [event_ty]) if !same_type(&r.elem, event_ty) => {
+            return Err(syn::Error::new_spanned(
+                &r.elem,
+                format!(
+                    "this is a handler for `{}`, so the event argument must be `&{}`",
+                    pretty(event_ty),
+                    pretty(event_ty)
+                )
+            ));
+        }
+        (Type::Reference(_), [_]) => {}
+        (other, [event_ty]) => {
+            return Err(syn::Error::new_spanned(
+                other,
+                format!("handlers get the event by reference: `&{}`", pretty(event_ty))
+            ));
+        }
+        (Type::Reference(r), _) if r.mutability.is_some() => {
+            return Err(syn::Error::new_spanned(&event.ty, "handlers get the event by shared reference, not `&mut`"));
+        }
+        (Type::Reference(r), _) if event_tys.iter().any(|t| same_type(&r.elem, t)) => {
+            return Err(syn::Error::new_spanned(
+                &r.elem,
+                "this handler takes several event types, so the event argument must be a type they all turn into: \
+                 `&dyn Trait` for a trait they implement, or `&Enum` for an enum with `From` impls for them"
+            ));
+        }
+        (Type::Reference(_), _) => {}
+        (other, _) => {
+            return Err(syn::Error::new_spanned(
+                other,
+                "handlers get the event by reference: `&dyn Trait` or `&Enum` when they take several event types"
+            ));
+        }
+    }
+    if let Some(ctx) = ctx {
+        let is_context = matches!(&*ctx.ty, Type::Reference(r)
+            if last_segment(&r.elem).is_some_and(|seg| seg.ident == "EventContext"));
+        if !is_context {
+            return Err(syn::Error::new_spanned(
+                &ctx.ty,
+                "the second handler argument must be `&EventContext` or `&mut EventContext`"
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn is_handler_attr(attr: &syn::Attribute) -> bool {
+    attr.path.segments.last().is_some_and(|s| s.ident == "event_handler")
+}
+
+/// Put on an `impl` block holding `#[event_handler]` methods to have
+/// conflicts between them reported up front: the same method handling the
+/// same event twice, or a handler whose generated `subscribe_<method>`
+/// would clash with a method of the block. The block itself is left
+/// unchanged.
+#[proc_macro_attribute]
+pub fn event_handlers(_attrs: TokenStream, input: TokenStream) -> TokenStream {
+    let block = parse_macro_input!(input as syn::ItemImpl);
+
+    let mut errors: Option<syn::Error> = None;
+    let mut report = |e: syn::Error| match &mut errors {
+        Some(all) => all.combine(e),
+        None => errors = Some(e),
+    };
+    // (event, method) of each handler.
+    let mut handlers: Vec<(String, &syn::Ident, &syn::Attribute)> = Vec::new();
+    let methods: Vec<&syn::Ident> = block.items.iter()
+        .filter_map(|item| match item {
+            syn::ImplItem::Method(method) => Some(&method.sig.ident),
+            _ => None,
+        })
+        .collect();
+    for item in &block.items {
+        let syn::ImplItem::Method(method) = item else { continue };
+        let name = &method.sig.ident;
+        for attr in method.attrs.iter().filter(|a| is_handler_attr(a)) {
+            let args = match attr.parse_args::<HandlerArgs>() {
+                Ok(args) => args,
+                Err(e) => {
+                    report(e);
+                    continue;
+                }
+            };
+            let mut duplicate = false;
+            for event_ty in &args.event_tys {
+                let event = quote!(#event_ty).to_string();
+                if let Some((_, _, first)) = handlers.iter().find(|(e, m, _)| *e == event && *m == name) {
+                    let mut e = syn::Error::new_spanned(
+                        attr,
+                        format!("`{}` is registered as a handler for `{}` more than once", name, event)
+                    );
+                    e.combine(syn::Error::new_spanned(first, "first registered here"));
+                    report(e);
+                    duplicate = true;
+                    break;
+                }
+            }
+            if duplicate {
+                continue;
+            }
+            handlers.extend(args.event_tys.iter().map(|event_ty| (quote!(#event_ty).to_string(), name, attr)));
+
+            let subscribe_name = format!("subscribe_{}", name);
+            if method.sig.receiver().is_some() || !args.uses.is_empty() {
+                if let Some(other) = methods.iter().find(|m| **m == subscribe_name.as_str()) {
+                    let mut e = syn::Error::new_spanned(
+                        name,
+                        format!("handler `{}` generates `{}`, which is already a method here; rename one of them", name, subscribe_name)
+                    );
+                    e.combine(syn::Error::new_spanned(other, "other method here"));
+                    report(e);
+                }
+            }
+        }
+    }
+
+    match errors {
+        Some(e) => {
+            let errors = e.to_compile_error();
+            TokenStream::from(quote! { #errors #block })
+        }
+        None => TokenStream::from(quote! { #block }),
+    }
+}
+
+/// `#[event_sender(MyEvent, map(field = "expr", ...), bus = "name")]`
+struct SenderArgs {
+    event_ty: Type,
+    map: Vec<(syn::Ident, syn::Expr)>,
+    bus: Option<syn::LitStr>,
+}
+
+impl syn::parse::Parse for SenderArgs {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Err(input.error("expected the event type: `#[event_sender(MyEvent)]`"));
+        }
+        let mut args = SenderArgs { event_ty: input.parse()?, map: Vec::new(), bus: None };
+        let mut mapped = false;
+        while input.parse::<Option<syn::Token![,]>>()?.is_some() && !input.is_empty() {
+            let option: syn::Ident = input.parse()?;
+            if option == "bus" {
+                input.parse::<syn::Token![=]>()?;
+                if !input.peek(syn::LitStr) {
+                    return Err(input.error("expected the bus name as a string: `bus = \"...\"`"));
+                }
+                if args.bus.replace(input.parse()?).is_some() {
+                    return Err(syn::Error::new_spanned(option, "`bus` given twice"));
+                }
+                continue;
+            }
+            if option != "map" {
+                return Err(syn::Error::new_spanned(option, "expected `map(field = \"expr\", ...)` or `bus = \"name\"`"));
+            }
+            if std::mem::replace(&mut mapped, true) {
+                return Err(syn::Error::new_spanned(option, "`map` given twice"));
+            }
+            let content;
+            syn::parenthesized!(content in input);
+            while !content.is_empty() {
+                let field = content.call(syn::Ident::parse_any)?;
+                content.parse::<syn::Token![=]>()?;
+                if !content.peek(syn::LitStr) {
+                    return Err(content.error(format!("expected the expression as a string: `{} = \"...\"`", field)));
+                }
+                let lit: syn::LitStr = content.parse()?;
+                if args.map.iter().any(|(f, _)| *f == field) {
+                    return Err(syn::Error::new_spanned(field, "field mapped twice"));
+                }
+                args.map.push((field, lit.parse()?));
+                if content.parse::<Option<syn::Token![,]>>()?.is_none() {
+                    break;
+                }
+            }
+            if !content.is_empty() {
+                return Err(content.error("expected `,`"));
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Whether `ident` appears anywhere in `tokens`.
+fn mentions(tokens: proc_macro2::TokenStream, ident: &syn::Ident) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Group(group) => mentions(group.stream(), ident),
+        proc_macro2::TokenTree::Ident(i) => i == *ident,
+        _ => false,
+    })
+}
+
+/// The return type of a sender method if it is `EmitControl<E>` or
+/// `Option<E>` for its event type `E`.
+fn emit_control_type(output: &syn::ReturnType, event_ty: &Type) -> Option<Type> {
+    let syn::ReturnType::Type(_, ty) = output else { return None };
+    let segment = last_segment(ty)?;
+    if segment.ident != "EmitControl" && segment.ident != "Option" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else { return None };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(inner)) if args.args.len() == 1 && same_type(inner, event_ty) => {
+            Some((**ty).clone())
+        }
+        _ => None,
+    }
+}
+
 /// Marks a method as an event sender. It builds an event
 /// from the method parameters, dispatches it, and returns the user's result.
+///
+/// Fields that don't come straight from a parameter are given as
+/// expressions, which may use `self` and the parameters:
+/// `#[event_sender(PlayerMoved, map(player_id = "self.id.clone()", x = "pos.0", y = "pos.1"))]`.
+/// The expressions are evaluated after the method body has run. A
+/// parameter used by an expression doesn't fill a field of its own.
+///
+/// A method returning `EmitControl<MyEvent>` or `Option<MyEvent>` builds the
+/// event itself and decides whether it goes out; it then returns whether
+/// the event was sent.
+///
+/// Events go to the global bus unless `bus = "name"` picks one of the
+/// component's own, which it names by implementing `EventEmitter`; that
+/// bus may also be a `LocalSharedEventBus`.
 #[proc_macro_attribute]
 pub fn event_sender(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let event_ty = parse_macro_input!(attr as Type);
+    let SenderArgs { event_ty, map, bus } = parse_macro_input!(attr as SenderArgs);
     let input_fn = parse_macro_input!(item as ItemFn);
 
-    let _fn_name = &input_fn.sig.ident;
+    if let (Some(name), None) = (&bus, input_fn.sig.receiver()) {
+        return syn::Error::new_spanned(name, "`bus = ...` needs a method taking `self`, whose `EventEmitter` impl names the bus")
+            .to_compile_error()
+            .into();
+    }
+
+    if let Some(control_ty) = emit_control_type(&input_fn.sig.output, &event_ty) {
+        if let Some((field, _)) = map.first() {
+            return syn::Error::new_spanned(
+                field,
+                "`map` has no effect when the method returns the event itself"
+            )
+            .to_compile_error()
+            .into();
+        }
+        let emit = match &bus {
+            Some(name) => quote! {
+                match ::nexus_events::core::EventEmitter::sender_named(self, #name) {
+                    Some(bus) => ::nexus_events::core::EmitControl::from(__control).emit_to(bus),
+                    None => false,
+                }
+            },
+            None => quote! { ::nexus_events::core::EmitControl::from(__control).emit() },
+        };
+        let ItemFn { attrs, vis, sig, block } = input_fn;
+        let mut sig = sig;
+        sig.output = syn::parse_quote!(-> bool);
+        return TokenStream::from(quote! {
+            #(#attrs)*
+            #vis #sig {
+                // a closure, so `return` and `?` in the body keep their meaning
+                #[allow(clippy::redundant_closure_call)]
+                let __control: #control_ty = (|| #block)();
+                #emit
+            }
+        });
+    }
+
+    let send = match &bus {
+        Some(name) => quote! {
+            // dispatch it to the bus the component calls `#name`, if it has one
+            if let Some(bus) = ::nexus_events::core::EventEmitter::sender_named(self, #name) {
+                ::nexus_events::core::EventSink::dispatch_event(bus, evt);
+            }
+        },
+        None => quote! {
+            // dispatch it to the global bus
+            ::nexus_events::core::dispatch(evt);
+        },
+    };
+
     let fn_vis = &input_fn.vis;
     let fn_attrs = &input_fn.attrs;
     let fn_block = &input_fn.block;
     let fn_sig = &input_fn.sig;
     let fn_inputs = &input_fn.sig.inputs;
 
-    // Gather parameter names for building the event
-    let param_idents: Vec<_> = fn_inputs.iter()
-        .skip(1) // skip &self
-        .filter_map(|arg| {
-            if let syn::FnArg::Typed(pt) = arg {
-                if let syn::Pat::Ident(ref pat_ident) = *pt.pat {
-                    return Some(pat_ident.ident.clone());
-                }
-            }
-            None
-        })
-        .collect();
+    // Every parameter but `self` fills the event field of the same name,
+    // unless that field is mapped or the parameter feeds a mapping. The
+    // field initializers carry the parameters' (or mappings') spans, so a
+    // field the event doesn't have is reported there.
+    let mut fields = Vec::new();
+    for arg in fn_inputs {
+        let syn::FnArg::Typed(pt) = arg else { continue };
+        let syn::Pat::Ident(pat_ident) = &*pt.pat else {
+            return syn::Error::new_spanned(
+                &pt.pat,
+                "sender parameters must be plain names; each one fills the event field of the same name"
+            )
+            .to_compile_error()
+            .into();
+        };
+        let ident = &pat_ident.ident;
+        let used = map.iter().any(|(field, expr)| field == ident || mentions(expr.to_token_stream(), ident));
+        if !used {
+            fields.push(quote_spanned!(ident.span()=> #ident: #ident));
+        }
+    }
+    for (field, expr) in &map {
+        fields.push(quote_spanned!(field.span()=> #field: #expr));
+    }
 
     let expanded = quote! {
         #(#fn_attrs)*
@@ -99,13 +1267,11 @@ pub fn event_sender(attr: TokenStream, item: TokenStream) -> TokenStream {
             };
 
             {
-                use ::nexus_events::core::dispatch;
                 // build an event from the method params
                 let evt = #event_ty {
-                    #(#param_idents: #param_idents),*
+                    #(#fields),*
                 };
-                // dispatch it to the global bus
-                dispatch(evt);
+                #send
             }
 
             __user_result
@@ -113,3 +1279,155 @@ pub fn event_sender(attr: TokenStream, item: TokenStream) -> TokenStream {
     };
     TokenStream::from(expanded)
 }
+
+/// Marks an associated function (in an `impl` block, without `self`) as the
+/// responder for a query type. The function is kept as-is, and a companion
+/// `Self::register_<name>()` is generated that registers it with the global
+/// bus and returns the `HandlerId`. A free function needs no companion:
+/// pass it to `register_responder` directly.
+///
+/// The function must take the query by reference and return its response,
+/// e.g. `fn potion_count(q: &GetPotionCount) -> PotionCount`.
+#[proc_macro_attribute]
+pub fn query_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let query_ty = match parse_attr_type(attr, "query", "#[query_handler(MyQuery)]") {
+        Ok(ty) => ty,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let func = parse_macro_input!(item as ItemFn);
+
+    if let Some(receiver) = func.sig.receiver() {
+        return syn::Error::new_spanned(
+            receiver,
+            "`#[query_handler]` functions cannot take `self`; responders are not tied to an instance"
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let takes_query = matches!(
+        func.sig.inputs.iter().collect::<Vec<_>>().as_slice(),
+        [syn::FnArg::Typed(pt)] if matches!(*pt.ty, Type::Reference(_))
+    );
+    if !takes_query {
+        return syn::Error::new_spanned(
+            &func.sig.inputs,
+            format!("`#[query_handler]` functions take just the query, by reference: `q: &{}`", pretty(&query_ty))
+        )
+        .to_compile_error()
+        .into();
+    }
+    if let syn::ReturnType::Default = func.sig.output {
+        return syn::Error::new_spanned(
+            &func.sig.ident,
+            format!("`#[query_handler]` functions must return the response of `{}`", pretty(&query_ty))
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let fn_name = &func.sig.ident;
+    let fn_vis = &func.vis;
+    let register_name = syn::Ident::new(&format!("register_{}", fn_name), fn_name.span());
+
+    let expanded = quote! {
+        #func
+
+        /// Registers the responder with the global bus.
+        #fn_vis fn #register_name() -> ::nexus_events::core::HandlerId {
+            ::nexus_events::core::register_responder::<#query_ty, _>(Self::#fn_name)
+        }
+    };
+    TokenStream::from(expanded)
+}
+
+/// Marks a free function as a handler that doesn't belong to a component,
+/// for logging, metrics or a cheat console. The function is kept as-is and
+/// registered at startup; `bus.subscribe_global_handlers()` subscribes it,
+/// along with every other one, to a bus of your choosing.
+///
+/// The function takes the event by reference and, optionally, the delivery
+/// context: `fn log_moves(ev: &PlayerMoved)` or
+/// `fn cheat(cmd: &ConsoleCommand, ctx: &mut EventContext)`.
+#[proc_macro_attribute]
+pub fn global_event_handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let event_ty = match parse_attr_type(attr, "event", "#[global_event_handler(MyEvent)]") {
+        Ok(ty) => ty,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    let func = parse_macro_input!(item as ItemFn);
+
+    if let Some(receiver) = func.sig.receiver() {
+        return syn::Error::new_spanned(
+            receiver,
+            "`#[global_event_handler]` functions cannot take `self`; use `#[event_handler]` for methods"
+        )
+        .to_compile_error()
+        .into();
+    }
+    let typed_args: Vec<&syn::PatType> = func.sig.inputs.iter()
+        .filter_map(|arg| match arg {
+            syn::FnArg::Typed(pt) => Some(pt),
+            syn::FnArg::Receiver(_) => None,
+        })
+        .collect();
+    if let Err(e) = check_handler_signature(&func.sig, &typed_args, std::slice::from_ref(&event_ty)) {
+        return e.to_compile_error().into();
+    }
+
+    let fn_name = &func.sig.ident;
+    let subscribe = if typed_args.len() == 2 {
+        quote! {
+            bus.subscribe_keyed_in_phase::<#event_ty, _>(
+                key,
+                ::nexus_events::core::Phase::PreInit,
+                0,
+                |ev: &#event_ty, ctx: &mut ::nexus_events::core::EventContext| #fn_name(ev, ctx)
+            )
+        }
+    } else {
+        quote! {
+            bus.subscribe_keyed::<#event_ty, _>(key, #fn_name)
+        }
+    };
+
+    let expanded = quote! {
+        #func
+
+        const _: () = {
+            fn __subscribe(
+                bus: &::nexus_events::core::SharedEventBus,
+                key: &str
+            ) -> ::nexus_events::core::EventResult<::nexus_events::core::HandlerId> {
+                #subscribe
+            }
+            ::nexus_events::__private::inventory::submit! {
+                ::nexus_events::core::GlobalHandler::new(concat!(module_path!(), "::", stringify!(#fn_name)), __subscribe)
+            }
+        };
+    };
+    TokenStream::from(expanded)
+}
+
+/// Implements `HasContract` for an event struct. The schema hash is taken
+/// from the struct's fields (names and types, in order), so any change to
+/// the definition changes the contract.
+#[proc_macro_attribute]
+pub fn event_contract(_attrs: TokenStream, input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as ItemStruct);
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let name_str = name.to_string();
+    let fields = &ast.fields;
+    let schema = quote!(#fields).to_string();
+
+    let expanded = quote! {
+        #ast
+
+        impl #impl_generics ::nexus_events::core::HasContract for #name #ty_generics #where_clause {
+            const CONTRACT: ::nexus_events::core::EventContract =
+                ::nexus_events::core::EventContract::new(#name_str, #schema);
+        }
+    };
+    TokenStream::from(expanded)
+}
@@ -0,0 +1,110 @@
+#[test]
+fn event_subscriber_derive() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/subscriber_generic.rs");
+    t.pass("tests/ui/subscriber_lifetime.rs");
+    t.pass("tests/ui/subscriber_set_active.rs");
+    t.pass("tests/ui/subscriber_address.rs");
+    t.compile_fail("tests/ui/subscriber_missing_field.rs");
+    t.compile_fail("tests/ui/subscriber_enum.rs");
+    t.compile_fail("tests/ui/subscriber_unknown_option.rs");
+    t.compile_fail("tests/ui/subscriber_wrong_set_type.rs");
+    t.compile_fail("tests/ui/subscriber_skip_and_active.rs");
+    t.pass("tests/ui/subscriber_lifecycle.rs");
+    t.compile_fail("tests/ui/subscriber_register_without_lifecycle.rs");
+}
+
+#[test]
+fn event_handler_diagnostics() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/handler_missing_event.rs");
+    t.compile_fail("tests/ui/handler_event_by_value.rs");
+    t.compile_fail("tests/ui/handler_event_mut.rs");
+    t.compile_fail("tests/ui/handler_wrong_event.rs");
+    t.compile_fail("tests/ui/handler_context_by_value.rs");
+    t.compile_fail("tests/ui/handler_context_wrong_type.rs");
+    t.compile_fail("tests/ui/handler_too_many_args.rs");
+    t.compile_fail("tests/ui/handler_async.rs");
+    t.compile_fail("tests/ui/handler_unknown_option.rs");
+    t.compile_fail("tests/ui/handler_throttle_and_debounce.rs");
+    t.compile_fail("tests/ui/handler_uses.rs");
+    t.compile_fail("tests/ui/handlers_duplicate.rs");
+    t.compile_fail("tests/ui/handlers_subscribe_clash.rs");
+    t.compile_fail("tests/ui/handler_multi_event_wrong_arg.rs");
+    t.compile_fail("tests/ui/handler_respect_active_return.rs");
+}
+
+#[test]
+fn multi_event_handlers() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/handler_multi_event.rs");
+}
+
+#[test]
+fn context_handlers() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/handler_context.rs");
+}
+
+#[test]
+fn throttled_handlers() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/handler_throttled.rs");
+}
+
+#[test]
+fn event_sender_diagnostics() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/sender_unknown_field.rs");
+    t.compile_fail("tests/ui/sender_missing_field.rs");
+    t.compile_fail("tests/ui/sender_pattern_param.rs");
+    t.compile_fail("tests/ui/sender_map_unknown_field.rs");
+    t.compile_fail("tests/ui/sender_map_not_string.rs");
+    t.compile_fail("tests/ui/sender_conditional_map.rs");
+    t.compile_fail("tests/ui/sender_bus_without_self.rs");
+    t.pass("tests/ui/sender_map.rs");
+    t.pass("tests/ui/sender_conditional.rs");
+    t.pass("tests/ui/sender_bus.rs");
+}
+
+#[test]
+fn query_handler_diagnostics() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/query_handler_self.rs");
+    t.compile_fail("tests/ui/query_handler_no_response.rs");
+    t.compile_fail("tests/ui/query_handler_by_value.rs");
+}
+
+#[test]
+fn event_component_diagnostics() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/component_tuple_struct.rs");
+}
+
+#[test]
+fn borrowed_publishing() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/dispatch_borrowed.rs");
+}
+
+#[test]
+fn global_event_handlers() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/global_event_handler.rs");
+    t.compile_fail("tests/ui/global_event_handler_self.rs");
+    t.compile_fail("tests/ui/global_event_handler_by_value.rs");
+}
+
+#[test]
+fn respect_active() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/handler_respect_active.rs");
+}
+
+#[test]
+fn derive_event_emitter() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/ui/derive_event_emitter.rs");
+    t.compile_fail("tests/ui/derive_event_emitter_mixed_buses.rs");
+    t.compile_fail("tests/ui/derive_event_emitter_no_bus.rs");
+}
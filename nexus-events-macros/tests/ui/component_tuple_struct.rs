@@ -0,0 +1,6 @@
+use nexus_events::prelude::*;
+
+#[event_component]
+struct Health(u32);
+
+fn main() {}
@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved {
+    x: i32,
+}
+
+#[derive(EventEmitter)]
+struct Player {
+    scene: SharedEventBus,
+    global: SharedEventBus,
+    #[allow(dead_code)]
+    name: String,
+}
+
+impl Player {
+    #[event_sender(PlayerMoved, bus = "scene")]
+    fn step(&mut self, x: i32) {}
+
+    #[event_sender(PlayerMoved, bus = "global")]
+    fn teleport(&self, x: i32) {}
+}
+
+#[derive(EventEmitter)]
+#[emitter(field = "hud_bus", name = "hud")]
+struct Hud {
+    hud_bus: SharedEventBus,
+    #[allow(dead_code)]
+    debug_bus: SharedEventBus,
+}
+
+impl Hud {
+    #[event_sender(PlayerMoved, bus = "hud")]
+    fn echo(&self, x: i32) {}
+}
+
+#[derive(EventSubscriber)]
+#[event_subscriber(skip_active, emitter)]
+struct Enemy {
+    id: u32,
+    subscriptions: SubscriptionSet,
+    bus: SharedEventBus,
+}
+
+impl Enemy {
+    #[event_sender(PlayerMoved, bus = "bus")]
+    fn chase(&self, x: i32) {}
+}
+
+fn counter(bus: &SharedEventBus) -> Arc<AtomicUsize> {
+    let count = Arc::new(AtomicUsize::new(0));
+    let c = count.clone();
+    bus.subscribe(move |_: &PlayerMoved| {
+        c.fetch_add(1, Ordering::SeqCst);
+    });
+    count
+}
+
+fn main() {
+    let mut player = Player { scene: SharedEventBus::new(), global: SharedEventBus::new(), name: "hero".into() };
+    let (scene, global) = (counter(&player.scene), counter(&player.global));
+    player.step(1);
+    player.teleport(2);
+    player.teleport(3);
+    player.scene.process();
+    player.global.process();
+    assert_eq!(scene.load(Ordering::SeqCst), 1);
+    assert_eq!(global.load(Ordering::SeqCst), 2);
+    assert!(player.sender_named("name").is_none());
+
+    let hud = Hud { hud_bus: SharedEventBus::new(), debug_bus: SharedEventBus::new() };
+    assert!(hud.sender_named("hud_bus").is_none());
+    assert!(hud.sender_named("debug_bus").is_none());
+    let echoed = counter(&hud.hud_bus);
+    hud.echo(4);
+    hud.hud_bus.process();
+    assert_eq!(echoed.load(Ordering::SeqCst), 1);
+
+    let bus = SharedEventBus::new();
+    let enemy = Enemy { id: 7, subscriptions: SubscriptionSet::new(&bus), bus: bus.clone() };
+    let chased = counter(&bus);
+    enemy.chase(5);
+    bus.process();
+    assert_eq!(chased.load(Ordering::SeqCst), 1);
+    assert_eq!(*enemy.subscriber_id(), 7);
+}
@@ -0,0 +1,13 @@
+use nexus_events::prelude::*;
+
+struct SceneBus(SharedEventBus);
+
+#[derive(EventEmitter)]
+#[emitter(field = "global")]
+#[emitter(field = "scene")]
+struct Player {
+    global: SharedEventBus,
+    scene: SceneBus,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use nexus_events::prelude::*;
+
+#[derive(EventEmitter)]
+struct Player {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use nexus_events::prelude::*;
+
+struct NavGrid {
+    cells: Vec<u8>,
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let grid = NavGrid { cells: vec![0; 4096] };
+    // Queued events outlive the call, so they can't be borrowed.
+    bus.dispatch(&grid);
+    bus.process();
+}
@@ -0,0 +1,58 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved {
+    x: u32,
+}
+
+#[derive(Debug, Clone)]
+struct ConsoleCommand(&'static str);
+
+static MOVED: AtomicU32 = AtomicU32::new(0);
+static CHEATS: AtomicU32 = AtomicU32::new(0);
+
+#[global_event_handler(PlayerMoved)]
+fn log_moves(ev: &PlayerMoved) {
+    MOVED.fetch_add(ev.x, Ordering::SeqCst);
+}
+
+#[global_event_handler(ConsoleCommand)]
+fn cheats(cmd: &ConsoleCommand, ctx: &mut EventContext) {
+    if cmd.0 == "god" {
+        CHEATS.fetch_add(1, Ordering::SeqCst);
+        ctx.stop_propagation();
+    }
+}
+
+mod metrics {
+    use super::*;
+
+    pub static COUNTED: AtomicU32 = AtomicU32::new(0);
+
+    #[global_event_handler(PlayerMoved)]
+    fn count_moves(_: &PlayerMoved) {
+        COUNTED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    assert_eq!(bus.subscribe_global_handlers().unwrap(), 3);
+    // Already there: nothing is subscribed twice.
+    assert_eq!(bus.subscribe_global_handlers().unwrap(), 0);
+
+    bus.publish(PlayerMoved { x: 5 }).unwrap();
+    assert_eq!(MOVED.load(Ordering::SeqCst), 5);
+    assert_eq!(metrics::COUNTED.load(Ordering::SeqCst), 1);
+
+    let report = bus.emit(ConsoleCommand("god")).unwrap();
+    assert!(report.consumed);
+    bus.publish(ConsoleCommand("noclip")).unwrap();
+    assert_eq!(CHEATS.load(Ordering::SeqCst), 1);
+
+    // The functions can still be called directly.
+    log_moves(&PlayerMoved { x: 1 });
+    assert_eq!(MOVED.load(Ordering::SeqCst), 6);
+}
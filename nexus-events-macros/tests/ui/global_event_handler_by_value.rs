@@ -0,0 +1,9 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved;
+
+#[global_event_handler(PlayerMoved)]
+fn log(_ev: PlayerMoved) {}
+
+fn main() {}
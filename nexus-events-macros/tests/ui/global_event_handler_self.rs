@@ -0,0 +1,13 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved;
+
+struct Logger;
+
+impl Logger {
+    #[global_event_handler(PlayerMoved)]
+    fn log(&self, _ev: &PlayerMoved) {}
+}
+
+fn main() {}
@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Hit {
+    damage: u32,
+}
+
+struct Shield {
+    up: bool,
+    absorbed: u32,
+}
+
+impl Shield {
+    #[event_handler(Hit)]
+    fn on_hit(&mut self, evt: &Hit, ctx: &mut EventContext) {
+        if self.up {
+            self.absorbed += evt.damage;
+            ctx.stop_propagation();
+        }
+    }
+}
+
+static SEEN: AtomicUsize = AtomicUsize::new(0);
+
+#[event_handler(Hit)]
+fn watch_hits(_evt: &Hit, ctx: &EventContext) {
+    if !ctx.is_propagation_stopped() {
+        SEEN.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let shield = Arc::new(Mutex::new(Shield { up: true, absorbed: 0 }));
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Shield::subscribe_on_hit(&shield, &mut subscriptions).unwrap();
+    let taken = Arc::new(AtomicUsize::new(0));
+    let t = taken.clone();
+    // Same priority, subscribed later: runs after the shield.
+    bus.subscribe::<Hit, _>(move |evt| {
+        t.fetch_add(evt.damage as usize, Ordering::SeqCst);
+    });
+
+    // The shield stops the hit before it gets through.
+    bus.publish(Hit { damage: 5 }).unwrap();
+    assert_eq!(shield.lock().unwrap().absorbed, 5);
+    assert_eq!(taken.load(Ordering::SeqCst), 0);
+
+    shield.lock().unwrap().up = false;
+    bus.publish(Hit { damage: 3 }).unwrap();
+    assert_eq!(shield.lock().unwrap().absorbed, 5);
+    assert_eq!(taken.load(Ordering::SeqCst), 3);
+
+    // Functions get the context too.
+    watch_hits(&Hit { damage: 1 }, &EventContext::default());
+    SharedEventBus::global().publish(Hit { damage: 1 }).unwrap();
+    assert_eq!(SEEN.load(Ordering::SeqCst), 2);
+}
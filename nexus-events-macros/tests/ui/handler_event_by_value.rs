@@ -0,0 +1,13 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+struct Player;
+
+impl Player {
+    #[event_handler(Tick)]
+    fn on_tick(&mut self, _evt: Tick) {}
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use nexus_events::prelude::*;
+
+struct Player;
+
+impl Player {
+    #[event_handler]
+    fn on_tick(&mut self, _evt: &u32) {}
+}
+
+fn main() {}
@@ -0,0 +1,110 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerJoined {
+    name: String,
+}
+
+#[derive(Debug, Clone)]
+struct PlayerLeft {
+    name: String,
+}
+
+trait RosterEvent {
+    fn name(&self) -> &str;
+}
+
+impl RosterEvent for PlayerJoined {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl RosterEvent for PlayerLeft {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+enum RosterChange {
+    Joined(PlayerJoined),
+    Left(PlayerLeft),
+}
+
+impl From<PlayerJoined> for RosterChange {
+    fn from(ev: PlayerJoined) -> Self {
+        RosterChange::Joined(ev)
+    }
+}
+
+impl From<PlayerLeft> for RosterChange {
+    fn from(ev: PlayerLeft) -> Self {
+        RosterChange::Left(ev)
+    }
+}
+
+#[derive(Default)]
+struct Lobby {
+    names: Vec<String>,
+    present: usize,
+}
+
+#[event_handlers]
+impl Lobby {
+    #[event_handler(PlayerJoined, PlayerLeft)]
+    fn on_roster(&mut self, evt: &dyn RosterEvent) {
+        self.names.push(evt.name().to_string());
+    }
+
+    #[event_handler(PlayerJoined, PlayerLeft)]
+    fn on_change(&mut self, evt: &RosterChange, _ctx: &EventContext) {
+        match evt {
+            RosterChange::Joined(_) => self.present += 1,
+            RosterChange::Left(_) => self.present -= 1,
+        }
+    }
+}
+
+static LOGGED: AtomicUsize = AtomicUsize::new(0);
+
+#[event_handler(PlayerJoined, PlayerLeft, throttle_ms = 16)]
+fn log_roster(evt: &dyn RosterEvent) {
+    let _ = evt.name();
+    LOGGED.fetch_add(1, Ordering::SeqCst);
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let lobby = Arc::new(Mutex::new(Lobby::default()));
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Lobby::subscribe_on_roster(&lobby, &mut subscriptions).unwrap();
+    Lobby::subscribe_on_change(&lobby, &mut subscriptions).unwrap();
+    // One subscription per event type and method.
+    assert_eq!(subscriptions.len(), 4);
+
+    bus.publish(PlayerJoined { name: "ada".into() }).unwrap();
+    bus.publish(PlayerJoined { name: "bo".into() }).unwrap();
+    bus.publish(PlayerLeft { name: "ada".into() }).unwrap();
+    {
+        let lobby = lobby.lock().unwrap();
+        assert_eq!(lobby.names, ["ada", "bo", "ada"]);
+        assert_eq!(lobby.present, 1);
+    }
+
+    // Gone with the set.
+    drop(subscriptions);
+    bus.publish(PlayerJoined { name: "cy".into() }).unwrap();
+    assert_eq!(lobby.lock().unwrap().names.len(), 3);
+
+    // The first call subscribes the function to the global bus.
+    log_roster(&PlayerLeft { name: "bo".into() });
+    assert_eq!(LOGGED.load(Ordering::SeqCst), 1);
+    SharedEventBus::global().publish(PlayerJoined { name: "cy".into() }).unwrap();
+    assert_eq!(LOGGED.load(Ordering::SeqCst), 2);
+    let counts = SharedEventBus::global().handler_counts();
+    assert_eq!(counts.get(std::any::type_name::<PlayerJoined>()), Some(&1));
+    assert_eq!(counts.get(std::any::type_name::<PlayerLeft>()), Some(&1));
+}
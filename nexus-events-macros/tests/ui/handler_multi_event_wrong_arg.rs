@@ -0,0 +1,16 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerJoined;
+
+#[derive(Debug, Clone)]
+struct PlayerLeft;
+
+struct Lobby;
+
+impl Lobby {
+    #[event_handler(PlayerJoined, PlayerLeft)]
+    fn on_roster(&mut self, _evt: &PlayerJoined) {}
+}
+
+fn main() {}
@@ -0,0 +1,81 @@
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(Debug, Clone)]
+struct Pause;
+
+#[derive(Debug, Clone)]
+struct Resume;
+
+#[derive(EventSubscriber)]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+    ticks: usize,
+    seen: usize,
+}
+
+impl Enemy {
+    #[event_handler(Tick, respect_active)]
+    fn on_tick(&mut self, _evt: &Tick) {
+        self.ticks += 1;
+    }
+
+    #[event_handler(Tick)]
+    fn count(&mut self, _evt: &Tick) {
+        self.seen += 1;
+    }
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let enemy = Arc::new(Mutex::new(Enemy {
+        id: 1,
+        active: true,
+        subscriptions: SubscriptionSet::new(&bus),
+        ticks: 0,
+        seen: 0,
+    }));
+    // Subscribed through a set of their own, so deactivating doesn't
+    // pause them: only `respect_active` keeps `on_tick` out.
+    let mut handlers = SubscriptionSet::new(&bus);
+    Enemy::subscribe_on_tick(&enemy, &mut handlers).unwrap();
+    Enemy::subscribe_count(&enemy, &mut handlers).unwrap();
+    let e = enemy.clone();
+    bus.subscribe::<Pause, _>(move |_| e.lock().unwrap().set_active(false));
+    let e = enemy.clone();
+    bus.subscribe::<Resume, _>(move |_| e.lock().unwrap().set_active(true));
+
+    // Toggled by events in the middle of one batch.
+    bus.dispatch(Tick);
+    bus.dispatch(Tick);
+    bus.dispatch(Pause);
+    bus.dispatch(Tick);
+    bus.dispatch(Resume);
+    bus.dispatch(Tick);
+    bus.process();
+    {
+        let enemy = enemy.lock().unwrap();
+        assert_eq!((enemy.ticks, enemy.seen), (3, 4));
+    }
+
+    // And between batches.
+    enemy.lock().unwrap().set_active(false);
+    bus.dispatch(Tick);
+    bus.process();
+    enemy.lock().unwrap().set_active(true);
+    bus.dispatch(Tick);
+    bus.process();
+    let mut enemy = enemy.lock().unwrap();
+    assert_eq!((enemy.ticks, enemy.seen), (4, 6));
+
+    // Called directly, the method runs whether active or not.
+    enemy.set_active(false);
+    enemy.on_tick(&Tick);
+    assert_eq!(enemy.ticks, 5);
+}
@@ -0,0 +1,20 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(EventSubscriber)]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+}
+
+impl Enemy {
+    #[event_handler(Tick, respect_active)]
+    fn on_tick(&mut self, _evt: &Tick) -> bool {
+        true
+    }
+}
+
+fn main() {}
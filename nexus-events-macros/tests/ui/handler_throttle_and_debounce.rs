@@ -0,0 +1,13 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+struct Player;
+
+impl Player {
+    #[event_handler(Tick, throttle_ms = 16, debounce_ms = 100)]
+    fn on_tick(&mut self, _evt: &Tick) {}
+}
+
+fn main() {}
@@ -0,0 +1,51 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct MouseMoved {
+    x: i32,
+}
+
+#[derive(Default)]
+struct Cursor {
+    // Every `x` each handler was called with.
+    drawn: Vec<i32>,
+    settled: Vec<i32>,
+}
+
+impl Cursor {
+    #[event_handler(MouseMoved, throttle_ms = 60000)]
+    fn draw(&mut self, evt: &MouseMoved) {
+        self.drawn.push(evt.x);
+    }
+
+    #[event_handler(MouseMoved, debounce_ms = 20)]
+    fn settle(&mut self, evt: &MouseMoved) {
+        self.settled.push(evt.x);
+    }
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let cursor = Arc::new(Mutex::new(Cursor::default()));
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Cursor::subscribe_draw(&cursor, &mut subscriptions).unwrap();
+    Cursor::subscribe_settle(&cursor, &mut subscriptions).unwrap();
+
+    for x in 0..5 {
+        bus.publish(MouseMoved { x }).unwrap();
+    }
+    // Throttled: only the first one gets through.
+    assert_eq!(cursor.lock().unwrap().drawn, [0]);
+
+    // Debounced: only the last one, once the burst is over.
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while cursor.lock().unwrap().settled.is_empty() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(cursor.lock().unwrap().settled, [4]);
+}
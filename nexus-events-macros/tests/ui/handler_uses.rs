@@ -0,0 +1,18 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+struct Player {
+    position: f32,
+    velocity: f32,
+}
+
+impl Player {
+    #[event_handler(Tick, uses(position, velocity))]
+    fn on_tick(&mut self, _evt: &Tick) {
+        self.position += self.velocity;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(Debug, Clone)]
+struct Hit;
+
+struct Player;
+
+impl Player {
+    #[event_handler(Tick)]
+    fn on_tick(&mut self, _evt: &Hit) {}
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+struct Player;
+
+#[event_handlers]
+impl Player {
+    #[event_handler(Tick)]
+    #[event_handler(Tick)]
+    fn on_tick(&mut self, _evt: &Tick) {}
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+struct Player;
+
+#[event_handlers]
+impl Player {
+    #[event_handler(Tick)]
+    fn on_tick(&mut self, _evt: &Tick) {}
+
+    fn subscribe_on_tick(&self) {}
+}
+
+fn main() {}
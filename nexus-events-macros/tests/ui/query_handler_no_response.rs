@@ -0,0 +1,13 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct GetPotionCount;
+
+impl Query for GetPotionCount {
+    type Response = u32;
+}
+
+#[query_handler(GetPotionCount)]
+fn potion_count(_q: &GetPotionCount) {}
+
+fn main() {}
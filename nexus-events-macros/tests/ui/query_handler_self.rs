@@ -0,0 +1,19 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct GetPotionCount;
+
+impl Query for GetPotionCount {
+    type Response = u32;
+}
+
+struct Inventory;
+
+impl Inventory {
+    #[query_handler(GetPotionCount)]
+    fn potion_count(&self, _q: &GetPotionCount) -> u32 {
+        3
+    }
+}
+
+fn main() {}
@@ -0,0 +1,69 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved {
+    x: i32,
+}
+
+struct Player {
+    scene: SharedEventBus,
+    global: SharedEventBus,
+}
+
+impl EventEmitter for Player {
+    type Bus = SharedEventBus;
+
+    fn sender_named(&self, name: &str) -> Option<&SharedEventBus> {
+        match name {
+            "scene" => Some(&self.scene),
+            "global" => Some(&self.global),
+            _ => None,
+        }
+    }
+}
+
+impl Player {
+    #[event_sender(PlayerMoved, bus = "scene")]
+    fn step(&mut self, x: i32) {}
+
+    #[event_sender(PlayerMoved, map(x = "x * 10"), bus = "global")]
+    fn teleport(&self, x: i32) {}
+
+    #[event_sender(PlayerMoved, bus = "global")]
+    fn try_move(&self, x: i32) -> Option<PlayerMoved> {
+        (x >= 0).then_some(PlayerMoved { x })
+    }
+
+    #[event_sender(PlayerMoved, bus = "nowhere")]
+    fn lost(&self, x: i32) -> EmitControl<PlayerMoved> {
+        EmitControl::Send(PlayerMoved { x })
+    }
+}
+
+fn counter(bus: &SharedEventBus) -> Arc<AtomicUsize> {
+    let count = Arc::new(AtomicUsize::new(0));
+    let c = count.clone();
+    bus.subscribe(move |_: &PlayerMoved| {
+        c.fetch_add(1, Ordering::SeqCst);
+    });
+    count
+}
+
+fn main() {
+    let mut player = Player { scene: SharedEventBus::new(), global: SharedEventBus::new() };
+    let (scene, global) = (counter(&player.scene), counter(&player.global));
+
+    player.step(1);
+    player.teleport(2);
+    assert!(player.try_move(3));
+    assert!(!player.try_move(-1));
+    assert!(!player.lost(4));
+    player.scene.process();
+    player.global.process();
+
+    assert_eq!(scene.load(Ordering::SeqCst), 1);
+    assert_eq!(global.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,11 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved {
+    x: i32,
+}
+
+#[event_sender(PlayerMoved, bus = "scene")]
+fn move_to(x: i32) {}
+
+fn main() {}
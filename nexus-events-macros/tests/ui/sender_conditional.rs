@@ -0,0 +1,45 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved {
+    x: i32,
+}
+
+struct Player {
+    max_x: i32,
+}
+
+impl Player {
+    #[event_sender(PlayerMoved)]
+    fn move_to(&self, x: i32) -> EmitControl<PlayerMoved> {
+        if x > self.max_x {
+            return EmitControl::Skip;
+        }
+        EmitControl::Send(PlayerMoved { x })
+    }
+
+    #[event_sender(PlayerMoved)]
+    fn step(&mut self, dx: Option<i32>) -> Option<PlayerMoved> {
+        let x = dx? + 1;
+        Some(PlayerMoved { x })
+    }
+}
+
+fn main() {
+    let moves = Arc::new(AtomicUsize::new(0));
+    let counter = moves.clone();
+    subscribe(move |_: &PlayerMoved| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let mut player = Player { max_x: 10 };
+    assert!(player.move_to(3));
+    assert!(!player.move_to(30));
+    assert!(player.step(Some(1)));
+    assert!(!player.step(None));
+    process_events();
+    assert_eq!(moves.load(Ordering::SeqCst), 2);
+}
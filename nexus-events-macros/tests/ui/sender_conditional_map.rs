@@ -0,0 +1,17 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved {
+    x: i32,
+}
+
+struct Player;
+
+impl Player {
+    #[event_sender(PlayerMoved, map(x = "x + 1"))]
+    fn move_to(&self, x: i32) -> Option<PlayerMoved> {
+        Some(PlayerMoved { x })
+    }
+}
+
+fn main() {}
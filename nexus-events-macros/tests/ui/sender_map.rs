@@ -0,0 +1,34 @@
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerMoved {
+    player_id: String,
+    x: f32,
+    y: f32,
+    running: bool,
+}
+
+struct Player {
+    id: String,
+}
+
+impl Player {
+    #[event_sender(PlayerMoved, map(player_id = "self.id.clone()", x = "pos.0", y = "pos.1"))]
+    fn move_to(&self, pos: (f32, f32), running: bool) {}
+}
+
+fn main() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    subscribe(move |ev: &PlayerMoved| sink.lock().unwrap().push(ev.clone()));
+
+    Player { id: "p1".into() }.move_to((1.0, 2.0), true);
+    process_events();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].player_id, "p1");
+    assert_eq!((seen[0].x, seen[0].y, seen[0].running), (1.0, 2.0, true));
+}
@@ -0,0 +1,17 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Attack {
+    damage: u32,
+}
+
+struct Enemy {
+    strength: u32,
+}
+
+impl Enemy {
+    #[event_sender(Attack, map(damage = "self.strength * 2", critical = "true"))]
+    fn attack(&self) {}
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Attack {
+    damage: u32,
+    critical: bool,
+}
+
+struct Enemy;
+
+impl Enemy {
+    #[event_sender(Attack)]
+    fn attack(&self, damage: u32) {
+        let _ = damage;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Moved {
+    x: f32,
+    y: f32,
+}
+
+struct Player;
+
+impl Player {
+    #[event_sender(Moved)]
+    fn move_to(&self, (x, y): (f32, f32)) {
+        let _ = (x, y);
+    }
+}
+
+fn main() {}
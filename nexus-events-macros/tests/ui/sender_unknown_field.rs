@@ -0,0 +1,17 @@
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Attack {
+    damage: u32,
+}
+
+struct Enemy;
+
+impl Enemy {
+    #[event_sender(Attack)]
+    fn attack(&self, damage: u32, critical: bool) {
+        let _ = (damage, critical);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,54 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Hit {
+    damage: u32,
+}
+
+#[derive(EventSubscriber)]
+#[event_subscriber(skip_active)]
+struct Enemy {
+    id: u32,
+    subscriptions: SubscriptionSet,
+}
+
+fn spawn(bus: &SharedEventBus, id: u32, hits: &Arc<AtomicUsize>) -> Enemy {
+    let mut enemy = Enemy { id, subscriptions: SubscriptionSet::new(bus) };
+    enemy.bind_address();
+    let hits = hits.clone();
+    enemy.subscriptions_mut().subscribe::<Hit, _>(move |hit| {
+        hits.fetch_add(hit.damage as usize, Ordering::SeqCst);
+    });
+    enemy
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let (first_hits, second_hits) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let _first = spawn(&bus, 1, &first_hits);
+    let _second = spawn(&bus, 2, &second_hits);
+    let broadcast = Arc::new(AtomicUsize::new(0));
+    let counter = broadcast.clone();
+    bus.subscribe(move |_: &Hit| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    bus.dispatch_to(&2u32, Hit { damage: 5 });
+    bus.process();
+    bus.publish_to(&1u32, Hit { damage: 3 }).unwrap();
+    assert_eq!(first_hits.load(Ordering::SeqCst), 3);
+    assert_eq!(second_hits.load(Ordering::SeqCst), 5);
+    assert_eq!(broadcast.load(Ordering::SeqCst), 0);
+
+    // Addressed handlers still get ordinary events, and an id of another
+    // type is another address.
+    bus.dispatch(Hit { damage: 1 });
+    bus.dispatch_to(&1u64, Hit { damage: 100 });
+    bus.process();
+    assert_eq!(first_hits.load(Ordering::SeqCst), 4);
+    assert_eq!(second_hits.load(Ordering::SeqCst), 6);
+    assert_eq!(broadcast.load(Ordering::SeqCst), 1);
+}
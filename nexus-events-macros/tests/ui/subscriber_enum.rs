@@ -0,0 +1,9 @@
+use nexus_events::prelude::*;
+
+#[derive(EventSubscriber)]
+enum Enemy {
+    Grunt,
+    Boss,
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use nexus_events::prelude::*;
+
+trait Item: Send + 'static {}
+
+impl Item for u32 {}
+
+#[derive(EventSubscriber)]
+#[event_subscriber(id = "owner")]
+struct Inventory<T: Item, const N: usize>
+where
+    T: Clone,
+{
+    owner: u64,
+    active: bool,
+    subscriptions: SubscriptionSet,
+    slots: [Option<T>; N],
+}
+
+fn id_of<S: EventSubscriber>(s: &S) -> &S::Id {
+    s.subscriber_id()
+}
+
+fn main() {
+    let inventory = Inventory::<u32, 4> {
+        owner: 7,
+        active: true,
+        subscriptions: SubscriptionSet::new(&SharedEventBus::new()),
+        slots: [None; 4],
+    };
+    assert_eq!(*id_of(&inventory), 7);
+    assert!(inventory.is_active());
+    assert!(inventory.slots.iter().all(Option::is_none));
+}
@@ -0,0 +1,79 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(EventSubscriber)]
+#[event_subscriber(lifecycle, register = "subscribe_handlers")]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+    ticks: Arc<AtomicUsize>,
+}
+
+impl Enemy {
+    fn subscribe_handlers(&mut self) {
+        let ticks = self.ticks.clone();
+        self.subscriptions.subscribe::<Tick, _>(move |_| {
+            ticks.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+}
+
+#[derive(EventSubscriber)]
+#[event_subscriber(skip_active, lifecycle)]
+struct Hud {
+    id: String,
+    subscriptions: SubscriptionSet,
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let lifecycle = Arc::new(Mutex::new(Vec::new()));
+    let log = lifecycle.clone();
+    bus.subscribe::<ComponentActivated<u32>, _>(move |ev| {
+        log.lock().unwrap().push(format!("+{}", ev.id));
+    });
+    let log = lifecycle.clone();
+    bus.subscribe::<ComponentDeactivated<u32>, _>(move |ev| {
+        assert!(ev.component.ends_with("Enemy"));
+        log.lock().unwrap().push(format!("-{}", ev.id));
+    });
+
+    // Starts out on a bus of its own, inactive.
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut enemy = Enemy {
+        id: 7,
+        active: false,
+        subscriptions: SubscriptionSet::new(&SharedEventBus::new()),
+        ticks: ticks.clone(),
+    };
+
+    enemy.activate(&bus).unwrap();
+    assert!(enemy.active);
+    assert_eq!(enemy.subscriptions().len(), 1);
+    bus.publish(Tick).unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+
+    // Activating again replaces the handlers instead of doubling them.
+    enemy.activate(&bus).unwrap();
+    bus.publish(Tick).unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 2);
+
+    enemy.deactivate().unwrap();
+    assert!(!enemy.is_active());
+    assert!(enemy.subscriptions().is_empty());
+    bus.publish(Tick).unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 2);
+    assert_eq!(*lifecycle.lock().unwrap(), ["+7", "+7", "-7"]);
+
+    let mut hud = Hud { id: "hud".to_string(), subscriptions: SubscriptionSet::new(&bus) };
+    hud.deactivate().unwrap();
+    assert!(!hud.is_active());
+    hud.activate(&bus).unwrap();
+    assert!(hud.is_active());
+}
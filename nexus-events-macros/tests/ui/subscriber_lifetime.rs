@@ -0,0 +1,16 @@
+use nexus_events::prelude::*;
+
+#[derive(EventSubscriber)]
+#[event_subscriber(subscriptions = "subs", skip_active)]
+struct Label<'a, T: ?Sized> {
+    id: &'a T,
+    subs: SubscriptionSet,
+}
+
+fn main() {
+    let name = String::from("hud");
+    let label = Label::<str> { id: &name, subs: SubscriptionSet::new(&SharedEventBus::new()) };
+    assert_eq!(*label.subscriber_id(), "hud");
+    assert!(label.is_active());
+    assert!(label.subscriptions().is_empty());
+}
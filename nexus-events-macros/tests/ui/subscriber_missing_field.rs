@@ -0,0 +1,10 @@
+use nexus_events::prelude::*;
+
+#[derive(EventSubscriber)]
+struct Enemy {
+    entity_id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use nexus_events::prelude::*;
+
+#[derive(EventSubscriber)]
+#[event_subscriber(register = "subscribe_handlers")]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(EventSubscriber)]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+}
+
+#[derive(EventSubscriber)]
+#[event_subscriber(skip_active)]
+struct Hud {
+    id: u32,
+    subscriptions: SubscriptionSet,
+}
+
+fn main() {
+    let bus = SharedEventBus::new();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut enemy = Enemy { id: 1, active: true, subscriptions: SubscriptionSet::new(&bus) };
+    let counter = ticks.clone();
+    enemy.subscriptions_mut().subscribe::<Tick, _>(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    enemy.set_active(false);
+    assert!(!enemy.active);
+    assert!(enemy.subscriptions().is_paused());
+    bus.dispatch(Tick);
+    bus.process();
+    assert_eq!(ticks.load(Ordering::SeqCst), 0);
+
+    enemy.set_active(true);
+    assert!(enemy.is_active());
+    bus.dispatch(Tick);
+    bus.process();
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+
+    let mut hud = Hud { id: 2, subscriptions: SubscriptionSet::new(&bus) };
+    hud.set_active(false);
+    assert!(!hud.is_active());
+    assert_eq!(*hud.subscriber_id(), 2);
+}
@@ -0,0 +1,11 @@
+use nexus_events::prelude::*;
+
+#[derive(EventSubscriber)]
+#[event_subscriber(skip_active, active = "enabled")]
+struct Enemy {
+    id: u32,
+    enabled: bool,
+    subscriptions: SubscriptionSet,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use nexus_events::prelude::*;
+
+#[derive(EventSubscriber)]
+#[event_subscriber(name = "entity_id")]
+struct Enemy {
+    entity_id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use nexus_events::prelude::*;
+
+#[derive(EventSubscriber)]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: Vec<HandlerId>,
+}
+
+fn main() {}
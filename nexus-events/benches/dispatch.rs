@@ -0,0 +1,197 @@
+// The dispatch path, one step at a time, so a regression shows up in the
+// step that caused it:
+//
+// - `publish`: `dispatch` alone, and with the inbox moved into the queue;
+// - `publish_process`: a dispatch delivered by `process`;
+// - `churn`: subscribing and unsubscribing next to existing handlers;
+// - `fan_out`: one event delivered to 1..1000 handlers;
+// - `event_types`: the snapshot and delivery cost on a bus that knows many
+//   event types;
+// - `contended`: several threads dispatching at once;
+// - `event_type_ids`: emitting a plain struct next to a `define_event!`
+//   type, which is looked up by its dense id.
+//
+// Run with `cargo bench --bench dispatch`.
+
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nexus_events::core::bench_hooks;
+use nexus_events::core::SharedEventBus;
+
+#[derive(Clone)]
+struct Tick(#[allow(dead_code)] u64);
+
+nexus_events::define_event! {
+    #[allow(dead_code)]
+    struct Defined { n: u64 }
+}
+
+/// One of 256 distinct event types.
+struct Typed<const A: u8, const B: u8>;
+
+macro_rules! subscribe_types {
+    ($bus:expr; $($a:literal)*) => {{
+        $( subscribe_types!(@row $bus, $a; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15); )*
+    }};
+    (@row $bus:expr, $a:literal; $($b:literal)*) => {{
+        $( $bus.subscribe(|_: &Typed<$a, $b>| {}); )*
+    }};
+}
+
+/// A bus with a handler on each of `16 * rows` event types, `Typed<0, 0>`
+/// included.
+fn bus_with_types(rows: usize) -> SharedEventBus {
+    let bus = SharedEventBus::new();
+    match rows {
+        1 => subscribe_types!(bus; 0),
+        4 => subscribe_types!(bus; 0 1 2 3),
+        16 => subscribe_types!(bus; 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15),
+        _ => unreachable!("no bus with {} rows of types", rows),
+    }
+    bus
+}
+
+fn bus_with_handlers(count: usize) -> SharedEventBus {
+    let bus = SharedEventBus::new();
+    for _ in 0..count {
+        bus.subscribe(|ev: &Tick| {
+            black_box(ev);
+        });
+    }
+    bus
+}
+
+fn publish(c: &mut Criterion) {
+    let mut group = c.benchmark_group("publish");
+    let bus = bus_with_handlers(1);
+    // Queued events pile up between samples; drop them off the clock.
+    group.bench_function("dispatch", |b| {
+        b.iter_custom(|iters| {
+            let started = Instant::now();
+            for i in 0..iters {
+                bus.dispatch(Tick(i));
+            }
+            let elapsed = started.elapsed();
+            bench_hooks::discard_queued(&bus);
+            elapsed
+        })
+    });
+    group.bench_function("dispatch_and_admit", |b| {
+        b.iter_custom(|iters| {
+            let started = Instant::now();
+            for i in 0..iters {
+                bus.dispatch(Tick(i));
+                bench_hooks::admit_inbox(&bus);
+            }
+            let elapsed = started.elapsed();
+            bench_hooks::discard_queued(&bus);
+            elapsed
+        })
+    });
+    group.finish();
+}
+
+fn publish_process(c: &mut Criterion) {
+    let bus = bus_with_handlers(1);
+    c.bench_function("publish_process", |b| {
+        b.iter(|| {
+            bus.dispatch(Tick(1));
+            bus.process();
+        })
+    });
+}
+
+fn event_type_ids(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_type_ids");
+    let bus = bus_with_handlers(1);
+    bus.subscribe(|ev: &Defined| {
+        black_box(ev);
+    });
+    group.bench_function("foreign", |b| b.iter(|| bus.emit(Tick(1))));
+    group.bench_function("defined", |b| b.iter(|| bus.emit(Defined { n: 1 })));
+    group.finish();
+}
+
+fn churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("churn");
+    for existing in [0, 100, 1000] {
+        let bus = bus_with_handlers(existing);
+        group.bench_with_input(BenchmarkId::new("subscribe_unsubscribe", existing), &bus, |b, bus| {
+            b.iter(|| {
+                let id = bus.subscribe(|ev: &Tick| {
+                    black_box(ev);
+                });
+                bus.unsubscribe::<Tick>(id);
+            })
+        });
+    }
+    group.finish();
+}
+
+fn fan_out(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fan_out");
+    for handlers in [1, 10, 100, 1000] {
+        let bus = bus_with_handlers(handlers);
+        group.throughput(Throughput::Elements(handlers as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(handlers), &bus, |b, bus| {
+            b.iter(|| bus.emit(Tick(1)))
+        });
+    }
+    group.finish();
+}
+
+fn event_types(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_types");
+    for rows in [1, 4, 16] {
+        let bus = bus_with_types(rows);
+        let types = rows * 16;
+        group.bench_with_input(BenchmarkId::new("plan", types), &bus, |b, bus| {
+            b.iter(|| bench_hooks::plan::<Typed<0, 0>>(bus))
+        });
+        group.bench_with_input(BenchmarkId::new("publish_process", types), &bus, |b, bus| {
+            b.iter(|| {
+                bus.dispatch(Typed::<0, 0>);
+                bus.process();
+            })
+        });
+    }
+    group.finish();
+}
+
+fn contended(c: &mut Criterion) {
+    let mut group = c.benchmark_group("contended");
+    let bus = bus_with_handlers(1);
+    for publishers in [2, 4, 8] {
+        group.bench_with_input(BenchmarkId::from_parameter(publishers), &publishers, |b, &publishers| {
+            b.iter_custom(|iters| {
+                let per_thread = iters / publishers as u64 + 1;
+                let elapsed = thread::scope(|scope| {
+                    let threads: Vec<_> = (0..publishers)
+                        .map(|_| {
+                            scope.spawn(|| {
+                                let started = Instant::now();
+                                for i in 0..per_thread {
+                                    bus.dispatch(Tick(i));
+                                }
+                                started.elapsed()
+                            })
+                        })
+                        .collect();
+                    threads.into_iter()
+                        .map(|t| t.join().unwrap())
+                        .max()
+                        .unwrap_or(Duration::ZERO)
+                });
+                bench_hooks::discard_queued(&bus);
+                elapsed
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, publish, publish_process, event_type_ids, churn, fan_out, event_types, contended);
+criterion_main!(benches);
@@ -0,0 +1,50 @@
+// `publish` against `publish_parallel` for one event and 1000 handlers,
+// the situation `publish_parallel` is for: every enemy on a `GameTick`.
+// Each handler does a little work of its own (`work` rounds of hashing),
+// since with empty handlers the thread handoff is all there is to measure.
+//
+// Run with `cargo bench --bench parallel --features rayon`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use nexus_events::core::SharedEventBus;
+
+const HANDLERS: usize = 1000;
+
+#[derive(Clone)]
+struct GameTick(u64);
+
+fn bus_with_enemies(work: u64) -> SharedEventBus {
+    let bus = SharedEventBus::new();
+    for enemy in 0..HANDLERS as u64 {
+        bus.subscribe(move |tick: &GameTick| {
+            let mut hasher = DefaultHasher::new();
+            for round in 0..work {
+                (enemy, tick.0, round).hash(&mut hasher);
+            }
+            black_box(hasher.finish());
+        });
+    }
+    bus
+}
+
+fn game_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("game_tick");
+    group.throughput(Throughput::Elements(HANDLERS as u64));
+    for work in [0, 10, 100] {
+        let bus = bus_with_enemies(work);
+        group.bench_with_input(BenchmarkId::new("serial", work), &bus, |b, bus| {
+            b.iter(|| bus.publish(GameTick(1)))
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", work), &bus, |b, bus| {
+            b.iter(|| bus.publish_parallel(GameTick(1)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, game_tick);
+criterion_main!(benches);
@@ -0,0 +1,118 @@
+// Publishing while the bus is busy running handlers.
+//
+// Several threads publish as fast as they can while another thread keeps
+// processing with a deliberately slow handler. `SharedEventBus` publishes
+// through a lock-free inbox; the baseline is the previous design, where
+// publishing took the same mutex that is held while handlers run.
+//
+// Run with `cargo bench --bench publish_contention`.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nexus_events::core::SharedEventBus;
+
+const PUBLISHERS: usize = 4;
+const EVENTS_PER_PUBLISHER: usize = 50_000;
+const HANDLER_COST: Duration = Duration::from_micros(20);
+
+struct Tick(#[allow(dead_code)] u64);
+
+fn busy_wait(d: Duration) {
+    let start = Instant::now();
+    while start.elapsed() < d {
+        std::hint::spin_loop();
+    }
+}
+
+/// The old design: one mutex around queue and handlers.
+#[derive(Default)]
+struct MutexBus {
+    queue: VecDeque<Box<dyn Any + Send>>,
+}
+
+fn bench_mutex_bus() -> Duration {
+    let bus = Arc::new(Mutex::new(MutexBus::default()));
+    let done = Arc::new(AtomicBool::new(false));
+    let processor = {
+        let (bus, done) = (bus.clone(), done.clone());
+        thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                let mut bus = bus.lock().unwrap();
+                while let Some(_ev) = bus.queue.pop_front() {
+                    busy_wait(HANDLER_COST);
+                }
+            }
+        })
+    };
+    let started = Instant::now();
+    let publishers: Vec<_> = (0..PUBLISHERS)
+        .map(|_| {
+            let bus = bus.clone();
+            thread::spawn(move || {
+                for i in 0..EVENTS_PER_PUBLISHER {
+                    bus.lock().unwrap().queue.push_back(Box::new(Tick(i as u64)));
+                }
+            })
+        })
+        .collect();
+    for p in publishers {
+        p.join().unwrap();
+    }
+    let elapsed = started.elapsed();
+    done.store(true, Ordering::Relaxed);
+    processor.join().unwrap();
+    elapsed
+}
+
+fn bench_shared_bus() -> Duration {
+    let bus = SharedEventBus::new();
+    bus.subscribe::<Tick, _>(|_| busy_wait(HANDLER_COST));
+    let done = Arc::new(AtomicBool::new(false));
+    let processor = {
+        let (bus, done) = (bus.clone(), done.clone());
+        thread::spawn(move || {
+            while !done.load(Ordering::Relaxed) {
+                bus.process();
+            }
+        })
+    };
+    let started = Instant::now();
+    let publishers: Vec<_> = (0..PUBLISHERS)
+        .map(|_| {
+            let bus = bus.clone();
+            thread::spawn(move || {
+                for i in 0..EVENTS_PER_PUBLISHER {
+                    bus.dispatch(Tick(i as u64));
+                }
+            })
+        })
+        .collect();
+    for p in publishers {
+        p.join().unwrap();
+    }
+    let elapsed = started.elapsed();
+    done.store(true, Ordering::Relaxed);
+    processor.join().unwrap();
+    elapsed
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let total = (PUBLISHERS * EVENTS_PER_PUBLISHER) as f64;
+    println!(
+        "{:<24} {:>10.2?} to publish {} events ({:.0} ns/publish)",
+        name,
+        elapsed,
+        total as u64,
+        elapsed.as_nanos() as f64 / total
+    );
+}
+
+fn main() {
+    report("mutex (previous design)", bench_mutex_bus());
+    report("SharedEventBus", bench_shared_bus());
+}
@@ -0,0 +1,102 @@
+//! Bevy integration (the `bevy` feature).
+//!
+//! `NexusEventsPlugin` makes a `SharedEventBus` available as a resource
+//! (`Res<SharedEventBus>`) and processes it once per frame in `First`.
+//! `NexusAppExt` bridges individual event types between the bus and Bevy's
+//! `Events<T>`:
+//!
+//! - `bridge_to_bevy::<E>()`: bus events show up in `EventReader<E>` during
+//!   `PreUpdate` of the same frame they were processed in.
+//! - `bridge_from_bevy::<E>()`: events sent with `EventWriter<E>` are
+//!   dispatched on the bus in `PostUpdate` and handled next frame.
+//!
+//! Don't bridge the same type both ways; every event would bounce forever.
+
+use std::sync::{Arc, Mutex};
+
+use bevy_app::{App, First, Plugin, PostUpdate, PreUpdate};
+use bevy_ecs::event::{Event as BevyEvent, EventReader, EventWriter};
+use bevy_ecs::system::{Res, Resource};
+
+use crate::core::{Event, SharedEventBus};
+
+impl Resource for SharedEventBus {}
+
+/// Inserts the bus as a resource and processes it at the start of every
+/// frame. Uses the global bus unless built with `NexusEventsPlugin::with_bus`.
+pub struct NexusEventsPlugin {
+    bus: SharedEventBus,
+}
+
+impl Default for NexusEventsPlugin {
+    fn default() -> Self {
+        Self { bus: SharedEventBus::global() }
+    }
+}
+
+impl NexusEventsPlugin {
+    pub fn with_bus(bus: SharedEventBus) -> Self {
+        Self { bus }
+    }
+}
+
+impl Plugin for NexusEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.bus.clone())
+            .add_systems(First, |bus: Res<SharedEventBus>| bus.process());
+    }
+}
+
+// Bus handlers run outside the ECS, so bridged events wait here until the
+// `PreUpdate` system moves them into `Events<E>`.
+#[derive(Resource)]
+struct Inbox<E: Send + Sync + 'static>(Arc<Mutex<Vec<E>>>);
+
+pub trait NexusAppExt {
+    /// Forwards every `E` processed on the bus into Bevy's `Events<E>`.
+    fn bridge_to_bevy<E>(&mut self) -> &mut Self
+    where
+        E: BevyEvent + Event + Clone;
+
+    /// Dispatches every `E` sent through Bevy's `Events<E>` on the bus.
+    fn bridge_from_bevy<E>(&mut self) -> &mut Self
+    where
+        E: BevyEvent + Event + Clone;
+}
+
+impl NexusAppExt for App {
+    fn bridge_to_bevy<E>(&mut self) -> &mut Self
+    where
+        E: BevyEvent + Event + Clone
+    {
+        let bus = self.world().get_resource::<SharedEventBus>()
+            .expect("add NexusEventsPlugin before bridging events")
+            .clone();
+        let inbox: Arc<Mutex<Vec<E>>> = Arc::default();
+        let sink = inbox.clone();
+        bus.subscribe::<E, _>(move |ev: &E| {
+            if let Ok(mut sink) = sink.lock() {
+                sink.push(ev.clone());
+            }
+        });
+        self.add_event::<E>()
+            .insert_resource(Inbox(inbox))
+            .add_systems(PreUpdate, |inbox: Res<Inbox<E>>, mut writer: EventWriter<E>| {
+                if let Ok(mut pending) = inbox.0.lock() {
+                    writer.send_batch(pending.drain(..));
+                }
+            })
+    }
+
+    fn bridge_from_bevy<E>(&mut self) -> &mut Self
+    where
+        E: BevyEvent + Event + Clone
+    {
+        self.add_event::<E>()
+            .add_systems(PostUpdate, |bus: Res<SharedEventBus>, mut reader: EventReader<E>| {
+                for ev in reader.read() {
+                    bus.dispatch(ev.clone());
+                }
+            })
+    }
+}
@@ -0,0 +1,181 @@
+//! Forwarding selected event types from one bus to another, e.g. from the
+//! simulation thread's bus to the render thread's.
+//!
+//! ```ignore
+//! let bridge = EventBridge::connect(&sim_bus, &render_bus)
+//!     .forward::<RenderCommand>()
+//!     .forward::<CameraMoved>();
+//! ```
+//!
+//! A plain bridge dispatches a clone of every forwarded event on the target
+//! bus as soon as the source bus processes it; the target handles it on its
+//! next `process`, on whatever thread calls that. `connect_buffered` puts a
+//! bounded queue in between instead, which the target side empties with
+//! `pump`, and `Backpressure` decides what happens when the source outruns
+//! it. Dropping the bridge stops forwarding.
+//!
+//! Don't forward the same type both ways between two buses; every event
+//! would bounce forever.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+use crate::core::{Event, SharedEventBus, SubscriptionSet};
+
+/// What a buffered bridge does with an event when its queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Drop the event being forwarded.
+    #[default]
+    DropNewest,
+    /// Drop the oldest queued event to make room.
+    DropOldest,
+    /// Hold up the source bus's `process` until `pump` makes room. Only
+    /// safe when another thread pumps.
+    Block,
+}
+
+struct Buffer {
+    queue: Mutex<VecDeque<Box<dyn Event>>>,
+    space: Condvar,
+    capacity: usize,
+    policy: Backpressure,
+    dropped: AtomicU64,
+    // Set when the bridge is dropped, so blocked forwarders give up.
+    closed: AtomicBool,
+}
+
+impl Buffer {
+    fn push(&self, ev: Box<dyn Event>) {
+        let Ok(mut queue) = self.queue.lock() else { return };
+        if queue.len() >= self.capacity {
+            match self.policy {
+                Backpressure::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Backpressure::DropOldest => {
+                    queue.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Backpressure::Block => {
+                    let full = |q: &mut VecDeque<Box<dyn Event>>| {
+                        q.len() >= self.capacity && !self.closed.load(Ordering::Relaxed)
+                    };
+                    let Ok(waited) = self.space.wait_while(queue, full) else { return };
+                    if self.closed.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    queue = waited;
+                }
+            }
+        }
+        queue.push_back(ev);
+    }
+}
+
+/// Forwards the chosen event types from a source bus to a target bus for as
+/// long as it lives.
+pub struct EventBridge {
+    to: SharedEventBus,
+    subscriptions: SubscriptionSet,
+    buffer: Option<Arc<Buffer>>,
+}
+
+impl EventBridge {
+    /// A bridge that dispatches forwarded events on `to` right away.
+    pub fn connect(from: &SharedEventBus, to: &SharedEventBus) -> Self {
+        Self { to: to.clone(), subscriptions: SubscriptionSet::new(from), buffer: None }
+    }
+
+    /// A bridge that queues up to `capacity` forwarded events until `pump`
+    /// dispatches them on `to`.
+    pub fn connect_buffered(
+        from: &SharedEventBus,
+        to: &SharedEventBus,
+        capacity: usize,
+        policy: Backpressure,
+    ) -> Self {
+        let buffer = Buffer {
+            queue: Mutex::new(VecDeque::with_capacity(capacity)),
+            space: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+            dropped: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        };
+        Self {
+            to: to.clone(),
+            subscriptions: SubscriptionSet::new(from),
+            buffer: Some(Arc::new(buffer)),
+        }
+    }
+
+    /// Forwards every `E` the source bus processes.
+    pub fn forward<E: Event + Clone + 'static>(self) -> Self {
+        self.forward_if::<E, _>(|_| true)
+    }
+
+    /// Forwards the `E`s for which `filter` returns true.
+    pub fn forward_if<E: Event + Clone + 'static, F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static
+    {
+        match &self.buffer {
+            Some(buffer) => {
+                let buffer = buffer.clone();
+                self.subscriptions.subscribe::<E, _>(move |ev: &E| {
+                    if filter(ev) {
+                        buffer.push(Box::new(ev.clone()));
+                    }
+                });
+            }
+            None => {
+                let to = self.to.clone();
+                self.subscriptions.subscribe::<E, _>(move |ev: &E| {
+                    if filter(ev) {
+                        to.dispatch(ev.clone());
+                    }
+                });
+            }
+        }
+        self
+    }
+
+    /// Dispatches everything queued on the target bus and returns how many
+    /// events that was. Call it on the target side before processing; plain
+    /// bridges have nothing to pump.
+    pub fn pump(&self) -> usize {
+        let Some(buffer) = &self.buffer else { return 0 };
+        let drained: Vec<Box<dyn Event>> = match buffer.queue.lock() {
+            Ok(mut queue) => queue.drain(..).collect(),
+            Err(_) => return 0,
+        };
+        buffer.space.notify_all();
+        let count = drained.len();
+        self.to.requeue(drained);
+        count
+    }
+
+    /// Events waiting for `pump`.
+    pub fn pending(&self) -> usize {
+        self.buffer.as_ref()
+            .and_then(|b| b.queue.lock().ok().map(|q| q.len()))
+            .unwrap_or(0)
+    }
+
+    /// Events the backpressure policy has dropped so far.
+    pub fn dropped(&self) -> u64 {
+        self.buffer.as_ref().map_or(0, |b| b.dropped.load(Ordering::Relaxed))
+    }
+}
+
+impl Drop for EventBridge {
+    fn drop(&mut self) {
+        if let Some(buffer) = &self.buffer {
+            buffer.closed.store(true, Ordering::Relaxed);
+            buffer.space.notify_all();
+        }
+    }
+}
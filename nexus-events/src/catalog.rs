@@ -0,0 +1,156 @@
+//! Every event type `define_event!` declared anywhere in the binary, with
+//! its fields, for inspectors, editors and modding APIs.
+//!
+//! ```ignore
+//! for ev in nexus_events::catalog::all() {
+//!     println!("{}", ev.name);
+//!     for field in ev.fields {
+//!         println!("    {}: {}", field.name, field.ty);
+//!     }
+//! }
+//! ```
+//!
+//! Structs list their named fields in declaration order; unit structs and
+//! enums have none. Generic events aren't cataloged, since they have no
+//! single name.
+//!
+//! Events are known by their bare name, so two crates (or modules) that
+//! both declare a `PlayerMoved` claim the same name, and only one of them
+//! gets it in `EventTypeRegistry`; the other fails to decode or downcast
+//! wherever the name is used. `conflicts()` lists every such clash, telling
+//! copies with the same fields from different events that happen to share
+//! a name, and `check()` turns the first one into an error for startup:
+//!
+//! ```ignore
+//! nexus_events::catalog::check()?;
+//! ```
+
+use std::any::TypeId;
+use std::sync::OnceLock;
+
+use crate::core::{wire_id, EventError, EventResult, EventTypeRegistry};
+
+/// One field of a cataloged event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldDescriptor {
+    pub name: &'static str,
+    /// The field's type as written in `define_event!`, e.g. `Vec<u32>`.
+    pub ty: &'static str,
+}
+
+/// An event type declared with `define_event!`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventDescriptor {
+    /// The name it was declared with, which is also its wire name.
+    pub name: &'static str,
+    pub fields: &'static [FieldDescriptor],
+    pub type_id: TypeId,
+    /// The full path of the type, telling apart events of the same name.
+    pub type_name: &'static str,
+    /// A hash of the fields (names and types as written) or, for an enum,
+    /// of its variants as written, doc comments included. Declarations of
+    /// the same shape hash alike.
+    pub schema_hash: u64,
+}
+
+/// What `define_event!` submits for each type. `TypeId::of` isn't usable in
+/// a static, so the id is resolved when the catalog is first read.
+#[doc(hidden)]
+pub struct CatalogEntry {
+    name: &'static str,
+    fields: &'static [FieldDescriptor],
+    variants: &'static str,
+    type_id: fn() -> TypeId,
+    type_name: fn() -> &'static str,
+}
+
+impl CatalogEntry {
+    pub const fn new<E: 'static>(name: &'static str, fields: &'static [FieldDescriptor], variants: &'static str) -> Self {
+        Self { name, fields, variants, type_id: TypeId::of::<E>, type_name: std::any::type_name::<E> }
+    }
+
+    fn describe(&self) -> EventDescriptor {
+        let mut schema = String::from(self.variants);
+        for field in self.fields {
+            schema.push_str(&format!("{}: {},", field.name, field.ty));
+        }
+        EventDescriptor {
+            name: self.name,
+            fields: self.fields,
+            type_id: (self.type_id)(),
+            type_name: (self.type_name)(),
+            schema_hash: wire_id(&schema),
+        }
+    }
+}
+
+inventory::collect!(CatalogEntry);
+
+/// Every cataloged event type, sorted by name.
+pub fn all() -> &'static [EventDescriptor] {
+    static ALL: OnceLock<Vec<EventDescriptor>> = OnceLock::new();
+    ALL.get_or_init(|| {
+        let mut all: Vec<EventDescriptor> = inventory::iter::<CatalogEntry>
+            .into_iter()
+            .map(CatalogEntry::describe)
+            .collect();
+        all.sort_by_key(|ev| (ev.name, ev.type_name));
+        all
+    })
+}
+
+/// The cataloged event type called `name`, if there is one.
+pub fn find(name: &str) -> Option<&'static EventDescriptor> {
+    all().iter().find(|ev| ev.name == name)
+}
+
+/// Two event types declared under the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventConflict {
+    pub name: &'static str,
+    /// The one `EventTypeRegistry` resolves the name to.
+    pub registered: &'static EventDescriptor,
+    /// One that lost the name.
+    pub other: &'static EventDescriptor,
+}
+
+impl EventConflict {
+    /// Whether both have the same fields: most likely one event declared
+    /// twice, say by two versions of a crate, rather than two events.
+    pub fn same_schema(&self) -> bool {
+        self.registered.schema_hash == self.other.schema_hash
+    }
+}
+
+/// Every name declared by more than one event type, once for each type
+/// that lost it, sorted by name.
+pub fn conflicts() -> &'static [EventConflict] {
+    static CONFLICTS: OnceLock<Vec<EventConflict>> = OnceLock::new();
+    CONFLICTS.get_or_init(|| {
+        let mut conflicts = Vec::new();
+        for same_name in all().chunk_by(|a, b| a.name == b.name).filter(|evs| evs.len() > 1) {
+            let winner = EventTypeRegistry::type_id(same_name[0].name);
+            let registered = same_name.iter().find(|ev| Some(ev.type_id) == winner).unwrap_or(&same_name[0]);
+            conflicts.extend(
+                same_name.iter()
+                    .filter(|ev| ev.type_id != registered.type_id)
+                    .map(|other| EventConflict { name: other.name, registered, other }),
+            );
+        }
+        conflicts
+    })
+}
+
+/// `EventError::ConflictingEventName` for the first of `conflicts()`, if
+/// there is one.
+pub fn check() -> EventResult<()> {
+    match conflicts().first() {
+        Some(conflict) => Err(EventError::ConflictingEventName {
+            name: conflict.name,
+            registered: conflict.registered.type_name,
+            other: conflict.other.type_name,
+            same_schema: conflict.same_schema(),
+        }),
+        None => Ok(()),
+    }
+}
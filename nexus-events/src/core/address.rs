@@ -0,0 +1,43 @@
+// Addressed delivery: events meant for one component rather than everyone
+// listening. A `SubscriptionSet` given an address (usually the component's
+// `EventSubscriber` id, see `bind_address`) registers its handlers under
+// it, and `dispatch_to` / `publish_to` deliver only to the handlers
+// registered under the address they name. Handlers without an address never
+// see addressed events; addressed handlers still see ordinary ones.
+//
+// An address is a 64-bit hash of the id and its type, so `1u32` and `1u64`
+// are different addresses.
+
+use std::any::TypeId;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use super::{Event, EventMeta, EventResult, SharedEventBus};
+
+/// The address handlers subscribed under `id` are registered with.
+pub(crate) fn address_of<Id: Hash + ?Sized + 'static>(id: &Id) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    TypeId::of::<Id>().hash(&mut hasher);
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl SharedEventBus {
+    /// Queues `ev` for the handlers subscribed under `to` only. It is
+    /// delivered by the next `process`, like a plain `dispatch`.
+    pub fn dispatch_to<Id: Hash + ?Sized + 'static, E: Event + 'static>(&self, to: &Id, ev: E) {
+        let meta = EventMeta { to: Some(address_of(to)), ..EventMeta::now(None) };
+        self.post(Box::new(ev), meta);
+    }
+
+    /// Delivers `ev` now to the handlers subscribed under `to` only, like
+    /// `publish`.
+    pub fn publish_to<Id: Hash + ?Sized + 'static, E: Event + 'static>(&self, to: &Id, ev: E) -> EventResult<()> {
+        let ev = match self.send_command(ev) {
+            Ok(sent) => return sent,
+            Err(ev) => ev,
+        };
+        self.emit_dyn(&ev, Some(address_of(to)))?;
+        self.keep(Box::new(ev));
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+// Handlers too heavy to run inside `process` (pathfinding after a
+// `NavMeshDirty`, say). A background handler gets a clone of the event on
+// one of the bus's worker threads, so `process` returns as soon as the
+// clone is handed over. The pool is started by the first
+// `subscribe_background`, with `EventBusBuilder::background_threads`
+// workers, and stopped with `SharedEventBus::stop_background` (or
+// `shutdown`).
+//
+// Background handlers run concurrently with each other and with `process`,
+// in no particular order, and can't consume events.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use super::{Event, HandlerId, SharedEventBus};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The worker pool of a bus. Background handlers share `jobs`, so they
+/// all stop at `stop_background` and resume when a pool is started again.
+#[derive(Default)]
+pub(crate) struct Background {
+    jobs: Arc<Mutex<Option<Sender<Job>>>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl SharedEventBus {
+    /// Subscribes `f` to run on a worker thread with a clone of every `E`,
+    /// starting the worker pool if it isn't running. Unsubscribe with
+    /// `unsubscribe` as usual.
+    pub fn subscribe_background<E: Event + Clone + 'static, F>(&self, f: F) -> HandlerId
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        let jobs = self.start_background();
+        let f = Arc::new(f);
+        self.subscribe::<E, _>(move |ev: &E| {
+            let Ok(jobs) = jobs.lock() else { return };
+            if let Some(tx) = &*jobs {
+                let (f, ev) = (f.clone(), ev.clone());
+                let _ = tx.send(Box::new(move || f(&ev)));
+            }
+        })
+    }
+
+    fn start_background(&self) -> Arc<Mutex<Option<Sender<Job>>>> {
+        let (jobs, size) = match self.inner.lock() {
+            Ok(bus) => (bus.background.jobs.clone(), bus.threads.background_threads),
+            Err(_) => return Arc::default(),
+        };
+        if jobs.lock().map_or(true, |tx| tx.is_some()) {
+            return jobs;
+        }
+        let size = size.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+        let (tx, rx) = mpsc::channel::<Job>();
+        let rx = Arc::new(Mutex::new(rx));
+        let workers: Vec<_> = (0..size.max(1))
+            .map(|_| {
+                let rx = rx.clone();
+                self.spawn_thread("background", move || loop {
+                    let job = match rx.lock() {
+                        Ok(rx) => rx.recv(),
+                        Err(_) => break,
+                    };
+                    let Ok(job) = job else { break };
+                    // A panicking handler shouldn't take its worker with it.
+                    let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                })
+                .expect("failed to spawn background worker thread")
+            })
+            .collect();
+        if let Ok(mut slot) = jobs.lock() {
+            // Another thread may have started a pool meanwhile; then ours
+            // exits as soon as `tx` is dropped here.
+            slot.get_or_insert(tx);
+        }
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.background.workers.extend(workers);
+        }
+        jobs
+    }
+
+    /// Stops the worker pool: background handlers stop receiving events,
+    /// and this waits for the ones already handed over to finish. A later
+    /// `subscribe_background` starts a new pool. Don't call it from a
+    /// background handler.
+    pub fn stop_background(&self) {
+        let workers = match self.inner.lock() {
+            Ok(mut bus) => {
+                if let Ok(mut jobs) = bus.background.jobs.lock() {
+                    jobs.take();
+                }
+                std::mem::take(&mut bus.background.workers)
+            }
+            Err(_) => return,
+        };
+        for worker in workers {
+            let _ = worker.join();
+        }
+    }
+}
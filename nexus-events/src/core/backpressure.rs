@@ -0,0 +1,208 @@
+// Bounded queues: with `max_queue_len` set, or a `ClassLimit` for the
+// event's class, an event dispatched while the queue is full is handled as
+// that limit's `OverflowPolicy` says (`queue_policy` for `max_queue_len`):
+//
+//     let bus = SharedEventBus::builder()
+//         .max_queue_len(10_000)
+//         .queue_policy(OverflowPolicy::Block)
+//         .build();
+//
+// `dispatch` can't fail, so under `Error` it drops the new event, as under
+// `DropNewest`; `try_dispatch` is where the two differ. Every event dropped
+// for lack of room is counted by class (`dropped_in_class`), in total
+// (`overflowed_events`) and with the other drops (`dropped_events`).
+//
+// `Block` makes `dispatch` and `try_dispatch` wait until `process` has made
+// room. It is for producers on other threads: a thread delivering on the
+// bus would wait for itself, so there the limit acts as under `Error`.
+// Dispatches of a bus that may block take the bus lock instead of the
+// lock-free inbox, which has no room to wait for.
+//
+// Other dispatches go through the inbox while the queue has room, as far
+// as a `QueueGauge` can tell without the lock: it counts what waits in the
+// queue and in the inbox, and counts everything in the inbox against the
+// dispatched event's class. Once the queue may be full they take the lock
+// and the limit applies right there, so events beyond it are dropped at
+// the dispatch instead of piling up in the inbox. Dispatches racing each
+// other for the last room may still put a few past the limit into the
+// inbox; the bus drops those as it takes them in.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, MutexGuard};
+use std::time::Duration;
+
+use super::class::{class_of, EventClass};
+use super::{snapshot, EventBus, EventError, EventResult, Queued, SharedEventBus};
+use super::config::BusConfig;
+
+/// What happens to an event dispatched when the queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "snake_case"))]
+pub enum OverflowPolicy {
+    /// `try_dispatch` fails with `EventError::QueueFull`; `dispatch` drops
+    /// the new event.
+    #[default]
+    Error,
+    /// The new event is dropped.
+    DropNewest,
+    /// The oldest event of its class (or, if it has none, of the class
+    /// processed last) is dropped to make room.
+    DropOldest,
+    /// The dispatching thread waits for `process` to make room.
+    Block,
+}
+
+// How long a blocked dispatch sleeps before looking again, should the room
+// made have gone unannounced.
+const RECHECK: Duration = Duration::from_millis(10);
+
+/// How full the queue of a bus is, readable without the bus lock.
+pub(crate) struct QueueGauge {
+    // Dispatched into the inbox, not yet taken into the queue.
+    inboxed: AtomicUsize,
+    queued: [AtomicUsize; 3],
+    // The limits, `usize::MAX` for none.
+    max_queued: [AtomicUsize; 3],
+    max_total: AtomicUsize,
+}
+
+impl Default for QueueGauge {
+    fn default() -> Self {
+        Self {
+            inboxed: AtomicUsize::new(0),
+            queued: Default::default(),
+            max_queued: EventClass::ALL.map(|_| AtomicUsize::new(usize::MAX)),
+            max_total: AtomicUsize::new(usize::MAX),
+        }
+    }
+}
+
+impl QueueGauge {
+    /// Records that `len` events of `class` are queued.
+    pub(crate) fn set_queued(&self, class: EventClass, len: usize) {
+        self.queued[class.index()].store(len, Ordering::Relaxed);
+    }
+
+    /// Whether an event of `class` would fit in the queue if the inbox
+    /// were all of its class.
+    fn has_room(&self, class: EventClass) -> bool {
+        let inboxed = self.inboxed.load(Ordering::Relaxed);
+        let queued: usize = self.queued.iter().map(|q| q.load(Ordering::Relaxed)).sum();
+        self.queued[class.index()].load(Ordering::Relaxed).saturating_add(inboxed)
+            < self.max_queued[class.index()].load(Ordering::Relaxed)
+            && queued.saturating_add(inboxed) < self.max_total.load(Ordering::Relaxed)
+    }
+}
+
+/// The sending end of a bus's inbox, counting what goes through it in the
+/// bus's `QueueGauge`.
+#[derive(Clone)]
+pub(crate) struct Inbox {
+    tx: Sender<Queued>,
+    gauge: Arc<QueueGauge>,
+}
+
+impl Inbox {
+    pub(crate) fn new(tx: Sender<Queued>, gauge: Arc<QueueGauge>) -> Self {
+        Self { tx, gauge }
+    }
+
+    pub(crate) fn send(&self, queued: Queued) {
+        self.gauge.inboxed.fetch_add(1, Ordering::Relaxed);
+        // The receiver lives as long as the bus, so this can't fail.
+        let _ = self.tx.send(queued);
+    }
+
+    /// Counts an event taken out of the inbox.
+    pub(crate) fn received(&self) {
+        self.gauge.inboxed.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Whether an event of `class` may go through the inbox, rather than
+    /// having its limit applied under the bus lock.
+    pub(crate) fn has_room(&self, class: EventClass) -> bool {
+        self.gauge.has_room(class)
+    }
+}
+
+impl BusConfig {
+    /// Whether any of the queue limits blocks.
+    pub(crate) fn may_block(&self) -> bool {
+        (self.max_queue_len.is_some() && self.queue_policy == OverflowPolicy::Block)
+            || self.class_limits.values().any(|limit| limit.on_full == OverflowPolicy::Block)
+    }
+}
+
+impl EventBus {
+    /// Takes up the queue limits of `config`. Call after changing the
+    /// configuration.
+    pub(crate) fn refresh_queue_limits(&self) {
+        let gauge = &self.inbox_tx.gauge;
+        for class in EventClass::ALL {
+            let max = self.config.class_limits.get(&class).map_or(usize::MAX, |limit| limit.max_queued);
+            gauge.max_queued[class.index()].store(max, Ordering::Relaxed);
+        }
+        gauge.max_total.store(self.config.max_queue_len.unwrap_or(usize::MAX), Ordering::Relaxed);
+    }
+
+    /// The limit an event of `class` would go over if queued now, as its
+    /// capacity and policy.
+    pub(crate) fn queue_limit(&self, class: EventClass) -> Option<(usize, OverflowPolicy)> {
+        if let Some(limit) = self.config.class_limits.get(&class).filter(|l| self.queue.queued(class) >= l.max_queued) {
+            return Some((limit.max_queued, limit.on_full));
+        }
+        self.config.max_queue_len
+            .filter(|&max| self.queue.len() >= max)
+            .map(|max| (max, self.config.queue_policy))
+    }
+
+    /// Queues an admitted event in its class, unless a full queue drops
+    /// it. Returns whether an event was dropped, this one or an older one.
+    pub(crate) fn queue_in_class(&mut self, queued: Queued) -> bool {
+        let class = class_of((*queued.ev).as_any().type_id());
+        let dropped = match self.queue_limit(class) {
+            None => false,
+            Some((_, OverflowPolicy::DropOldest)) if self.queue.drop_oldest(class) => true,
+            Some(_) => {
+                self.queue.count_drop(class);
+                return true;
+            }
+        };
+        self.queue.push(class, queued);
+        dropped
+    }
+}
+
+impl SharedEventBus {
+    /// Waits, if the limit an event of `class` would go over blocks, until
+    /// there is room for it or waiting is pointless (see the top of this
+    /// file).
+    pub(crate) fn wait_for_room<'a>(
+        &'a self,
+        mut bus: MutexGuard<'a, EventBus>,
+        class: EventClass,
+    ) -> EventResult<MutexGuard<'a, EventBus>> {
+        loop {
+            bus.pull_inbox();
+            match bus.queue_limit(class) {
+                Some((_, OverflowPolicy::Block)) if !self.is_shut_down() && !snapshot::delivering_on(bus.bus_id) => {
+                    bus = self.inner.wait_for_room(bus, RECHECK).map_err(|_| EventError::BusPoisoned)?;
+                }
+                _ => return Ok(bus),
+            }
+        }
+    }
+
+    /// Events dropped so far because the queue was full, whatever the
+    /// limit and class.
+    pub fn overflowed_events(&self) -> u64 {
+        self.inner.lock()
+            .map(|mut bus| {
+                bus.pull_inbox();
+                bus.queue.total_dropped()
+            })
+            .unwrap_or(0)
+    }
+}
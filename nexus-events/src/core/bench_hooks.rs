@@ -0,0 +1,30 @@
+// Internals the benchmarks in `benches/` use to time one step of the
+// dispatch path at a time, and to reset a bus between iterations without
+// timing that too. Not part of the API.
+
+use std::any::TypeId;
+
+use super::{Event, SharedEventBus};
+
+/// Moves lock-free dispatches into the queue, as the next `process` would.
+pub fn admit_inbox(bus: &SharedEventBus) {
+    if let Ok(mut bus) = bus.inner.lock() {
+        bus.pull_inbox();
+    }
+}
+
+/// Takes the handler snapshot a delivery of `E` would take, without running
+/// anything, and returns how many handlers it holds.
+pub fn plan<E: Event + 'static>(bus: &SharedEventBus) -> usize {
+    let Ok(bus) = bus.inner.lock() else { return 0 };
+    bus.plan(TypeId::of::<E>()).handler_count()
+}
+
+/// Throws away everything queued without delivering it.
+pub fn discard_queued(bus: &SharedEventBus) {
+    if let Ok(mut bus) = bus.inner.lock() {
+        bus.pull_inbox();
+        bus.queue.clear();
+        bus.inline_due.clear();
+    }
+}
@@ -0,0 +1,136 @@
+// Budgeted processing: drain only part of the queue per call so a burst of
+// events is spread over several frames instead of spiking one. Whatever the
+// budget doesn't cover stays queued, ahead of anything dispatched later.
+
+use std::any::TypeId;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::delivery::Pending;
+use super::{Event, EventBus, Queued, SharedEventBus};
+
+/// Limits for one `process_with_budget` call. The limits combine: processing
+/// stops at whichever is hit first. At least one event is always processed
+/// when any are queued, so a tiny budget can't starve the bus.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessBudget {
+    max_events: Option<usize>,
+    max_time: Option<Duration>,
+    by_class: bool,
+}
+
+impl ProcessBudget {
+    /// No limits; like `process`, but with ordering applied if asked for.
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn events(max: usize) -> Self {
+        Self::default().max_events(max)
+    }
+
+    pub fn time(max: Duration) -> Self {
+        Self::default().max_time(max)
+    }
+
+    pub fn max_events(mut self, max: usize) -> Self {
+        self.max_events = Some(max);
+        self
+    }
+
+    pub fn max_time(mut self, max: Duration) -> Self {
+        self.max_time = Some(max);
+        self
+    }
+
+    /// Process higher event classes (see `SharedEventBus::set_event_class`)
+    /// first. Events of the same class keep their dispatch order.
+    pub fn by_class(mut self) -> Self {
+        self.by_class = true;
+        self
+    }
+}
+
+/// What a `process_with_budget` call got through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetOutcome {
+    pub processed: usize,
+    /// Events left queued for the next call.
+    pub carried_over: usize,
+}
+
+impl EventBus {
+    pub(crate) fn event_class(&self, ev: &dyn Event) -> i32 {
+        self.event_classes.get(&ev.as_any().type_id()).copied().unwrap_or(0)
+    }
+
+    /// Takes the queue in the order `budget` asks for. Returns it with the
+    /// number of critical events at its front, which the budget doesn't
+    /// cover, and the inline events still waiting to bubble up.
+    fn take_budgeted(&mut self, budget: ProcessBudget) -> (VecDeque<Queued>, usize, Vec<Queued>) {
+        self.prune_expired();
+        self.compact_handlers();
+        self.pull_inbox();
+        self.throttle_counts.clear();
+        let mut current = self.queue.take();
+        if budget.by_class {
+            current.make_contiguous()
+                .sort_by_key(|q| std::cmp::Reverse(self.event_class(&*q.ev)));
+        }
+        let mut critical = if self.boosts_critical() { self.front_load_critical(&mut current) } else { 0 };
+        for queued in self.inline_due.drain(..).rev() {
+            current.push_front(queued);
+            critical += 1;
+        }
+        (current, critical, std::mem::take(&mut self.inline_bubbling))
+    }
+}
+
+impl SharedEventBus {
+    /// Like `process`, but stops once `budget` is used up. Unprocessed
+    /// events are kept, in order, for the next call.
+    pub fn process_with_budget(&self, budget: ProcessBudget) -> BudgetOutcome {
+        let started = Instant::now();
+        let (current, critical, mut bubbling, parent, removed) = match self.inner.lock() {
+            Ok(mut bus) => {
+                let (current, critical, bubbling) = bus.take_budgeted(budget);
+                (current, critical, bubbling, bus.parent.clone(), bus.take_removed())
+            }
+            Err(_) => return BudgetOutcome { processed: 0, carried_over: 0 },
+        };
+        self.inner.room_made();
+        drop(removed);
+
+        // Critical events go first and don't count against the budget. The
+        // rest of the queue goes back when `pending` is dropped.
+        let mut pending = Pending::new(&self.inner, current);
+        let mut processed = 0;
+        while processed <= critical
+            || (budget.max_events.is_none_or(|max| processed - critical < max)
+                && budget.max_time.is_none_or(|max| started.elapsed() < max))
+        {
+            let Some((queued, consumed)) = pending.deliver_next() else { break };
+            if !consumed && parent.is_some() {
+                bubbling.push(queued);
+            } else {
+                self.keep(queued.ev);
+            }
+            processed += 1;
+        }
+        let carried_over = pending.events.len();
+        drop(pending);
+
+        Self::bubble(bubbling, parent);
+        self.flush_coalesced();
+        BudgetOutcome { processed, carried_over }
+    }
+
+    /// Sets the class `ProcessBudget::by_class` orders `E` by. Higher classes
+    /// go first; unclassified events are class 0.
+    pub fn set_event_class<E: Event + 'static>(&self, class: i32) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.event_classes.insert(TypeId::of::<E>(), class);
+            bus.refresh_inline_types();
+        }
+    }
+}
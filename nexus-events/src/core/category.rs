@@ -0,0 +1,141 @@
+// Event categories: an event type may name a parent category, and a handler
+// subscribed to the category sees every event in it, after the handlers of
+// the concrete type. `define_event!(pub struct DamageDealt { .. } : CombatEvent)`
+// puts `DamageDealt` in `CombatEvent`; categories may themselves be in a
+// category, and a handler of an outer category sees the whole tree.
+//
+// A category is any `'static` type, usually a unit struct that is never
+// dispatched itself.
+
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use super::slots::Registration;
+use super::stats::LoadTracker;
+use super::{ErasedHandler, Event, EventBus, EventContext, HandlerId, HandlerImpl, Phase, SharedEventBus};
+
+/// Submitted by `define_event!` for events declared with a category.
+#[doc(hidden)]
+pub struct CategoryLink {
+    event: fn() -> TypeId,
+    category: fn() -> TypeId,
+}
+
+impl CategoryLink {
+    pub const fn new<E: 'static, C: ?Sized + 'static>() -> Self {
+        Self { event: TypeId::of::<E>, category: TypeId::of::<C> }
+    }
+}
+
+inventory::collect!(CategoryLink);
+
+fn parents() -> &'static RwLock<HashMap<TypeId, Vec<TypeId>>> {
+    static PARENTS: OnceLock<RwLock<HashMap<TypeId, Vec<TypeId>>>> = OnceLock::new();
+    PARENTS.get_or_init(|| {
+        let mut parents: HashMap<TypeId, Vec<TypeId>> = HashMap::new();
+        for link in inventory::iter::<CategoryLink> {
+            let list = parents.entry((link.event)()).or_default();
+            let category = (link.category)();
+            if !list.contains(&category) {
+                list.push(category);
+            }
+        }
+        RwLock::new(parents)
+    })
+}
+
+/// Puts `E` in category `C` at runtime, for types not declared with
+/// `define_event!` (generic events, say). A type may be in several
+/// categories.
+pub fn categorize_as<E: 'static, C: ?Sized + 'static>() {
+    if let Ok(mut parents) = parents().write() {
+        let list = parents.entry(TypeId::of::<E>()).or_default();
+        if !list.contains(&TypeId::of::<C>()) {
+            list.push(TypeId::of::<C>());
+        }
+    }
+}
+
+/// Every category `tid` is in, directly or through other categories,
+/// nearest first. Each shows up once even if reachable twice, and cycles end.
+pub(crate) fn categories_of(tid: TypeId) -> Vec<TypeId> {
+    let Ok(parents) = parents().read() else { return Vec::new() };
+    let mut seen = HashSet::from([tid]);
+    let mut found = Vec::new();
+    let mut next = 0;
+    let mut current = tid;
+    loop {
+        for &parent in parents.get(&current).into_iter().flatten() {
+            if seen.insert(parent) {
+                found.push(parent);
+            }
+        }
+        let Some(&following) = found.get(next) else { return found };
+        current = following;
+        next += 1;
+    }
+}
+
+impl EventBus {
+    fn subscribe_category<C: ?Sized + 'static, F>(&mut self, priority: i32, phase: Phase, closure: F) -> HandlerId
+    where
+        F: Fn(&dyn Event, &mut EventContext) + Send + Sync + 'static
+    {
+        let tid = TypeId::of::<C>();
+        let id = self.slots.insert(None, Registration::Category(tid));
+        let erased: Arc<dyn ErasedHandler> = Arc::new(HandlerImpl {
+            id: id.0,
+            priority,
+            phase,
+            event_name: std::any::type_name::<C>(),
+            load: LoadTracker::default(),
+            retired: AtomicBool::new(false),
+            independent: false,
+            func: closure,
+        });
+        self.slots.attach(id, erased.clone());
+        let list = Arc::make_mut(self.category_handlers.entry(tid).or_default());
+        let pos = list.partition_point(|h| h.priority() >= priority);
+        list.insert(pos, erased);
+        self.refresh_interest();
+        self.tag_generation(id.0);
+        id
+    }
+}
+
+impl SharedEventBus {
+    /// Subscribes to every event in category `C`. The handler runs after the
+    /// handlers of the event's own type, and only if none of them consumed it.
+    pub fn subscribe_category<C: ?Sized + 'static, F>(&self, f: F) -> HandlerId
+    where
+        F: Fn(&dyn Event) + Send + Sync + 'static
+    {
+        self.subscribe_category_with_priority::<C, _>(0, move |ev: &dyn Event, _ctx: &mut EventContext| f(ev))
+    }
+
+    /// Like `subscribe_category`, with a context and a priority among the
+    /// other handlers of `C`.
+    pub fn subscribe_category_with_priority<C: ?Sized + 'static, F>(&self, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&dyn Event, &mut EventContext) + Send + Sync + 'static
+    {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.subscribe_category::<C, _>(priority, Phase::PreInit, f)
+        } else {
+            HandlerId::NONE
+        }
+    }
+
+    pub fn unsubscribe_category<C: ?Sized + 'static>(&self, handler_id: HandlerId) {
+        if let Ok(mut bus) = self.inner.lock() {
+            if bus.slots.get(handler_id) == Some(Registration::Category(TypeId::of::<C>())) {
+                bus.remove_registration(handler_id);
+                let removed = bus.take_removed();
+                drop(bus);
+                drop(removed);
+            }
+        }
+    }
+}
@@ -0,0 +1,47 @@
+// Bridges a bus subscription into an mpsc channel, so events can be consumed
+// on another thread with a plain `recv()` loop instead of a handler closure.
+
+use std::ops::Deref;
+use std::sync::mpsc::{self, Receiver};
+
+use super::{Event, HandlerId, SharedEventBus};
+
+/// The receiving end of `SharedEventBus::channel_for`. Derefs to the
+/// underlying `Receiver`; dropping it removes the subscription.
+pub struct EventReceiver<E: Event + 'static> {
+    rx: Receiver<E>,
+    bus: SharedEventBus,
+    handler: HandlerId,
+}
+
+impl<E: Event + 'static> EventReceiver<E> {
+    pub fn handler_id(&self) -> HandlerId {
+        self.handler
+    }
+}
+
+impl<E: Event + 'static> Deref for EventReceiver<E> {
+    type Target = Receiver<E>;
+    fn deref(&self) -> &Receiver<E> {
+        &self.rx
+    }
+}
+
+impl<E: Event + 'static> Drop for EventReceiver<E> {
+    fn drop(&mut self) {
+        self.bus.unsubscribe::<E>(self.handler);
+    }
+}
+
+impl SharedEventBus {
+    /// Subscribes a handler that sends a clone of every `E` into a channel
+    /// and returns the receiving end.
+    pub fn channel_for<E: Event + Clone + 'static>(&self) -> EventReceiver<E> {
+        let (tx, rx) = mpsc::channel::<E>();
+        let handler = self.subscribe::<E, _>(move |ev: &E| {
+            // A closed channel is fine: the receiver is unsubscribing us.
+            let _ = tx.send(ev.clone());
+        });
+        EventReceiver { rx, bus: self.clone(), handler }
+    }
+}
@@ -0,0 +1,266 @@
+// Event classes: control, gameplay and telemetry events wait in queues of
+// their own, so a flood of one doesn't hold up the others. `process` takes
+// the queues in the order of `BusConfig::class_order` (control, gameplay,
+// telemetry unless set), each in dispatch order, and a `ProcessBudget`
+// that runs out leaves the later classes for the next call. Each class may
+// have a `ClassLimit` saying how many of its events may wait and what
+// happens beyond that (see `backpressure.rs`).
+//
+// `define_event!` puts an event in a class with `#[class(telemetry)]`;
+// other types are put in one with `classify_as`. Events without a class
+// are gameplay events.
+//
+// Not to be confused with the numeric class of `set_event_class`, which
+// marks critical events and orders a `ProcessBudget::by_class`.
+
+use std::any::TypeId;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use super::backpressure::QueueGauge;
+use super::{EventBus, OverflowPolicy, Queued, SharedEventBus};
+
+/// Which queue an event waits in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "lowercase"))]
+pub enum EventClass {
+    /// Input, pausing, quitting: whatever steers the game.
+    Control,
+    #[default]
+    Gameplay,
+    /// Metrics, analytics, debug overlays: nothing that may delay play.
+    Telemetry,
+}
+
+impl EventClass {
+    pub const ALL: [EventClass; 3] = [EventClass::Control, EventClass::Gameplay, EventClass::Telemetry];
+
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// How many events of a class may wait to be processed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(deny_unknown_fields))]
+pub struct ClassLimit {
+    pub max_queued: usize,
+    #[cfg_attr(feature = "config", serde(default))]
+    pub on_full: OverflowPolicy,
+}
+
+impl ClassLimit {
+    pub fn drop_newest(max_queued: usize) -> Self {
+        Self { max_queued, on_full: OverflowPolicy::DropNewest }
+    }
+
+    pub fn drop_oldest(max_queued: usize) -> Self {
+        Self { max_queued, on_full: OverflowPolicy::DropOldest }
+    }
+}
+
+/// Submitted by `define_event!` for events declared with a class.
+#[doc(hidden)]
+pub struct ClassLink {
+    event: fn() -> TypeId,
+    class: EventClass,
+}
+
+impl ClassLink {
+    pub const fn new<E: 'static>(class: EventClass) -> Self {
+        Self { event: TypeId::of::<E>, class }
+    }
+}
+
+inventory::collect!(ClassLink);
+
+// Set once any type is in a class other than gameplay, so buses that don't
+// use classes skip the lookup.
+static CLASSED: AtomicBool = AtomicBool::new(false);
+
+fn classes() -> &'static RwLock<HashMap<TypeId, EventClass>> {
+    static CLASSES: OnceLock<RwLock<HashMap<TypeId, EventClass>>> = OnceLock::new();
+    CLASSES.get_or_init(|| {
+        let classes: HashMap<TypeId, EventClass> = inventory::iter::<ClassLink>
+            .into_iter()
+            .map(|link| ((link.event)(), link.class))
+            .collect();
+        if classes.values().any(|&class| class != EventClass::Gameplay) {
+            CLASSED.store(true, Ordering::Relaxed);
+        }
+        RwLock::new(classes)
+    })
+}
+
+/// Puts `E` in `class` at runtime, for types not declared with
+/// `define_event!` (generic events, say). Applies to every bus, from the
+/// next dispatch on.
+pub fn classify_as<E: 'static>(class: EventClass) {
+    if let Ok(mut classes) = classes().write() {
+        classes.insert(TypeId::of::<E>(), class);
+        if class != EventClass::Gameplay {
+            CLASSED.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The class of events of type `tid`.
+pub(crate) fn class_of(tid: TypeId) -> EventClass {
+    let classes = classes();
+    if !CLASSED.load(Ordering::Relaxed) {
+        return EventClass::Gameplay;
+    }
+    classes.read().ok().and_then(|classes| classes.get(&tid).copied()).unwrap_or_default()
+}
+
+/// The queues of a bus, one per class, and how many events each dropped.
+/// Their lengths are kept in `gauge` as well.
+pub(crate) struct ClassQueues {
+    queues: [VecDeque<Queued>; 3],
+    dropped: [u64; 3],
+    order: [EventClass; 3],
+    gauge: Arc<QueueGauge>,
+}
+
+impl ClassQueues {
+    pub(crate) fn new(gauge: Arc<QueueGauge>) -> Self {
+        Self { queues: Default::default(), dropped: [0; 3], order: EventClass::ALL, gauge }
+    }
+
+    pub(crate) fn reserve(&mut self, capacity: usize) {
+        self.queues[EventClass::Gameplay.index()].reserve(capacity);
+    }
+
+    fn measure(&self, class: EventClass) {
+        self.gauge.set_queued(class, self.queues[class.index()].len());
+    }
+
+    fn measure_all(&self) {
+        EventClass::ALL.into_iter().for_each(|class| self.measure(class));
+    }
+
+    /// Takes the classes in `order`, followed by any it leaves out.
+    pub(crate) fn set_order(&mut self, order: &[EventClass]) {
+        let mut full = Vec::with_capacity(3);
+        for &class in order.iter().chain(&EventClass::ALL) {
+            if !full.contains(&class) {
+                full.push(class);
+            }
+        }
+        self.order.copy_from_slice(&full);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    pub(crate) fn queued(&self, class: EventClass) -> usize {
+        self.queues[class.index()].len()
+    }
+
+    pub(crate) fn dropped(&self, class: EventClass) -> u64 {
+        self.dropped[class.index()]
+    }
+
+    /// Queues `queued` in `class`, whatever the limits.
+    pub(crate) fn push(&mut self, class: EventClass, queued: Queued) {
+        self.queues[class.index()].push_back(queued);
+        self.measure(class);
+    }
+
+    /// Counts an event of `class` dropped for lack of room.
+    pub(crate) fn count_drop(&mut self, class: EventClass) {
+        self.dropped[class.index()] += 1;
+    }
+
+    /// Drops the oldest event of `class` or, if it has none, of the class
+    /// processed last that has any. Returns whether there was one.
+    pub(crate) fn drop_oldest(&mut self, class: EventClass) -> bool {
+        let Some(victim) = std::iter::once(class)
+            .chain(self.order.into_iter().rev())
+            .find(|c| !self.queues[c.index()].is_empty())
+        else {
+            return false;
+        };
+        self.queues[victim.index()].pop_front();
+        self.measure(victim);
+        self.count_drop(victim);
+        true
+    }
+
+    /// Queues `queued` in its class, whatever the class's limit.
+    pub(crate) fn push_back(&mut self, queued: Queued) {
+        let class = class_of((*queued.ev).as_any().type_id());
+        self.queues[class.index()].push_back(queued);
+        self.measure(class);
+    }
+
+    /// Puts `events`, taken earlier, back in front of their queues, in the
+    /// order they were in.
+    pub(crate) fn put_back(&mut self, events: impl DoubleEndedIterator<Item = Queued>) {
+        for queued in events.rev() {
+            let class = class_of((*queued.ev).as_any().type_id());
+            self.queues[class.index()].push_front(queued);
+        }
+        self.measure_all();
+    }
+
+    /// Everything queued, class by class in processing order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Queued> {
+        self.order.iter().flat_map(|class| &self.queues[class.index()])
+    }
+
+    /// Takes everything queued, class by class in processing order.
+    pub(crate) fn take(&mut self) -> VecDeque<Queued> {
+        let mut all = VecDeque::with_capacity(self.len());
+        for class in self.order {
+            all.append(&mut self.queues[class.index()]);
+        }
+        self.measure_all();
+        all
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.queues.iter_mut().for_each(VecDeque::clear);
+        self.measure_all();
+    }
+
+    /// Events dropped for lack of room, in all classes.
+    pub(crate) fn total_dropped(&self) -> u64 {
+        self.dropped.iter().sum()
+    }
+}
+
+impl EventBus {
+    /// Takes up `config.class_order`. Call after changing the configuration.
+    pub(crate) fn refresh_class_order(&mut self) {
+        self.queue.set_order(&self.config.class_order);
+    }
+}
+
+impl SharedEventBus {
+    /// Events of `class` waiting to be processed.
+    pub fn queued_in_class(&self, class: EventClass) -> usize {
+        self.inner.lock()
+            .map(|mut bus| {
+                bus.pull_inbox();
+                bus.queue.queued(class)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Events of `class` dropped so far for lack of room, because its
+    /// `ClassLimit` or `max_queue_len` was reached. They are counted in
+    /// `dropped_events` too.
+    pub fn dropped_in_class(&self, class: EventClass) -> u64 {
+        self.inner.lock()
+            .map(|mut bus| {
+                bus.pull_inbox();
+                bus.queue.dropped(class)
+            })
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,79 @@
+// Coalescing subscriptions, for events that arrive in bursts about the same
+// thing (sixty `TransformChanged` a frame for one entity). The events a
+// `process` call delivers are merged per key as they arrive, and once that
+// call has delivered everything, the handler sees one merged event per key.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use super::{Event, EventContext, HandlerId, Phase, SharedEventBus};
+
+/// Hands what a coalescing subscription merged during one `process` to its
+/// handler.
+pub(crate) type Flush = Arc<dyn Fn() + Send + Sync>;
+
+struct Window<K, E> {
+    // Keys in the order they first appeared.
+    order: Vec<K>,
+    merged: HashMap<K, E>,
+}
+
+impl SharedEventBus {
+    /// Subscribes `handler` to one merged `E` per key per `process`. Every
+    /// `E` delivered is grouped by `key` and folded into the first of its
+    /// group with `merge`; when `process` (or `process_with_budget`) has
+    /// delivered everything it took, `handler` runs once per group, in the
+    /// order the keys first appeared.
+    pub fn subscribe_coalesced<E, K, KF, MF, F>(&self, key: KF, merge: MF, handler: F) -> HandlerId
+    where
+        E: Event + Clone + 'static,
+        K: Hash + Eq + Clone + Send + 'static,
+        KF: Fn(&E) -> K + Send + Sync + 'static,
+        MF: Fn(&mut E, &E) + Send + Sync + 'static,
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        let window = Arc::new(Mutex::new(Window { order: Vec::new(), merged: HashMap::new() }));
+        let incoming = window.clone();
+        let collect = move |ev: &E, _ctx: &mut EventContext| {
+            let Ok(mut window) = incoming.lock() else { return };
+            let k = key(ev);
+            match window.merged.get_mut(&k) {
+                Some(acc) => merge(acc, ev),
+                None => {
+                    window.order.push(k.clone());
+                    window.merged.insert(k, ev.clone());
+                }
+            }
+        };
+        let flush: Flush = Arc::new(move || {
+            let batch: Vec<E> = match window.lock() {
+                Ok(mut window) => {
+                    let Window { order, merged } = &mut *window;
+                    order.drain(..).filter_map(|k| merged.remove(&k)).collect()
+                }
+                Err(_) => return,
+            };
+            for ev in &batch {
+                handler(ev);
+            }
+        });
+
+        let Ok(mut bus) = self.inner.lock() else { return HandlerId::NONE };
+        let Ok(id) = bus.subscribe(0, Phase::PreInit, collect) else { return HandlerId::NONE };
+        bus.coalesced.push((id.0, flush));
+        id
+    }
+
+    /// Runs the handlers of every coalescing subscription on what was merged
+    /// since the last call. The bus is unlocked meanwhile.
+    pub(crate) fn flush_coalesced(&self) {
+        let flushes: Vec<Flush> = match self.inner.lock() {
+            Ok(bus) if !bus.coalesced.is_empty() => bus.coalesced.iter().map(|(_, f)| f.clone()).collect(),
+            _ => return,
+        };
+        for flush in flushes {
+            flush();
+        }
+    }
+}
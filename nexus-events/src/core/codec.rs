@@ -0,0 +1,260 @@
+// Encoding events for the wire or for disk. Every event type that should go
+// through a codec is registered under a stable name; the name travels with
+// the payload so the receiving side knows what to decode it into.
+//
+// The schema version of the event travels along too. When a saved payload
+// is older than the registered type, the codec decodes it as the type it
+// was written from and converts it with a migration (`migrate_from`).
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{wire_id, Event, EventError, EventResult, HasWireId, SharedEventBus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WireFormat {
+    Json,
+    Bincode,
+    /// Bincode, tagged with the 64-bit `wire_id` of the name instead of the
+    /// name itself.
+    Compact,
+}
+
+type EncodeFn = fn(&dyn Event, &Entry, WireFormat) -> EventResult<Vec<u8>>;
+type DecodeFn = fn(Payload) -> EventResult<Box<dyn Event>>;
+type MigrateFn = Box<dyn Fn(Payload) -> EventResult<Box<dyn Event>> + Send + Sync>;
+
+struct Entry {
+    name: &'static str,
+    id: u64,
+    version: u32,
+    encode: EncodeFn,
+    decode: DecodeFn,
+}
+
+// What actually goes over the wire: the registered name next to the event.
+// JSON nests the event as a normal object; bincode nests its own bytes.
+#[derive(Serialize, Deserialize)]
+struct Tagged<'a, T> {
+    name: &'a str,
+    payload: T,
+    #[serde(default = "first_version")]
+    version: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IdTagged {
+    id: u64,
+    payload: Vec<u8>,
+    #[serde(default = "first_version")]
+    version: u32,
+}
+
+// Bincode payloads written before versions were recorded. Bincode can't
+// default a missing trailing field, so these layouts are tried when the
+// current one runs out of bytes.
+#[derive(Deserialize)]
+struct UnversionedTagged<'a> {
+    name: &'a str,
+    payload: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct UnversionedIdTagged {
+    id: u64,
+    payload: Vec<u8>,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+enum Payload {
+    Json(serde_json::Value),
+    Bincode(Vec<u8>),
+}
+
+/// A decoded event, ready to be dispatched on any bus.
+pub struct DecodedEvent {
+    pub name: &'static str,
+    event: Box<dyn Event>,
+}
+impl DecodedEvent {
+    pub fn event(&self) -> &dyn Event {
+        &*self.event
+    }
+    pub fn publish(self, bus: &SharedEventBus) {
+        bus.dispatch_boxed(self.event);
+    }
+}
+
+/// A registry of serializable event types.
+#[derive(Default)]
+pub struct EventCodec {
+    by_type: HashMap<TypeId, Entry>,
+    by_name: HashMap<&'static str, TypeId>,
+    by_id: HashMap<u64, TypeId>,
+    migrations: HashMap<(TypeId, u32), MigrateFn>,
+}
+
+impl EventCodec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `E` under `name` at schema version 1. The name must be the
+    /// same on both ends.
+    ///
+    /// Fails with `WireIdCollision` if `name` hashes to the same wire id as
+    /// a name registered for another type.
+    pub fn register<E>(&mut self, name: &'static str) -> EventResult<&mut Self>
+    where
+        E: Event + Serialize + DeserializeOwned
+    {
+        self.register_versioned::<E>(name, 1)
+    }
+
+    /// Registers `E` under `name` at schema `version`. Payloads written at
+    /// older versions need a migration (see `migrate_from`).
+    pub fn register_versioned<E>(&mut self, name: &'static str, version: u32) -> EventResult<&mut Self>
+    where
+        E: Event + Serialize + DeserializeOwned
+    {
+        let tid = TypeId::of::<E>();
+        let id = wire_id(name);
+        if let Some(other) = self.by_id.get(&id).filter(|other| **other != tid) {
+            return Err(EventError::WireIdCollision { name, existing: self.by_type[other].name, id });
+        }
+        self.by_type.insert(tid, Entry { name, id, version, encode: encode::<E>, decode: decode::<E> });
+        self.by_name.insert(name, tid);
+        self.by_id.insert(id, tid);
+        Ok(self)
+    }
+
+    /// Registers an event declared with `define_event!` under its own name
+    /// and version.
+    pub fn register_event<E>(&mut self) -> EventResult<&mut Self>
+    where
+        E: Event + HasWireId + Serialize + DeserializeOwned
+    {
+        self.register_versioned::<E>(E::WIRE_NAME, E::WIRE_VERSION)
+    }
+
+    /// Decodes `E` payloads written at `version` as an `Old`, the event as it
+    /// was declared then (kept around under another name), and turns them
+    /// into an `E` with `migrate`. A migration goes straight to the current
+    /// version, so compose the steps if the type changed several times.
+    pub fn migrate_from<E, Old, F>(&mut self, version: u32, migrate: F) -> &mut Self
+    where
+        E: Event,
+        Old: DeserializeOwned,
+        F: Fn(Old) -> E + Send + Sync + 'static
+    {
+        let migrate: MigrateFn = Box::new(move |payload: Payload| {
+            Ok(Box::new(migrate(decode_as::<Old>(payload)?)) as Box<dyn Event>)
+        });
+        self.migrations.insert((TypeId::of::<E>(), version), migrate);
+        self
+    }
+
+    /// The name `E` is registered under.
+    pub fn name_of<E: Event>(&self) -> Option<&'static str> {
+        self.by_type.get(&TypeId::of::<E>()).map(|entry| entry.name)
+    }
+
+    /// Every registered name with its current version, sorted by name.
+    pub fn names(&self) -> Vec<(&'static str, u32)> {
+        let mut names: Vec<_> = self.by_type.values().map(|entry| (entry.name, entry.version)).collect();
+        names.sort_unstable();
+        names
+    }
+
+    pub fn encode(&self, ev: &dyn Event, format: WireFormat) -> EventResult<Vec<u8>> {
+        let entry = self.by_type.get(&ev.as_any().type_id())
+            .ok_or_else(|| EventError::UnregisteredEvent(ev.event_name().to_string()))?;
+        (entry.encode)(ev, entry, format)
+    }
+
+    pub fn decode(&self, bytes: &[u8], format: WireFormat) -> EventResult<DecodedEvent> {
+        let by_name = |name: &str| {
+            self.by_name.get(name).ok_or_else(|| EventError::UnregisteredEvent(name.to_string()))
+        };
+        let (tid, version, payload) = match format {
+            WireFormat::Json => {
+                let t: Tagged<serde_json::Value> = serde_json::from_slice(bytes).map_err(decode_err)?;
+                (by_name(t.name)?, t.version, Payload::Json(t.payload))
+            }
+            WireFormat::Bincode => {
+                let t: Tagged<Vec<u8>> = bincode::deserialize(bytes)
+                    .or_else(|e| {
+                        bincode::deserialize::<UnversionedTagged>(bytes)
+                            .map(|t| Tagged { name: t.name, payload: t.payload, version: 1 })
+                            .map_err(|_| e)
+                    })
+                    .map_err(decode_err)?;
+                (by_name(t.name)?, t.version, Payload::Bincode(t.payload))
+            }
+            WireFormat::Compact => {
+                let t: IdTagged = bincode::deserialize(bytes)
+                    .or_else(|e| {
+                        bincode::deserialize::<UnversionedIdTagged>(bytes)
+                            .map(|t| IdTagged { id: t.id, payload: t.payload, version: 1 })
+                            .map_err(|_| e)
+                    })
+                    .map_err(decode_err)?;
+                let tid = self.by_id.get(&t.id)
+                    .ok_or_else(|| EventError::UnregisteredEvent(format!("wire id {:#018x}", t.id)))?;
+                (tid, t.version, Payload::Bincode(t.payload))
+            }
+        };
+        let entry = &self.by_type[tid];
+        let event = if version == entry.version {
+            (entry.decode)(payload)?
+        } else {
+            let migrate = self.migrations.get(&(*tid, version))
+                .ok_or(EventError::NoMigration { name: entry.name, from: version, to: entry.version })?;
+            migrate(payload)?
+        };
+        Ok(DecodedEvent { name: entry.name, event })
+    }
+}
+
+fn encode<E: Event + Serialize>(ev: &dyn Event, entry: &Entry, format: WireFormat) -> EventResult<Vec<u8>> {
+    let payload = ev.as_any().downcast_ref::<E>()
+        .expect("codec entry registered under the wrong TypeId");
+    let (name, version) = (entry.name, entry.version);
+    match format {
+        WireFormat::Json => serde_json::to_vec(&Tagged { name, payload, version }).map_err(encode_err(ev)),
+        WireFormat::Bincode => {
+            let payload = bincode::serialize(payload).map_err(encode_err(ev))?;
+            bincode::serialize(&Tagged { name, payload, version }).map_err(encode_err(ev))
+        }
+        WireFormat::Compact => {
+            let payload = bincode::serialize(payload).map_err(encode_err(ev))?;
+            bincode::serialize(&IdTagged { id: entry.id, payload, version }).map_err(encode_err(ev))
+        }
+    }
+}
+
+fn decode<E: Event + DeserializeOwned>(payload: Payload) -> EventResult<Box<dyn Event>> {
+    Ok(Box::new(decode_as::<E>(payload)?))
+}
+
+fn decode_as<T: DeserializeOwned>(payload: Payload) -> EventResult<T> {
+    match payload {
+        Payload::Json(v) => serde_json::from_value(v).map_err(decode_err),
+        Payload::Bincode(b) => bincode::deserialize(&b).map_err(decode_err),
+    }
+}
+
+fn encode_err<E: std::error::Error + Send + Sync + 'static>(ev: &dyn Event) -> impl FnOnce(E) -> EventError {
+    let event = ev.event_name();
+    move |e| EventError::SerializeFailed { event, source: Box::new(e) }
+}
+
+fn decode_err(e: impl std::error::Error + Send + Sync + 'static) -> EventError {
+    EventError::DeserializeFailed(Box::new(e))
+}
@@ -0,0 +1,101 @@
+// Publishing for an answer from every handler: validation-style events such
+// as `CanPlayerMove`, where each system votes and the publisher weighs the
+// votes.
+//
+//     bus.subscribe_returning::<CanPlayerMove, bool, _>(|ev| !walls.blocks(ev.to));
+//     let votes = bus.publish_collect::<CanPlayerMove, bool>(ev)?;
+//     let allowed = votes.iter().all(|&ok| ok);
+//
+// These handlers are kept apart from the ordinary ones, under the event and
+// result type together: `publish_collect::<E, R>` calls only those
+// subscribed with `subscribe_returning::<E, R>`, and `publish` or
+// `dispatch` of an `E` never calls them. They run right away, in the order
+// they were subscribed, without the bus lock, like a query's responder.
+
+use std::any::{Any, TypeId};
+use std::sync::Arc;
+
+use super::{Event, EventBus, EventError, EventResult, HandlerId, SharedEventBus};
+use super::slots::Registration;
+
+type Collector<E, R> = Arc<dyn Fn(&E) -> R + Send + Sync>;
+
+/// The returning handlers of one event and result type, by id, each a
+/// `Collector<E, R>`.
+pub(crate) type Collectors = Vec<(u64, Box<dyn Any + Send + Sync>)>;
+
+/// The key the handlers of `E` returning `R` are kept under.
+fn key<E: 'static, R: 'static>() -> TypeId {
+    TypeId::of::<Collector<E, R>>()
+}
+
+impl EventBus {
+    fn subscribe_returning<E: Event + 'static, R: 'static, F>(&mut self, f: F) -> HandlerId
+    where
+        F: Fn(&E) -> R + Send + Sync + 'static
+    {
+        let key = key::<E, R>();
+        let id = self.slots.insert(None, Registration::Collector(key));
+        let collector: Collector<E, R> = Arc::new(f);
+        self.collectors.entry(key).or_default().push((id.0, Box::new(collector)));
+        self.tag_generation(id.0);
+        id
+    }
+
+    /// Drops the handler `id` from the returning handlers under `key`.
+    pub(crate) fn remove_collector(&mut self, key: TypeId, id: u64) {
+        let Some(list) = self.collectors.get_mut(&key) else { return };
+        if let Some(pos) = list.iter().position(|(c, _)| *c == id) {
+            let (_, collector) = list.remove(pos);
+            self.removed.push(collector);
+        }
+        if list.is_empty() {
+            self.collectors.remove(&key);
+        }
+    }
+
+    fn collectors<E: 'static, R: 'static>(&self) -> Vec<Collector<E, R>> {
+        self.collectors.get(&key::<E, R>())
+            .into_iter()
+            .flatten()
+            .filter_map(|(_, c)| c.downcast_ref::<Collector<E, R>>())
+            .cloned()
+            .collect()
+    }
+}
+
+impl SharedEventBus {
+    /// Subscribes `f` to be called by `publish_collect::<E, R>`, which
+    /// gathers what it returns (see the top of this file).
+    pub fn subscribe_returning<E: Event + 'static, R: 'static, F>(&self, f: F) -> HandlerId
+    where
+        F: Fn(&E) -> R + Send + Sync + 'static
+    {
+        match self.inner.lock() {
+            Ok(mut bus) => bus.subscribe_returning(f),
+            Err(_) => HandlerId::NONE,
+        }
+    }
+
+    pub fn unsubscribe_returning<E: Event + 'static, R: 'static>(&self, handler_id: HandlerId) {
+        if let Ok(mut bus) = self.inner.lock() {
+            if bus.slots.get(handler_id) == Some(Registration::Collector(key::<E, R>())) {
+                bus.remove_registration(handler_id);
+            }
+            let removed = bus.take_removed();
+            drop(bus);
+            drop(removed);
+        }
+    }
+
+    /// Calls every handler subscribed with `subscribe_returning::<E, R>`
+    /// with `event` and returns their results, in the order they were
+    /// subscribed. Empty if there are none.
+    pub fn publish_collect<E: Event + 'static, R: 'static>(&self, event: E) -> EventResult<Vec<R>> {
+        self.check_running()?;
+        let collectors = self.inner.lock()
+            .map_err(|_| EventError::BusPoisoned)?
+            .collectors::<E, R>();
+        Ok(collectors.iter().map(|f| f(&event)).collect())
+    }
+}
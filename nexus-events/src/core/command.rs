@@ -0,0 +1,152 @@
+// Commands: events taken by exactly one consumer instead of broadcast, such
+// as `SpawnEnemy`, which the spawner should act on once no matter how many
+// systems listen.
+//
+//     let spawns = bus.command_queue::<SpawnEnemy>()?;
+//     bus.publish(SpawnEnemy { at })?;       // queued for `spawns`
+//     while let Some(cmd) = spawns.pop() { spawner.spawn(cmd.at) }
+//
+// A type becomes a command once a queue is taken for it or it is declared
+// with `declare_command`, and stays one. From then on its events only go to
+// the queue: handlers subscribed to it aren't called, and it doesn't bubble.
+// While nobody holds the queue, `publish`, `emit` and `try_dispatch` fail
+// with `EventError::NoCommandQueue`, and a plain `dispatch` is counted as
+// dropped. Commands must be published by value; `publish_ref` fails with
+// `EventError::CommandByReference`.
+
+use std::any::{Any, TypeId};
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use super::{Event, EventBus, EventError, EventMeta, EventResult, SharedEventBus};
+
+/// Commands waiting for their consumer.
+pub(crate) type Commands = Mutex<VecDeque<Box<dyn Event>>>;
+
+/// The one consumer of a command type, from `SharedEventBus::command_queue`.
+/// Dropping it leaves the type a command with no queue.
+pub struct CommandQueue<E: Event + 'static> {
+    bus: SharedEventBus,
+    commands: Arc<Commands>,
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E: Event + 'static> CommandQueue<E> {
+    /// The oldest command not taken yet.
+    pub fn pop(&self) -> Option<E> {
+        self.pull();
+        let ev = self.commands.lock().ok()?.pop_front()?;
+        let ev: Box<dyn Any> = ev;
+        ev.downcast::<E>().ok().map(|ev| *ev)
+    }
+
+    /// Takes every command waiting, oldest first.
+    pub fn drain(&self) -> Vec<E> {
+        self.pull();
+        let Ok(mut commands) = self.commands.lock() else { return Vec::new() };
+        commands.drain(..)
+            .filter_map(|ev| (ev as Box<dyn Any>).downcast::<E>().ok())
+            .map(|ev| *ev)
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pull();
+        self.commands.lock().map(|commands| commands.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Moves dispatched commands still in the bus's inbox into the queue.
+    fn pull(&self) {
+        if let Ok(mut bus) = self.bus.inner.lock() {
+            bus.pull_inbox();
+        }
+    }
+}
+
+impl EventBus {
+    fn declare_command(&mut self, tid: TypeId) {
+        self.commands.entry(tid).or_default();
+        self.interest.add(tid);
+        if let Ok(mut types) = self.command_types.write() {
+            types.insert(tid);
+        }
+    }
+
+    fn has_command_queue(&self, tid: TypeId) -> bool {
+        self.commands.get(&tid).is_some_and(|commands| commands.strong_count() > 0)
+    }
+
+    /// Hands `ev` to the queue of its command type; `ev` back if its type
+    /// isn't a command. A command nobody holds the queue for is dropped.
+    pub(crate) fn route_command(&mut self, ev: Box<dyn Event>) -> Option<Box<dyn Event>> {
+        let Some(commands) = self.commands.get(&(*ev).as_any().type_id()) else { return Some(ev) };
+        match commands.upgrade() {
+            Some(commands) => {
+                if let Ok(mut commands) = commands.lock() {
+                    commands.push_back(ev);
+                }
+            }
+            None => self.dropped += 1,
+        }
+        None
+    }
+}
+
+impl SharedEventBus {
+    /// Takes the queue `E` goes to from now on, making `E` a command (see
+    /// the top of this file). Fails with `EventError::CommandQueueTaken`
+    /// while another queue for `E` is alive.
+    pub fn command_queue<E: Event + 'static>(&self) -> EventResult<CommandQueue<E>> {
+        let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        let tid = TypeId::of::<E>();
+        if bus.has_command_queue(tid) {
+            return Err(EventError::CommandQueueTaken(std::any::type_name::<E>()));
+        }
+        bus.declare_command(tid);
+        let commands = Arc::new(Commands::default());
+        bus.commands.insert(tid, Arc::downgrade(&commands));
+        Ok(CommandQueue { bus: self.clone(), commands, _event: PhantomData })
+    }
+
+    /// Makes `E` a command before its consumer takes the queue, so that
+    /// publishing one too early fails instead of reaching handlers.
+    pub fn declare_command<E: Event + 'static>(&self) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.declare_command(TypeId::of::<E>());
+        }
+    }
+
+    pub fn is_command<E: Event + 'static>(&self) -> bool {
+        let tid = TypeId::of::<E>();
+        !self.interest.unheard(tid) && self.command_types.read().is_ok_and(|types| types.contains(&tid))
+    }
+
+    /// Queues `ev` for its consumer if `E` is a command, returning the
+    /// outcome; `ev` back otherwise.
+    pub(crate) fn send_command<E: Event + 'static>(&self, ev: E) -> Result<EventResult<()>, E> {
+        if !self.is_command::<E>() {
+            return Err(ev);
+        }
+        Ok(self.send_boxed_command(Box::new(ev)))
+    }
+
+    fn send_boxed_command(&self, ev: Box<dyn Event>) -> EventResult<()> {
+        self.check_running()?;
+        self.validate(&*ev)?;
+        self.trace_publish(&*ev, None);
+        let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        // Earlier dispatches are taken first.
+        bus.pull_inbox();
+        let tid = (*ev).as_any().type_id();
+        if !bus.has_command_queue(tid) {
+            return Err(EventError::NoCommandQueue((*ev).event_name()));
+        }
+        bus.admit(ev, EventMeta::now(None));
+        Ok(())
+    }
+}
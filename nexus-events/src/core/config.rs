@@ -0,0 +1,395 @@
+// Bus tuning that can live outside the code: queue bounds, per-event
+// throttles and which event categories are enabled. Build a bus from it with
+// `EventBusBuilder`, or (with the `config` feature) load it from a TOML file.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+use super::{
+    ClassLimit, CriticalPolicy, DeadLetterSink, Event, EventBus, EventClass, EventError, EventMeta, EventResult,
+    OverflowPolicy, PanicPolicy, Queued, SharedEventBus,
+};
+use super::threads::ThreadSettings;
+use super::event_type::DispatchTable;
+#[cfg(feature = "config")]
+use super::EventResultExt;
+
+/// Tuning knobs for a bus. Every field is optional in the file.
+///
+/// ```toml
+/// max_queue_len = 4096
+/// queue_policy = "drop_oldest"
+/// enabled_categories = ["gameplay", "ui"]
+/// critical_class = 100
+/// critical_policy = "inline"
+/// class_order = ["control", "gameplay", "telemetry"]
+///
+/// [throttles]
+/// PlayerMoved = 30
+///
+/// [class_limits.telemetry]
+/// max_queued = 1000
+/// on_full = "drop_oldest"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(default, deny_unknown_fields))]
+pub struct BusConfig {
+    /// At most this many events are queued; what happens to those
+    /// dispatched beyond that is up to `queue_policy`.
+    pub max_queue_len: Option<usize>,
+    pub queue_policy: OverflowPolicy,
+    /// At most this many events of the named type are queued per
+    /// `process`. Names are matched against the type name with or without
+    /// its module path (`PlayerMoved` or `my_game::events::PlayerMoved`).
+    pub throttles: HashMap<String, u32>,
+    /// When set, events in any other category are dropped. Events without
+    /// a category (see `SharedEventBus::categorize`) are always let through.
+    pub enabled_categories: Option<HashSet<String>>,
+    /// Events of this class or higher (see `SharedEventBus::set_event_class`)
+    /// are critical and handled per `critical_policy`.
+    pub critical_class: Option<i32>,
+    pub critical_policy: CriticalPolicy,
+    /// The order `process` takes the event classes in; classes left out
+    /// follow in the default order (control, gameplay, telemetry).
+    pub class_order: Vec<EventClass>,
+    /// How many events of each class may be queued, and what happens to
+    /// those dispatched beyond that. Classes not listed are only bounded by
+    /// `max_queue_len`.
+    pub class_limits: HashMap<EventClass, ClassLimit>,
+}
+
+impl BusConfig {
+    /// Parses a TOML document.
+    #[cfg(feature = "config")]
+    pub fn from_toml_str(text: &str) -> EventResult<Self> {
+        toml::from_str(text).map_err(|e| EventError::Config(Box::new(e)))
+    }
+
+    /// Reads and parses a TOML file.
+    #[cfg(feature = "config")]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> EventResult<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        Self::from_toml_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub(crate) fn throttle_for(&self, type_name: &str) -> Option<u32> {
+        if self.throttles.is_empty() {
+            return None;
+        }
+        self.throttles.get(type_name)
+            .or_else(|| self.throttles.get(short_name(type_name)))
+            .copied()
+    }
+
+    pub(crate) fn category_enabled(&self, category: Option<&str>) -> bool {
+        match (&self.enabled_categories, category) {
+            (Some(enabled), Some(category)) => enabled.contains(category),
+            _ => true,
+        }
+    }
+}
+
+/// One difference between two configurations.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigChange {
+    MaxQueueLen { from: Option<usize>, to: Option<usize> },
+    QueuePolicy { from: OverflowPolicy, to: OverflowPolicy },
+    Throttle { event: String, from: Option<u32>, to: Option<u32> },
+    EnabledCategories { from: Option<HashSet<String>>, to: Option<HashSet<String>> },
+    CriticalClass { from: Option<i32>, to: Option<i32> },
+    CriticalPolicy { from: CriticalPolicy, to: CriticalPolicy },
+    ClassOrder { from: Vec<EventClass>, to: Vec<EventClass> },
+    ClassLimit { class: EventClass, from: Option<ClassLimit>, to: Option<ClassLimit> },
+}
+
+/// Dispatched on a bus after `apply_config` changed its configuration.
+#[derive(Debug, Clone)]
+pub struct BusReconfigured {
+    pub changes: Vec<ConfigChange>,
+}
+
+impl BusConfig {
+    /// What changes when going from `self` to `next`. Throttle changes are
+    /// sorted by event name, class limit changes by class.
+    pub fn diff(&self, next: &BusConfig) -> Vec<ConfigChange> {
+        let mut changes = Vec::new();
+        if self.max_queue_len != next.max_queue_len {
+            changes.push(ConfigChange::MaxQueueLen { from: self.max_queue_len, to: next.max_queue_len });
+        }
+        if self.queue_policy != next.queue_policy {
+            changes.push(ConfigChange::QueuePolicy { from: self.queue_policy, to: next.queue_policy });
+        }
+        let mut events: Vec<&String> = self.throttles.keys().chain(next.throttles.keys()).collect();
+        events.sort();
+        events.dedup();
+        for event in events {
+            let (from, to) = (self.throttles.get(event).copied(), next.throttles.get(event).copied());
+            if from != to {
+                changes.push(ConfigChange::Throttle { event: event.clone(), from, to });
+            }
+        }
+        if self.enabled_categories != next.enabled_categories {
+            changes.push(ConfigChange::EnabledCategories {
+                from: self.enabled_categories.clone(),
+                to: next.enabled_categories.clone(),
+            });
+        }
+        if self.critical_class != next.critical_class {
+            changes.push(ConfigChange::CriticalClass { from: self.critical_class, to: next.critical_class });
+        }
+        if self.critical_policy != next.critical_policy {
+            changes.push(ConfigChange::CriticalPolicy { from: self.critical_policy, to: next.critical_policy });
+        }
+        if self.class_order != next.class_order {
+            changes.push(ConfigChange::ClassOrder { from: self.class_order.clone(), to: next.class_order.clone() });
+        }
+        let classes: BTreeSet<EventClass> = self.class_limits.keys().chain(next.class_limits.keys()).copied().collect();
+        for class in classes {
+            let (from, to) = (self.class_limits.get(&class).copied(), next.class_limits.get(&class).copied());
+            if from != to {
+                changes.push(ConfigChange::ClassLimit { class, from, to });
+            }
+        }
+        changes
+    }
+}
+
+/// `my_game::events::PlayerMoved<T>` -> `PlayerMoved`.
+pub(crate) fn short_name(type_name: &str) -> &str {
+    let base = type_name.split('<').next().unwrap_or(type_name);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+/// Builds a `SharedEventBus` with a given configuration.
+#[derive(Clone, Default)]
+pub struct EventBusBuilder {
+    config: BusConfig,
+    threads: ThreadSettings,
+    id_seed: u64,
+    handler_capacity: usize,
+    queue_capacity: usize,
+    panic_policy: PanicPolicy,
+    dead_letters: Option<DeadLetterSink>,
+    dead_letter_invalid: bool,
+    metrics: bool,
+    max_handlers: Option<usize>,
+}
+
+impl fmt::Debug for EventBusBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventBusBuilder")
+            .field("config", &self.config)
+            .field("threads", &self.threads)
+            .field("id_seed", &self.id_seed)
+            .field("handler_capacity", &self.handler_capacity)
+            .field("queue_capacity", &self.queue_capacity)
+            .field("panic_policy", &self.panic_policy)
+            .field("dead_letters", &self.dead_letters.is_some())
+            .field("dead_letter_invalid", &self.dead_letter_invalid)
+            .field("metrics", &self.metrics)
+            .field("max_handlers", &self.max_handlers)
+            .finish()
+    }
+}
+
+impl EventBusBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts from the TOML file at `path`; builder calls made afterwards
+    /// override what the file says.
+    #[cfg(feature = "config")]
+    pub fn from_config(path: impl AsRef<std::path::Path>) -> EventResult<Self> {
+        Ok(Self::with_config(BusConfig::from_file(path)?))
+    }
+
+    pub fn with_config(config: BusConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn max_queue_len(mut self, len: usize) -> Self {
+        self.config.max_queue_len = Some(len);
+        self
+    }
+
+    /// What happens to events dispatched beyond `max_queue_len`. Defaults
+    /// to `OverflowPolicy::Error`.
+    pub fn queue_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.config.queue_policy = policy;
+        self
+    }
+
+    pub fn throttle(mut self, event_name: impl Into<String>, per_process: u32) -> Self {
+        self.config.throttles.insert(event_name.into(), per_process);
+        self
+    }
+
+    pub fn enable_category(mut self, category: impl Into<String>) -> Self {
+        self.config.enabled_categories
+            .get_or_insert_with(HashSet::new)
+            .insert(category.into());
+        self
+    }
+
+    /// Marks events of `class` or higher as critical and sets how they are
+    /// handled.
+    pub fn critical_events(mut self, class: i32, policy: CriticalPolicy) -> Self {
+        self.config.critical_class = Some(class);
+        self.config.critical_policy = policy;
+        self
+    }
+
+    /// Has `process` take the event classes in `order`.
+    pub fn class_order(mut self, order: impl IntoIterator<Item = EventClass>) -> Self {
+        self.config.class_order = order.into_iter().collect();
+        self
+    }
+
+    pub fn class_limit(mut self, class: EventClass, limit: ClassLimit) -> Self {
+        self.config.class_limits.insert(class, limit);
+        self
+    }
+
+    /// Threads the bus starts (loader workers, parallel systems) are named
+    /// `<prefix>-<role>`. The default prefix is `nexus-events`.
+    pub fn thread_name_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.threads.name_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Pins the threads the bus starts to these cores, round-robin. Only
+    /// takes effect with the `affinity` feature.
+    pub fn core_affinity(mut self, core_ids: impl IntoIterator<Item = usize>) -> Self {
+        self.threads.core_ids = core_ids.into_iter().collect();
+        self
+    }
+
+    /// Worker threads for background handlers (see
+    /// `SharedEventBus::subscribe_background`). Defaults to one per core.
+    pub fn background_threads(mut self, count: usize) -> Self {
+        self.threads.background_threads = Some(count);
+        self
+    }
+
+    /// Seed for keyed handler ids (see `SharedEventBus::subscribe_keyed`).
+    /// Buses built with the same seed give a key the same id.
+    pub fn handler_id_seed(mut self, seed: u64) -> Self {
+        self.id_seed = seed;
+        self
+    }
+
+    /// Room for handlers of this many event types before the handler map
+    /// has to grow. Types declared with `define_event!` don't count; they
+    /// are looked up by their `EventTypeId`.
+    pub fn handler_capacity(mut self, event_types: usize) -> Self {
+        self.handler_capacity = event_types;
+        self
+    }
+
+    /// Room for this many queued events before the queue has to grow. Not
+    /// a bound; see `max_queue_len` for that.
+    pub fn queue_capacity(mut self, events: usize) -> Self {
+        self.queue_capacity = events;
+        self
+    }
+
+    /// What happens when a handler panics. Defaults to
+    /// `PanicPolicy::Propagate`.
+    pub fn panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.panic_policy = policy;
+        self
+    }
+
+    /// Passes every event `process` delivers to no handler (on this bus or
+    /// its capturing children) to `sink`. The bus is unlocked while `sink`
+    /// runs. Unhandled events of a child bus still bubble to its parent
+    /// afterwards.
+    pub fn dead_letters<F>(mut self, sink: F) -> Self
+    where
+        F: Fn(&dyn Event) + Send + Sync + 'static
+    {
+        self.dead_letters = Some(Arc::new(sink));
+        self
+    }
+
+    /// Also passes events rejected by validation (see `Validate`) to the
+    /// `dead_letters` sink. Off by default.
+    pub fn dead_letter_invalid(mut self, enabled: bool) -> Self {
+        self.dead_letter_invalid = enabled;
+        self
+    }
+
+    /// Starts the bus with metrics recording on (see
+    /// `SharedEventBus::set_metrics_enabled`). Off by default.
+    pub fn metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    /// Refuses to subscribe more than `max` handlers to any one event
+    /// type: `try_subscribe` and `subscribe_keyed` fail with
+    /// `EventError::TooManyHandlers`, and `subscribe` returns
+    /// `HandlerId::NONE`.
+    pub fn max_handlers_per_type(mut self, max: usize) -> Self {
+        self.max_handlers = Some(max);
+        self
+    }
+
+    pub fn build(self) -> SharedEventBus {
+        let mut bus = EventBus::new();
+        bus.config = self.config;
+        bus.threads = self.threads;
+        bus.id_seed = self.id_seed;
+        bus.handlers = DispatchTable::with_capacity(self.handler_capacity);
+        bus.queue.reserve(self.queue_capacity);
+        bus.panic_policy = self.panic_policy;
+        bus.dead_letters = self.dead_letters;
+        bus.dead_letter_invalid = self.dead_letter_invalid;
+        bus.metrics.set_enabled(self.metrics);
+        bus.max_handlers = self.max_handlers;
+        bus.refresh_inline_types();
+        bus.refresh_class_order();
+        bus.refresh_queue_limits();
+        bus.refresh_interest();
+        SharedEventBus::from_bus(bus)
+    }
+}
+
+impl SharedEventBus {
+    /// Replaces the configuration of a live bus in one step and returns what
+    /// changed. If anything did, a `BusReconfigured` event is queued; it is
+    /// never dropped by the new settings. Events already queued stay queued
+    /// even if the new bounds would not have admitted them.
+    pub fn apply_config(&self, config: BusConfig) -> EventResult<Vec<ConfigChange>> {
+        let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        let changes = bus.config.diff(&config);
+        if !changes.is_empty() {
+            bus.config = config;
+            bus.refresh_inline_types();
+            bus.refresh_class_order();
+            bus.refresh_queue_limits();
+            self.inner.set_blocks(bus.config.may_block());
+            bus.pull_inbox();
+            let meta = bus.stamp(EventMeta::now(None));
+            bus.queue.push_back(Queued {
+                ev: Box::new(BusReconfigured { changes: changes.clone() }),
+                meta,
+            });
+        }
+        Ok(changes)
+    }
+
+    /// Reads a TOML file and applies it with `apply_config`.
+    #[cfg(feature = "config")]
+    pub fn apply_config_file(&self, path: impl AsRef<std::path::Path>) -> EventResult<Vec<ConfigChange>> {
+        self.apply_config(BusConfig::from_file(path)?)
+    }
+
+    pub fn config(&self) -> BusConfig {
+        self.inner.lock().map(|bus| bus.config.clone()).unwrap_or_default()
+    }
+}
@@ -0,0 +1,39 @@
+// Event contracts: a name plus a hash of the event's schema, compiled into
+// both the publishing and the subscribing crate so a mismatch between the
+// two builds can be detected at startup instead of as garbage at runtime.
+
+/// The compile-time identity of an event definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventContract {
+    pub name: &'static str,
+    pub schema_hash: u64,
+}
+
+impl EventContract {
+    /// Builds a contract from the event name and a textual description of
+    /// its schema (usually the field list, as `#[event_contract]` does).
+    pub const fn new(name: &'static str, schema: &str) -> Self {
+        Self { name, schema_hash: fnv1a(schema.as_bytes()) }
+    }
+}
+
+/// Implemented by events that carry a contract, normally via
+/// `#[event_contract]` on the event struct.
+pub trait HasContract {
+    const CONTRACT: EventContract;
+}
+
+const fn fnv1a(bytes: &[u8]) -> u64 {
+    fnv1a_from(0xcbf2_9ce4_8422_2325, bytes)
+}
+
+/// FNV-1a continued from `hash`, to chain several inputs into one hash.
+pub(crate) const fn fnv1a_from(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        i += 1;
+    }
+    hash
+}
@@ -0,0 +1,65 @@
+// Keeping critical events (quit, device lost) responsive under load. Events
+// whose class (see `SharedEventBus::set_event_class`) reaches
+// `BusConfig::critical_class` are treated according to `critical_policy`.
+
+use std::any::TypeId;
+use std::collections::{HashSet, VecDeque};
+
+use super::{Event, EventBus, Queued};
+
+/// What the bus does with critical events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "config", derive(serde::Deserialize))]
+#[cfg_attr(feature = "config", serde(rename_all = "lowercase"))]
+pub enum CriticalPolicy {
+    /// No special treatment.
+    Queue,
+    /// Delivered before everything else queued, and never held back by a
+    /// `ProcessBudget`.
+    #[default]
+    Boost,
+    /// Delivered during `dispatch`, before it returns, even when dispatched
+    /// from inside a handler. If nothing consumes the event it bubbles to the
+    /// parent on the next `process`.
+    Inline,
+}
+
+impl EventBus {
+    pub(crate) fn is_critical(&self, ev: &dyn Event) -> bool {
+        self.config.critical_class.is_some_and(|class| self.event_class(ev) >= class)
+    }
+
+    pub(crate) fn boosts_critical(&self) -> bool {
+        self.config.critical_class.is_some() && self.config.critical_policy == CriticalPolicy::Boost
+    }
+
+    pub(crate) fn inlines_critical(&self) -> bool {
+        self.config.critical_class.is_some() && self.config.critical_policy == CriticalPolicy::Inline
+    }
+
+    /// Republishes which types publishers must deliver inline. Call after
+    /// changing the configuration or event classes.
+    pub(crate) fn refresh_inline_types(&self) {
+        let types: HashSet<TypeId> = match self.config.critical_class {
+            Some(critical) if self.inlines_critical() => self.event_classes.iter()
+                .filter(|(_, class)| **class >= critical)
+                .map(|(tid, _)| *tid)
+                .collect(),
+            _ => HashSet::new(),
+        };
+        if let Ok(mut inline) = self.inline_types.write() {
+            *inline = types;
+        }
+    }
+
+    /// Moves critical events to the front, keeping order on both sides.
+    /// Returns how many there are.
+    pub(crate) fn front_load_critical(&self, queue: &mut VecDeque<Queued>) -> usize {
+        let (critical, rest): (VecDeque<Queued>, VecDeque<Queued>) =
+            queue.drain(..).partition(|q| self.is_critical(&*q.ev));
+        let count = critical.len();
+        *queue = critical;
+        queue.extend(rest);
+        count
+    }
+}
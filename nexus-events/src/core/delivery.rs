@@ -0,0 +1,317 @@
+// Running handlers with the bus unlocked. For every event the bus is locked
+// just long enough to take a snapshot of the handlers it reaches (a
+// `Delivery`), and the handlers run after the lock is released. A handler
+// may therefore dispatch, subscribe, unsubscribe or even process the bus it
+// is running on. Handler lists are copy-on-write, so taking the snapshot is
+// usually just cloning an `Arc`, and a change made during a delivery doesn't
+// affect that delivery:
+//
+// - a handler subscribed while an event is being delivered first sees the
+//   next event of that type, even if it would have sorted after the
+//   handler that subscribed it;
+// - pausing a subscription set or advancing the phase while an event is
+//   being delivered doesn't change which handlers that event reaches.
+//
+// Unsubscribing is the exception: a handler unsubscribed (or removed with
+// its subscription set or generation) while an event is being delivered
+// doesn't get that event if it hasn't yet, even on another thread. Every
+// handler is checked just before it is called; only a call that has
+// already started still finishes.
+//
+// Events taken off the queue for delivery are held by a `Pending`. If a
+// handler panics, the events after the one it was handling go back to the
+// front of the queue instead of being lost, and the bus stays usable. A bus
+// built with `PanicPolicy::Catch` doesn't let the panic out at all.
+
+use std::any::TypeId;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::metrics::MetricsRecorder;
+use super::watchdog::Watchdog;
+use super::snapshot::BusLock;
+use super::{
+    category, record, snapshot, trace, tracer, Converter, DeliveryReport, ErasedHandler, Event, EventBus, EventContext, EventMeta,
+    EventTraceCollector, HandlerList, Queued,
+};
+
+/// What a bus does when one of its handlers panics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// The panic unwinds out of `process` (or `emit`); events not yet
+    /// delivered stay queued.
+    #[default]
+    Propagate,
+    /// The panic is caught and counted in the `DeliveryReport`, and the
+    /// event goes on to the next handler.
+    Catch,
+}
+
+/// The handlers one event reaches on one bus, in the order they run: those
+/// of its type, then those of its categories.
+pub(crate) struct Delivery {
+    handlers: HandlerList,
+    categories: Vec<HandlerList>,
+    // Subscribed handlers left out because they weren't due.
+    pub(crate) skipped: usize,
+    metrics: Option<Arc<MetricsRecorder>>,
+    tracer: Option<EventTraceCollector>,
+    watchdog: Option<Watchdog>,
+    panic_policy: PanicPolicy,
+    // The bus's id, to list the handlers running in a `debug_snapshot`.
+    bus: u64,
+    // Deprecated types are also delivered, converted, to the handlers of
+    // the type that replaces them.
+    pub(crate) alias: Option<(Converter, Box<Delivery>)>,
+}
+
+impl Delivery {
+    /// Runs the handlers and reports what happened, including whether one
+    /// of them consumed the event.
+    pub(crate) fn run(&self, ev: &dyn Event, meta: EventMeta) -> DeliveryReport {
+        let mut ctx = EventContext { stopped: false, meta };
+        let mut report = DeliveryReport { skipped: self.skipped, ..DeliveryReport::default() };
+        for h in self.all_handlers() {
+            if h.is_retired() {
+                continue;
+            }
+            if self.call(&**h, ev, &mut ctx, &mut report) && ctx.is_propagation_stopped() {
+                report.consumed = true;
+                return report;
+            }
+        }
+        if let Some(((_, convert), next)) = &self.alias {
+            report.merge(next.run(&*convert(ev), meta));
+        }
+        report
+    }
+
+    /// The handlers of the type, then those of its categories.
+    pub(super) fn all_handlers(&self) -> impl Iterator<Item = &Arc<dyn ErasedHandler>> {
+        self.handlers.iter().chain(self.categories.iter().flat_map(|list| list.iter()))
+    }
+
+    /// Calls `h` and counts it in `report`. Returns false if it panicked
+    /// (and the panic was caught).
+    pub(super) fn call(
+        &self,
+        h: &dyn ErasedHandler,
+        ev: &dyn Event,
+        ctx: &mut EventContext,
+        report: &mut DeliveryReport,
+    ) -> bool {
+        let _running = snapshot::running(self.bus, ev.event_name(), h.id());
+        let mut call = || trace::handler_span(ev.event_name(), h.id(), || match &self.tracer {
+            Some(_) => tracer::running(h.id(), || h.handle(ev, ctx)),
+            None => h.handle(ev, ctx),
+        });
+        let elapsed = match self.panic_policy {
+            PanicPolicy::Propagate => call(),
+            PanicPolicy::Catch => match panic::catch_unwind(AssertUnwindSafe(call)) {
+                Ok(elapsed) => elapsed,
+                Err(_) => {
+                    report.handlers += 1;
+                    report.panicked += 1;
+                    if let Some(tracer) = &self.tracer {
+                        tracer.handled(ev.event_name(), h.id(), Duration::ZERO, true);
+                    }
+                    return false;
+                }
+            },
+        };
+        report.handlers += 1;
+        report.duration += elapsed;
+        if let Some(metrics) = &self.metrics {
+            metrics.handled(ev.as_any().type_id(), ev.event_name(), elapsed);
+        }
+        if let Some(tracer) = &self.tracer {
+            tracer.handled(ev.event_name(), h.id(), elapsed, false);
+        }
+        if let Some(watchdog) = &self.watchdog {
+            watchdog.check(h.id(), ev, elapsed);
+        }
+        true
+    }
+
+    /// Narrows the delivery to the handlers subscribed under address `to`.
+    /// Category handlers never have an address.
+    fn address(&mut self, to: u64, addresses: &HashMap<u64, u64>) {
+        if !self.handlers.is_empty() {
+            self.handlers = Arc::new(
+                self.handlers.iter().filter(|h| addresses.get(&h.id()) == Some(&to)).cloned().collect()
+            );
+        }
+        self.categories.clear();
+        if let Some((_, next)) = &mut self.alias {
+            next.address(to, addresses);
+        }
+    }
+
+    /// How many handlers `run` would call if none consumed the event.
+    pub(crate) fn handler_count(&self) -> usize {
+        self.handlers.len()
+            + self.categories.iter().map(|list| list.len()).sum::<usize>()
+            + self.alias.as_ref().map_or(0, |(_, next)| next.handler_count())
+    }
+}
+
+impl EventBus {
+    /// Copies out the handlers an event of type `tid` reaches right now:
+    /// those of the type itself (phase and stagger applied), then those of
+    /// its categories, then, if it is deprecated, those of its replacement.
+    pub(crate) fn plan(&self, tid: TypeId) -> Delivery {
+        let mut skipped = 0;
+        let handlers = self.handlers.get(&tid)
+            .map(|list| {
+                let stagger = self.staggered.get(&tid).map(|s| (s, s.advance()));
+                let visible = self.visible(list, |h| stagger.is_none_or(|(s, slice)| s.in_slice(h.id(), slice)));
+                skipped += list.len() - visible.len();
+                visible
+            })
+            .unwrap_or_default();
+        let categories = if self.category_handlers.is_empty() {
+            Vec::new()
+        } else {
+            category::categories_of(tid).into_iter()
+                .filter_map(|parent| self.category_handlers.get(&parent))
+                .map(|list| {
+                    let visible = self.visible(list, |_| true);
+                    skipped += list.len() - visible.len();
+                    visible
+                })
+                .collect()
+        };
+        // `alias` refuses cycles, so this ends.
+        let alias = self.aliases.get(&tid)
+            .map(|converter| (converter.clone(), Box::new(self.plan(converter.0))));
+        Delivery {
+            handlers,
+            categories,
+            skipped,
+            metrics: self.metrics.is_enabled().then(|| self.metrics.clone()),
+            tracer: self.tracer.clone(),
+            watchdog: self.watchdog.clone(),
+            panic_policy: self.panic_policy,
+            bus: self.bus_id,
+            alias,
+        }
+    }
+
+    /// `plan` for an event with the given metadata: an addressed event
+    /// only reaches the handlers subscribed under its address.
+    pub(crate) fn plan_for(&self, tid: TypeId, meta: EventMeta) -> Delivery {
+        let mut delivery = self.plan(tid);
+        if let Some(to) = meta.to {
+            delivery.address(to, &self.addresses);
+        }
+        delivery
+    }
+
+    /// The handlers of `list` that are due (still subscribed, phase
+    /// reached, not paused, and `due`): `list` itself when all of them are,
+    /// otherwise a copy.
+    fn visible<F>(&self, list: &HandlerList, due: F) -> HandlerList
+    where
+        F: Fn(&Arc<dyn ErasedHandler>) -> bool
+    {
+        let due = |h: &Arc<dyn ErasedHandler>| {
+            !h.is_retired() && h.phase() <= self.phase && !self.paused.contains(&h.id()) && due(h)
+        };
+        if list.iter().all(due) {
+            list.clone()
+        } else {
+            Arc::new(list.iter().filter(|h| due(h)).cloned().collect())
+        }
+    }
+}
+
+/// Delivers to `bus`, then to its capturing children. Each bus is only
+/// locked to plan the delivery. The report covers every bus reached.
+pub(crate) fn deliver_down(bus: &BusLock, ev: &dyn Event, meta: EventMeta) -> DeliveryReport {
+    deliver_down_with(bus, ev, meta, Delivery::run)
+}
+
+/// `deliver_down`, running each bus's handlers with `run`.
+pub(crate) fn deliver_down_with(bus: &BusLock, ev: &dyn Event, meta: EventMeta, run: Run) -> DeliveryReport {
+    let (delivery, children) = match bus.lock() {
+        Ok(bus) => (bus.plan_for(ev.as_any().type_id(), meta), bus.children.clone()),
+        Err(_) => return DeliveryReport::default(),
+    };
+    let mut report = run(&delivery, ev, meta);
+    for edge in children.iter().filter(|c| c.allows(ev)) {
+        if report.consumed {
+            break;
+        }
+        let Some(child) = edge.bus.upgrade() else { continue };
+        report.merge(deliver_down_with(&child, ev, meta, run));
+    }
+    report
+}
+
+/// How a `Delivery` is run: `Delivery::run`, or in parallel.
+pub(crate) type Run = fn(&Delivery, &dyn Event, EventMeta) -> DeliveryReport;
+
+/// Delivers inline critical events taken from `bus` (see
+/// `EventBus::inline_due`) with it unlocked. Those nobody consumed bubble to
+/// the parent on the next `process`.
+pub(crate) fn deliver_inline(bus: &BusLock, due: Vec<Queued>) {
+    if due.is_empty() {
+        return;
+    }
+    let (consumed, unconsumed): (Vec<Queued>, Vec<Queued>) = due.into_iter()
+        .partition(|q| deliver_down(bus, &*q.ev, q.meta).consumed);
+    let Ok(mut bus) = bus.lock() else { return };
+    let mut done = consumed;
+    if bus.parent.is_some() {
+        bus.inline_bubbling.extend(unconsumed);
+    } else {
+        done.extend(unconsumed);
+    }
+    let record = bus.record.clone();
+    drop(bus);
+    for q in done {
+        record::keep_in(&record, q.ev);
+    }
+}
+
+/// Events taken off a bus's queue for delivery. Whatever is left when this
+/// is dropped (a budget ran out, or a handler panicked) goes back to the
+/// front of the queue, ahead of anything dispatched meanwhile.
+pub(crate) struct Pending<'a> {
+    bus: &'a BusLock,
+    pub(crate) events: VecDeque<Queued>,
+}
+
+impl<'a> Pending<'a> {
+    pub(crate) fn new(bus: &'a BusLock, events: VecDeque<Queued>) -> Self {
+        Self { bus, events }
+    }
+
+    /// Delivers the next event and hands it back with whether a handler
+    /// consumed it, or returns `None` once all are delivered. An event no
+    /// handler received goes to the bus's dead-letter sink, if it has one.
+    pub(crate) fn deliver_next(&mut self) -> Option<(Queued, bool)> {
+        let queued = self.events.pop_front()?;
+        let report = deliver_down(self.bus, &*queued.ev, queued.meta);
+        if !report.delivered() {
+            let sink = self.bus.lock().ok().and_then(|bus| bus.dead_letters.clone());
+            if let Some(sink) = sink {
+                sink(&*queued.ev);
+            }
+        }
+        Some((queued, report.consumed))
+    }
+}
+
+impl Drop for Pending<'_> {
+    fn drop(&mut self) {
+        if self.events.is_empty() {
+            return;
+        }
+        if let Ok(mut bus) = self.bus.lock() {
+            bus.queue.put_back(std::mem::take(&mut self.events).into_iter());
+        }
+    }
+}
@@ -0,0 +1,141 @@
+// Domains: isolated buses for the plugins of an engine, so two plugins can
+// use the same event type without hearing each other, and neither can
+// listen in on the other's events.
+//
+//     let physics = bus.domain("physics");
+//     physics.export::<Collision>();       // physics -> the bus it hangs off
+//     let audio = bus.domain("audio");
+//     audio.import::<Collision>();         // that bus -> audio
+//
+// A domain is a bus of its own, owned by the bus it was created from (its
+// root) and reached again by name. Everything published or subscribed on
+// it stays there, except for the types it exports, which are republished
+// on the root as they are delivered in the domain, and the types it
+// imports, which are republished in the domain as they are delivered on
+// the root. A domain exporting a type to another that imports it thus
+// reaches that domain through the root. An event isn't sent back to the
+// domain it came from, and one consumed by a handler isn't forwarded. Both
+// directions clone the event.
+//
+// `process` on the root processes its domains afterwards, so the game loop
+// doesn't need to know about them.
+
+use std::cell::RefCell;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::snapshot::BusLock;
+use super::{Event, EventContext, EventResult, Phase, SharedEventBus};
+
+thread_local! {
+    // The domains an event is being forwarded into or out of on this
+    // thread, by the address of their bus.
+    static FORWARDING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A named, isolated bus owned by another bus; see `SharedEventBus::domain`.
+/// Derefs to the domain's own bus for publishing, subscribing and the rest.
+#[derive(Clone)]
+pub struct EventDomain {
+    name: Arc<str>,
+    bus: SharedEventBus,
+    root: SharedEventBus,
+}
+
+impl EventDomain {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The bus this domain was created from.
+    pub fn root(&self) -> &SharedEventBus {
+        &self.root
+    }
+
+    /// Republishes every `E` delivered in this domain on the root, where
+    /// the root's handlers and the domains importing `E` get it. Exporting
+    /// a type twice does nothing more.
+    pub fn export<E: Event + Clone + 'static>(&self) -> &Self {
+        let root = self.root.downgrade();
+        let domain = id_of(&self.bus.inner);
+        let _ = self.bus.subscribe_keyed_in_phase::<E, _>(
+            "nexus_events::domain::export",
+            Phase::PreInit,
+            i32::MIN,
+            move |ev: &E, _: &mut EventContext| {
+                if let Some(root) = root.upgrade() {
+                    forward(domain, || root.publish(ev.clone()));
+                }
+            },
+        );
+        self
+    }
+
+    /// Republishes every `E` delivered on the root in this domain, whether
+    /// published there or exported by another domain. Importing a type
+    /// twice does nothing more.
+    pub fn import<E: Event + Clone + 'static>(&self) -> &Self {
+        let bus = self.bus.clone();
+        let domain = id_of(&self.bus.inner);
+        let _ = self.root.subscribe_keyed_in_phase::<E, _>(
+            &format!("nexus_events::domain::import::{}", self.name),
+            Phase::PreInit,
+            i32::MIN,
+            move |ev: &E, _: &mut EventContext| forward(domain, || bus.publish(ev.clone())),
+        );
+        self
+    }
+}
+
+impl Deref for EventDomain {
+    type Target = SharedEventBus;
+
+    fn deref(&self) -> &SharedEventBus {
+        &self.bus
+    }
+}
+
+fn id_of(bus: &Arc<BusLock>) -> usize {
+    Arc::as_ptr(bus) as usize
+}
+
+/// Runs `send` unless an event is already being forwarded into or out of
+/// `domain` on this thread, which would send it back where it came from.
+fn forward<R>(domain: usize, send: impl FnOnce() -> EventResult<R>) {
+    if FORWARDING.with(|f| f.borrow().contains(&domain)) {
+        return;
+    }
+    struct Done;
+    impl Drop for Done {
+        fn drop(&mut self) {
+            FORWARDING.with(|f| f.borrow_mut().pop());
+        }
+    }
+    FORWARDING.with(|f| f.borrow_mut().push(domain));
+    let _done = Done;
+    let _ = send();
+}
+
+impl SharedEventBus {
+    /// The domain called `name` on this bus, created empty the first time
+    /// (see the top of this file).
+    pub fn domain(&self, name: &str) -> EventDomain {
+        let bus = match self.inner.lock() {
+            Ok(mut bus) => bus.domains.entry(name.to_string()).or_default().clone(),
+            Err(_) => SharedEventBus::new(),
+        };
+        EventDomain { name: name.into(), bus, root: self.clone() }
+    }
+
+    /// Processes the domains of this bus, after `process` has processed
+    /// the bus itself.
+    pub(crate) fn process_domains(&self) {
+        let domains: Vec<SharedEventBus> = match self.inner.lock() {
+            Ok(bus) if !bus.domains.is_empty() => bus.domains.values().cloned().collect(),
+            _ => return,
+        };
+        for domain in domains {
+            domain.process();
+        }
+    }
+}
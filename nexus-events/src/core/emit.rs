@@ -0,0 +1,89 @@
+// What an `#[event_sender]` method returns when its body decides whether
+// the event goes out, e.g. a move that turns out to be invalid. The macro
+// dispatches a `Send` event on the global bus and drops a `Skip`.
+//
+// Components that send on buses of their own, e.g. a scene bus next to the
+// global one, name them through `EventEmitter`, and their sender methods
+// pick one with `#[event_sender(MyEvent, bus = "scene")]`. The buses may
+// be `SharedEventBus`es or, with the `local` feature, single-threaded
+// `LocalSharedEventBus`es: the macro sends through `EventSink`, which both
+// implement.
+// `#[derive(EventEmitter)]` writes the impl from the component's bus fields.
+
+use super::{dispatch, Event, SharedEventBus};
+
+/// A bus an event can be dispatched on, whichever kind it is.
+pub trait EventSink<E> {
+    fn dispatch_event(&self, ev: E);
+}
+
+impl<E: Event + 'static> EventSink<E> for SharedEventBus {
+    fn dispatch_event(&self, ev: E) {
+        self.dispatch(ev);
+    }
+}
+
+/// A component with named buses for its `#[event_sender]` methods.
+///
+/// ```ignore
+/// impl EventEmitter for Door {
+///     type Bus = SharedEventBus;
+///
+///     fn sender_named(&self, name: &str) -> Option<&SharedEventBus> {
+///         match name {
+///             "scene" => Some(&self.scene_bus),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+///
+/// A sender whose bus isn't there (`None`) sends nothing.
+/// `#[derive(EventEmitter)]` generates this from the bus fields.
+pub trait EventEmitter {
+    type Bus;
+
+    fn sender_named(&self, name: &str) -> Option<&Self::Bus>;
+}
+
+/// The outcome of a conditional `#[event_sender]` method. `Option<E>`
+/// works the same way, `None` meaning `Skip`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmitControl<E> {
+    Send(E),
+    Skip,
+}
+
+impl<E: Event> EmitControl<E> {
+    /// Dispatches a `Send` event on the global bus. Returns whether there
+    /// was one.
+    pub fn emit(self) -> bool {
+        match self {
+            EmitControl::Send(ev) => {
+                dispatch(ev);
+                true
+            }
+            EmitControl::Skip => false,
+        }
+    }
+
+}
+
+impl<E> EmitControl<E> {
+    /// Dispatches a `Send` event on `bus`. Returns whether there was one.
+    pub fn emit_to<B: EventSink<E> + ?Sized>(self, bus: &B) -> bool {
+        match self {
+            EmitControl::Send(ev) => {
+                bus.dispatch_event(ev);
+                true
+            }
+            EmitControl::Skip => false,
+        }
+    }
+}
+
+impl<E> From<Option<E>> for EmitControl<E> {
+    fn from(ev: Option<E>) -> Self {
+        ev.map_or(EmitControl::Skip, EmitControl::Send)
+    }
+}
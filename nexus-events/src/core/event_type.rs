@@ -0,0 +1,139 @@
+// Dense ids for event types, so the per-delivery handler lookup indexes a
+// `Vec` instead of probing a map. Every non-generic type declared with
+// `define_event!` is registered at startup (see `registry`) and numbered
+// from 0 in registration order, once, when the table is first used; the
+// table never changes afterwards, so finding an id takes no lock. Each of
+// those types implements `EventType` and keeps its id in an atomic after
+// the first lookup.
+//
+// Types the table doesn't know (plain structs, generic events, anything
+// from another crate) go to the `TypeId` map of a `DispatchTable`, as
+// before.
+
+use std::any::TypeId;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
+
+use super::registry::registered_types;
+use super::type_map::TypeMap;
+use super::Event;
+
+/// The dense id of an event type declared with `define_event!`: ids run
+/// from 0 up to the number of such types. Only stable within one run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EventTypeId(u32);
+
+impl EventTypeId {
+    /// The id of `E`, or `None` if the table doesn't have it (its
+    /// registration wasn't collected on this target).
+    pub fn of<E: EventType>() -> Option<Self> {
+        E::event_type_id()
+    }
+
+    /// The id of the type `tid`, if it has one.
+    pub fn lookup(tid: TypeId) -> Option<Self> {
+        ids().get(&tid).map(|&id| EventTypeId(id))
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// An event type with a dense id, implemented by `define_event!`.
+pub trait EventType: Event {
+    fn event_type_id() -> Option<EventTypeId>;
+}
+
+fn ids() -> &'static TypeMap<u32> {
+    static IDS: OnceLock<TypeMap<u32>> = OnceLock::new();
+    IDS.get_or_init(|| {
+        let mut ids = TypeMap::default();
+        for tid in registered_types() {
+            let next = ids.len() as u32;
+            ids.entry(tid).or_insert(next);
+        }
+        ids
+    })
+}
+
+/// Where a `define_event!` type caches its id.
+#[doc(hidden)]
+pub struct EventTypeIdCell(AtomicU32);
+
+impl EventTypeIdCell {
+    const UNSET: u32 = u32::MAX;
+
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self(AtomicU32::new(Self::UNSET))
+    }
+
+    pub fn get<E: Event>(&self) -> Option<EventTypeId> {
+        let id = self.0.load(Ordering::Relaxed);
+        if id != Self::UNSET {
+            return Some(EventTypeId(id));
+        }
+        let id = EventTypeId::lookup(TypeId::of::<E>())?;
+        self.0.store(id.0, Ordering::Relaxed);
+        Some(id)
+    }
+}
+
+/// Values by event type: a `Vec` slot for types with a dense id, a map
+/// entry for the rest.
+pub(crate) struct DispatchTable<V> {
+    dense: Vec<Option<V>>,
+    foreign: TypeMap<V>,
+}
+
+impl<V> Default for DispatchTable<V> {
+    fn default() -> Self {
+        Self { dense: Vec::new(), foreign: TypeMap::default() }
+    }
+}
+
+impl<V: Default> DispatchTable<V> {
+    /// Room for this many types without a dense id before the map grows.
+    pub(crate) fn with_capacity(foreign: usize) -> Self {
+        Self { dense: Vec::new(), foreign: TypeMap::with_capacity_and_hasher(foreign, Default::default()) }
+    }
+
+    pub(crate) fn get(&self, tid: &TypeId) -> Option<&V> {
+        match ids().get(tid) {
+            Some(&id) => self.dense.get(id as usize)?.as_ref(),
+            None => self.foreign.get(tid),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, tid: &TypeId) -> Option<&mut V> {
+        match ids().get(tid) {
+            Some(&id) => self.dense.get_mut(id as usize)?.as_mut(),
+            None => self.foreign.get_mut(tid),
+        }
+    }
+
+    /// The value of `tid`, inserting the default first if there is none.
+    pub(crate) fn entry_or_default(&mut self, tid: TypeId) -> &mut V {
+        match ids().get(&tid) {
+            Some(&id) => {
+                let id = id as usize;
+                if self.dense.len() <= id {
+                    self.dense.resize_with(id + 1, || None);
+                }
+                self.dense[id].get_or_insert_with(V::default)
+            }
+            None => self.foreign.entry(tid).or_default(),
+        }
+    }
+
+    /// Every type with a value, and the value.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (TypeId, &V)> {
+        let dense = ids().iter().filter_map(|(tid, &id)| Some((*tid, self.dense.get(id as usize)?.as_ref()?)));
+        dense.chain(self.foreign.iter().map(|(tid, v)| (*tid, v)))
+    }
+
+    pub(crate) fn values(&self) -> impl Iterator<Item = &V> {
+        self.dense.iter().flatten().chain(self.foreign.values())
+    }
+}
@@ -0,0 +1,46 @@
+// Handlers on free functions, for the ones that don't belong to a component:
+// logging, metrics, a cheat console.
+//
+//     #[global_event_handler(PlayerMoved)]
+//     fn log_moves(ev: &PlayerMoved) { log::debug!("moved to {:?}", ev.pos) }
+//
+//     bus.subscribe_global_handlers()?;   // once, at startup
+//
+// Every function marked `#[global_event_handler]` anywhere in the program is
+// collected at startup and subscribed by `subscribe_global_handlers`, keyed
+// by its path (see `subscribe_keyed`), so subscribing them twice on the same
+// bus adds nothing.
+
+use super::{EventError, EventResult, HandlerId, SharedEventBus};
+
+/// Submitted by `#[global_event_handler]`; collected when the handlers are
+/// subscribed.
+#[doc(hidden)]
+pub struct GlobalHandler {
+    path: &'static str,
+    subscribe: fn(&SharedEventBus, &str) -> EventResult<HandlerId>,
+}
+
+impl GlobalHandler {
+    pub const fn new(path: &'static str, subscribe: fn(&SharedEventBus, &str) -> EventResult<HandlerId>) -> Self {
+        Self { path, subscribe }
+    }
+}
+
+inventory::collect!(GlobalHandler);
+
+impl SharedEventBus {
+    /// Subscribes every `#[global_event_handler]` function not subscribed
+    /// on this bus yet and returns how many that was.
+    pub fn subscribe_global_handlers(&self) -> EventResult<usize> {
+        let mut subscribed = 0;
+        for handler in inventory::iter::<GlobalHandler> {
+            match (handler.subscribe)(self, handler.path) {
+                Ok(_) => subscribed += 1,
+                Err(EventError::DuplicateHandlerKey { .. }) => {}
+                Err(e) => return Err(e.context(format!("subscribing `{}`", handler.path))),
+            }
+        }
+        Ok(subscribed)
+    }
+}
@@ -0,0 +1,79 @@
+// Registration generations, for game code that is hot-reloaded as a dylib.
+// Handlers registered by the old library point into code that is about to
+// be unloaded, so they must all be gone before it is. The reload goes:
+//
+//     let gen = bus.begin_generation();
+//     game_lib.register(&bus);          // handlers are tagged with `gen`
+//     ...
+//     bus.retire_generation(gen);       // before unloading the library
+//     let gen = bus.begin_generation();
+//     new_game_lib.register(&bus);
+//
+// Retiring covers event and category handlers (including those made through
+// subscription sets, labels and the decorators) and query responders. It
+// takes the bus lock once, so none of them is called once it returns, but a
+// call already running on another thread still finishes: retire between
+// `process` calls. Events of types defined by the
+// old library that are still queued should be drained first, too.
+
+use std::collections::HashSet;
+
+use super::{EventBus, SharedEventBus};
+
+/// One batch of registrations, e.g. everything one build of a hot-reloaded
+/// library subscribed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Generation(u64);
+
+impl EventBus {
+    pub(crate) fn tag_generation(&mut self, id: u64) {
+        if let Some(generation) = self.generation {
+            self.generations.insert(id, generation);
+        }
+    }
+}
+
+impl SharedEventBus {
+    /// Starts a new generation: every handler and responder registered on
+    /// this bus from now on, from any thread, is tagged with it until the
+    /// next `begin_generation` or `end_generation`.
+    pub fn begin_generation(&self) -> Generation {
+        let Ok(mut bus) = self.inner.lock() else { return Generation(0) };
+        bus.last_generation += 1;
+        let generation = Generation(bus.last_generation);
+        bus.generation = Some(generation);
+        generation
+    }
+
+    /// Stops tagging registrations; the ones made from now on belong to no
+    /// generation and are never retired with one.
+    pub fn end_generation(&self) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.generation = None;
+        }
+    }
+
+    /// The generation registrations are being tagged with, if any.
+    pub fn current_generation(&self) -> Option<Generation> {
+        self.inner.lock().ok().and_then(|bus| bus.generation)
+    }
+
+    /// Removes every handler and responder tagged with `generation`, in one
+    /// step, and returns how many there were. If it is the current
+    /// generation, tagging stops as with `end_generation`.
+    pub fn retire_generation(&self, generation: Generation) -> usize {
+        let Ok(mut bus) = self.inner.lock() else { return 0 };
+        if bus.generation == Some(generation) {
+            bus.generation = None;
+        }
+        let ids: HashSet<u64> = bus.generations.iter()
+            .filter(|(_, g)| **g == generation)
+            .map(|(&id, _)| id)
+            .collect();
+        bus.remove_handlers(&ids);
+        let removed = bus.take_removed();
+        drop(bus);
+        drop(removed);
+        ids.len()
+    }
+}
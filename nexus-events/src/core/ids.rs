@@ -0,0 +1,64 @@
+// Stable handler ids. Plain `subscribe` numbers handlers in subscription
+// order, which shifts whenever startup order does. A keyed subscription
+// derives its id from the bus seed, the event type and a caller-chosen key
+// (typically owner and method name), so the same handler gets the same id in
+// every run and traces from different sessions line up.
+
+use super::contract::fnv1a_from;
+use super::slots::KEYED_BIT;
+use super::{Event, EventContext, EventError, EventResult, HandlerId, Phase, SharedEventBus};
+
+pub(crate) fn keyed_id(seed: u64, event: &str, key: &str) -> HandlerId {
+    let hash = fnv1a_from(fnv1a_from(seed ^ 0xcbf2_9ce4_8422_2325, event.as_bytes()), key.as_bytes());
+    HandlerId(hash | KEYED_BIT)
+}
+
+impl SharedEventBus {
+    /// Subscribes with an id derived from `key` instead of a counter. Fails
+    /// if a handler with that key is already subscribed to `E`.
+    pub fn subscribe_keyed<E: Event + 'static, F>(&self, key: &str, f: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        self.insert_keyed::<E, _>(key, Phase::PreInit, 0, true, move |ev: &E, _ctx: &mut EventContext| f(ev))
+    }
+
+    pub fn subscribe_keyed_in_phase<E: Event + 'static, F>(
+        &self,
+        key: &str,
+        phase: Phase,
+        priority: i32,
+        f: F,
+    ) -> EventResult<HandlerId>
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.insert_keyed::<E, _>(key, phase, priority, false, f)
+    }
+
+    fn insert_keyed<E: Event + 'static, F>(
+        &self,
+        key: &str,
+        phase: Phase,
+        priority: i32,
+        independent: bool,
+        f: F,
+    ) -> EventResult<HandlerId>
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        let event = std::any::type_name::<E>();
+        let id = keyed_id(bus.id_seed, event, key);
+        if bus.slots.get(id).is_some() {
+            return Err(EventError::DuplicateHandlerKey { key: key.to_string(), event });
+        }
+        bus.insert_handler(Some(id), priority, phase, independent, f)
+    }
+
+    /// The id `subscribe_keyed::<E>(key)` gives (or gave) on this bus.
+    pub fn keyed_handler_id<E: Event + 'static>(&self, key: &str) -> HandlerId {
+        let seed = self.inner.lock().map(|bus| bus.id_seed).unwrap_or(0);
+        keyed_id(seed, std::any::type_name::<E>(), key)
+    }
+}
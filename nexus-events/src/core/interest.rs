@@ -0,0 +1,74 @@
+// Publishing a type nobody listens to should cost next to nothing, and
+// finding that out through the handler table means locking the bus first.
+// `Interest` answers it without the lock: a small bloom filter over the
+// event types the bus may deliver, kept next to the bus and read with two
+// relaxed loads. It only ever errs one way: a type it calls unheard has no
+// handlers, while one it calls heard may have lost them since (or share
+// its bits with one that has some).
+//
+// Types are added as they gain handlers, aliases, categories or a command
+// queue, and the filter is rebuilt from the handler table when a type's
+// last handler goes. Whatever makes the bus see every publish, whatever
+// its type (category handlers, metrics, a trace collector, a parent or
+// capturing children), sets `everything` instead, and the filter is no
+// longer consulted until that is over.
+//
+// All writes happen under the bus lock, so a rebuild never races an add.
+// It stores one word at a time, but a type still listened to has its bits
+// set in the old words and the new ones alike.
+
+use std::any::TypeId;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use super::type_map::TypeIdHasher;
+
+const WORDS: usize = 16;
+const BITS: u64 = WORDS as u64 * 64;
+
+#[derive(Default)]
+pub(crate) struct Interest {
+    bits: [AtomicU64; WORDS],
+    everything: AtomicBool,
+}
+
+impl Interest {
+    /// Notes that `tid` may be listened to from now on.
+    pub(crate) fn add(&self, tid: TypeId) {
+        for bit in bits_of(tid) {
+            self.bits[bit / 64].fetch_or(1 << (bit % 64), Ordering::Relaxed);
+        }
+    }
+
+    /// Whether every publish has to go through the bus, whatever its type.
+    pub(crate) fn set_everything(&self, everything: bool) {
+        self.everything.store(everything, Ordering::Relaxed);
+    }
+
+    /// Whether nothing on the bus can receive an event of type `tid`.
+    pub(crate) fn unheard(&self, tid: TypeId) -> bool {
+        !self.everything.load(Ordering::Relaxed)
+            && bits_of(tid).iter().any(|&bit| self.bits[bit / 64].load(Ordering::Relaxed) & (1 << (bit % 64)) == 0)
+    }
+
+    /// Forgets every type but `types`.
+    pub(crate) fn rebuild(&self, types: impl IntoIterator<Item = TypeId>) {
+        let mut words = [0u64; WORDS];
+        for tid in types {
+            for bit in bits_of(tid) {
+                words[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        for (word, bits) in self.bits.iter().zip(words) {
+            word.store(bits, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The two bits standing for `tid`, from either half of its hash.
+fn bits_of(tid: TypeId) -> [usize; 2] {
+    let mut hasher = TypeIdHasher::default();
+    tid.hash(&mut hasher);
+    let hash = hasher.finish();
+    [(hash % BITS) as usize, ((hash >> 32) % BITS) as usize]
+}
@@ -0,0 +1,54 @@
+// Handlers grouped under a caller-chosen label ("debug", "tutorial", ...)
+// so a whole group can be torn down at once, whatever events its handlers
+// are subscribed to. Labels also show up in `handler_stats`.
+
+use std::collections::HashSet;
+
+use super::{Event, EventContext, HandlerId, Phase, SharedEventBus};
+
+impl SharedEventBus {
+    /// Subscribes `f` under `label`. Remove it with `unsubscribe` as usual,
+    /// or together with the rest of its group with `unsubscribe_label`.
+    pub fn subscribe_labeled<E: Event + 'static, F>(&self, label: &str, f: F) -> HandlerId
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        self.subscribe_labeled_in_phase::<E, _>(label, Phase::PreInit, 0, move |ev: &E, _ctx: &mut EventContext| f(ev))
+    }
+
+    pub fn subscribe_labeled_in_phase<E: Event + 'static, F>(&self, label: &str, phase: Phase, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        let Ok(mut bus) = self.inner.lock() else { return HandlerId::NONE };
+        let Ok(id) = bus.subscribe(priority, phase, f) else { return HandlerId::NONE };
+        bus.labels.insert(id.0, label.to_string());
+        id
+    }
+
+    /// Unsubscribes every handler labeled `label` and returns how many
+    /// there were.
+    pub fn unsubscribe_label(&self, label: &str) -> usize {
+        let Ok(mut bus) = self.inner.lock() else { return 0 };
+        let ids: HashSet<u64> = bus.labels.iter()
+            .filter(|(_, l)| *l == label)
+            .map(|(&id, _)| id)
+            .collect();
+        bus.remove_handlers(&ids);
+        let removed = bus.take_removed();
+        drop(bus);
+        drop(removed);
+        ids.len()
+    }
+
+    /// The ids of the handlers labeled `label`, in subscription order.
+    pub fn labeled_handlers(&self, label: &str) -> Vec<HandlerId> {
+        let Ok(bus) = self.inner.lock() else { return Vec::new() };
+        let mut ids: Vec<HandlerId> = bus.labels.iter()
+            .filter(|(_, l)| *l == label)
+            .map(|(&id, _)| HandlerId(id))
+            .collect();
+        ids.sort_by_key(|&id| bus.slots.order(id));
+        ids
+    }
+}
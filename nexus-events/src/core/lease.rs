@@ -0,0 +1,112 @@
+// Leased subscriptions: handlers that go away by themselves at a set time,
+// such as a buff that listens for `GameTick` for ten seconds whether or not
+// whoever applied it remembers to remove it.
+//
+//     let buff = bus.subscribe_for(Duration::from_secs(10), |t: &GameTick| haste.apply(t));
+//     buff.expire_at(Instant::now() + Duration::from_secs(15)); // extended
+//
+// Expiry is checked lazily: an expired handler is removed the next time the
+// bus is processed or an event is published on it, before anything is
+// delivered, or right away by `prune_expired`. An event already being
+// delivered when the lease runs out still reaches the handler.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use super::{Event, EventBus, HandlerId, Phase, SharedEventBus};
+
+/// A handler subscribed with `subscribe_for`, and its lease. Dropping it
+/// leaves the handler subscribed until the lease runs out.
+pub struct Subscription {
+    bus: SharedEventBus,
+    id: HandlerId,
+}
+
+impl Subscription {
+    pub fn id(&self) -> HandlerId {
+        self.id
+    }
+
+    /// Moves the end of the lease to `at`, earlier or later. A time already
+    /// past has the handler removed before the next delivery. Does nothing
+    /// once the handler is gone.
+    pub fn expire_at(&self, at: Instant) {
+        if let Ok(mut bus) = self.bus.inner.lock() {
+            if bus.slots.get(self.id).is_some() {
+                bus.lease(self.id.0, at);
+            }
+        }
+    }
+
+    /// When the lease runs out, or `None` once the handler is gone.
+    pub fn expires_at(&self) -> Option<Instant> {
+        self.bus.inner.lock().ok().and_then(|bus| bus.leases.get(&self.id.0).copied())
+    }
+
+    /// Unsubscribes the handler now.
+    pub fn cancel(self) {
+        if let Ok(mut bus) = self.bus.inner.lock() {
+            bus.remove_registration(self.id);
+            let removed = bus.take_removed();
+            drop(bus);
+            drop(removed);
+        }
+    }
+}
+
+impl EventBus {
+    fn lease(&mut self, id: u64, at: Instant) {
+        self.leases.insert(id, at);
+        self.next_expiry = Some(self.next_expiry.map_or(at, |next| next.min(at)));
+    }
+
+    /// Removes the handlers whose lease ran out by now and returns how many
+    /// there were. Cheap while none is due.
+    pub(crate) fn prune_expired(&mut self) -> usize {
+        let Some(next) = self.next_expiry else { return 0 };
+        let now = Instant::now();
+        if next > now {
+            return 0;
+        }
+        let expired: HashSet<u64> = self.leases.iter()
+            .filter(|(_, at)| **at <= now)
+            .map(|(&id, _)| id)
+            .collect();
+        self.remove_handlers(&expired);
+        self.next_expiry = self.leases.values().min().copied();
+        expired.len()
+    }
+}
+
+impl SharedEventBus {
+    /// Subscribes `f` for `duration`, after which it is removed (see the
+    /// top of this file). If `E` already has as many handlers as the bus
+    /// allows, nothing is subscribed and the id is `HandlerId::NONE`.
+    pub fn subscribe_for<E: Event + 'static, F>(&self, duration: Duration, f: F) -> Subscription
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        let id = match self.inner.lock() {
+            Ok(mut bus) => match bus.subscribe_independent(0, Phase::PreInit, f) {
+                Ok(id) => {
+                    bus.lease(id.0, Instant::now() + duration);
+                    id
+                }
+                Err(_) => HandlerId::NONE,
+            },
+            Err(_) => HandlerId::NONE,
+        };
+        Subscription { bus: self.clone(), id }
+    }
+
+    /// Removes every handler whose lease has run out and returns how many
+    /// there were.
+    pub fn prune_expired(&self) -> usize {
+        let Ok(mut bus) = self.inner.lock() else { return 0 };
+        let pruned = bus.prune_expired();
+        let removed = bus.take_removed();
+        drop(bus);
+        drop(removed);
+        pruned
+    }
+}
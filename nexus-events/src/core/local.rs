@@ -0,0 +1,239 @@
+// A single-threaded bus for targets like wasm32-unknown-unknown, where the
+// `Send + Sync` bounds of `SharedEventBus` only get in the way: handlers
+// holding `Rc`s or JS values, and events carrying them, are accepted as
+// they are. Same shape as the threaded bus (subscribe, dispatch, process,
+// subscription sets), minus everything that needs threads or a clock:
+// `Instant::now` panics in the browser, so local handlers get a
+// `LocalContext` without timestamps.
+//
+// Handlers run with the bus unborrowed, so like on the threaded bus they
+// may dispatch, subscribe or unsubscribe while handling an event. Since
+// nothing here crosses threads, a handler may also be an `FnMut` that owns
+// the state it updates (`subscribe_mut`), instead of sharing it through an
+// `Arc<Mutex<_>>`.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::rc::Rc;
+
+use super::{EventSink, HandlerId, SenderId};
+
+type LocalHandlerFn = dyn Fn(&dyn Any, &mut LocalContext);
+
+#[derive(Clone)]
+struct LocalHandler {
+    id: u64,
+    priority: i32,
+    func: Rc<LocalHandlerFn>,
+}
+
+/// Per-delivery state handed to context-aware local handlers; the
+/// `EventContext` of the local bus.
+#[derive(Debug, Default)]
+pub struct LocalContext {
+    stopped: bool,
+    sender: Option<SenderId>,
+}
+impl LocalContext {
+    /// Who dispatched the event, if it was sent with `dispatch_from`.
+    pub fn sender(&self) -> Option<SenderId> {
+        self.sender
+    }
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// The state behind a `LocalSharedEventBus`.
+pub struct LocalEventBus {
+    handlers: HashMap<TypeId, Vec<LocalHandler>>,
+    queue: VecDeque<(Box<dyn Any>, Option<SenderId>)>,
+    next_id: u64,
+}
+impl LocalEventBus {
+    fn new() -> Self {
+        Self { handlers: HashMap::new(), queue: VecDeque::new(), next_id: 1 }
+    }
+    fn subscribe<E: 'static, F>(&mut self, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut LocalContext) + 'static
+    {
+        let id = HandlerId(self.next_id);
+        self.next_id += 1;
+        let func = move |ev: &dyn Any, ctx: &mut LocalContext| {
+            if let Some(ev) = ev.downcast_ref::<E>() {
+                f(ev, ctx);
+            }
+        };
+        let list = self.handlers.entry(TypeId::of::<E>()).or_default();
+        let pos = list.partition_point(|h| h.priority >= priority);
+        list.insert(pos, LocalHandler { id: id.0, priority, func: Rc::new(func) });
+        id
+    }
+    fn remove_handler(&mut self, tid: TypeId, handler_id: HandlerId) {
+        if let Some(list) = self.handlers.get_mut(&tid) {
+            list.retain(|h| h.id != handler_id.0);
+        }
+    }
+}
+
+/// A cheaply clonable handle to a single-threaded event bus (the `local`
+/// feature). Neither it nor its handlers and events need to be `Send`, so
+/// it can't leave the thread it was created on.
+#[derive(Clone)]
+pub struct LocalSharedEventBus {
+    inner: Rc<RefCell<LocalEventBus>>,
+}
+
+impl Default for LocalSharedEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalSharedEventBus {
+    pub fn new() -> Self {
+        Self { inner: Rc::new(RefCell::new(LocalEventBus::new())) }
+    }
+
+    pub fn dispatch<E: 'static>(&self, ev: E) {
+        self.inner.borrow_mut().queue.push_back((Box::new(ev), None));
+    }
+
+    /// Dispatches `ev` on behalf of `sender`; context-aware handlers can
+    /// read it back with `LocalContext::sender`.
+    pub fn dispatch_from<E: 'static>(&self, sender: SenderId, ev: E) {
+        self.inner.borrow_mut().queue.push_back((Box::new(ev), Some(sender)));
+    }
+
+    /// Delivers everything queued so far, in dispatch order. Events
+    /// dispatched by the handlers wait for the next call.
+    pub fn process(&self) {
+        let pending = std::mem::take(&mut self.inner.borrow_mut().queue);
+        for (ev, sender) in pending {
+            let tid = (*ev).type_id();
+            let handlers = self.inner.borrow().handlers.get(&tid).cloned().unwrap_or_default();
+            let mut ctx = LocalContext { stopped: false, sender };
+            for h in handlers {
+                (h.func)(&*ev, &mut ctx);
+                if ctx.is_propagation_stopped() {
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn subscribe<E: 'static, F>(&self, f: F) -> HandlerId
+    where
+        F: Fn(&E) + 'static
+    {
+        self.subscribe_with_priority::<E, _>(0, move |ev: &E, _ctx: &mut LocalContext| f(ev))
+    }
+
+    /// Subscribes a handler that changes its own state, such as a UI model
+    /// it owns. If the handler is reached again while it runs (it calls
+    /// `process` itself), that delivery skips it.
+    pub fn subscribe_mut<E: 'static, F>(&self, f: F) -> HandlerId
+    where
+        F: FnMut(&E) + 'static
+    {
+        self.subscribe::<E, _>(exclusive(f))
+    }
+
+    /// Subscribes a context-aware handler. Higher priorities run first;
+    /// equal priorities run in subscription order.
+    pub fn subscribe_with_priority<E: 'static, F>(&self, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut LocalContext) + 'static
+    {
+        self.inner.borrow_mut().subscribe(priority, f)
+    }
+
+    pub fn unsubscribe<E: 'static>(&self, handler_id: HandlerId) {
+        self.inner.borrow_mut().remove_handler(TypeId::of::<E>(), handler_id);
+    }
+
+    /// Events waiting for `process`.
+    pub fn pending(&self) -> usize {
+        self.inner.borrow().queue.len()
+    }
+}
+
+impl<E: 'static> EventSink<E> for LocalSharedEventBus {
+    fn dispatch_event(&self, ev: E) {
+        self.dispatch(ev);
+    }
+}
+
+/// `f` as an `Fn` that skips calls made while it is already running.
+fn exclusive<E, F: FnMut(&E)>(f: F) -> impl Fn(&E) {
+    let f = RefCell::new(f);
+    move |ev: &E| {
+        if let Ok(mut f) = f.try_borrow_mut() {
+            f(ev);
+        }
+    }
+}
+
+/// The `SubscriptionSet` of the local bus: subscriptions made through it
+/// are removed when it is dropped.
+pub struct LocalSubscriptionSet {
+    bus: LocalSharedEventBus,
+    entries: Vec<(TypeId, HandlerId)>,
+}
+
+impl LocalSubscriptionSet {
+    pub fn new(bus: &LocalSharedEventBus) -> Self {
+        Self { bus: bus.clone(), entries: Vec::new() }
+    }
+
+    pub fn subscribe<E: 'static, F>(&mut self, f: F) -> HandlerId
+    where
+        F: Fn(&E) + 'static
+    {
+        self.subscribe_with_priority::<E, _>(0, move |ev: &E, _ctx: &mut LocalContext| f(ev))
+    }
+
+    /// `LocalSharedEventBus::subscribe_mut`, removed with the set.
+    pub fn subscribe_mut<E: 'static, F>(&mut self, f: F) -> HandlerId
+    where
+        F: FnMut(&E) + 'static
+    {
+        self.subscribe::<E, _>(exclusive(f))
+    }
+
+    pub fn subscribe_with_priority<E: 'static, F>(&mut self, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut LocalContext) + 'static
+    {
+        let id = self.bus.subscribe_with_priority(priority, f);
+        self.entries.push((TypeId::of::<E>(), id));
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes every subscription in the set.
+    pub fn clear(&mut self) {
+        let mut bus = self.bus.inner.borrow_mut();
+        for (tid, id) in self.entries.drain(..) {
+            bus.remove_handler(tid, id);
+        }
+    }
+}
+
+impl Drop for LocalSubscriptionSet {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
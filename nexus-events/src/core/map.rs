@@ -0,0 +1,95 @@
+// Mapping stages: `bus.map::<RawInput, GameAction>(|raw| ...)` subscribes
+// a handler to `RawInput` that turns each one it receives into a
+// `GameAction`, if the closure returns one, and publishes that on the same
+// bus. Stages chain: the `GameAction` goes through whatever stages map it
+// further.
+//
+// A stage that would close a loop of stages (`A -> B`, `B -> A`) is refused
+// with `EventError::MapCycle` when it is added. Loops the bus can't see
+// coming, through a handler that publishes the source type of a stage
+// feeding it, are cut when the event comes round: a stage already mapping
+// an event further up this thread's call stack doesn't run again.
+//
+// A stage is an ordinary handler of its source type and goes with
+// `unsubscribe::<RawInput>(id)` like any other.
+
+use std::any::{type_name, TypeId};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::{Event, EventBus, EventError, EventResult, HandlerId, Phase, SharedEventBus};
+
+static NEXT_STAGE: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // The stages mapping an event on this thread, innermost last.
+    static MAPPING: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+}
+
+/// One mapping stage, for the cycle check.
+pub(crate) struct MapStage {
+    from: TypeId,
+    to: TypeId,
+    to_name: &'static str,
+}
+
+impl EventBus {
+    /// The loop `from -> to` would close, as the type names along it from
+    /// `from` round to `from` again, if it closes one.
+    fn map_cycle(&self, from: (TypeId, &'static str), to: (TypeId, &'static str)) -> Option<Vec<&'static str>> {
+        let mut paths = vec![(to.0, vec![from.1, to.1])];
+        let mut seen = vec![to.0];
+        while let Some((tid, path)) = paths.pop() {
+            if tid == from.0 {
+                return Some(path);
+            }
+            for stage in self.maps.values().filter(|s| s.from == tid) {
+                if !seen.contains(&stage.to) {
+                    seen.push(stage.to);
+                    let mut next = path.clone();
+                    next.push(stage.to_name);
+                    paths.push((stage.to, next));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl SharedEventBus {
+    /// Publishes what `convert` makes of every `From` delivered on this
+    /// bus, skipping those it returns `None` for (see the top of this
+    /// file). Returns the id of the stage's handler.
+    pub fn map<From: Event + 'static, To: Event + 'static, F>(&self, convert: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&From) -> Option<To> + Send + Sync + 'static
+    {
+        let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        let (from, to) = ((TypeId::of::<From>(), type_name::<From>()), (TypeId::of::<To>(), type_name::<To>()));
+        if let Some(path) = bus.map_cycle(from, to) {
+            return Err(EventError::MapCycle { path });
+        }
+        let target = self.downgrade();
+        let stage = NEXT_STAGE.fetch_add(1, Ordering::Relaxed);
+        let id = bus.subscribe_independent(0, Phase::PreInit, move |ev: &From| {
+            let Some(bus) = target.upgrade() else { return };
+            if MAPPING.with(|m| m.borrow().contains(&stage)) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("`{}` came back to the stage mapping it to `{}`; not mapped again", from.1, to.1);
+                return;
+            }
+            let Some(mapped) = convert(ev) else { return };
+            struct Done;
+            impl Drop for Done {
+                fn drop(&mut self) {
+                    MAPPING.with(|m| m.borrow_mut().pop());
+                }
+            }
+            MAPPING.with(|m| m.borrow_mut().push(stage));
+            let _done = Done;
+            let _ = bus.publish(mapped);
+        })?;
+        bus.maps.insert(id.0, MapStage { from: from.0, to: to.0, to_name: to.1 });
+        Ok(id)
+    }
+}
@@ -0,0 +1,153 @@
+// Built-in counters per event type: how often it was published, how often
+// its handlers ran and how long they took. Off by default; recording costs
+// a lock and a few increments per publish and per handler call.
+
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::SharedEventBus;
+
+/// Bucket `i` counts calls that took less than 2^i microseconds; the last
+/// bucket counts everything slower.
+const BUCKETS: usize = 22;
+
+/// Handler latencies in power-of-two microsecond buckets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    counts: [u64; BUCKETS],
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self { counts: [0; BUCKETS] }
+    }
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, elapsed: Duration) {
+        let micros = elapsed.as_micros();
+        let bucket = (u128::BITS - micros.leading_zeros()) as usize;
+        self.counts[bucket.min(BUCKETS - 1)] += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// `(upper bound, count)` per bucket, fastest first. The last bucket has
+    /// no upper bound and reports `Duration::MAX`.
+    pub fn buckets(&self) -> impl Iterator<Item = (Duration, u64)> + '_ {
+        self.counts.iter().enumerate().map(|(i, &count)| {
+            let bound = if i == BUCKETS - 1 { Duration::MAX } else { Duration::from_micros(1 << i) };
+            (bound, count)
+        })
+    }
+
+    /// Upper bound of the bucket holding the `p`-th percentile (0.0..=1.0),
+    /// or zero when nothing was recorded.
+    pub fn percentile(&self, p: f64) -> Duration {
+        let total = self.count();
+        if total == 0 {
+            return Duration::ZERO;
+        }
+        let target = ((total as f64 * p.clamp(0.0, 1.0)).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bound, count) in self.buckets() {
+            seen += count;
+            if seen >= target {
+                return bound;
+            }
+        }
+        Duration::MAX
+    }
+}
+
+/// Counters for one event type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventTypeMetrics {
+    /// Dispatch calls, including events the configuration then dropped.
+    pub published: u64,
+    pub handler_invocations: u64,
+    pub latency: LatencyHistogram,
+}
+
+/// A snapshot of a bus's metrics, keyed by event type name.
+#[derive(Debug, Clone, Default)]
+pub struct EventBusMetrics {
+    pub enabled: bool,
+    pub events: BTreeMap<&'static str, EventTypeMetrics>,
+}
+
+impl EventBusMetrics {
+    pub fn total_published(&self) -> u64 {
+        self.events.values().map(|m| m.published).sum()
+    }
+
+    pub fn total_handler_invocations(&self) -> u64 {
+        self.events.values().map(|m| m.handler_invocations).sum()
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct MetricsRecorder {
+    // Atomic because deliveries in flight share the recorder (see
+    // `Delivery`) while the bus toggles it.
+    enabled: AtomicBool,
+    by_type: Mutex<HashMap<TypeId, (&'static str, EventTypeMetrics)>>,
+}
+
+impl MetricsRecorder {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub(crate) fn published(&self, tid: TypeId, name: &'static str) {
+        if let Ok(mut by_type) = self.by_type.lock() {
+            by_type.entry(tid).or_insert_with(|| (name, EventTypeMetrics::default())).1.published += 1;
+        }
+    }
+
+    pub(crate) fn handled(&self, tid: TypeId, name: &'static str, elapsed: Duration) {
+        if let Ok(mut by_type) = self.by_type.lock() {
+            let metrics = &mut by_type.entry(tid).or_insert_with(|| (name, EventTypeMetrics::default())).1;
+            metrics.handler_invocations += 1;
+            metrics.latency.record(elapsed);
+        }
+    }
+
+    fn snapshot(&self) -> EventBusMetrics {
+        let events = self.by_type.lock()
+            .map(|by_type| by_type.values().map(|(name, m)| (*name, m.clone())).collect())
+            .unwrap_or_default();
+        EventBusMetrics { enabled: self.is_enabled(), events }
+    }
+}
+
+impl SharedEventBus {
+    /// Turns metrics recording on or off. Counters are kept while off.
+    pub fn set_metrics_enabled(&self, enabled: bool) {
+        if let Ok(bus) = self.inner.lock() {
+            bus.metrics.set_enabled(enabled);
+            bus.refresh_interest();
+        }
+    }
+
+    pub fn metrics(&self) -> EventBusMetrics {
+        self.inner.lock().map(|bus| bus.metrics.snapshot()).unwrap_or_default()
+    }
+
+    pub fn reset_metrics(&self) {
+        if let Ok(bus) = self.inner.lock() {
+            if let Ok(mut by_type) = bus.metrics.by_type.lock() {
+                by_type.clear();
+            }
+        }
+    }
+}
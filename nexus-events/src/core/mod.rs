@@ -1,149 +1,1129 @@
 use std::any::{Any, TypeId};
-use std::sync::{Arc, Mutex, OnceLock};
-use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock, RwLock, Weak};
+use std::time::{Duration, Instant};
+use std::collections::{HashMap, HashSet};
 use std::collections::VecDeque;
 
+use smallvec::SmallVec;
+
+#[cfg(feature = "serde")]
+pub mod codec;
+mod address;
+mod background;
+mod backpressure;
+#[doc(hidden)]
+pub mod bench_hooks;
+mod budget;
+mod category;
+mod channel;
+mod class;
+mod coalesce;
+mod collect;
+mod command;
+mod config;
+mod contract;
+mod critical;
+mod delivery;
+mod domain;
+mod emit;
+mod event_type;
+mod free_handlers;
+mod generation;
+mod ids;
+mod interest;
+mod labels;
+mod lease;
+mod map;
+#[cfg(feature = "local")]
+mod local;
+mod metrics;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod record;
+mod registry;
+mod report;
+mod scheduler;
+mod scope;
+mod sequence;
+mod shared;
+mod slots;
+mod stagger;
+mod stats;
+#[cfg(feature = "futures")]
+mod stream;
+mod shutdown;
+mod snapshot;
+mod subscriptions;
+mod systems;
+mod threads;
+mod warmup;
+mod watchdog;
+mod trace;
+mod tracer;
+mod type_map;
+mod validate;
+mod wire_id;
+pub(crate) use address::address_of;
+use type_map::TypeMap;
+pub use budget::{BudgetOutcome, ProcessBudget};
+pub use category::{categorize_as, CategoryLink};
+pub use channel::EventReceiver;
+pub use backpressure::OverflowPolicy;
+pub use class::{classify_as, ClassLimit, ClassLink, EventClass};
+pub use command::CommandQueue;
+pub use config::{BusConfig, BusReconfigured, ConfigChange, EventBusBuilder};
+pub use critical::CriticalPolicy;
+pub use delivery::PanicPolicy;
+pub use domain::EventDomain;
+pub use emit::{EmitControl, EventEmitter, EventSink};
+pub use event_type::{EventType, EventTypeId, EventTypeIdCell};
+pub use free_handlers::GlobalHandler;
+pub use generation::Generation;
+pub use lease::Subscription;
+#[cfg(feature = "local")]
+pub use local::{LocalContext, LocalEventBus, LocalSharedEventBus, LocalSubscriptionSet};
+pub use contract::{EventContract, HasContract};
+pub use metrics::{EventBusMetrics, EventTypeMetrics, LatencyHistogram};
+pub use registry::{EventRegistration, EventTypeRegistry};
+pub use report::DeliveryReport;
+pub use scheduler::{ScheduleHandle, SchedulerThread};
+pub use scope::{EventScope, ScopeAccess};
+pub use sequence::{sequence_gaps, EventEnvelope, SequenceCheck, SequenceTracker};
+pub use shared::{ChildEdge, EdgeFilter, SharedEventBus};
+pub use shutdown::ShutdownPolicy;
+pub use snapshot::{BusSnapshot, RunningHandler};
+pub use stats::HandlerStats;
+#[cfg(feature = "futures")]
+pub use stream::EventStream;
+pub use subscriptions::{SubscriptionInfo, SubscriptionSet};
+pub use systems::System;
+pub use tracer::{Emitter, EventTraceCollector, Trace, TraceRecord};
+pub use validate::{validate_as, Validate, ValidationError, ValidatorLink};
+pub use warmup::{DefaultEvent, WarmUpReport};
+pub use watchdog::HandlerOverBudget;
+pub use wire_id::{wire_id, wire_id32, HasWireId};
+use shared::Edge;
+use background::Background;
+use event_type::DispatchTable;
+use interest::Interest;
+use backpressure::{Inbox, QueueGauge};
+use class::ClassQueues;
+use coalesce::Flush;
+use command::Commands;
+use metrics::MetricsRecorder;
+pub(crate) use record::EventLog;
+use scheduler::Schedule;
+use slots::{HandlerSlots, Registration};
+use stagger::Stagger;
+use stats::LoadTracker;
+use threads::ThreadSettings;
+use warmup::WarmUp;
+use watchdog::Watchdog;
+
 // --------------------------------------------------------------------
 // 1. Event trait
 // --------------------------------------------------------------------
 pub trait Event: Any + Send + Sync + 'static {
     fn as_any(&self) -> &dyn Any;
+    /// The Rust type name of the concrete event, for diagnostics.
+    fn event_name(&self) -> &'static str;
 }
 impl<T: Any + Send + Sync + 'static> Event for T {
     fn as_any(&self) -> &dyn Any { self }
+    fn event_name(&self) -> &'static str { std::any::type_name::<T>() }
 }
 
 // --------------------------------------------------------------------
 // 2. Handler ID
 // --------------------------------------------------------------------
+/// Names one registration on a bus, to remove it later. Ids aren't reused:
+/// once a handler is gone, its id matches nothing, even if a later handler
+/// took its place.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct HandlerId(pub usize);
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HandlerId(u64);
+impl HandlerId {
+    /// Returned when nothing was registered; matches no handler.
+    pub const NONE: HandlerId = HandlerId(0);
+}
+impl fmt::Display for HandlerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 & slots::KEYED_BIT != 0 {
+            write!(f, "k{:x}", self.0 & !slots::KEYED_BIT)
+        } else {
+            write!(f, "{}v{}", self.0 & u32::MAX as u64, self.0 >> 32)
+        }
+    }
+}
 
 // --------------------------------------------------------------------
 // 3. Internal trait for stored handlers
 // --------------------------------------------------------------------
 trait ErasedHandler: Send + Sync {
-    fn handle(&self, ev: &dyn Event);
-    fn id(&self) -> usize;
-    fn box_clone(&self) -> Box<dyn ErasedHandler>;
-}
-impl Clone for Box<dyn ErasedHandler> {
-    fn clone(&self) -> Self { self.box_clone() }
+    /// Runs the handler and returns how long it took.
+    fn handle(&self, ev: &dyn Event, ctx: &mut EventContext) -> Duration;
+    fn id(&self) -> u64;
+    fn priority(&self) -> i32;
+    fn phase(&self) -> Phase;
+    fn stats(&self) -> HandlerStats;
+    /// Marks the handler unsubscribed; deliveries leave it out from now on,
+    /// including ones that have already started.
+    fn retire(&self);
+    fn is_retired(&self) -> bool;
+    /// Whether the handler ignores its context, so it can neither stop
+    /// propagation nor tell whether others ran before it.
+    #[cfg_attr(not(feature = "rayon"), allow(dead_code))]
+    fn is_independent(&self) -> bool;
 }
 
+/// The handlers of one event type, by descending priority and then in
+/// subscription order; inserts and removals keep that order. Copy-on-write:
+/// deliveries in progress keep the version they started with, and changes
+/// made meanwhile go into a fresh copy (`Arc::make_mut`). Most types have
+/// a handful of handlers, which are kept inline.
+type HandlerList = Arc<SmallVec<[Arc<dyn ErasedHandler>; 4]>>;
+
+/// Receives the events `process` delivered to no handler (see
+/// `EventBusBuilder::dead_letters`).
+type DeadLetterSink = Arc<dyn Fn(&dyn Event) + Send + Sync>;
+
 // Concrete struct that wraps the user’s closure
 struct HandlerImpl<F> {
-    id: usize,
-    func: Arc<F>,
+    id: u64,
+    priority: i32,
+    phase: Phase,
+    event_name: &'static str,
+    load: LoadTracker,
+    retired: AtomicBool,
+    independent: bool,
+    func: F,
 }
 impl<F> ErasedHandler for HandlerImpl<F>
 where
-    F: Fn(&dyn Event) + Send + Sync + 'static
+    F: Fn(&dyn Event, &mut EventContext) + Send + Sync + 'static
 {
-    fn handle(&self, ev: &dyn Event) {
-        (self.func)(ev);
+    fn handle(&self, ev: &dyn Event, ctx: &mut EventContext) -> Duration {
+        let started = Instant::now();
+        (self.func)(ev, ctx);
+        let elapsed = started.elapsed();
+        self.load.record(started, elapsed);
+        elapsed
     }
-    fn id(&self) -> usize {
+    fn id(&self) -> u64 {
         self.id
     }
-    fn box_clone(&self) -> Box<dyn ErasedHandler> {
-        Box::new(Self { id: self.id, func: self.func.clone() })
+    fn priority(&self) -> i32 {
+        self.priority
+    }
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+    fn stats(&self) -> HandlerStats {
+        self.load.snapshot(HandlerId(self.id), self.event_name)
+    }
+    fn retire(&self) {
+        self.retired.store(true, Ordering::Relaxed);
+    }
+    fn is_retired(&self) -> bool {
+        self.retired.load(Ordering::Relaxed)
+    }
+    fn is_independent(&self) -> bool {
+        self.independent
+    }
+}
+
+/// Identifies whoever dispatched an event (see `SharedEventBus::dispatch_from`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SenderId(pub u64);
+
+/// Recorded when an event is dispatched and carried with it until delivery.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EventMeta {
+    published_at: Instant,
+    sender: Option<SenderId>,
+    synthetic: bool,
+    // Assigned when the event is queued (see `sequence`); 0 until then.
+    sequence: u64,
+    // Set for addressed events: only handlers subscribed under this
+    // address receive them (see `address`).
+    to: Option<u64>,
+}
+impl EventMeta {
+    pub(crate) fn now(sender: Option<SenderId>) -> Self {
+        Self { published_at: Instant::now(), sender, synthetic: false, sequence: 0, to: None }
     }
 }
 
+/// A queued event and where it came from.
+pub(crate) struct Queued {
+    pub(crate) ev: Box<dyn Event>,
+    pub(crate) meta: EventMeta,
+}
+
+/// Per-delivery state handed to context-aware handlers. A handler that
+/// calls `stop_propagation` consumes the event: handlers after it (lower
+/// priority, or same priority but subscribed later) never see it.
+#[derive(Debug)]
+pub struct EventContext {
+    stopped: bool,
+    meta: EventMeta,
+}
+impl Default for EventContext {
+    /// A context for an event published just now by nobody in particular,
+    /// for calling context-aware handlers directly.
+    fn default() -> Self {
+        Self { stopped: false, meta: EventMeta::now(None) }
+    }
+}
+impl EventContext {
+    /// When the event was dispatched.
+    pub fn published_at(&self) -> Instant {
+        self.meta.published_at
+    }
+    /// Who dispatched the event, if it was sent with `dispatch_from`.
+    pub fn sender(&self) -> Option<SenderId> {
+        self.meta.sender
+    }
+    /// True while warming up (see `SharedEventBus::warm_up`): the event is
+    /// a stand-in and the handler should skip lasting side effects.
+    pub fn is_synthetic(&self) -> bool {
+        self.meta.synthetic
+    }
+    pub fn stop_propagation(&mut self) {
+        self.stopped = true;
+    }
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.stopped
+    }
+}
+
+/// Startup phases. A handler registered for a phase only starts receiving
+/// events once the bus has advanced to that phase, so a plugin can keep its
+/// handlers quiet until the systems they rely on have been constructed.
+/// Plain `subscribe` registers in `PreInit`, which is where every bus starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    PreInit,
+    Init,
+    PostInit,
+}
+
 // --------------------------------------------------------------------
-// 4. The global EventBus
+// 4. The EventBus
 // --------------------------------------------------------------------
 pub struct EventBus {
-    handlers: HashMap<TypeId, Vec<Box<dyn ErasedHandler>>>,
-    queue: VecDeque<Box<dyn Event>>,
-    next_id: usize,
+    handlers: DispatchTable<HandlerList>,
+    category_handlers: TypeMap<HandlerList>,
+    // Where every registration is, by id (see `slots`).
+    slots: HandlerSlots,
+    // How many retired handlers each list in `handlers` still holds.
+    retired: TypeMap<usize>,
+    // Handlers of paused `SubscriptionSet`s: still registered, not delivered to.
+    paused: HashSet<u64>,
+    // Labels given with `subscribe_labeled`, by handler id.
+    labels: HashMap<u64, String>,
+    // The component type and id each handler was subscribed for, by handler
+    // (see `SubscriptionSet::set_owner`).
+    owners: HashMap<u64, (&'static str, Arc<str>)>,
+    // When leased handlers expire (see `subscribe_for`), by handler id, and
+    // the earliest of those times (possibly one already removed).
+    leases: HashMap<u64, Instant>,
+    next_expiry: Option<Instant>,
+    // Coalescing subscriptions, by handler id, flushed after every process.
+    coalesced: Vec<(u64, Flush)>,
+    // Generation every handler registered during one was tagged with (see
+    // `begin_generation`), by handler id.
+    generations: HashMap<u64, Generation>,
+    generation: Option<Generation>,
+    last_generation: u64,
+    // Addresses of handlers subscribed through an addressed
+    // `SubscriptionSet`, by handler id.
+    addresses: HashMap<u64, u64>,
+    responders: HashMap<TypeId, (HandlerId, Box<dyn Any + Send + Sync>)>,
+    // Handlers subscribed with `subscribe_returning`, by event and result
+    // type (see `collect.rs`).
+    collectors: HashMap<TypeId, collect::Collectors>,
+    aliases: HashMap<TypeId, Converter>,
+    // Mapping stages, by the id of their handler (see `map.rs`).
+    maps: HashMap<u64, map::MapStage>,
+    // What waits for `process`, by class (see `class.rs`).
+    queue: ClassQueues,
+    id_seed: u64,
+    last_sequence: u64,
+    phase: Phase,
+    published_contracts: HashMap<&'static str, EventContract>,
+    required_contracts: Vec<EventContract>,
+    parent: Option<Edge>,
+    children: Vec<Edge>,
+    systems: Vec<Arc<System>>,
+    config: BusConfig,
+    categories: HashMap<TypeId, &'static str>,
+    throttle_counts: HashMap<TypeId, u32>,
+    dropped: u64,
+    threads: ThreadSettings,
+    background: Background,
+    staggered: HashMap<TypeId, Stagger>,
+    event_classes: HashMap<TypeId, i32>,
+    schedule: Schedule,
+    metrics: Arc<MetricsRecorder>,
+    // Inline critical events admitted while the bus was locked, waiting to
+    // be delivered once it is unlocked.
+    inline_due: Vec<Queued>,
+    inline_bubbling: Vec<Queued>,
+    warmups: Vec<(TypeId, WarmUp)>,
+    // Dispatches land here without taking the bus lock; they are moved into
+    // `queue` (and filtered) whenever the bus is next locked to queue or
+    // process events.
+    inbox: Receiver<Queued>,
+    inbox_tx: Inbox,
+    // Types delivered inline at dispatch (see `CriticalPolicy::Inline`),
+    // readable by publishers without the bus lock.
+    inline_types: Arc<RwLock<HashSet<TypeId>>>,
+    // Command types and the queues they go to (see `command_queue`); the
+    // queue is gone while nobody holds it. The types are readable by
+    // publishers without the bus lock.
+    commands: HashMap<TypeId, Weak<Commands>>,
+    command_types: Arc<RwLock<HashSet<TypeId>>>,
+    // The types publishers can skip the bus lock for, since nothing here
+    // would receive them (see `interest.rs`).
+    interest: Arc<Interest>,
+    // Isolated buses owned by this one, by name (see `domain`).
+    domains: HashMap<String, SharedEventBus>,
+    // Set by `shutdown`, readable by publishers without the bus lock.
+    shut_down: Arc<AtomicBool>,
+    // Where delivered events go once `record_delivered` is called, readable
+    // by publishers without the bus lock.
+    record: Arc<OnceLock<EventLog>>,
+    // Where handler calls are traced, and the same collector readable by
+    // publishers without the bus lock (see `set_trace_collector`).
+    tracer: Option<EventTraceCollector>,
+    publish_tracer: Arc<RwLock<Option<EventTraceCollector>>>,
+    shutdown_hooks: Vec<Box<dyn FnOnce() + Send>>,
+    panic_policy: PanicPolicy,
+    // Checks handler calls against a time budget (see `set_handler_time_budget`).
+    watchdog: Option<Watchdog>,
+    // Handlers, responders and flushes removed while the bus is locked.
+    // Their captures may lock the bus when dropped (a `SubscriptionSet`,
+    // say), so they wait here until the bus is unlocked (see `take_removed`).
+    removed: Vec<Box<dyn Send>>,
+    dead_letters: Option<DeadLetterSink>,
+    // Whether events rejected by validation go to `dead_letters` too.
+    dead_letter_invalid: bool,
+    max_handlers: Option<usize>,
+    // Tells this bus's running handlers apart from other buses' in a
+    // `debug_snapshot`.
+    bus_id: u64,
 }
 impl EventBus {
     fn new() -> Self {
+        let (inbox_tx, inbox) = mpsc::channel();
+        let gauge = Arc::new(QueueGauge::default());
         Self {
-            handlers: HashMap::new(),
-            queue: VecDeque::new(),
-            next_id: 0,
+            handlers: DispatchTable::default(),
+            category_handlers: TypeMap::default(),
+            slots: HandlerSlots::default(),
+            retired: TypeMap::default(),
+            paused: HashSet::new(),
+            labels: HashMap::new(),
+            owners: HashMap::new(),
+            leases: HashMap::new(),
+            next_expiry: None,
+            coalesced: Vec::new(),
+            generations: HashMap::new(),
+            generation: None,
+            last_generation: 0,
+            addresses: HashMap::new(),
+            responders: HashMap::new(),
+            aliases: HashMap::new(),
+            maps: HashMap::new(),
+            collectors: HashMap::new(),
+            queue: ClassQueues::new(gauge.clone()),
+            id_seed: 0,
+            last_sequence: 0,
+            phase: Phase::PreInit,
+            published_contracts: HashMap::new(),
+            required_contracts: Vec::new(),
+            parent: None,
+            children: Vec::new(),
+            systems: Vec::new(),
+            config: BusConfig::default(),
+            categories: HashMap::new(),
+            throttle_counts: HashMap::new(),
+            dropped: 0,
+            threads: ThreadSettings::default(),
+            background: Background::default(),
+            staggered: HashMap::new(),
+            event_classes: HashMap::new(),
+            schedule: Schedule::default(),
+            metrics: Arc::default(),
+            inline_due: Vec::new(),
+            inline_bubbling: Vec::new(),
+            warmups: Vec::new(),
+            inbox,
+            inbox_tx: Inbox::new(inbox_tx, gauge),
+            inline_types: Arc::default(),
+            commands: HashMap::new(),
+            command_types: Arc::default(),
+            interest: Arc::default(),
+            domains: HashMap::new(),
+            shut_down: Arc::default(),
+            record: Arc::default(),
+            tracer: None,
+            publish_tracer: Arc::default(),
+            shutdown_hooks: Vec::new(),
+            panic_policy: PanicPolicy::default(),
+            watchdog: None,
+            removed: Vec::new(),
+            dead_letters: None,
+            dead_letter_invalid: false,
+            max_handlers: None,
+            bus_id: snapshot::next_bus_id(),
         }
     }
-    fn dispatch<E: Event + 'static>(&mut self, ev: E) {
-        self.queue.push_back(Box::new(ev));
+    /// Configures and builds a bus; see `EventBusBuilder`.
+    pub fn builder() -> EventBusBuilder {
+        EventBusBuilder::new()
     }
-    fn process(&mut self) {
-        let mut current = std::mem::take(&mut self.queue);
-        while let Some(ev) = current.pop_front() {
-            let tid = ev.as_any().type_id();
-            if let Some(list) = self.handlers.get(&tid) {
-                for h in list.iter() {
-                    h.handle(&*ev);
-                }
+    /// Queues `ev` after everything dispatched before it, unless the bus
+    /// configuration says to drop it.
+    fn enqueue(&mut self, ev: Box<dyn Event>, meta: EventMeta) {
+        self.pull_inbox();
+        self.admit(ev, meta);
+    }
+    /// Moves lock-free dispatches into the queue.
+    fn pull_inbox(&mut self) {
+        while let Ok(Queued { ev, meta }) = self.inbox.try_recv() {
+            self.inbox_tx.received();
+            self.admit(ev, meta);
+        }
+    }
+    fn admit(&mut self, ev: Box<dyn Event>, meta: EventMeta) {
+        let _span = trace::publish_span((*ev).event_name());
+        let tid = (*ev).as_any().type_id();
+        if self.metrics.is_enabled() {
+            self.metrics.published(tid, (*ev).event_name());
+        }
+        let Some(ev) = self.route_command(ev) else { return };
+        let category = self.categories.get(&tid).copied();
+        if !self.config.category_enabled(category) {
+            self.dropped += 1;
+            return;
+        }
+        // Inline critical events never enter the queue, so its bounds don't
+        // apply to them.
+        if self.inlines_critical() && self.is_critical(&*ev) {
+            let meta = self.stamp(meta);
+            self.inline_due.push(Queued { ev, meta });
+            return;
+        }
+        if let Some(limit) = self.config.throttle_for((*ev).event_name()) {
+            let count = self.throttle_counts.entry(tid).or_insert(0);
+            if *count >= limit {
+                self.dropped += 1;
+                return;
             }
+            *count += 1;
         }
+        let meta = self.stamp(meta);
+        if self.queue_in_class(Queued { ev, meta }) {
+            self.dropped += 1;
+        }
+    }
+    /// Takes everything queued, critical events first when they are
+    /// boosted, along with inline events still waiting to bubble up.
+    /// Expired leases are pruned and lists holding retired handlers are
+    /// compacted on the way.
+    fn take_pending(&mut self) -> (VecDeque<Queued>, Vec<Queued>) {
+        self.prune_expired();
+        self.compact_handlers();
+        self.pull_inbox();
+        self.throttle_counts.clear();
+        let mut current = self.queue.take();
+        if self.boosts_critical() {
+            self.front_load_critical(&mut current);
+        }
+        for queued in self.inline_due.drain(..).rev() {
+            current.push_front(queued);
+        }
+        (current, std::mem::take(&mut self.inline_bubbling))
+    }
+    fn subscribe<E: Event + 'static, F>(&mut self, priority: i32, phase: Phase, closure: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.insert_handler(None, priority, phase, false, closure)
     }
-    fn subscribe<E: Event + 'static, F>(&mut self, closure: F) -> HandlerId
+    /// `subscribe` for a handler that takes no context, which
+    /// `publish_parallel` may run alongside the others.
+    fn subscribe_independent<E: Event + 'static, F>(&mut self, priority: i32, phase: Phase, closure: F) -> EventResult<HandlerId>
     where
         F: Fn(&E) + Send + Sync + 'static
     {
-        let id = HandlerId(self.next_id);
-        self.next_id += 1;
-
+        self.insert_handler(None, priority, phase, true, move |ev: &E, _ctx: &mut EventContext| closure(ev))
+    }
+    /// Adds a handler under `key`, or under a fresh id if there is none,
+    /// unless `E` already has as many as the bus allows.
+    fn insert_handler<E: Event + 'static, F>(
+        &mut self,
+        key: Option<HandlerId>,
+        priority: i32,
+        phase: Phase,
+        independent: bool,
+        closure: F,
+    ) -> EventResult<HandlerId>
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
         let tid = TypeId::of::<E>();
-        let erased = HandlerImpl {
+        if let Some(max) = self.max_handlers {
+            let retired = self.retired.get(&tid).copied().unwrap_or(0);
+            if self.handlers.get(&tid).is_some_and(|list| list.len() - retired >= max) {
+                return Err(EventError::TooManyHandlers { event: std::any::type_name::<E>(), max });
+            }
+        }
+        let id = self.slots.insert(key, Registration::Event(tid));
+        let erased: Arc<dyn ErasedHandler> = Arc::new(HandlerImpl {
             id: id.0,
-            func: Arc::new(move |ev: &dyn Event| {
+            priority,
+            phase,
+            event_name: std::any::type_name::<E>(),
+            load: LoadTracker::default(),
+            retired: AtomicBool::new(false),
+            independent,
+            func: move |ev: &dyn Event, ctx: &mut EventContext| {
                 if let Some(real) = ev.as_any().downcast_ref::<E>() {
-                    closure(real);
+                    closure(real, ctx);
                 }
-            }),
-        };
+            },
+        });
+        self.slots.attach(id, erased.clone());
 
-        self.handlers.entry(tid).or_default()
-            .push(Box::new(erased));
-        id
+        // Keep each list sorted by descending priority; equal priorities
+        // stay in subscription order.
+        let list = Arc::make_mut(self.handlers.entry_or_default(tid));
+        let pos = list.partition_point(|h| h.priority() >= priority);
+        list.insert(pos, erased);
+        self.interest.add(tid);
+        self.tag_generation(id.0);
+        Ok(id)
+    }
+    fn reserve_handlers(&mut self, tid: TypeId, additional: usize) {
+        Arc::make_mut(self.handlers.entry_or_default(tid)).reserve(additional);
     }
     fn unsubscribe<E: Event + 'static>(&mut self, handler_id: HandlerId) {
-        let tid = TypeId::of::<E>();
+        self.remove_handler(TypeId::of::<E>(), handler_id);
+    }
+    /// Removes the event handler `handler_id`, if it is subscribed to `tid`.
+    fn remove_handler(&mut self, tid: TypeId, handler_id: HandlerId) {
+        if self.slots.get(handler_id) == Some(Registration::Event(tid)) {
+            self.remove_registration(handler_id);
+        }
+    }
+    /// Removes the registrations with these ids, whatever they are: event
+    /// handlers, category handlers, query responders or returning handlers.
+    fn remove_handlers(&mut self, ids: &HashSet<u64>) {
+        for &id in ids {
+            self.remove_registration(HandlerId(id));
+        }
+    }
+    fn remove_registration(&mut self, id: HandlerId) {
+        let Some((registration, handler)) = self.slots.remove(id) else { return };
+        // Deliveries already under way skip it from now on.
+        if let Some(handler) = handler {
+            handler.retire();
+            self.removed.push(Box::new(handler));
+        }
+        match registration {
+            // Retiring is enough for deliveries; the list catches up in
+            // `compact_handlers`.
+            Registration::Event(tid) => {
+                let retired = self.retired.entry(tid).or_default();
+                *retired += 1;
+                if self.handlers.get(&tid).is_some_and(|list| *retired * 2 >= list.len()) {
+                    self.compact_list(tid);
+                }
+            }
+            Registration::Category(tid) => {
+                if let Some(list) = self.category_handlers.get_mut(&tid) {
+                    Arc::make_mut(list).retain(|h| h.id() != id.0);
+                }
+                self.refresh_interest();
+            }
+            Registration::Responder(tid) => {
+                if matches!(self.responders.get(&tid), Some((current, _)) if *current == id) {
+                    if let Some(responder) = self.responders.remove(&tid) {
+                        self.removed.push(Box::new(responder));
+                    }
+                }
+            }
+            Registration::Collector(key) => self.remove_collector(key, id.0),
+        }
+        self.paused.remove(&id.0);
+        self.labels.remove(&id.0);
+        self.owners.remove(&id.0);
+        self.maps.remove(&id.0);
+        self.leases.remove(&id.0);
+        self.addresses.remove(&id.0);
+        self.generations.remove(&id.0);
+        if let Some(pos) = self.coalesced.iter().position(|(c, _)| *c == id.0) {
+            let flush = self.coalesced.remove(pos);
+            self.removed.push(Box::new(flush));
+        }
+    }
+    /// Drops retired handlers from the lists that hold any.
+    fn compact_handlers(&mut self) {
+        if self.retired.is_empty() {
+            return;
+        }
+        let tids: Vec<TypeId> = self.retired.keys().copied().collect();
+        for tid in tids {
+            self.compact_list(tid);
+        }
+    }
+    fn compact_list(&mut self, tid: TypeId) {
+        self.retired.remove(&tid);
         if let Some(list) = self.handlers.get_mut(&tid) {
-            list.retain(|h| h.id() != handler_id.0);
+            let list = Arc::make_mut(list);
+            self.removed.extend(list.iter().filter(|h| h.is_retired()).map(|h| Box::new(h.clone()) as Box<dyn Send>));
+            list.retain(|h| !h.is_retired());
+            if list.is_empty() {
+                self.rebuild_interest();
+            }
+        }
+    }
+    /// Rebuilds `interest` from the types that can still be received,
+    /// dropping those that lost their last handler.
+    fn rebuild_interest(&self) {
+        let Ok(commands) = self.command_types.read() else { return };
+        let handled = self.handlers.iter().filter(|(_, list)| !list.is_empty()).map(|(tid, _)| tid);
+        self.interest.rebuild(
+            handled
+                .chain(self.aliases.keys().copied())
+                .chain(self.categories.keys().copied())
+                .chain(commands.iter().copied()),
+        );
+    }
+    /// Whether publishes of any type have to go through the bus: for its
+    /// category handlers, metrics, trace collector or related buses.
+    fn refresh_interest(&self) {
+        self.interest.set_everything(
+            self.category_handlers.values().any(|list| !list.is_empty())
+                || self.metrics.is_enabled()
+                || self.tracer.is_some()
+                || self.parent.is_some()
+                || !self.children.is_empty(),
+        );
+    }
+    /// What was removed since the last call, to be dropped once the bus is
+    /// unlocked.
+    fn take_removed(&mut self) -> Vec<Box<dyn Send>> {
+        std::mem::take(&mut self.removed)
+    }
+    fn has_handler(&self, tid: TypeId, handler_id: HandlerId) -> bool {
+        self.slots.get(handler_id) == Some(Registration::Event(tid))
+    }
+    /// The subscribed handlers of every event type.
+    fn live_handlers(&self) -> impl Iterator<Item = &Arc<dyn ErasedHandler>> {
+        self.handlers.values().flat_map(|list| list.iter()).filter(|h| !h.is_retired())
+    }
+    /// Load of every handler, hottest (by average execution time) first.
+    fn handler_stats(&self) -> Vec<HandlerStats> {
+        let mut stats: Vec<HandlerStats> = self.live_handlers()
+            .chain(self.category_handlers.values().flat_map(|list| list.iter()))
+            .map(|h| HandlerStats { label: self.labels.get(&h.id()).cloned(), ..h.stats() })
+            .collect();
+        stats.sort_by_key(|s| std::cmp::Reverse(s.avg_exec_time));
+        stats
+    }
+    fn advance_phase(&mut self) -> Phase {
+        self.phase = match self.phase {
+            Phase::PreInit => Phase::Init,
+            Phase::Init | Phase::PostInit => Phase::PostInit,
+        };
+        self.phase
+    }
+    fn publish_contract(&mut self, contract: EventContract) -> EventResult<()> {
+        match self.published_contracts.get(contract.name) {
+            Some(existing) if existing.schema_hash != contract.schema_hash => {
+                Err(EventError::ContractMismatch {
+                    name: contract.name,
+                    expected: existing.schema_hash,
+                    found: contract.schema_hash,
+                })
+            }
+            _ => {
+                self.published_contracts.insert(contract.name, contract);
+                Ok(())
+            }
+        }
+    }
+    fn verify_contracts(&self) -> EventResult<()> {
+        for required in &self.required_contracts {
+            let published = self.published_contracts.get(required.name)
+                .ok_or(EventError::MissingContract(required.name))?;
+            if published.schema_hash != required.schema_hash {
+                return Err(EventError::ContractMismatch {
+                    name: required.name,
+                    expected: required.schema_hash,
+                    found: published.schema_hash,
+                });
+            }
         }
+        Ok(())
     }
+    fn alias<Old: Event, New: Event, F>(&mut self, convert: F) -> EventResult<()>
+    where
+        F: Fn(&Old) -> New + Send + Sync + 'static
+    {
+        let old = TypeId::of::<Old>();
+        let mut target = TypeId::of::<New>();
+        loop {
+            if target == old {
+                return Err(EventError::AliasCycle(std::any::type_name::<Old>()));
+            }
+            match self.aliases.get(&target) {
+                Some((next, _)) => target = *next,
+                None => break,
+            }
+        }
+        let converter: Converter = (
+            TypeId::of::<New>(),
+            Arc::new(move |ev: &dyn Event| -> Box<dyn Event> {
+                let real = ev.as_any().downcast_ref::<Old>()
+                    .expect("alias registered under the wrong TypeId");
+                Box::new(convert(real))
+            }),
+        );
+        self.aliases.insert(old, converter);
+        self.interest.add(old);
+        Ok(())
+    }
+    fn register_responder<Q: Query, F>(&mut self, closure: F) -> HandlerId
+    where
+        F: Fn(&Q) -> Q::Response + Send + Sync + 'static
+    {
+        let tid = TypeId::of::<Q>();
+        // The responder replaced goes, along with its registration.
+        if let Some(&(previous, _)) = self.responders.get(&tid) {
+            self.remove_registration(previous);
+        }
+        let id = self.slots.insert(None, Registration::Responder(tid));
+        let responder: Responder<Q> = Arc::new(closure);
+        self.responders.insert(tid, (id, Box::new(responder)));
+        self.tag_generation(id.0);
+        id
+    }
+    fn unregister_responder<Q: Query>(&mut self, handler_id: HandlerId) {
+        if self.slots.get(handler_id) == Some(Registration::Responder(TypeId::of::<Q>())) {
+            self.remove_registration(handler_id);
+        }
+    }
+    fn responder<Q: Query>(&self) -> Option<Responder<Q>> {
+        self.responders.get(&TypeId::of::<Q>())
+            .and_then(|(_, r)| r.downcast_ref::<Responder<Q>>())
+            .cloned()
+    }
+}
+
+// --------------------------------------------------------------------
+// 5. Errors
+// --------------------------------------------------------------------
+/// An underlying error kept as the `source` of an `EventError`.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Everything that can go wrong. Variants caused by another error (I/O,
+/// serialization, a TOML parse) keep it as their `source`, and `context`
+/// wraps an error in a note about what was being done, so a logger walking
+/// `source()` gets the whole story.
+#[derive(Debug, thiserror::Error)]
+pub enum EventError {
+    #[error("no responder registered for query `{0}`")]
+    NoResponder(&'static str),
+    #[error("aliasing `{0}` would create a cycle")]
+    AliasCycle(&'static str),
+    #[error("mapping would create a cycle: {}", .path.join(" -> "))]
+    MapCycle { path: Vec<&'static str> },
+    #[error("no publisher declared a contract for event `{0}`")]
+    MissingContract(&'static str),
+    #[error("event `{name}` contract mismatch: expected schema {expected:#018x}, found {found:#018x}")]
+    ContractMismatch { name: &'static str, expected: u64, found: u64 },
+    #[error("event type `{0}` is not registered")]
+    UnregisteredEvent(String),
+    #[error("event name `{name}` is already registered for `{existing}`, not `{new}`")]
+    DuplicateEventName { name: String, existing: &'static str, new: &'static str },
+    #[error("wire id {id:#018x} of `{name}` collides with `{existing}`")]
+    WireIdCollision { name: &'static str, existing: &'static str, id: u64 },
+    #[error(
+        "event name `{name}` is declared by both `{registered}` and `{other}`{}",
+        if *.same_schema { ", with the same fields" } else { ", with different fields" }
+    )]
+    ConflictingEventName { name: &'static str, registered: &'static str, other: &'static str, same_schema: bool },
+    #[error("a handler with key `{key}` is already subscribed to `{event}`")]
+    DuplicateHandlerKey { key: String, event: &'static str },
+    #[error("`{handler}` is already subscribed to `{event}` in this set, as handler {id}")]
+    DuplicateHandler { event: &'static str, handler: &'static str, id: HandlerId },
+    #[error("event `{event}` failed validation")]
+    ValidationFailed { event: &'static str, #[source] source: ValidationError },
+    #[error("`{event}` already has the maximum of {max} handlers")]
+    TooManyHandlers { event: &'static str, max: usize },
+    #[error("boxed value is not a `{expected}`")]
+    EventTypeMismatch { expected: &'static str },
+    #[error("failed to encode event `{event}`")]
+    SerializeFailed { event: &'static str, #[source] source: BoxError },
+    #[error("failed to decode event")]
+    DeserializeFailed(#[source] BoxError),
+    #[error("no migration for event `{name}` from version {from} to {to}")]
+    NoMigration { name: &'static str, from: u32, to: u32 },
+    #[error("systems form a produce/consume cycle: {0}")]
+    SystemCycle(String),
+    #[error("invalid bus configuration")]
+    Config(#[source] BoxError),
+    #[error("event store: {0}")]
+    Store(String),
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("the event queue is full ({capacity} events)")]
+    QueueFull { capacity: usize },
+    #[error("the event bus has shut down")]
+    BusShutDown,
+    #[error("timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("scope `{scope}` may not {access} event `{event}`")]
+    EventNotAllowed { scope: String, event: &'static str, access: ScopeAccess },
+    #[error("the event bus lock was poisoned")]
+    BusPoisoned,
+    #[error("the global event bus is already in use")]
+    GlobalBusInstalled,
+    #[error("command `{0}` has no queue to take it")]
+    NoCommandQueue(&'static str),
+    #[error("the queue for command `{0}` is already taken")]
+    CommandQueueTaken(&'static str),
+    #[error("command `{0}` can only be published by value")]
+    CommandByReference(&'static str),
+    #[error("{context}")]
+    Context { context: String, #[source] source: Box<EventError> },
 }
 
-// A global OnceLock for the bus
-static GLOBAL_BUS: OnceLock<Arc<Mutex<EventBus>>> = OnceLock::new();
+pub type EventResult<T> = Result<T, EventError>;
+
+impl EventError {
+    /// Wraps the error in a note about what was being done.
+    pub fn context(self, context: impl Into<String>) -> EventError {
+        EventError::Context { context: context.into(), source: Box::new(self) }
+    }
 
-fn global_bus() -> Arc<Mutex<EventBus>> {
-    GLOBAL_BUS.get_or_init(|| {
-        Arc::new(Mutex::new(EventBus::new()))
-    }).clone()
+    /// The error under every `context`.
+    pub fn root(&self) -> &EventError {
+        match self {
+            EventError::Context { source, .. } => source.root(),
+            other => other,
+        }
+    }
 }
 
+/// `context` for results: `bus.emit(ev).context("publishing PlayerMoved")?`.
+pub trait EventResultExt<T> {
+    fn context(self, context: impl Into<String>) -> EventResult<T>;
+
+    /// Like `context`, building the note only on error.
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> EventResult<T>;
+}
+
+impl<T, E: Into<EventError>> EventResultExt<T> for Result<T, E> {
+    fn context(self, context: impl Into<String>) -> EventResult<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> EventResult<T> {
+        self.map_err(|e| e.into().context(f()))
+    }
+}
+
+// --------------------------------------------------------------------
+// 6. Queries (request/response)
 // --------------------------------------------------------------------
-// 5. Public API
+/// An event that expects exactly one answer. Register a responder with
+/// `register_responder` and ask it with `query`.
+pub trait Query: Event {
+    type Response: Send + 'static;
+}
+
+type Responder<Q> = Arc<dyn Fn(&Q) -> <Q as Query>::Response + Send + Sync>;
+
 // --------------------------------------------------------------------
+// 7. Aliases
+// --------------------------------------------------------------------
+/// Target type of an alias, and how to turn the deprecated event into it.
+type Converter = (TypeId, Arc<dyn Fn(&dyn Event) -> Box<dyn Event> + Send + Sync>);
+
+// --------------------------------------------------------------------
+// 8. Public API
+// --------------------------------------------------------------------
+// These all operate on `SharedEventBus::global()`.
 pub fn dispatch<E: Event + 'static>(ev: E) {
-    if let Ok(mut bus) = global_bus().lock() {
-        bus.dispatch(ev);
-    }
+    SharedEventBus::global().dispatch(ev);
+}
+pub fn dispatch_from<E: Event + 'static>(sender: SenderId, ev: E) {
+    SharedEventBus::global().dispatch_from(sender, ev);
+}
+pub fn emit<E: Event + 'static>(ev: E) -> EventResult<DeliveryReport> {
+    SharedEventBus::global().emit(ev)
+}
+pub fn emit_ref<E: Event + 'static>(ev: &E) -> EventResult<DeliveryReport> {
+    SharedEventBus::global().emit_ref(ev)
+}
+pub fn publish<E: Event + 'static>(ev: E) -> EventResult<()> {
+    SharedEventBus::global().publish(ev)
+}
+pub fn publish_ref<E: Event + 'static>(ev: &E) -> EventResult<()> {
+    SharedEventBus::global().publish_ref(ev)
 }
 pub fn process_events() {
-    if let Ok(mut bus) = global_bus().lock() {
-        bus.process();
-    }
+    SharedEventBus::global().process();
 }
 pub fn subscribe<E: Event + 'static, F>(f: F) -> HandlerId
 where
     F: Fn(&E) + Send + Sync + 'static
 {
-    if let Ok(mut bus) = global_bus().lock() {
-        bus.subscribe(f)
-    } else {
-        HandlerId(0)
-    }
+    SharedEventBus::global().subscribe(f)
+}
+/// Like `subscribe`, but the handler also receives the `EventContext` and
+/// may call `ctx.stop_propagation()` to consume the event.
+pub fn subscribe_with_context<E: Event + 'static, F>(f: F) -> HandlerId
+where
+    F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_with_context(f)
+}
+/// Subscribes `f` along with `state`, which every call gets to update (see
+/// `SharedEventBus::subscribe_with_state`).
+pub fn subscribe_with_state<E: Event + 'static, S, F>(state: S, f: F) -> HandlerId
+where
+    S: Send + 'static,
+    F: Fn(&E, &mut S) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_with_state(state, f)
+}
+/// Subscribes `f` for `duration` (see `SharedEventBus::subscribe_for`).
+pub fn subscribe_for<E: Event + 'static, F>(duration: Duration, f: F) -> Subscription
+where
+    F: Fn(&E) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_for(duration, f)
+}
+/// Subscribes a context-aware handler that runs before every handler of
+/// lower `priority` (the default is 0), e.g. UI above gameplay.
+pub fn subscribe_with_priority<E: Event + 'static, F>(priority: i32, f: F) -> HandlerId
+where
+    F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_with_priority(priority, f)
+}
+/// Subscribes a context-aware handler that stays offline until the bus
+/// reaches `phase` (see `advance_phase`).
+pub fn subscribe_in_phase<E: Event + 'static, F>(phase: Phase, priority: i32, f: F) -> HandlerId
+where
+    F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_in_phase(phase, priority, f)
+}
+/// Moves the bus to the next startup phase and returns it. Advancing past
+/// `PostInit` is a no-op.
+pub fn advance_phase() -> Phase {
+    SharedEventBus::global().advance_phase()
+}
+pub fn current_phase() -> Phase {
+    SharedEventBus::global().current_phase()
 }
 pub fn unsubscribe<E: Event + 'static>(handler_id: HandlerId) {
-    if let Ok(mut bus) = global_bus().lock() {
-        bus.unsubscribe::<E>(handler_id);
-    }
+    SharedEventBus::global().unsubscribe::<E>(handler_id);
+}
+/// Subscribes every `#[global_event_handler]` function (see
+/// `SharedEventBus::subscribe_global_handlers`).
+pub fn subscribe_global_handlers() -> EventResult<usize> {
+    SharedEventBus::global().subscribe_global_handlers()
+}
+/// Subscribes `handler` to one `E` per key per `process_events`, merged
+/// from all the `E`s with that key (see `SharedEventBus::subscribe_coalesced`).
+pub fn subscribe_coalesced<E, K, KF, MF, F>(key: KF, merge: MF, handler: F) -> HandlerId
+where
+    E: Event + Clone + 'static,
+    K: std::hash::Hash + Eq + Clone + Send + 'static,
+    KF: Fn(&E) -> K + Send + Sync + 'static,
+    MF: Fn(&mut E, &E) + Send + Sync + 'static,
+    F: Fn(&E) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_coalesced(key, merge, handler)
+}
+/// Subscribes to every event in category `C` (see `define_event!`), after
+/// the handlers of each event's own type.
+pub fn subscribe_category<C: ?Sized + 'static, F>(f: F) -> HandlerId
+where
+    F: Fn(&dyn Event) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_category::<C, _>(f)
+}
+pub fn unsubscribe_category<C: ?Sized + 'static>(handler_id: HandlerId) {
+    SharedEventBus::global().unsubscribe_category::<C>(handler_id);
+}
+/// Registers the responder for `Q`, replacing any previous one.
+pub fn register_responder<Q: Query, F>(f: F) -> HandlerId
+where
+    F: Fn(&Q) -> Q::Response + Send + Sync + 'static
+{
+    SharedEventBus::global().register_responder(f)
+}
+pub fn unregister_responder<Q: Query>(handler_id: HandlerId) {
+    SharedEventBus::global().unregister_responder::<Q>(handler_id);
+}
+/// Asks the registered responder for `Q` and returns its answer immediately.
+/// The bus lock is released before the responder runs, so responders may
+/// dispatch events or issue further queries.
+pub fn query<Q: Query>(q: Q) -> EventResult<Q::Response> {
+    SharedEventBus::global().query(q)
+}
+/// Subscribes `f` to be called by `publish_collect::<E, R>`.
+pub fn subscribe_returning<E: Event + 'static, R: 'static, F>(f: F) -> HandlerId
+where
+    F: Fn(&E) -> R + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_returning(f)
+}
+/// Calls every handler subscribed with `subscribe_returning::<E, R>` right
+/// away and returns what they returned, in the order they were subscribed.
+pub fn publish_collect<E: Event + 'static, R: 'static>(event: E) -> EventResult<Vec<R>> {
+    SharedEventBus::global().publish_collect(event)
+}
+/// Marks `Old` as a deprecated alias of `New`. Every `Old` event that is
+/// processed is converted with `convert` and also delivered to the handlers
+/// of `New`, so subscribers can migrate one at a time.
+pub fn alias<Old: Event, New: Event, F>(convert: F) -> EventResult<()>
+where
+    F: Fn(&Old) -> New + Send + Sync + 'static
+{
+    SharedEventBus::global().alias(convert)
+}
+/// Called by the crate that publishes an event to declare the definition it
+/// was built with. Declaring the same name twice with different schemas is
+/// an error.
+pub fn publish_contract(contract: EventContract) -> EventResult<()> {
+    SharedEventBus::global().publish_contract(contract)
+}
+/// Called by a crate that subscribes to an event to record the definition it
+/// was built against; checked later by `verify_contracts`.
+pub fn require_contract(contract: EventContract) {
+    SharedEventBus::global().require_contract(contract);
+}
+/// Checks every required contract against the published ones. Call this once
+/// at startup, after all modules have registered, to fail fast when two game
+/// modules were built against different event definitions.
+pub fn verify_contracts() -> EventResult<()> {
+    SharedEventBus::global().verify_contracts()
+}
+/// Same as `SharedEventBus::channel_for` on the global bus.
+pub fn channel_for<E: Event + Clone + 'static>() -> EventReceiver<E> {
+    SharedEventBus::global().channel_for()
 }
@@ -0,0 +1,92 @@
+// Fan-out delivery on rayon's thread pool, for events with many handlers
+// that don't depend on each other, such as a `GameTick` every enemy
+// listens for. `publish_parallel` is `publish` with one difference: the
+// handlers subscribed without a context (`subscribe`, `subscribe_keyed`,
+// `SubscriptionSet::subscribe`) can't stop propagation or see whether
+// others ran, so each run of them between two context-aware handlers is
+// called concurrently, and the next handler waits until all of them are
+// done. Context-aware handlers still run one at a time in their usual
+// place, so one of them consuming the event keeps every later handler
+// from seeing it.
+//
+// Within such a run the handlers start in no particular order. Handlers
+// on the buses the event bubbles up to are called serially, as usual.
+
+use rayon::prelude::*;
+
+use super::delivery::Delivery;
+use super::{DeliveryReport, ErasedHandler, Event, EventContext, EventMeta, EventResult, SharedEventBus};
+
+/// Independent handlers in a row, below which splitting them up across
+/// threads costs more than it saves.
+const MIN_BATCH: usize = 2;
+
+impl Delivery {
+    /// `run`, with the independent handlers run in parallel.
+    pub(crate) fn run_parallel(&self, ev: &dyn Event, meta: EventMeta) -> DeliveryReport {
+        let mut ctx = EventContext { stopped: false, meta };
+        let mut report = DeliveryReport { skipped: self.skipped, ..DeliveryReport::default() };
+        let mut batch: Vec<&dyn ErasedHandler> = Vec::new();
+        for h in self.all_handlers() {
+            if h.is_independent() {
+                batch.push(&**h);
+                continue;
+            }
+            report.merge(self.run_batch(&batch, ev, meta));
+            batch.clear();
+            if h.is_retired() {
+                continue;
+            }
+            if self.call(&**h, ev, &mut ctx, &mut report) && ctx.is_propagation_stopped() {
+                report.consumed = true;
+                return report;
+            }
+        }
+        report.merge(self.run_batch(&batch, ev, meta));
+        if let Some(((_, convert), next)) = &self.alias {
+            report.merge(next.run_parallel(&*convert(ev), meta));
+        }
+        report
+    }
+
+    /// Calls every handler in `batch`, across the pool if there are enough
+    /// of them, and returns once all have finished.
+    fn run_batch(&self, batch: &[&dyn ErasedHandler], ev: &dyn Event, meta: EventMeta) -> DeliveryReport {
+        let call = |mut report: DeliveryReport, h: &&dyn ErasedHandler| {
+            if !h.is_retired() {
+                self.call(*h, ev, &mut EventContext { stopped: false, meta }, &mut report);
+            }
+            report
+        };
+        if batch.len() < MIN_BATCH {
+            return batch.iter().fold(DeliveryReport::default(), call);
+        }
+        batch.par_iter()
+            .fold(DeliveryReport::default, call)
+            .reduce(DeliveryReport::default, |mut a, b| {
+                a.merge(b);
+                a
+            })
+    }
+}
+
+impl SharedEventBus {
+    /// `emit`, with the handlers that take no context run in parallel on
+    /// rayon's global thread pool (see the top of this file). Returns once
+    /// every handler has finished. `duration` in the report adds up the
+    /// time of all handlers, so it can exceed the time the call took.
+    pub fn emit_parallel<E: Event + 'static>(&self, ev: E) -> EventResult<DeliveryReport> {
+        let ev = match self.send_command(ev) {
+            Ok(sent) => return sent.map(|_| DeliveryReport::default()),
+            Err(ev) => ev,
+        };
+        let report = self.emit_with(&ev, None, Delivery::run_parallel)?;
+        self.keep(Box::new(ev));
+        Ok(report)
+    }
+
+    /// `emit_parallel` without the report.
+    pub fn publish_parallel<E: Event + 'static>(&self, ev: E) -> EventResult<()> {
+        self.emit_parallel(ev).map(|_| ())
+    }
+}
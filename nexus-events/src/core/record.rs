@@ -0,0 +1,52 @@
+// Keeping delivered events instead of dropping them, which
+// `testing::TestBus` is built on. Once `record_delivered` is called, every
+// event the bus is done with (processed from the queue, or published by
+// value) goes into a log, whether or not a handler received it. Events
+// published by reference (`emit_ref`, `publish_ref`) belong to the caller
+// and aren't kept, and neither are those handed up to a parent bus.
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use super::{Event, SharedEventBus};
+
+/// Delivered events, in the order their delivery finished.
+pub(crate) type EventLog = Arc<Mutex<Vec<Box<dyn Event>>>>;
+
+impl SharedEventBus {
+    /// Starts keeping delivered events, if the bus doesn't already, and
+    /// returns the log they go to.
+    pub(crate) fn record_delivered(&self) -> EventLog {
+        self.record.get_or_init(EventLog::default).clone()
+    }
+
+    /// Adds `ev` to the log if the bus keeps one; otherwise drops it.
+    pub(crate) fn keep(&self, ev: Box<dyn Event>) {
+        keep_in(&self.record, ev);
+    }
+
+    /// Runs `f` over the events still waiting to be delivered, in the order
+    /// they will be.
+    pub(crate) fn with_queued<R>(&self, f: impl FnOnce(&mut dyn Iterator<Item = &dyn Event>) -> R) -> R {
+        match self.inner.lock() {
+            Ok(mut bus) => {
+                bus.pull_inbox();
+                let bus = &*bus;
+                f(&mut bus.inline_due.iter().chain(bus.queue.iter()).map(|q| &*q.ev))
+            }
+            Err(_) => f(&mut std::iter::empty()),
+        }
+    }
+}
+
+/// Adds `ev` to `record`'s log, if the bus keeps one.
+pub(crate) fn keep_in(record: &OnceLock<EventLog>, ev: Box<dyn Event>) {
+    if let Some(log) = record.get() {
+        keep(log, ev);
+    }
+}
+
+fn keep(log: &EventLog, ev: Box<dyn Event>) {
+    if let Ok(mut log) = log.lock() {
+        log.push(ev);
+    }
+}
@@ -0,0 +1,158 @@
+// Runtime names for event types. Network and scripting layers only learn
+// which event they hold at runtime; the registry maps a name to its `TypeId`
+// and knows how to turn a `Box<dyn Any + Send>` of that type back into an
+// event, which is what `SharedEventBus::publish_boxed` needs.
+//
+// Every struct declared with `define_event!` is registered under its bare
+// name at startup. Register other types (or extra names) by hand.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use super::{Event, EventError, EventResult, SharedEventBus};
+
+type Erase = fn(Box<dyn Any + Send>) -> Result<Box<dyn Event>, Box<dyn Any + Send>>;
+
+fn erase<E: Event + 'static>(value: Box<dyn Any + Send>) -> Result<Box<dyn Event>, Box<dyn Any + Send>> {
+    value.downcast::<E>().map(|ev| ev as Box<dyn Event>)
+}
+
+/// Submitted by `define_event!`; collected when the registry is first used.
+#[doc(hidden)]
+pub struct EventRegistration {
+    name: &'static str,
+    type_id: fn() -> TypeId,
+    type_name: fn() -> &'static str,
+    erase: Erase,
+}
+
+impl EventRegistration {
+    pub const fn new<E: Event + 'static>(name: &'static str) -> Self {
+        Self {
+            name,
+            type_id: TypeId::of::<E>,
+            type_name: std::any::type_name::<E>,
+            erase: erase::<E>,
+        }
+    }
+}
+
+inventory::collect!(EventRegistration);
+
+/// The types `define_event!` registered, in the order inventory yields them.
+pub(crate) fn registered_types() -> impl Iterator<Item = TypeId> {
+    inventory::iter::<EventRegistration>.into_iter().map(|reg| (reg.type_id)())
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    type_id: TypeId,
+    type_name: &'static str,
+    erase: Erase,
+}
+
+#[derive(Default)]
+struct Tables {
+    by_name: HashMap<String, Entry>,
+    names: HashMap<TypeId, String>,
+}
+
+impl Tables {
+    fn insert(&mut self, name: String, entry: Entry) -> EventResult<()> {
+        match self.by_name.get(&name) {
+            Some(existing) if existing.type_id != entry.type_id => {
+                return Err(EventError::DuplicateEventName {
+                    name,
+                    existing: existing.type_name,
+                    new: entry.type_name,
+                });
+            }
+            Some(_) => return Ok(()),
+            None => {}
+        }
+        self.names.entry(entry.type_id).or_insert_with(|| name.clone());
+        self.by_name.insert(name, entry);
+        Ok(())
+    }
+}
+
+fn tables() -> &'static RwLock<Tables> {
+    static TABLES: OnceLock<RwLock<Tables>> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut tables = Tables::default();
+        for reg in inventory::iter::<EventRegistration> {
+            let entry = Entry { type_id: (reg.type_id)(), type_name: (reg.type_name)(), erase: reg.erase };
+            // Two `define_event!` structs with the same name in different
+            // modules: the first one keeps the name, the other has to be
+            // registered under a name of its own. `catalog::conflicts()`
+            // lists them.
+            if let Err(_e) = tables.insert(reg.name.to_string(), entry) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("{_e}; see `nexus_events::catalog::conflicts()`");
+            }
+        }
+        RwLock::new(tables)
+    })
+}
+
+/// The process-wide table of event type names.
+pub struct EventTypeRegistry;
+
+impl EventTypeRegistry {
+    /// Registers `E` under `name`. A type may have several names, but a name
+    /// belongs to one type only.
+    pub fn register<E: Event + 'static>(name: impl Into<String>) -> EventResult<()> {
+        let entry = Entry {
+            type_id: TypeId::of::<E>(),
+            type_name: std::any::type_name::<E>(),
+            erase: erase::<E>,
+        };
+        tables().write().map_err(|_| EventError::BusPoisoned)?.insert(name.into(), entry)
+    }
+
+    pub fn type_id(name: &str) -> Option<TypeId> {
+        tables().read().ok()?.by_name.get(name).map(|e| e.type_id)
+    }
+
+    /// The name `type_id` was first registered under.
+    pub fn name_of(type_id: TypeId) -> Option<String> {
+        tables().read().ok()?.names.get(&type_id).cloned()
+    }
+
+    /// Every registered name, sorted.
+    pub fn names() -> Vec<String> {
+        let mut names: Vec<String> = tables().read()
+            .map(|t| t.by_name.keys().cloned().collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    fn entry(type_id: TypeId) -> Option<Entry> {
+        let tables = tables().read().ok()?;
+        let name = tables.names.get(&type_id)?;
+        tables.by_name.get(name).copied()
+    }
+}
+
+impl SharedEventBus {
+    /// Dispatches an event whose type is only known at runtime. `type_id`
+    /// must be registered with `EventTypeRegistry` and `event` must hold a
+    /// value of that type.
+    pub fn publish_boxed(&self, type_id: TypeId, event: Box<dyn Any + Send>) -> EventResult<()> {
+        let entry = EventTypeRegistry::entry(type_id)
+            .ok_or_else(|| EventError::UnregisteredEvent(format!("{:?}", type_id)))?;
+        let ev = (entry.erase)(event)
+            .map_err(|_| EventError::EventTypeMismatch { expected: entry.type_name })?;
+        self.dispatch_boxed(ev);
+        Ok(())
+    }
+
+    /// `publish_boxed` with the type looked up by registered name.
+    pub fn publish_named(&self, name: &str, event: Box<dyn Any + Send>) -> EventResult<()> {
+        let type_id = EventTypeRegistry::type_id(name)
+            .ok_or_else(|| EventError::UnregisteredEvent(name.to_string()))?;
+        self.publish_boxed(type_id, event)
+    }
+}
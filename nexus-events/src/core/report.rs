@@ -0,0 +1,133 @@
+// Synchronous publishing for callers that need to know what happened to an
+// event: whether anyone received it, how many handlers were left out and
+// how long the handlers took. `emit` delivers on the spot, like an inline
+// critical event, instead of queueing for the next `process`.
+//
+// Since nothing is kept once `emit` returns, the event doesn't have to be
+// handed over either: `emit_ref` and `publish_ref` deliver one the caller
+// keeps, such as a pathfinding grid, without moving or cloning it. Whatever
+// outlives the call (`dispatch`, `dispatch_to`, scheduled events) takes the
+// event by value, so a borrowed one can't end up there.
+
+use std::ops::AddAssign;
+use std::time::Duration;
+
+use super::delivery::{self, Delivery, Run};
+use super::{trace, Event, EventError, EventMeta, EventResult, SharedEventBus};
+
+/// What delivering one event did, across every bus it reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    /// Handlers that ran.
+    pub handlers: usize,
+    /// Subscribed handlers that didn't run because they weren't due: their
+    /// phase wasn't reached, their subscription set was paused, or a
+    /// stagger left them out this time.
+    pub skipped: usize,
+    /// Handlers that panicked, on buses built with `PanicPolicy::Catch`.
+    /// They are counted in `handlers` too.
+    pub panicked: usize,
+    /// A handler stopped propagation.
+    pub consumed: bool,
+    /// Time spent in the handlers that ran.
+    pub duration: Duration,
+}
+
+impl DeliveryReport {
+    /// Whether any handler received the event.
+    pub fn delivered(&self) -> bool {
+        self.handlers > 0
+    }
+
+    pub(crate) fn merge(&mut self, other: DeliveryReport) {
+        self.handlers += other.handlers;
+        self.skipped += other.skipped;
+        self.panicked += other.panicked;
+        self.consumed |= other.consumed;
+        self.duration += other.duration;
+    }
+}
+
+impl AddAssign for DeliveryReport {
+    fn add_assign(&mut self, other: DeliveryReport) {
+        self.merge(other);
+    }
+}
+
+impl SharedEventBus {
+    /// Delivers `ev` now, to this bus, its capturing children and, if
+    /// nobody consumed it, up the parent chain, and reports what happened.
+    /// The event is numbered like a queued one but bypasses the queue, so
+    /// queue bounds and throttles don't apply; an event whose category is
+    /// disabled is dropped and reaches nobody. An event of a type nothing
+    /// on the bus listens to returns at once, without taking the bus lock
+    /// or a sequence number. Must not be called while
+    /// holding a lock a handler might take.
+    pub fn emit<E: Event + 'static>(&self, ev: E) -> EventResult<DeliveryReport> {
+        let ev = match self.send_command(ev) {
+            Ok(sent) => return sent.map(|_| DeliveryReport::default()),
+            Err(ev) => ev,
+        };
+        let report = self.emit_dyn(&ev, None)?;
+        self.keep(Box::new(ev));
+        Ok(report)
+    }
+
+    /// `emit` for an event the caller keeps. Handlers only see it while
+    /// they run; one that needs it afterwards copies what it needs.
+    pub fn emit_ref<E: Event + 'static>(&self, ev: &E) -> EventResult<DeliveryReport> {
+        if self.is_command::<E>() {
+            return Err(EventError::CommandByReference(std::any::type_name::<E>()));
+        }
+        self.emit_dyn(ev, None)
+    }
+
+    /// `emit`, addressed to `to` if given (see `publish_to`).
+    pub(crate) fn emit_dyn(&self, ev: &dyn Event, to: Option<u64>) -> EventResult<DeliveryReport> {
+        self.emit_with(ev, to, Delivery::run)
+    }
+
+    /// `emit_dyn`, running the handlers on this bus and its capturing
+    /// children with `run`.
+    pub(crate) fn emit_with(&self, ev: &dyn Event, to: Option<u64>, run: Run) -> EventResult<DeliveryReport> {
+        self.check_running()?;
+        self.validate(ev)?;
+        if self.interest.unheard(ev.as_any().type_id()) {
+            return Ok(DeliveryReport::default());
+        }
+        self.trace_publish(ev, None);
+        let (meta, parent, removed) = {
+            let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+            let _span = trace::publish_span(ev.event_name());
+            bus.prune_expired();
+            // Earlier dispatches get the earlier sequence numbers.
+            bus.pull_inbox();
+            let tid = ev.as_any().type_id();
+            if bus.metrics.is_enabled() {
+                bus.metrics.published(tid, ev.event_name());
+            }
+            let category = bus.categories.get(&tid).copied();
+            if !bus.config.category_enabled(category) {
+                bus.dropped += 1;
+                return Ok(DeliveryReport::default());
+            }
+            (bus.stamp(EventMeta { to, ..EventMeta::now(None) }), bus.parent.clone(), bus.take_removed())
+        };
+        drop(removed);
+        let mut report = delivery::deliver_down_with(&self.inner, ev, meta, run);
+        if !report.consumed {
+            report += Self::bubble_one(ev, meta, parent);
+        }
+        Ok(report)
+    }
+
+    /// `emit` without the report.
+    pub fn publish<E: Event + 'static>(&self, ev: E) -> EventResult<()> {
+        self.emit(ev).map(|_| ())
+    }
+
+    /// `emit_ref` without the report.
+    pub fn publish_ref<E: Event + 'static>(&self, ev: &E) -> EventResult<()> {
+        self.emit_ref(ev).map(|_| ())
+    }
+}
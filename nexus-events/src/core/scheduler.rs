@@ -0,0 +1,159 @@
+// Delayed and repeating publishes ("respawn the player in 3 seconds"). The
+// schedule runs on the bus's own clock, which only moves when `tick` is
+// called: call it from the game loop with the frame time, or let
+// `start_scheduler` call it from a background thread with wall-clock time.
+// Either way, due events are queued and handled on the next `process`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use super::delivery;
+use super::snapshot::BusLock;
+use super::{Event, EventBus, EventMeta, SharedEventBus};
+
+type Factory = Box<dyn FnMut() -> Option<Box<dyn Event>> + Send>;
+
+struct Entry {
+    due: Duration,
+    every: Option<Duration>,
+    make: Factory,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+pub(crate) struct Schedule {
+    now: Duration,
+    entries: Vec<Entry>,
+}
+
+impl Schedule {
+    /// Advances the clock by `dt` and returns the events that fell due, in
+    /// the order they were due.
+    fn advance(&mut self, dt: Duration) -> Vec<Box<dyn Event>> {
+        self.now += dt;
+        let mut due: Vec<(Duration, Box<dyn Event>)> = Vec::new();
+        for entry in &mut self.entries {
+            while !entry.cancelled.load(Ordering::Relaxed) && entry.due <= self.now {
+                match (entry.make)() {
+                    Some(ev) => due.push((entry.due, ev)),
+                    None => entry.cancelled.store(true, Ordering::Relaxed),
+                }
+                match entry.every {
+                    Some(every) => entry.due += every,
+                    None => entry.cancelled.store(true, Ordering::Relaxed),
+                }
+            }
+        }
+        self.entries.retain(|e| !e.cancelled.load(Ordering::Relaxed));
+        due.sort_by_key(|(at, _)| *at);
+        due.into_iter().map(|(_, ev)| ev).collect()
+    }
+}
+
+/// Cancels a scheduled publish. Dropping the handle does not cancel it.
+#[derive(Debug, Clone)]
+pub struct ScheduleHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ScheduleHandle {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// True once cancelled, or once a one-shot publish has fired.
+    pub fn is_done(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The background thread started by `start_scheduler`; stops when dropped.
+pub struct SchedulerThread {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for SchedulerThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl EventBus {
+    fn tick(&mut self, dt: Duration) {
+        for ev in self.schedule.advance(dt) {
+            self.enqueue(ev, EventMeta::now(None));
+        }
+    }
+}
+
+impl SharedEventBus {
+    /// Dispatches `ev` once `delay` has passed on the bus clock.
+    pub fn publish_after<E: Event + 'static>(&self, delay: Duration, ev: E) -> ScheduleHandle {
+        let mut ev = Some(ev);
+        self.schedule(delay, None, Box::new(move || ev.take().map(|ev| Box::new(ev) as Box<dyn Event>)))
+    }
+
+    /// Dispatches a fresh event from `factory` every `interval`, starting one
+    /// interval from now. If a single `tick` spans several intervals, every
+    /// missed one is published.
+    pub fn publish_every<E: Event + 'static, F>(&self, interval: Duration, mut factory: F) -> ScheduleHandle
+    where
+        F: FnMut() -> E + Send + 'static
+    {
+        let interval = interval.max(Duration::from_nanos(1));
+        self.schedule(interval, Some(interval), Box::new(move || Some(Box::new(factory()) as Box<dyn Event>)))
+    }
+
+    fn schedule(&self, delay: Duration, every: Option<Duration>, make: Factory) -> ScheduleHandle {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Ok(mut bus) = self.inner.lock() {
+            let due = bus.schedule.now + delay;
+            bus.schedule.entries.push(Entry { due, every, make, cancelled: cancelled.clone() });
+        }
+        ScheduleHandle { cancelled }
+    }
+
+    /// Advances the bus clock by `dt` and queues whatever fell due.
+    pub fn tick(&self, dt: Duration) {
+        Self::tick_inner(&self.inner, dt);
+    }
+
+    fn tick_inner(inner: &BusLock, dt: Duration) {
+        let due = match inner.lock() {
+            Ok(mut bus) => {
+                bus.tick(dt);
+                std::mem::take(&mut bus.inline_due)
+            }
+            Err(_) => return,
+        };
+        delivery::deliver_inline(inner, due);
+    }
+
+    /// Ticks the bus with wall-clock time every `resolution` from a
+    /// background thread, so scheduled events are queued without a game
+    /// loop. Don't also call `tick` yourself. The thread doesn't keep the
+    /// bus alive.
+    pub fn start_scheduler(&self, resolution: Duration) -> SchedulerThread {
+        let stop = Arc::new(AtomicBool::new(false));
+        let bus: Weak<_> = Arc::downgrade(&self.inner);
+        let stop_flag = stop.clone();
+        let thread = self.spawn_thread("scheduler", move || {
+            let mut last = Instant::now();
+            while !stop_flag.load(Ordering::Relaxed) {
+                std::thread::sleep(resolution);
+                let Some(inner) = bus.upgrade() else { break };
+                let now = Instant::now();
+                SharedEventBus::tick_inner(&inner, now - last);
+                last = now;
+            }
+        })
+        .expect("failed to spawn scheduler thread");
+        SchedulerThread { stop, thread: Some(thread) }
+    }
+}
@@ -0,0 +1,142 @@
+// Scopes: a named view of a bus for one module or plugin, limited to the
+// event types it was given. Subscribing to or publishing anything else fails
+// with `EventError::EventNotAllowed`, so boundaries between modules are
+// checked when handlers are registered instead of discovered in review.
+
+use std::any::TypeId;
+use std::collections::HashSet;
+
+use super::{Event, EventContext, EventError, EventResult, HandlerId, Phase, SharedEventBus};
+
+#[derive(Debug, Clone, Default)]
+struct TypeList {
+    allowed: Option<HashSet<TypeId>>,
+    denied: HashSet<TypeId>,
+}
+
+impl TypeList {
+    fn permits(&self, tid: TypeId) -> bool {
+        !self.denied.contains(&tid) && self.allowed.as_ref().is_none_or(|a| a.contains(&tid))
+    }
+}
+
+/// Whether an `EventScope` may subscribe to or publish an event type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeAccess {
+    Subscribe,
+    Publish,
+}
+
+impl std::fmt::Display for ScopeAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ScopeAccess::Subscribe => "subscribe to",
+            ScopeAccess::Publish => "publish",
+        })
+    }
+}
+
+/// A bus handle restricted to a set of event types. Create one with
+/// `SharedEventBus::scope`. Until something is allowed, every type is; once
+/// anything is allowed for a direction, only the allowed types are. Denied
+/// types are always refused.
+#[derive(Clone)]
+pub struct EventScope {
+    bus: SharedEventBus,
+    name: String,
+    subscribe: TypeList,
+    publish: TypeList,
+}
+
+impl EventScope {
+    pub fn allow_subscribe<E: Event + 'static>(mut self) -> Self {
+        self.subscribe.allowed.get_or_insert_with(HashSet::new).insert(TypeId::of::<E>());
+        self
+    }
+
+    pub fn allow_publish<E: Event + 'static>(mut self) -> Self {
+        self.publish.allowed.get_or_insert_with(HashSet::new).insert(TypeId::of::<E>());
+        self
+    }
+
+    pub fn deny_subscribe<E: Event + 'static>(mut self) -> Self {
+        self.subscribe.denied.insert(TypeId::of::<E>());
+        self
+    }
+
+    pub fn deny_publish<E: Event + 'static>(mut self) -> Self {
+        self.publish.denied.insert(TypeId::of::<E>());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The unrestricted bus behind this scope.
+    pub fn bus(&self) -> &SharedEventBus {
+        &self.bus
+    }
+
+    pub fn can_subscribe<E: Event + 'static>(&self) -> bool {
+        self.subscribe.permits(TypeId::of::<E>())
+    }
+
+    pub fn can_publish<E: Event + 'static>(&self) -> bool {
+        self.publish.permits(TypeId::of::<E>())
+    }
+
+    fn check<E: Event + 'static>(&self, access: ScopeAccess) -> EventResult<()> {
+        let list = match access {
+            ScopeAccess::Subscribe => &self.subscribe,
+            ScopeAccess::Publish => &self.publish,
+        };
+        if list.permits(TypeId::of::<E>()) {
+            Ok(())
+        } else {
+            Err(EventError::EventNotAllowed {
+                scope: self.name.clone(),
+                event: std::any::type_name::<E>(),
+                access,
+            })
+        }
+    }
+
+    pub fn subscribe<E: Event + 'static, F>(&self, f: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        self.check::<E>(ScopeAccess::Subscribe)?;
+        self.bus.try_subscribe::<E, _>(f)
+    }
+
+    pub fn subscribe_with_priority<E: Event + 'static, F>(&self, priority: i32, f: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.check::<E>(ScopeAccess::Subscribe)?;
+        self.bus.try_subscribe_in_phase::<E, _>(Phase::PreInit, priority, f)
+    }
+
+    pub fn dispatch<E: Event + 'static>(&self, ev: E) -> EventResult<()> {
+        self.check::<E>(ScopeAccess::Publish)?;
+        self.bus.dispatch(ev);
+        Ok(())
+    }
+
+    pub fn unsubscribe<E: Event + 'static>(&self, handler_id: HandlerId) {
+        self.bus.unsubscribe::<E>(handler_id);
+    }
+}
+
+impl SharedEventBus {
+    /// A restricted handle on this bus for the module called `name`.
+    pub fn scope(&self, name: impl Into<String>) -> EventScope {
+        EventScope {
+            bus: self.clone(),
+            name: name.into(),
+            subscribe: TypeList::default(),
+            publish: TypeList::default(),
+        }
+    }
+}
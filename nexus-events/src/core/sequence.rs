@@ -0,0 +1,129 @@
+// Sequence numbers for networking and replay. Every event a bus admits to
+// its queue is stamped with the next number of that bus, starting at 1, so
+// the numbers follow delivery order (critical events boosted to the front
+// of the queue aside) and the events a bus delivers form a gap-free stream.
+// Events the configuration drops are never numbered.
+// Handlers read the stamp, with the publish time and sender, from
+// `EventContext::envelope`; a receiver on the other end of a socket or a
+// replay file uses `SequenceTracker` to notice what went missing.
+//
+// Events keep the sequence of the bus they were dispatched on when they
+// bubble up or are captured down. Warm-up events are numbered 0.
+
+use std::ops::Range;
+use std::time::Instant;
+
+use super::{EventBus, EventContext, EventMeta, SenderId, SharedEventBus};
+
+/// What the bus recorded about an event when it was dispatched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventEnvelope {
+    /// Position in the stream of the bus it was dispatched on, from 1.
+    pub sequence: u64,
+    pub published_at: Instant,
+    pub sender: Option<SenderId>,
+}
+
+impl EventContext {
+    pub fn envelope(&self) -> EventEnvelope {
+        EventEnvelope {
+            sequence: self.meta.sequence,
+            published_at: self.meta.published_at,
+            sender: self.meta.sender,
+        }
+    }
+
+    /// Shorthand for `envelope().sequence`.
+    pub fn sequence(&self) -> u64 {
+        self.meta.sequence
+    }
+}
+
+impl EventBus {
+    /// Numbers an event that is about to be queued.
+    pub(super) fn stamp(&mut self, meta: EventMeta) -> EventMeta {
+        self.last_sequence += 1;
+        EventMeta { sequence: self.last_sequence, ..meta }
+    }
+}
+
+impl SharedEventBus {
+    /// The sequence number of the last event queued on this bus, or 0 if
+    /// there hasn't been one.
+    pub fn last_sequence(&self) -> u64 {
+        self.inner.lock()
+            .map(|mut bus| {
+                bus.pull_inbox();
+                bus.last_sequence
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// How an observed sequence number relates to the ones before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SequenceCheck {
+    /// The number right after the last one.
+    InOrder,
+    /// Later than expected; the numbers in the range were skipped.
+    Gap(Range<u64>),
+    /// Not after the last one: a duplicate or a straggler.
+    Stale,
+}
+
+/// Follows a stream of sequence numbers on the receiving end and reports
+/// what is missing.
+#[derive(Debug, Clone, Default)]
+pub struct SequenceTracker {
+    last: u64,
+    missed: u64,
+}
+
+impl SequenceTracker {
+    /// A tracker expecting the stream to start at 1.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A tracker picking the stream up after `last`, e.g. after loading a
+    /// snapshot taken at that point.
+    pub fn starting_after(last: u64) -> Self {
+        Self { last, missed: 0 }
+    }
+
+    pub fn observe(&mut self, sequence: u64) -> SequenceCheck {
+        if sequence <= self.last {
+            return SequenceCheck::Stale;
+        }
+        let expected = self.last + 1;
+        self.last = sequence;
+        if sequence == expected {
+            SequenceCheck::InOrder
+        } else {
+            self.missed += sequence - expected;
+            SequenceCheck::Gap(expected..sequence)
+        }
+    }
+
+    /// The highest number seen so far.
+    pub fn last(&self) -> u64 {
+        self.last
+    }
+
+    /// How many numbers have been skipped so far.
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+}
+
+/// The ranges missing from a set of sequence numbers, between the lowest
+/// and the highest, in order. Duplicates and ordering don't matter.
+pub fn sequence_gaps(sequences: impl IntoIterator<Item = u64>) -> Vec<Range<u64>> {
+    let mut sorted: Vec<u64> = sequences.into_iter().collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+    sorted.windows(2)
+        .filter(|pair| pair[1] > pair[0] + 1)
+        .map(|pair| pair[0] + 1..pair[1])
+        .collect()
+}
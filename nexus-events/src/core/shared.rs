@@ -0,0 +1,569 @@
+use std::any::TypeId;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex, OnceLock, RwLock, Weak};
+
+use super::backpressure::Inbox;
+use super::class::class_of;
+use super::delivery::{self, Pending};
+use super::interest::Interest;
+use super::snapshot::BusLock;
+use super::{
+    DeliveryReport, Event, EventBus, EventBusBuilder, EventContext, EventContract, EventError, EventLog, EventMeta, EventResult, HandlerId,
+    EventTraceCollector, HandlerStats, OverflowPolicy, Phase, Query, Queued, SenderId,
+};
+
+/// Decides whether an event may cross an edge between two buses.
+pub type EdgeFilter = Arc<dyn Fn(&dyn Event) -> bool + Send + Sync>;
+
+/// One direction of a parent/child link, as stored on a bus.
+#[derive(Clone)]
+pub(crate) struct Edge {
+    pub(crate) bus: Weak<BusLock>,
+    pub(crate) filter: Option<EdgeFilter>,
+}
+impl Edge {
+    pub(crate) fn allows(&self, ev: &dyn Event) -> bool {
+        self.filter.as_ref().is_none_or(|f| f(ev))
+    }
+}
+
+/// A `SharedEventBus` that doesn't keep the bus alive, for handlers that
+/// publish on a bus owning the one they are subscribed to.
+#[derive(Clone)]
+pub(crate) struct WeakBus {
+    inner: Weak<BusLock>,
+    inbox: Inbox,
+    inline_types: Arc<RwLock<HashSet<TypeId>>>,
+    command_types: Arc<RwLock<HashSet<TypeId>>>,
+    shut_down: Arc<AtomicBool>,
+    record: Arc<OnceLock<EventLog>>,
+    tracer: Arc<RwLock<Option<EventTraceCollector>>>,
+    interest: Arc<Interest>,
+}
+
+impl WeakBus {
+    pub(crate) fn upgrade(&self) -> Option<SharedEventBus> {
+        Some(SharedEventBus {
+            inner: self.inner.upgrade()?,
+            inbox: self.inbox.clone(),
+            inline_types: self.inline_types.clone(),
+            command_types: self.command_types.clone(),
+            shut_down: self.shut_down.clone(),
+            record: self.record.clone(),
+            tracer: self.tracer.clone(),
+            interest: self.interest.clone(),
+        })
+    }
+}
+
+/// How a child bus is wired to its parent (see `SharedEventBus::create_child_with`).
+///
+/// By default every event a child processes bubbles up to the parent unless a
+/// child handler consumed it. `capture` additionally sends events processed by
+/// the parent down to the child.
+#[derive(Clone, Default)]
+pub struct ChildEdge {
+    capture: bool,
+    bubble_filter: Option<EdgeFilter>,
+    capture_filter: Option<EdgeFilter>,
+}
+impl ChildEdge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Also deliver the parent's events to the child, after the parent's own
+    /// handlers have run and only if none of them consumed the event.
+    pub fn capture(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+    /// Only bubble events for which `f` returns true.
+    pub fn bubble_if<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&dyn Event) -> bool + Send + Sync + 'static
+    {
+        self.bubble_filter = Some(Arc::new(f));
+        self
+    }
+    /// Only capture events for which `f` returns true. Implies `capture`.
+    pub fn capture_if<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&dyn Event) -> bool + Send + Sync + 'static
+    {
+        self.capture = true;
+        self.capture_filter = Some(Arc::new(f));
+        self
+    }
+}
+
+/// A cheaply clonable handle to an event bus. The free functions in
+/// `nexus_events::core` all operate on `SharedEventBus::global()`; create more
+/// with `new` or `create_child` for per-scene or per-entity buses.
+///
+/// Dispatching doesn't take the bus lock while the queue has room, so
+/// publishers don't wait for each other or for handlers that are running;
+/// a bounded queue that may be full is checked under the lock (see
+/// `backpressure.rs`). Handlers run with the lock released, so they may use
+/// the bus they are running on. Handler lists are copy-on-write, so
+/// `publish` holds the lock only to clone the lists it delivers to.
+#[derive(Clone)]
+pub struct SharedEventBus {
+    pub(crate) inner: Arc<BusLock>,
+    inbox: Inbox,
+    inline_types: Arc<RwLock<HashSet<TypeId>>>,
+    pub(crate) command_types: Arc<RwLock<HashSet<TypeId>>>,
+    pub(crate) shut_down: Arc<AtomicBool>,
+    pub(crate) record: Arc<OnceLock<EventLog>>,
+    pub(crate) tracer: Arc<RwLock<Option<EventTraceCollector>>>,
+    pub(crate) interest: Arc<Interest>,
+}
+
+static GLOBAL_BUS: OnceLock<SharedEventBus> = OnceLock::new();
+
+impl Default for SharedEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SharedEventBus {
+    pub fn new() -> Self {
+        Self::from_bus(EventBus::new())
+    }
+
+    /// Configures and builds a bus; see `EventBusBuilder`.
+    pub fn builder() -> EventBusBuilder {
+        EventBusBuilder::new()
+    }
+
+    pub(crate) fn from_bus(bus: EventBus) -> Self {
+        Self {
+            inbox: bus.inbox_tx.clone(),
+            inline_types: bus.inline_types.clone(),
+            command_types: bus.command_types.clone(),
+            shut_down: bus.shut_down.clone(),
+            record: bus.record.clone(),
+            tracer: bus.publish_tracer.clone(),
+            interest: bus.interest.clone(),
+            inner: Arc::new(BusLock::new(bus)),
+        }
+    }
+
+    pub(crate) fn downgrade(&self) -> WeakBus {
+        WeakBus {
+            inner: Arc::downgrade(&self.inner),
+            inbox: self.inbox.clone(),
+            inline_types: self.inline_types.clone(),
+            command_types: self.command_types.clone(),
+            shut_down: self.shut_down.clone(),
+            record: self.record.clone(),
+            tracer: self.tracer.clone(),
+            interest: self.interest.clone(),
+        }
+    }
+
+    /// The process-wide bus used by the free functions and the macros, or
+    /// the one standing in for it on this thread (see
+    /// `global::with_scoped_bus`).
+    pub fn global() -> SharedEventBus {
+        if let Some(bus) = crate::global::scoped_bus() {
+            return bus;
+        }
+        GLOBAL_BUS.get_or_init(SharedEventBus::new).clone()
+    }
+
+    pub(crate) fn install_global(bus: SharedEventBus) -> EventResult<()> {
+        GLOBAL_BUS.set(bus).map_err(|_| EventError::GlobalBusInstalled)
+    }
+
+    /// Creates a child bus whose unconsumed events bubble up to this one.
+    pub fn create_child(&self) -> SharedEventBus {
+        self.create_child_with(ChildEdge::default())
+    }
+
+    /// Creates a child bus wired according to `edge`.
+    pub fn create_child_with(&self, edge: ChildEdge) -> SharedEventBus {
+        let child = SharedEventBus::new();
+        if let Ok(mut bus) = child.inner.lock() {
+            bus.parent = Some(Edge {
+                bus: Arc::downgrade(&self.inner),
+                filter: edge.bubble_filter,
+            });
+            bus.refresh_interest();
+        }
+        if edge.capture {
+            if let Ok(mut bus) = self.inner.lock() {
+                bus.children.retain(|c| c.bus.strong_count() > 0);
+                bus.children.push(Edge {
+                    bus: Arc::downgrade(&child.inner),
+                    filter: edge.capture_filter,
+                });
+                bus.refresh_interest();
+            }
+        }
+        child
+    }
+
+    pub fn dispatch<E: Event + 'static>(&self, ev: E) {
+        self.post(Box::new(ev), EventMeta::now(None));
+    }
+
+    /// Dispatches `ev` on behalf of `sender`; context-aware handlers can
+    /// read it back with `EventContext::sender`.
+    pub fn dispatch_from<E: Event + 'static>(&self, sender: SenderId, ev: E) {
+        self.post(Box::new(ev), EventMeta::now(Some(sender)));
+    }
+
+    /// Like `dispatch`, but fails with `EventError::QueueFull` instead of
+    /// dropping the event when the queue is full and the limit's
+    /// `OverflowPolicy` is `Error` (or `Block`, where waiting isn't
+    /// possible), and with `EventError::ValidationFailed` when it is
+    /// invalid (see `Validate`). Other configured drops (throttles,
+    /// disabled categories, the other overflow policies) still happen
+    /// silently.
+    pub fn try_dispatch<E: Event + 'static>(&self, ev: E) -> EventResult<()> {
+        let ev = match self.send_command(ev) {
+            Ok(sent) => return sent,
+            Err(ev) => ev,
+        };
+        self.check_running()?;
+        let ev: Box<dyn Event> = Box::new(ev);
+        self.validate(&*ev)?;
+        self.trace_publish(&*ev, None);
+        let due = {
+            let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+            bus.pull_inbox();
+            if !(bus.inlines_critical() && bus.is_critical(&*ev)) {
+                let class = class_of((*ev).as_any().type_id());
+                bus = self.wait_for_room(bus, class)?;
+                if let Some((capacity, OverflowPolicy::Error | OverflowPolicy::Block)) = bus.queue_limit(class) {
+                    return Err(EventError::QueueFull { capacity });
+                }
+            }
+            bus.admit(ev, EventMeta::now(None));
+            std::mem::take(&mut bus.inline_due)
+        };
+        delivery::deliver_inline(&self.inner, due);
+        Ok(())
+    }
+
+    pub(crate) fn dispatch_boxed(&self, ev: Box<dyn Event>) {
+        self.post(ev, EventMeta::now(None));
+    }
+
+    pub(crate) fn post(&self, ev: Box<dyn Event>, meta: EventMeta) {
+        if self.is_shut_down() || self.validate(&*ev).is_err() {
+            return;
+        }
+        self.trace_publish(&*ev, meta.sender);
+        let tid = (*ev).as_any().type_id();
+        let class = class_of(tid);
+        let inline = self.inline_types.read().is_ok_and(|types| types.contains(&tid));
+        if inline {
+            let due = match self.inner.lock() {
+                Ok(mut bus) => {
+                    bus.enqueue(ev, meta);
+                    std::mem::take(&mut bus.inline_due)
+                }
+                Err(_) => return,
+            };
+            delivery::deliver_inline(&self.inner, due);
+        } else if self.inner.blocks() || !self.inbox.has_room(class) {
+            // The limit applies here and now (see `backpressure.rs`).
+            let due = match self.inner.lock() {
+                Ok(bus) => {
+                    let Ok(mut bus) = self.wait_for_room(bus, class) else { return };
+                    bus.admit(ev, meta);
+                    std::mem::take(&mut bus.inline_due)
+                }
+                Err(_) => return,
+            };
+            delivery::deliver_inline(&self.inner, due);
+        } else {
+            self.inbox.send(Queued { ev, meta });
+        }
+    }
+
+    /// Takes every event still queued, in dispatch order, leaving the queue
+    /// empty. Meant for shutdown: persist them, or hand them to a replacement
+    /// bus with `requeue`. Sender and publish time are not kept.
+    pub fn drain_remaining(&self) -> Vec<Box<dyn Event>> {
+        self.inner.lock()
+            .map(|mut bus| {
+                bus.pull_inbox();
+                let inline = std::mem::take(&mut bus.inline_due);
+                let events = inline.into_iter().chain(bus.queue.take()).map(|q| q.ev).collect();
+                self.inner.room_made();
+                events
+            })
+            .unwrap_or_default()
+    }
+
+    /// Queues events taken from another bus with `drain_remaining`, after
+    /// anything already queued. The bus configuration applies as for
+    /// `dispatch`.
+    pub fn requeue(&self, events: impl IntoIterator<Item = Box<dyn Event>>) {
+        if self.is_shut_down() {
+            return;
+        }
+        let due = match self.inner.lock() {
+            Ok(mut bus) => {
+                for ev in events {
+                    bus.enqueue(ev, EventMeta::now(None));
+                }
+                std::mem::take(&mut bus.inline_due)
+            }
+            Err(_) => return,
+        };
+        delivery::deliver_inline(&self.inner, due);
+    }
+
+    /// Puts `E` in `category`, for `BusConfig::enabled_categories`.
+    pub fn categorize<E: Event + 'static>(&self, category: &'static str) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.categories.insert(std::any::TypeId::of::<E>(), category);
+            bus.interest.add(std::any::TypeId::of::<E>());
+        }
+    }
+
+    /// How many events the configuration has dropped so far.
+    pub fn dropped_events(&self) -> u64 {
+        self.inner.lock()
+            .map(|mut bus| {
+                bus.pull_inbox();
+                bus.dropped
+            })
+            .unwrap_or(0)
+    }
+
+    /// Delivers everything queued, then processes the bus's domains (see
+    /// `domain`). Handlers run with the bus unlocked (see `delivery`), so
+    /// they may dispatch, subscribe or query; what they dispatch is
+    /// delivered by the next `process`.
+    pub fn process(&self) {
+        let (pending, mut bubbling, parent, removed) = match self.inner.lock() {
+            Ok(mut bus) => {
+                let (pending, bubbling) = bus.take_pending();
+                (pending, bubbling, bus.parent.clone(), bus.take_removed())
+            }
+            Err(_) => return,
+        };
+        self.inner.room_made();
+        drop(removed);
+        let mut pending = Pending::new(&self.inner, pending);
+        while let Some((queued, consumed)) = pending.deliver_next() {
+            if !consumed && parent.is_some() {
+                bubbling.push(queued);
+            } else {
+                self.keep(queued.ev);
+            }
+        }
+        Self::bubble(bubbling, parent);
+        self.flush_coalesced();
+        self.process_domains();
+    }
+
+    /// Hands unconsumed events to the parent chain and reports on what they
+    /// reached there. Called with our own lock released, so a parent that
+    /// is processing at the same time (and capturing down into us) can't
+    /// deadlock.
+    pub(crate) fn bubble(bubbling: Vec<Queued>, parent: Option<Edge>) -> DeliveryReport {
+        let mut report = DeliveryReport::default();
+        for Queued { ev, meta } in bubbling {
+            report.merge(Self::bubble_one(&*ev, meta, parent.clone()));
+        }
+        report
+    }
+
+    /// `bubble` for one event.
+    pub(crate) fn bubble_one(ev: &dyn Event, meta: EventMeta, parent: Option<Edge>) -> DeliveryReport {
+        let mut report = DeliveryReport::default();
+        let tid = ev.as_any().type_id();
+        let mut edge = parent;
+        while let Some(e) = edge {
+            let Some(bus) = e.bus.upgrade().filter(|_| e.allows(ev)) else {
+                break;
+            };
+            let Ok((delivery, next)) = bus.lock().map(|bus| (bus.plan_for(tid, meta), bus.parent.clone())) else {
+                break;
+            };
+            let delivered = delivery.run(ev, meta);
+            let consumed = delivered.consumed;
+            report.merge(delivered);
+            if consumed {
+                break;
+            }
+            edge = next;
+        }
+        report
+    }
+
+    /// Subscribes `f` to every `E` at priority 0. The handlers of an event
+    /// run by descending priority and, within a priority, in the order they
+    /// were subscribed; unsubscribing one leaves the others in order.
+    pub fn subscribe<E: Event + 'static, F>(&self, f: F) -> HandlerId
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        self.try_subscribe::<E, _>(f).unwrap_or(HandlerId::NONE)
+    }
+
+    pub fn subscribe_with_context<E: Event + 'static, F>(&self, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.subscribe_with_priority::<E, _>(0, f)
+    }
+
+    /// Subscribes `f` along with `state`, which the bus keeps and hands to
+    /// every call to update, so the handler needs no shared cell of its
+    /// own. Calls from different threads take turns on the state.
+    pub fn subscribe_with_state<E: Event + 'static, S, F>(&self, state: S, f: F) -> HandlerId
+    where
+        S: Send + 'static,
+        F: Fn(&E, &mut S) + Send + Sync + 'static
+    {
+        let state = Mutex::new(state);
+        self.subscribe::<E, _>(move |ev: &E| {
+            if let Ok(mut state) = state.lock() {
+                f(ev, &mut state);
+            }
+        })
+    }
+
+    /// Subscribes `f` to run before the handlers of `E` with a lower
+    /// priority and after those with a higher or equal one that are already
+    /// subscribed.
+    pub fn subscribe_with_priority<E: Event + 'static, F>(&self, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.subscribe_in_phase::<E, _>(Phase::PreInit, priority, f)
+    }
+
+    /// Subscribes `f`. If `E` already has as many handlers as the bus
+    /// allows (see `EventBusBuilder::max_handlers_per_type`), nothing is
+    /// subscribed and the id is `HandlerId::NONE`; use `try_subscribe` to get
+    /// the error instead.
+    pub fn subscribe_in_phase<E: Event + 'static, F>(&self, phase: Phase, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.try_subscribe_in_phase::<E, _>(phase, priority, f).unwrap_or(HandlerId::NONE)
+    }
+
+    pub fn try_subscribe<E: Event + 'static, F>(&self, f: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        bus.subscribe_independent(0, Phase::PreInit, f)
+    }
+
+    pub fn try_subscribe_in_phase<E: Event + 'static, F>(&self, phase: Phase, priority: i32, f: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        bus.subscribe(priority, phase, f)
+    }
+
+    pub fn unsubscribe<E: Event + 'static>(&self, handler_id: HandlerId) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.unsubscribe::<E>(handler_id);
+            let removed = bus.take_removed();
+            drop(bus);
+            drop(removed);
+        }
+    }
+
+    /// Makes room for `additional` more handlers of `E`, so subscribing
+    /// them doesn't grow its handler list. Up to four fit without any. Best
+    /// done during setup: a change made while an `E` is being delivered
+    /// copies the list at its exact size.
+    pub fn reserve_handlers<E: Event + 'static>(&self, additional: usize) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.reserve_handlers(TypeId::of::<E>(), additional);
+        }
+    }
+
+    /// Per-handler load (moving averages of execution time and call rate),
+    /// hottest first.
+    pub fn handler_stats(&self) -> Vec<HandlerStats> {
+        self.inner.lock().map(|bus| bus.handler_stats()).unwrap_or_default()
+    }
+
+    pub fn advance_phase(&self) -> Phase {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.advance_phase()
+        } else {
+            Phase::PreInit
+        }
+    }
+
+    pub fn current_phase(&self) -> Phase {
+        if let Ok(bus) = self.inner.lock() {
+            bus.phase
+        } else {
+            Phase::PreInit
+        }
+    }
+
+    pub fn register_responder<Q: Query, F>(&self, f: F) -> HandlerId
+    where
+        F: Fn(&Q) -> Q::Response + Send + Sync + 'static
+    {
+        let Ok(mut bus) = self.inner.lock() else { return HandlerId::NONE };
+        let id = bus.register_responder(f);
+        let removed = bus.take_removed();
+        drop(bus);
+        drop(removed);
+        id
+    }
+
+    pub fn unregister_responder<Q: Query>(&self, handler_id: HandlerId) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.unregister_responder::<Q>(handler_id);
+            let removed = bus.take_removed();
+            drop(bus);
+            drop(removed);
+        }
+    }
+
+    pub fn query<Q: Query>(&self, q: Q) -> EventResult<Q::Response> {
+        self.check_running()?;
+        let responder = self.inner.lock()
+            .map_err(|_| EventError::BusPoisoned)?
+            .responder::<Q>()
+            .ok_or(EventError::NoResponder(std::any::type_name::<Q>()))?;
+        Ok(responder(&q))
+    }
+
+    pub fn alias<Old: Event, New: Event, F>(&self, convert: F) -> EventResult<()>
+    where
+        F: Fn(&Old) -> New + Send + Sync + 'static
+    {
+        self.inner.lock()
+            .map_err(|_| EventError::BusPoisoned)?
+            .alias(convert)
+    }
+
+    pub fn publish_contract(&self, contract: EventContract) -> EventResult<()> {
+        self.inner.lock()
+            .map_err(|_| EventError::BusPoisoned)?
+            .publish_contract(contract)
+    }
+
+    pub fn require_contract(&self, contract: EventContract) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.required_contracts.push(contract);
+        }
+    }
+
+    pub fn verify_contracts(&self) -> EventResult<()> {
+        self.inner.lock()
+            .map_err(|_| EventError::BusPoisoned)?
+            .verify_contracts()
+    }
+}
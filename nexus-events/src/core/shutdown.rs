@@ -0,0 +1,101 @@
+// Stopping a bus for good, so nothing runs on it while the game tears down.
+// After `shutdown`:
+//
+// - `dispatch` and `requeue` drop their events; `try_dispatch`, `emit`,
+//   `publish` and `query` fail with `EventError::BusShutDown`;
+// - the events queued beforehand have been delivered or dropped, per the
+//   `ShutdownPolicy`;
+// - every handler and responder is gone, so their captures are dropped and
+//   `channel_for` receivers disconnect; streams end once they are empty;
+// - the background worker pool has stopped.
+
+use std::sync::atomic::Ordering;
+
+use super::{EventError, EventResult, SharedEventBus};
+
+/// What `shutdown_with` does with the events still queued.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShutdownPolicy {
+    /// Deliver them in one last `process`. Whatever the handlers dispatch
+    /// meanwhile is dropped.
+    #[default]
+    Drain,
+    /// Drop them undelivered.
+    Discard,
+}
+
+impl SharedEventBus {
+    /// Shuts the bus down, delivering what is still queued first.
+    pub fn shutdown(&self) {
+        self.shutdown_with(ShutdownPolicy::Drain);
+    }
+
+    /// Shuts the bus down, for every clone of it; calling it again does
+    /// nothing. Don't call it from a handler of this bus.
+    pub fn shutdown_with(&self, policy: ShutdownPolicy) {
+        if self.shut_down.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // Dispatches waiting for room give up.
+        self.inner.room_made();
+        match policy {
+            ShutdownPolicy::Drain => self.process(),
+            ShutdownPolicy::Discard => {
+                if let Ok(mut bus) = self.inner.lock() {
+                    drop(bus.take_pending());
+                }
+            }
+        }
+        // Handlers are dropped with the bus unlocked; their captures may do
+        // anything when dropped.
+        let (handlers, categories, responders, coalesced, removed, hooks) = match self.inner.lock() {
+            Ok(mut bus) => {
+                bus.slots.clear();
+                bus.retired.clear();
+                bus.paused.clear();
+                bus.labels.clear();
+                bus.addresses.clear();
+                bus.generations.clear();
+                (
+                    std::mem::take(&mut bus.handlers),
+                    std::mem::take(&mut bus.category_handlers),
+                    std::mem::take(&mut bus.responders),
+                    std::mem::take(&mut bus.coalesced),
+                    bus.take_removed(),
+                    std::mem::take(&mut bus.shutdown_hooks),
+                )
+            }
+            Err(_) => return,
+        };
+        drop((handlers, categories, responders, coalesced, removed));
+        for hook in hooks {
+            hook();
+        }
+        self.stop_background();
+    }
+
+    pub fn is_shut_down(&self) -> bool {
+        self.shut_down.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn check_running(&self) -> EventResult<()> {
+        if self.is_shut_down() {
+            Err(EventError::BusShutDown)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Runs `hook` when the bus shuts down, or right away if it already
+    /// has.
+    #[cfg(feature = "futures")]
+    pub(crate) fn on_shutdown(&self, hook: impl FnOnce() + Send + 'static) {
+        if let Ok(mut bus) = self.inner.lock() {
+            if !self.is_shut_down() {
+                bus.shutdown_hooks.push(Box::new(hook));
+                return;
+            }
+        }
+        hook();
+    }
+}
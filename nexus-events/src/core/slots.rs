@@ -0,0 +1,138 @@
+// Every registration on a bus (event handler, category handler, query
+// responder) holds a slot here, found from its `HandlerId` without a
+// search. A counted id is the slot's index and generation; the generation
+// goes up whenever a slot is freed, so an id kept after its handler was
+// removed never matches the handler that reuses the slot. Keyed ids (see
+// `subscribe_keyed`) are hashes, looked up in a side map.
+//
+// Unsubscribing an event handler only retires it here and in its type's
+// list; the list drops retired handlers once per `process`, or sooner when
+// they make up half of it (`EventBus::compact_handlers`).
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::{ErasedHandler, HandlerId};
+
+/// Keyed ids have the top bit set; counted ids never do.
+pub(crate) const KEYED_BIT: u64 = 1 << 63;
+
+/// Generations stay below this, clear of `KEYED_BIT`.
+const GENERATIONS: u32 = 1 << 31;
+
+/// What a slot holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Registration {
+    Event(TypeId),
+    Category(TypeId),
+    Responder(TypeId),
+    // Keyed by event and result type (see `collect.rs`).
+    Collector(TypeId),
+}
+
+struct Entry {
+    id: HandlerId,
+    registration: Registration,
+    // Event and category handlers only.
+    handler: Option<Arc<dyn ErasedHandler>>,
+    // Counts registrations, for listing them in the order they were made.
+    order: u64,
+}
+
+struct Slot {
+    generation: u32,
+    entry: Option<Entry>,
+}
+
+#[derive(Default)]
+pub(crate) struct HandlerSlots {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    keyed: HashMap<u64, u32>,
+    registered: u64,
+}
+
+impl HandlerSlots {
+    /// Takes a slot for `registration` and returns its id: `key`, if
+    /// given, otherwise a counted id. Attach the handler afterwards.
+    pub(crate) fn insert(&mut self, key: Option<HandlerId>, registration: Registration) -> HandlerId {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.slots.push(Slot { generation: 1, entry: None });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        let slot = &mut self.slots[index as usize];
+        let id = match key {
+            Some(key) => {
+                self.keyed.insert(key.0, index);
+                key
+            }
+            None => HandlerId((slot.generation as u64) << 32 | index as u64),
+        };
+        self.registered += 1;
+        slot.entry = Some(Entry { id, registration, handler: None, order: self.registered });
+        id
+    }
+
+    pub(crate) fn attach(&mut self, id: HandlerId, handler: Arc<dyn ErasedHandler>) {
+        if let Some(entry) = self.entry_mut(id) {
+            entry.handler = Some(handler);
+        }
+    }
+
+    fn index(&self, id: HandlerId) -> Option<usize> {
+        let index = if id.0 & KEYED_BIT != 0 {
+            *self.keyed.get(&id.0)? as usize
+        } else {
+            (id.0 & u32::MAX as u64) as usize
+        };
+        let entry = self.slots.get(index)?.entry.as_ref()?;
+        (entry.id == id).then_some(index)
+    }
+
+    fn entry_mut(&mut self, id: HandlerId) -> Option<&mut Entry> {
+        let index = self.index(id)?;
+        self.slots[index].entry.as_mut()
+    }
+
+    pub(crate) fn get(&self, id: HandlerId) -> Option<Registration> {
+        let index = self.index(id)?;
+        self.slots[index].entry.as_ref().map(|e| e.registration)
+    }
+
+    /// When `id` was registered relative to the others; 0 if it isn't.
+    pub(crate) fn order(&self, id: HandlerId) -> u64 {
+        self.index(id).and_then(|i| self.slots[i].entry.as_ref()).map_or(0, |e| e.order)
+    }
+
+    /// Frees the slot of `id` and returns what it held.
+    pub(crate) fn remove(&mut self, id: HandlerId) -> Option<(Registration, Option<Arc<dyn ErasedHandler>>)> {
+        let index = self.index(id)?;
+        let slot = &mut self.slots[index];
+        let entry = slot.entry.take()?;
+        slot.generation = (slot.generation + 1) % GENERATIONS;
+        // Generation 0 would make slot 0's id `HandlerId::NONE`.
+        if slot.generation == 0 {
+            slot.generation = 1;
+        }
+        if id.0 & KEYED_BIT != 0 {
+            self.keyed.remove(&id.0);
+        }
+        self.free.push(index as u32);
+        Some((entry.registration, entry.handler))
+    }
+
+    /// Frees every slot, retiring the handlers. Generations carry on, so no
+    /// id given out so far matches a later registration.
+    pub(crate) fn clear(&mut self) {
+        let ids: Vec<HandlerId> = self.slots.iter().filter_map(|s| s.entry.as_ref().map(|e| e.id)).collect();
+        for id in ids {
+            if let Some((_, Some(handler))) = self.remove(id) {
+                handler.retire();
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+// Looking at what a bus is doing while it seems stuck, from another thread:
+//
+//     let snapshot = bus.debug_snapshot();
+//     eprintln!("{snapshot:#?}");
+//
+// The snapshot never waits for the bus lock. If the lock is free it lists
+// the queued events and the subscribed handlers; if it is held, those are
+// left out and the snapshot says for how long the lock has been held, as
+// far as snapshots can tell: the bus counts how often its lock is taken,
+// and a lock seen held at the same count by two snapshots has been held
+// all the time in between. Call it repeatedly (from a watchdog thread,
+// say) to see a lock that is never released.
+//
+// Which handlers are running is known without the lock: every thread
+// keeps the handler calls it is in the middle of (more than one when a
+// handler emits synchronously) where snapshots can read them.
+
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, LockResult, Mutex, MutexGuard, PoisonError, TryLockError, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::{EventBus, HandlerId, SharedEventBus};
+
+static NEXT_BUS: AtomicU64 = AtomicU64::new(1);
+
+/// A fresh id for a bus, to tell its running handlers from those of other
+/// buses.
+pub(crate) fn next_bus_id() -> u64 {
+    NEXT_BUS.fetch_add(1, Ordering::Relaxed)
+}
+
+/// The bus lock, counting how often it is taken.
+pub(crate) struct BusLock {
+    bus: Mutex<EventBus>,
+    id: u64,
+    taken: AtomicU64,
+    // The count the last snapshot found the lock held at, and when.
+    seen_held: Mutex<Option<(u64, Instant)>>,
+    // Dispatches waiting for room in a full queue (see `backpressure.rs`),
+    // and whether any limit makes them wait.
+    room: Condvar,
+    blocks: AtomicBool,
+}
+
+impl BusLock {
+    pub(crate) fn new(bus: EventBus) -> Self {
+        Self {
+            id: bus.bus_id,
+            blocks: AtomicBool::new(bus.config.may_block()),
+            bus: Mutex::new(bus),
+            taken: AtomicU64::new(0),
+            seen_held: Mutex::new(None),
+            room: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> LockResult<MutexGuard<'_, EventBus>> {
+        let bus = self.bus.lock();
+        self.taken.fetch_add(1, Ordering::Relaxed);
+        bus
+    }
+
+    /// Whether dispatches may have to wait for room.
+    pub(crate) fn blocks(&self) -> bool {
+        self.blocks.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_blocks(&self, blocks: bool) {
+        self.blocks.store(blocks, Ordering::Relaxed);
+    }
+
+    /// Releases `bus` until room is made in the queue or `timeout` passes.
+    pub(crate) fn wait_for_room<'a>(
+        &self,
+        bus: MutexGuard<'a, EventBus>,
+        timeout: Duration,
+    ) -> LockResult<MutexGuard<'a, EventBus>> {
+        match self.room.wait_timeout(bus, timeout) {
+            Ok((bus, _)) => {
+                self.taken.fetch_add(1, Ordering::Relaxed);
+                Ok(bus)
+            }
+            Err(e) => Err(PoisonError::new(e.into_inner().0)),
+        }
+    }
+
+    /// Wakes the dispatches waiting for room.
+    pub(crate) fn room_made(&self) {
+        if self.blocks() {
+            self.room.notify_all();
+        }
+    }
+}
+
+/// A handler call in progress.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RunningHandler {
+    /// Type name of the event it was called with.
+    pub event: &'static str,
+    pub handler: HandlerId,
+    /// The thread's name, or its id if it has none.
+    pub thread: String,
+}
+
+/// What a bus was doing when `SharedEventBus::debug_snapshot` looked.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BusSnapshot {
+    /// Whether another thread held the bus lock. `queued` and `handlers`
+    /// are then empty, since they can't be read.
+    pub locked: bool,
+    /// How long the lock has been held by the same holder, measured from
+    /// the first snapshot that found it held; zero for that snapshot.
+    pub lock_held_for: Option<Duration>,
+    /// Queued events by type name.
+    pub queued: BTreeMap<&'static str, usize>,
+    /// Subscribed handlers by event type name.
+    pub handlers: BTreeMap<&'static str, usize>,
+    /// Handler calls in progress, on any thread, innermost last for each
+    /// thread.
+    pub running: Vec<RunningHandler>,
+}
+
+impl SharedEventBus {
+    /// What the bus is doing right now, without waiting for its lock (see
+    /// the top of this file).
+    pub fn debug_snapshot(&self) -> BusSnapshot {
+        let lock = &*self.inner;
+        let mut snapshot = BusSnapshot { running: running_on(lock.id), ..BusSnapshot::default() };
+        let bus = match lock.bus.try_lock() {
+            Ok(bus) => Some(bus),
+            Err(TryLockError::Poisoned(bus)) => Some(bus.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        };
+        let mut seen_held = lock.seen_held.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(mut bus) = bus else {
+            let taken = lock.taken.load(Ordering::Relaxed);
+            let since = match *seen_held {
+                Some((at, since)) if at == taken => since,
+                _ => seen_held.insert((taken, Instant::now())).1,
+            };
+            snapshot.locked = true;
+            snapshot.lock_held_for = Some(since.elapsed());
+            return snapshot;
+        };
+        *seen_held = None;
+        drop(seen_held);
+        bus.pull_inbox();
+        for q in bus.inline_due.iter().chain(bus.queue.iter()) {
+            *snapshot.queued.entry((*q.ev).event_name()).or_insert(0) += 1;
+        }
+        for h in bus.live_handlers() {
+            *snapshot.handlers.entry(h.stats().event).or_insert(0) += 1;
+        }
+        snapshot
+    }
+}
+
+struct Call {
+    bus: u64,
+    event: &'static str,
+    handler: u64,
+}
+
+/// The handler calls one thread is in the middle of.
+struct ThreadCalls {
+    thread: String,
+    calls: Mutex<Vec<Call>>,
+}
+
+static THREADS: Mutex<Vec<Weak<ThreadCalls>>> = Mutex::new(Vec::new());
+
+thread_local! {
+    static CALLS: OnceCell<Arc<ThreadCalls>> = const { OnceCell::new() };
+}
+
+/// Marks a handler call as running on this thread until dropped.
+pub(crate) struct Running(());
+
+/// Notes that handler `handler` of bus `bus` is being called with an
+/// `event` on this thread, until the returned guard is dropped.
+pub(crate) fn running(bus: u64, event: &'static str, handler: u64) -> Running {
+    with_calls(|calls| calls.push(Call { bus, event, handler }));
+    Running(())
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        with_calls(|calls| {
+            calls.pop();
+        });
+    }
+}
+
+/// Whether a handler of bus `bus` is running on this thread.
+pub(crate) fn delivering_on(bus: u64) -> bool {
+    CALLS.try_with(|cell| {
+        cell.get().is_some_and(|calls| {
+            calls.calls.lock().unwrap_or_else(PoisonError::into_inner).iter().any(|c| c.bus == bus)
+        })
+    })
+    .unwrap_or(false)
+}
+
+fn with_calls(f: impl FnOnce(&mut Vec<Call>)) {
+    let _ = CALLS.try_with(|cell| {
+        let calls = cell.get_or_init(|| {
+            let current = thread::current();
+            let thread = current.name().map_or_else(|| format!("{:?}", current.id()), str::to_string);
+            let calls = Arc::new(ThreadCalls { thread, calls: Mutex::default() });
+            if let Ok(mut threads) = THREADS.lock() {
+                threads.retain(|t| t.strong_count() > 0);
+                threads.push(Arc::downgrade(&calls));
+            }
+            calls
+        });
+        f(&mut calls.calls.lock().unwrap_or_else(PoisonError::into_inner));
+    });
+}
+
+/// The handler calls of bus `bus` running on any thread.
+fn running_on(bus: u64) -> Vec<RunningHandler> {
+    let threads: Vec<Arc<ThreadCalls>> = match THREADS.lock() {
+        Ok(threads) => threads.iter().filter_map(Weak::upgrade).collect(),
+        Err(_) => return Vec::new(),
+    };
+    let mut running = Vec::new();
+    for t in threads {
+        let calls = t.calls.lock().unwrap_or_else(PoisonError::into_inner);
+        running.extend(calls.iter().filter(|c| c.bus == bus).map(|c| RunningHandler {
+            event: c.event,
+            handler: HandlerId(c.handler),
+            thread: t.thread.clone(),
+        }));
+    }
+    running
+}
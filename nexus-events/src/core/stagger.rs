@@ -0,0 +1,42 @@
+// Staggered delivery: for event types with very many subscribers (a `Tick`
+// every component listens to), each delivery only reaches one slice of the
+// handlers, and the slices take turns. With `stagger::<Tick>(4)` every
+// handler sees every fourth tick, and a frame pays for a quarter of them.
+
+use std::any::TypeId;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::{Event, SharedEventBus};
+
+pub(crate) struct Stagger {
+    slices: usize,
+    next: AtomicUsize,
+}
+
+impl Stagger {
+    /// Which slice the next delivery goes to.
+    pub(crate) fn advance(&self) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % self.slices
+    }
+
+    /// Handlers are sliced by id rather than by position, so subscribing or
+    /// unsubscribing doesn't move the others into a different slice.
+    pub(crate) fn in_slice(&self, handler_id: u64, slice: usize) -> bool {
+        handler_id % self.slices as u64 == slice as u64
+    }
+}
+
+impl SharedEventBus {
+    /// Spreads the handlers of `E` over `slices` round-robin slices: each
+    /// delivered `E` runs one slice only. Priorities still order the handlers
+    /// within a slice. `slices` of 0 or 1 turns staggering off again.
+    pub fn stagger<E: Event + 'static>(&self, slices: usize) {
+        if let Ok(mut bus) = self.inner.lock() {
+            if slices > 1 {
+                bus.staggered.insert(TypeId::of::<E>(), Stagger { slices, next: AtomicUsize::new(0) });
+            } else {
+                bus.staggered.remove(&TypeId::of::<E>());
+            }
+        }
+    }
+}
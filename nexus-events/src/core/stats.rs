@@ -0,0 +1,76 @@
+// Per-handler load tracking. Every handler keeps exponentially weighted
+// moving averages of how long it takes and how often it runs, so hot
+// handlers show up in `SharedEventBus::handler_stats` without a profiler.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::HandlerId;
+
+/// Weight of the newest sample in every moving average.
+const EWMA_ALPHA: f64 = 0.2;
+
+/// A snapshot of one handler's load.
+#[derive(Debug, Clone)]
+pub struct HandlerStats {
+    pub id: HandlerId,
+    /// Type name of the event the handler is subscribed to.
+    pub event: &'static str,
+    /// Set with `SharedEventBus::subscribe_labeled`.
+    pub label: Option<String>,
+    pub invocations: u64,
+    /// Moving average of the time spent in the handler per call.
+    pub avg_exec_time: Duration,
+    /// Moving average of calls per second.
+    pub rate_per_sec: f64,
+}
+
+#[derive(Default)]
+struct Load {
+    invocations: u64,
+    avg_exec_secs: f64,
+    rate_per_sec: f64,
+    last_call: Option<Instant>,
+}
+
+#[derive(Default)]
+pub(crate) struct LoadTracker {
+    load: Mutex<Load>,
+}
+
+impl LoadTracker {
+    pub(crate) fn record(&self, started: Instant, elapsed: Duration) {
+        let Ok(mut load) = self.load.lock() else { return };
+        let exec = elapsed.as_secs_f64();
+        if load.invocations == 0 {
+            load.avg_exec_secs = exec;
+        } else {
+            load.avg_exec_secs += EWMA_ALPHA * (exec - load.avg_exec_secs);
+        }
+        if let Some(last) = load.last_call {
+            let gap = started.duration_since(last).as_secs_f64();
+            if gap > 0.0 && load.invocations == 1 {
+                load.rate_per_sec = 1.0 / gap;
+            } else if gap > 0.0 {
+                load.rate_per_sec += EWMA_ALPHA * (1.0 / gap - load.rate_per_sec);
+            }
+        }
+        load.last_call = Some(started);
+        load.invocations += 1;
+    }
+
+    pub(crate) fn snapshot(&self, id: HandlerId, event: &'static str) -> HandlerStats {
+        let (invocations, avg_exec_secs, rate_per_sec) = match self.load.lock() {
+            Ok(l) => (l.invocations, l.avg_exec_secs, l.rate_per_sec),
+            Err(_) => (0, 0.0, 0.0),
+        };
+        HandlerStats {
+            id,
+            event,
+            label: None,
+            invocations,
+            avg_exec_time: Duration::from_secs_f64(avg_exec_secs),
+            rate_per_sec,
+        }
+    }
+}
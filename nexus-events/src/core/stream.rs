@@ -0,0 +1,161 @@
+// Bridges a bus subscription into a futures `Stream`, for async code that
+// consumes events with `while let Some(ev) = stream.next().await`. The
+// handler pushes clones into a bounded queue and wakes the task polling the
+// stream; `Backpressure` decides what happens when that task falls behind.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+
+use futures_core::Stream;
+
+use super::{Event, HandlerId, SharedEventBus};
+use crate::bridge::Backpressure;
+
+/// Queue size of `SharedEventBus::stream`.
+const DEFAULT_CAPACITY: usize = 256;
+
+struct Pending<E> {
+    queue: VecDeque<E>,
+    waker: Option<Waker>,
+}
+
+struct Buffer<E> {
+    pending: Mutex<Pending<E>>,
+    space: Condvar,
+    capacity: usize,
+    policy: Backpressure,
+    dropped: AtomicU64,
+    // Set when the stream is dropped, so a blocked handler gives up, or
+    // when the bus shuts down, so the stream ends.
+    closed: AtomicBool,
+}
+
+impl<E> Buffer<E> {
+    fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+        self.space.notify_all();
+        if let Ok(mut pending) = self.pending.lock() {
+            if let Some(waker) = pending.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    fn push(&self, ev: E) {
+        let Ok(mut pending) = self.pending.lock() else { return };
+        if pending.queue.len() >= self.capacity {
+            match self.policy {
+                Backpressure::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                Backpressure::DropOldest => {
+                    pending.queue.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Backpressure::Block => {
+                    let full = |p: &mut Pending<E>| {
+                        p.queue.len() >= self.capacity && !self.closed.load(Ordering::Relaxed)
+                    };
+                    let Ok(waited) = self.space.wait_while(pending, full) else { return };
+                    if self.closed.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    pending = waited;
+                }
+            }
+        }
+        pending.queue.push_back(ev);
+        if let Some(waker) = pending.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The stream returned by `SharedEventBus::stream`. It ends when the bus
+/// shuts down, once the events already queued have been polled; dropping it
+/// removes the subscription.
+pub struct EventStream<E: Event + 'static> {
+    buffer: Arc<Buffer<E>>,
+    bus: SharedEventBus,
+    handler: HandlerId,
+}
+
+impl<E: Event + 'static> EventStream<E> {
+    pub fn handler_id(&self) -> HandlerId {
+        self.handler
+    }
+
+    /// Events waiting to be polled.
+    pub fn pending(&self) -> usize {
+        self.buffer.pending.lock().map_or(0, |p| p.queue.len())
+    }
+
+    /// Events the overflow policy has dropped so far.
+    pub fn dropped(&self) -> u64 {
+        self.buffer.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl<E: Event + 'static> Stream for EventStream<E> {
+    type Item = E;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<E>> {
+        let Ok(mut pending) = self.buffer.pending.lock() else { return Poll::Ready(None) };
+        match pending.queue.pop_front() {
+            Some(ev) => {
+                self.buffer.space.notify_one();
+                Poll::Ready(Some(ev))
+            }
+            None if self.buffer.closed.load(Ordering::Relaxed) => Poll::Ready(None),
+            None => {
+                pending.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<E: Event + 'static> Drop for EventStream<E> {
+    fn drop(&mut self) {
+        self.buffer.closed.store(true, Ordering::Relaxed);
+        self.buffer.space.notify_all();
+        self.bus.unsubscribe::<E>(self.handler);
+    }
+}
+
+impl SharedEventBus {
+    /// Subscribes a handler that queues a clone of every `E` and returns a
+    /// stream of them. Up to 256 events wait to be polled; past that the
+    /// oldest is dropped.
+    pub fn stream<E: Event + Clone + 'static>(&self) -> EventStream<E> {
+        self.stream_with(DEFAULT_CAPACITY, Backpressure::DropOldest)
+    }
+
+    /// Like `stream`, with room for `capacity` events and `policy` deciding
+    /// what happens when the stream isn't polled fast enough. `Block` holds
+    /// up `process` until the stream is polled, so only use it when the
+    /// stream is polled on another thread.
+    pub fn stream_with<E: Event + Clone + 'static>(&self, capacity: usize, policy: Backpressure) -> EventStream<E> {
+        let buffer = Arc::new(Buffer {
+            pending: Mutex::new(Pending { queue: VecDeque::new(), waker: None }),
+            space: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+            dropped: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+        });
+        let queued = buffer.clone();
+        let handler = self.subscribe::<E, _>(move |ev: &E| queued.push(ev.clone()));
+        let closing = Arc::downgrade(&buffer);
+        self.on_shutdown(move || {
+            if let Some(buffer) = closing.upgrade() {
+                buffer.close();
+            }
+        });
+        EventStream { buffer, bus: self.clone(), handler }
+    }
+}
@@ -0,0 +1,309 @@
+// A component's subscriptions kept together, so they can be listed for
+// debugging, paused while the component is inactive, addressed with the
+// component's id and removed in one go when it goes away.
+//
+// The set also knows which closure each handler is, by its type, so a
+// component that subscribes its handlers again (on re-activation, say) can
+// be caught: `try_subscribe` fails with `EventError::DuplicateHandler` if
+// the same closure is already subscribed to the event through the set.
+// A closure's type is its expression in the source, whatever it captured,
+// so handlers made in a loop are all the same one; subscribe those with
+// `subscribe`, which doesn't check.
+//
+// A component can also hand the set a registration: a function that
+// subscribes its handlers through the set, kept under a key. The companions
+// `#[event_handler]` generates for methods register this way, keyed by the
+// type, name and definition site of the method, so same-named handlers of
+// two components sharing a set don't replace each other.
+
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use super::address::address_of;
+use super::{Event, EventBus, EventContext, EventError, EventResult, HandlerId, Phase, SharedEventBus};
+
+/// What a `SubscriptionSet` knows about one of its handlers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionInfo {
+    /// Type name of the event subscribed to.
+    pub event: &'static str,
+    pub id: HandlerId,
+    pub phase: Phase,
+    /// Still registered on the bus, its phase has been reached and the set
+    /// isn't paused.
+    pub active: bool,
+}
+
+struct Entry {
+    tid: TypeId,
+    event: &'static str,
+    /// The type of the handler closure.
+    handler: TypeId,
+    id: HandlerId,
+    phase: Phase,
+}
+
+/// Subscribes handlers through the set it is given.
+type Registration = Arc<dyn Fn(&mut SubscriptionSet) -> EventResult<()> + Send + Sync>;
+
+/// Subscriptions made through this set are removed when it is dropped.
+pub struct SubscriptionSet {
+    bus: SharedEventBus,
+    entries: Vec<Entry>,
+    registrations: Vec<(String, Registration)>,
+    paused: bool,
+    address: Option<u64>,
+    owner: Option<(&'static str, Arc<str>)>,
+}
+
+impl SubscriptionSet {
+    pub fn new(bus: &SharedEventBus) -> Self {
+        Self { bus: bus.clone(), entries: Vec::new(), registrations: Vec::new(), paused: false, address: None, owner: None }
+    }
+
+    pub fn subscribe<E: Event + 'static, F>(&mut self, f: F) -> HandlerId
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        self.add::<E, F>(Phase::PreInit, false, |bus| bus.subscribe_independent(0, Phase::PreInit, f))
+            .unwrap_or(HandlerId::NONE)
+    }
+
+    pub fn subscribe_in_phase<E: Event + 'static, F>(&mut self, phase: Phase, priority: i32, f: F) -> HandlerId
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.add::<E, F>(phase, false, |bus| bus.subscribe(priority, phase, f)).unwrap_or(HandlerId::NONE)
+    }
+
+    /// Like `subscribe`, but fails with `EventError::DuplicateHandler` if
+    /// `f` is already subscribed to `E` through this set (see the top of
+    /// this file).
+    pub fn try_subscribe<E: Event + 'static, F>(&mut self, f: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        self.add::<E, F>(Phase::PreInit, true, |bus| bus.subscribe_independent(0, Phase::PreInit, f))
+    }
+
+    /// `subscribe_in_phase` with the check of `try_subscribe`.
+    pub fn try_subscribe_in_phase<E: Event + 'static, F>(&mut self, phase: Phase, priority: i32, f: F) -> EventResult<HandlerId>
+    where
+        F: Fn(&E, &mut EventContext) + Send + Sync + 'static
+    {
+        self.add::<E, F>(phase, true, |bus| bus.subscribe(priority, phase, f))
+    }
+
+    /// Runs `register`, which subscribes handlers through the set, and keeps
+    /// it under `key` (see the top of this file), replacing any registration
+    /// with the same key. Nothing is kept if it fails.
+    pub fn register<F>(&mut self, key: &str, register: F) -> EventResult<()>
+    where
+        F: Fn(&mut SubscriptionSet) -> EventResult<()> + Send + Sync + 'static
+    {
+        register(self)?;
+        let register: Registration = Arc::new(register);
+        match self.registrations.iter_mut().find(|(k, _)| k == key) {
+            Some((_, r)) => *r = register,
+            None => self.registrations.push((key.to_string(), register)),
+        }
+        Ok(())
+    }
+
+    /// Keys of the registrations kept by the set, in registration order.
+    pub fn registrations(&self) -> impl Iterator<Item = &str> + '_ {
+        self.registrations.iter().map(|(key, _)| key.as_str())
+    }
+
+    /// The entry for handler type `handler` on event `tid`, if that handler
+    /// is still on the bus.
+    fn find(&self, bus: &EventBus, tid: TypeId, handler: TypeId) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.tid == tid && e.handler == handler && bus.has_handler(tid, e.id))
+    }
+
+    /// Subscribes through `subscribe` and keeps track of the handler,
+    /// unless it is `unique` and already there.
+    fn add<E: Event + 'static, F: 'static>(
+        &mut self,
+        phase: Phase,
+        unique: bool,
+        subscribe: impl FnOnce(&mut EventBus) -> EventResult<HandlerId>,
+    ) -> EventResult<HandlerId> {
+        let mut bus = self.bus.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+        let (tid, handler) = (TypeId::of::<E>(), TypeId::of::<F>());
+        if let Some(e) = self.find(&bus, tid, handler).filter(|_| unique) {
+            return Err(EventError::DuplicateHandler { event: e.event, handler: std::any::type_name::<F>(), id: e.id });
+        }
+        let id = subscribe(&mut bus)?;
+        if self.paused {
+            bus.paused.insert(id.0);
+        }
+        if let Some(address) = self.address {
+            bus.addresses.insert(id.0, address);
+        }
+        if let Some(owner) = &self.owner {
+            bus.owners.insert(id.0, owner.clone());
+        }
+        drop(bus);
+        self.entries.push(Entry {
+            tid,
+            event: std::any::type_name::<E>(),
+            handler,
+            id,
+            phase,
+        });
+        Ok(id)
+    }
+
+    /// Metadata for every subscription in the set, in subscription order.
+    pub fn iter(&self) -> impl Iterator<Item = SubscriptionInfo> + '_ {
+        let (registered, phase) = match self.bus.inner.lock() {
+            Ok(bus) => {
+                let registered: Vec<bool> = self.entries.iter()
+                    .map(|e| bus.has_handler(e.tid, e.id))
+                    .collect();
+                (registered, bus.phase)
+            }
+            Err(_) => (vec![false; self.entries.len()], Phase::PreInit),
+        };
+        self.entries.iter().zip(registered).map(move |(e, registered)| SubscriptionInfo {
+            event: e.event,
+            id: e.id,
+            phase: e.phase,
+            active: registered && e.phase <= phase && !self.paused,
+        })
+    }
+
+    /// Stops delivering to every handler in the set, including ones added
+    /// later, without unsubscribing them. Events dispatched while paused are
+    /// not replayed on `resume`.
+    pub fn pause(&mut self) {
+        self.set_paused(true);
+    }
+
+    pub fn resume(&mut self) {
+        self.set_paused(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        if let Ok(mut bus) = self.bus.inner.lock() {
+            for e in &self.entries {
+                if paused {
+                    bus.paused.insert(e.id.0);
+                } else {
+                    bus.paused.remove(&e.id.0);
+                }
+            }
+        }
+    }
+
+    /// Registers every handler in the set, including ones added later,
+    /// under `id`, so events sent with `SharedEventBus::dispatch_to(id, ..)`
+    /// or `publish_to` reach them. Replaces any earlier address.
+    pub fn set_address<Id: Hash + ?Sized + 'static>(&mut self, id: &Id) {
+        self.set_address_hash(address_of(id));
+    }
+
+    pub(crate) fn set_address_hash(&mut self, address: u64) {
+        self.address = Some(address);
+        if let Ok(mut bus) = self.bus.inner.lock() {
+            for e in &self.entries {
+                bus.addresses.insert(e.id.0, address);
+            }
+        }
+    }
+
+    pub fn is_addressed(&self) -> bool {
+        self.address.is_some()
+    }
+
+    /// Records every handler in the set, including ones added later, as
+    /// subscribed for the component of type `component` with id `id`, so
+    /// `SharedEventBus::export_subscription_plan` lists them under it.
+    pub fn set_owner(&mut self, component: &'static str, id: &str) {
+        let owner = (component, Arc::from(id));
+        if let Ok(mut bus) = self.bus.inner.lock() {
+            for e in &self.entries {
+                bus.owners.insert(e.id.0, owner.clone());
+            }
+        }
+        self.owner = Some(owner);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The bus the set subscribes on.
+    pub fn bus(&self) -> &SharedEventBus {
+        &self.bus
+    }
+
+    /// Removes every subscription in the set and has it subscribe on `bus`
+    /// from now on, running its registrations again there. The set stays
+    /// paused, addressed or owned if it was.
+    pub fn rebind(&mut self, bus: &SharedEventBus) -> EventResult<()> {
+        self.clear();
+        self.bus = bus.clone();
+        let registrations: Vec<Registration> = self.registrations.iter().map(|(_, r)| r.clone()).collect();
+        for register in registrations {
+            register(self)?;
+        }
+        Ok(())
+    }
+
+    /// Removes every subscription in the set. Its registrations are kept
+    /// for `rebind`.
+    pub fn clear(&mut self) {
+        if let Ok(mut bus) = self.bus.inner.lock() {
+            for e in self.entries.drain(..) {
+                bus.remove_handler(e.tid, e.id);
+            }
+            let removed = bus.take_removed();
+            drop(bus);
+            drop(removed);
+        }
+    }
+}
+
+impl Drop for SubscriptionSet {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl SharedEventBus {
+    /// Component type, component id and event type name of every handler
+    /// subscribed through a set with an owner.
+    pub(crate) fn owned_handlers(&self) -> Vec<(&'static str, Arc<str>, &'static str)> {
+        let Ok(bus) = self.inner.lock() else { return Vec::new() };
+        bus.live_handlers()
+            .filter_map(|h| {
+                let (component, id) = bus.owners.get(&h.id())?;
+                Some((*component, id.clone(), h.stats().event))
+            })
+            .collect()
+    }
+
+    /// How many handlers are subscribed to each event type, by type name.
+    pub fn handler_counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        if let Ok(bus) = self.inner.lock() {
+            for h in bus.live_handlers() {
+                *counts.entry(h.stats().event).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+}
@@ -0,0 +1,170 @@
+// Systems: named groups of handlers that declare which event types they
+// consume and produce. On every `run_systems` the bus orders them so that
+// producers run before consumers, and systems with no dependency between
+// them can run in parallel.
+
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use super::{Event, EventError, EventResult, Queued, SharedEventBus};
+
+type SystemHandler = Arc<dyn Fn(&dyn Event) + Send + Sync>;
+
+pub struct System {
+    name: String,
+    consumes: HashMap<TypeId, Vec<SystemHandler>>,
+    produces: HashSet<TypeId>,
+}
+
+impl System {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), consumes: HashMap::new(), produces: HashSet::new() }
+    }
+
+    /// Handles `E` inside this system. Declaring a consumer also orders the
+    /// system after every system that produces `E`.
+    pub fn consumes<E: Event + 'static, F>(mut self, f: F) -> Self
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        let handler: SystemHandler = Arc::new(move |ev: &dyn Event| {
+            if let Some(real) = ev.as_any().downcast_ref::<E>() {
+                f(real);
+            }
+        });
+        self.consumes.entry(TypeId::of::<E>()).or_default().push(handler);
+        self
+    }
+
+    /// Declares that this system dispatches `E` while it runs.
+    pub fn produces<E: Event + 'static>(mut self) -> Self {
+        self.produces.insert(TypeId::of::<E>());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(&self, pending: &[Queued]) {
+        for ev in pending.iter().map(|q| &*q.ev) {
+            if let Some(handlers) = self.consumes.get(&ev.as_any().type_id()) {
+                for h in handlers {
+                    h(ev);
+                }
+            }
+        }
+    }
+}
+
+/// Groups systems into levels: every producer of a type sits in an earlier
+/// level than its consumers, and systems within a level are independent.
+fn schedule(systems: &[Arc<System>]) -> EventResult<Vec<Vec<Arc<System>>>> {
+    let n = systems.len();
+    let mut indegree = vec![0usize; n];
+    let mut edges: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (p, producer) in systems.iter().enumerate() {
+        for (c, consumer) in systems.iter().enumerate() {
+            // A system that consumes its own output simply doesn't see it
+            // until the next run; that's not a cycle.
+            if p != c && producer.produces.iter().any(|t| consumer.consumes.contains_key(t)) {
+                edges[p].push(c);
+                indegree[c] += 1;
+            }
+        }
+    }
+
+    let mut levels = Vec::new();
+    let mut ready: Vec<usize> = (0..n).filter(|&i| indegree[i] == 0).collect();
+    let mut placed = 0;
+    while !ready.is_empty() {
+        let mut next = Vec::new();
+        for &i in &ready {
+            for &c in &edges[i] {
+                indegree[c] -= 1;
+                if indegree[c] == 0 {
+                    next.push(c);
+                }
+            }
+        }
+        placed += ready.len();
+        levels.push(ready.iter().map(|&i| systems[i].clone()).collect());
+        ready = next;
+    }
+
+    if placed < n {
+        let stuck = (0..n).filter(|&i| indegree[i] > 0)
+            .map(|i| systems[i].name.clone())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(EventError::SystemCycle(stuck));
+    }
+    Ok(levels)
+}
+
+impl SharedEventBus {
+    pub fn add_system(&self, system: System) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.systems.push(Arc::new(system));
+        }
+    }
+
+    /// The order `run_systems` would use right now, as levels of system names.
+    pub fn system_order(&self) -> EventResult<Vec<Vec<String>>> {
+        let systems = self.inner.lock().map_err(|_| EventError::BusPoisoned)?.systems.clone();
+        Ok(schedule(&systems)?
+            .into_iter()
+            .map(|level| level.iter().map(|s| s.name.clone()).collect())
+            .collect())
+    }
+
+    /// Runs every system once, level by level, then processes the queue as
+    /// usual so plain subscribers see the same events. Events a system
+    /// dispatches are visible to the systems in later levels.
+    pub fn run_systems(&self) -> EventResult<()> {
+        self.run_systems_inner(false)
+    }
+
+    /// Like `run_systems`, but the systems within one level run on their own
+    /// threads.
+    pub fn run_systems_parallel(&self) -> EventResult<()> {
+        self.run_systems_inner(true)
+    }
+
+    fn run_systems_inner(&self, parallel: bool) -> EventResult<()> {
+        let systems = self.inner.lock().map_err(|_| EventError::BusPoisoned)?.systems.clone();
+        let levels = schedule(&systems)?;
+
+        // The lock is only held to move events out of the queue, so systems
+        // are free to dispatch what they produce.
+        let mut pending: Vec<Queued> = Vec::new();
+        for level in &levels {
+            let mut bus = self.inner.lock().map_err(|_| EventError::BusPoisoned)?;
+            bus.pull_inbox();
+            pending.extend(bus.queue.take());
+            drop(bus);
+            self.inner.room_made();
+            if parallel && level.len() > 1 {
+                std::thread::scope(|scope| {
+                    for system in level {
+                        let pending = &pending;
+                        if self.spawn_scoped(scope, "system", move || system.run(pending)).is_err() {
+                            system.run(pending);
+                        }
+                    }
+                });
+            } else {
+                for system in level {
+                    system.run(&pending);
+                }
+            }
+        }
+
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.queue.put_back(pending.into_iter());
+        }
+        self.process();
+        Ok(())
+    }
+}
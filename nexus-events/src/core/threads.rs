@@ -0,0 +1,88 @@
+// Naming (and optionally pinning) the threads the bus starts on its own:
+// loader workers, background handler workers and parallel system levels.
+// Configured on the builder.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle, Scope, ScopedJoinHandle};
+
+use super::SharedEventBus;
+
+#[derive(Debug, Default)]
+pub(crate) struct ThreadSettings {
+    pub(crate) name_prefix: Option<String>,
+    pub(crate) core_ids: Vec<usize>,
+    // Size of the background handler pool; one per core if unset.
+    pub(crate) background_threads: Option<usize>,
+    next_core: AtomicUsize,
+}
+
+impl Clone for ThreadSettings {
+    fn clone(&self) -> Self {
+        Self {
+            name_prefix: self.name_prefix.clone(),
+            core_ids: self.core_ids.clone(),
+            background_threads: self.background_threads,
+            next_core: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ThreadSettings {
+    fn builder(&self, role: &str) -> (thread::Builder, Option<usize>) {
+        let prefix = self.name_prefix.as_deref().unwrap_or("nexus-events");
+        let core = (!self.core_ids.is_empty()).then(|| {
+            let i = self.next_core.fetch_add(1, Ordering::Relaxed);
+            self.core_ids[i % self.core_ids.len()]
+        });
+        (thread::Builder::new().name(format!("{}-{}", prefix, role)), core)
+    }
+}
+
+#[cfg(feature = "affinity")]
+fn pin(core: Option<usize>) {
+    if let Some(id) = core {
+        core_affinity::set_for_current(core_affinity::CoreId { id });
+    }
+}
+
+#[cfg(not(feature = "affinity"))]
+fn pin(_core: Option<usize>) {}
+
+impl SharedEventBus {
+    fn thread_builder(&self, role: &str) -> (thread::Builder, Option<usize>) {
+        match self.inner.lock() {
+            Ok(bus) => bus.threads.builder(role),
+            Err(_) => ThreadSettings::default().builder(role),
+        }
+    }
+
+    /// Spawns a background thread named and pinned per the bus settings.
+    pub(crate) fn spawn_thread<F>(&self, role: &str, f: F) -> io::Result<JoinHandle<()>>
+    where
+        F: FnOnce() + Send + 'static
+    {
+        let (builder, core) = self.thread_builder(role);
+        builder.spawn(move || {
+            pin(core);
+            f();
+        })
+    }
+
+    /// Like `spawn_thread`, inside a `thread::scope`.
+    pub(crate) fn spawn_scoped<'scope, 'env, F>(
+        &self,
+        scope: &'scope Scope<'scope, 'env>,
+        role: &str,
+        f: F,
+    ) -> io::Result<ScopedJoinHandle<'scope, ()>>
+    where
+        F: FnOnce() + Send + 'scope
+    {
+        let (builder, core) = self.thread_builder(role);
+        builder.spawn_scoped(scope, move || {
+            pin(core);
+            f();
+        })
+    }
+}
@@ -0,0 +1,37 @@
+// `tracing` spans for publishes and handler calls (the `tracing` feature).
+// Without the feature these compile to nothing.
+
+use std::time::Duration;
+
+/// Entered for the duration of a `dispatch` (filtering and queueing).
+#[cfg(feature = "tracing")]
+pub(crate) fn publish_span(event: &'static str) -> tracing::span::EnteredSpan {
+    tracing::trace_span!("publish", event).entered()
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn publish_span(_event: &'static str) -> NoSpan {
+    NoSpan
+}
+
+/// Runs one handler call inside a span that records how long it took.
+#[cfg(feature = "tracing")]
+pub(crate) fn handler_span(event: &'static str, handler_id: u64, call: impl FnOnce() -> Duration) -> Duration {
+    let span = tracing::trace_span!(
+        "handler",
+        event,
+        handler_id,
+        duration_us = tracing::field::Empty,
+    );
+    let elapsed = span.in_scope(call);
+    span.record("duration_us", elapsed.as_micros() as u64);
+    elapsed
+}
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) fn handler_span(_event: &'static str, _handler_id: u64, call: impl FnOnce() -> Duration) -> Duration {
+    call()
+}
@@ -0,0 +1,330 @@
+// Recording what a bus does, to look at afterwards when an event storm needs
+// debugging: every publish and every handler call, with a timestamp, the
+// thread it happened on and, for publishes, who published. `flush` hands
+// over what was recorded as a `Trace`, which renders as Chrome
+// `trace_event` JSON (load it in chrome://tracing or Perfetto) or as a
+// Graphviz graph of who publishes what and who handles it.
+//
+// The publisher is known when the event was dispatched with
+// `dispatch_from` (the sender) or by a handler while a traced bus ran it
+// (that handler). Child buses aren't traced along with their parent; give
+// them the same collector to see everything on one timeline.
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+use super::{Event, HandlerId, SenderId, SharedEventBus};
+
+/// Records an `EventTraceCollector::new` keeps.
+const DEFAULT_CAPACITY: usize = 65_536;
+
+thread_local! {
+    // The handler a traced bus is running on this thread, if any.
+    static RUNNING: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Who published an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Emitter {
+    /// Dispatched with `dispatch_from`.
+    Sender(SenderId),
+    /// Published by this handler while it ran.
+    Handler(HandlerId),
+    /// Published from anywhere else.
+    Outside,
+}
+
+impl fmt::Display for Emitter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Emitter::Sender(sender) => write!(f, "sender {}", sender.0),
+            Emitter::Handler(id) => write!(f, "handler {}", id),
+            Emitter::Outside => f.write_str("outside"),
+        }
+    }
+}
+
+/// One thing a traced bus did. `at` is the time since the collector was
+/// created; `thread` indexes `Trace::threads`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceRecord {
+    /// An event was dispatched, emitted or published.
+    Published {
+        event: &'static str,
+        at: Duration,
+        thread: usize,
+        emitter: Emitter,
+    },
+    /// A handler ran. Calls that panicked (with `PanicPolicy::Catch`) have
+    /// no duration.
+    Handled {
+        event: &'static str,
+        handler: HandlerId,
+        at: Duration,
+        duration: Duration,
+        thread: usize,
+        panicked: bool,
+    },
+}
+
+/// What a collector recorded between two `flush` calls.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    pub records: Vec<TraceRecord>,
+    /// Names of the threads the records happened on, by index.
+    pub threads: Vec<String>,
+    /// Records dropped because the collector was full.
+    pub dropped: u64,
+}
+
+impl Trace {
+    /// The trace in Chrome's `trace_event` format: publishes are instant
+    /// events, handler calls are complete events named after the event
+    /// they handled.
+    pub fn to_chrome_json(&self) -> String {
+        let mut out = String::from("{\"traceEvents\":[");
+        let mut first = true;
+        let mut next = |out: &mut String| {
+            if !std::mem::take(&mut first) {
+                out.push(',');
+            }
+        };
+        for (tid, name) in self.threads.iter().enumerate() {
+            next(&mut out);
+            let _ = write!(out, "{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":1,\"tid\":{},\"args\":{{\"name\":", tid);
+            json_str(&mut out, name);
+            out.push_str("}}");
+        }
+        for record in &self.records {
+            next(&mut out);
+            match record {
+                TraceRecord::Published { event, at, thread, emitter } => {
+                    out.push_str("{\"name\":");
+                    json_str(&mut out, event);
+                    let _ = write!(out, ",\"cat\":\"publish\",\"ph\":\"i\",\"s\":\"t\",\"ts\":{:.3},\"pid\":1,\"tid\":{},\"args\":{{\"from\":", micros(*at), thread);
+                    json_str(&mut out, &emitter.to_string());
+                    out.push_str("}}");
+                }
+                TraceRecord::Handled { event, handler, at, duration, thread, panicked } => {
+                    out.push_str("{\"name\":");
+                    json_str(&mut out, event);
+                    let _ = write!(
+                        out,
+                        ",\"cat\":\"handler\",\"ph\":\"X\",\"ts\":{:.3},\"dur\":{:.3},\"pid\":1,\"tid\":{},\"args\":{{\"handler\":\"{}\",\"panicked\":{}}}}}",
+                        micros(*at), micros(*duration), thread, handler, panicked
+                    );
+                }
+            }
+        }
+        let _ = write!(out, "],\"displayTimeUnit\":\"ms\",\"otherData\":{{\"dropped\":{}}}}}", self.dropped);
+        out
+    }
+
+    /// The trace as a Graphviz digraph: publishers point at the event types
+    /// they published, event types at the handlers that ran for them, each
+    /// edge labelled with how often it happened.
+    pub fn to_dot(&self) -> String {
+        let mut events = BTreeMap::new();
+        let mut edges: BTreeMap<(String, String), u64> = BTreeMap::new();
+        for record in &self.records {
+            let (event, from, to) = match record {
+                TraceRecord::Published { event, emitter, .. } => (*event, emitter.to_string(), format!("event {}", event)),
+                TraceRecord::Handled { event, handler, .. } => {
+                    (*event, format!("event {}", event), Emitter::Handler(*handler).to_string())
+                }
+            };
+            events.insert(format!("event {}", event), event);
+            *edges.entry((from, to)).or_default() += 1;
+        }
+        let mut out = String::from("digraph events {\n    rankdir=LR;\n    node [shape=box];\n");
+        for (id, event) in &events {
+            out.push_str("    ");
+            json_str(&mut out, id);
+            out.push_str(" [shape=ellipse, label=");
+            json_str(&mut out, event);
+            out.push_str("];\n");
+        }
+        for ((from, to), count) in &edges {
+            out.push_str("    ");
+            json_str(&mut out, from);
+            out.push_str(" -> ");
+            json_str(&mut out, to);
+            let _ = writeln!(out, " [label=\"{}\"];", count);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn micros(d: Duration) -> f64 {
+    d.as_secs_f64() * 1e6
+}
+
+/// Appends `s` as a quoted string, escaped for JSON (and so for dot too).
+fn json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[derive(Default)]
+struct Log {
+    records: VecDeque<TraceRecord>,
+    // Kept across flushes, so thread indices stay valid.
+    threads: Vec<(ThreadId, String)>,
+    dropped: u64,
+}
+
+impl Log {
+    fn thread(&mut self) -> usize {
+        let current = thread::current();
+        if let Some(i) = self.threads.iter().position(|(id, _)| *id == current.id()) {
+            return i;
+        }
+        let name = current.name().map_or_else(|| format!("{:?}", current.id()), str::to_owned);
+        self.threads.push((current.id(), name));
+        self.threads.len() - 1
+    }
+
+    fn push(&mut self, capacity: usize, record: TraceRecord) {
+        if self.records.len() >= capacity {
+            self.records.pop_front();
+            self.dropped += 1;
+        }
+        self.records.push_back(record);
+    }
+}
+
+struct Collector {
+    started: Instant,
+    capacity: usize,
+    log: Mutex<Log>,
+}
+
+/// Collects the publishes and handler calls of the buses it is attached to
+/// (see `SharedEventBus::set_trace_collector`). Clones share the records.
+/// Past its capacity the oldest records are dropped.
+#[derive(Clone)]
+pub struct EventTraceCollector {
+    inner: Arc<Collector>,
+}
+
+impl Default for EventTraceCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventTraceCollector {
+    /// A collector that keeps the last 65536 records.
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(records: usize) -> Self {
+        Self {
+            inner: Arc::new(Collector {
+                started: Instant::now(),
+                capacity: records.max(1),
+                log: Mutex::new(Log::default()),
+            }),
+        }
+    }
+
+    /// Records waiting to be flushed.
+    pub fn len(&self) -> usize {
+        self.inner.log.lock().map_or(0, |log| log.records.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Takes everything recorded since the last flush.
+    pub fn flush(&self) -> Trace {
+        let Ok(mut log) = self.inner.log.lock() else { return Trace::default() };
+        Trace {
+            records: log.records.drain(..).collect(),
+            threads: log.threads.iter().map(|(_, name)| name.clone()).collect(),
+            dropped: std::mem::take(&mut log.dropped),
+        }
+    }
+
+    fn record(&self, record: impl FnOnce(usize) -> TraceRecord) {
+        if let Ok(mut log) = self.inner.log.lock() {
+            let thread = log.thread();
+            let record = record(thread);
+            log.push(self.inner.capacity, record);
+        }
+    }
+
+    pub(crate) fn published(&self, event: &'static str, sender: Option<SenderId>) {
+        let at = self.inner.started.elapsed();
+        let emitter = match (sender, RUNNING.get()) {
+            (Some(sender), _) => Emitter::Sender(sender),
+            (None, Some(id)) => Emitter::Handler(HandlerId(id)),
+            (None, None) => Emitter::Outside,
+        };
+        self.record(|thread| TraceRecord::Published { event, at, thread, emitter });
+    }
+
+    /// Records a handler call that just ended after `duration`.
+    pub(crate) fn handled(&self, event: &'static str, handler: u64, duration: Duration, panicked: bool) {
+        let at = self.inner.started.elapsed().saturating_sub(duration);
+        let handler = HandlerId(handler);
+        self.record(|thread| TraceRecord::Handled { event, handler, at, duration, thread, panicked });
+    }
+}
+
+/// Runs `call` as handler `id`, so what it publishes is traced as coming
+/// from that handler.
+pub(crate) fn running<R>(id: u64, call: impl FnOnce() -> R) -> R {
+    struct Restore(Option<u64>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RUNNING.set(self.0);
+        }
+    }
+    let _restore = Restore(RUNNING.replace(Some(id)));
+    call()
+}
+
+impl SharedEventBus {
+    /// Records this bus's publishes and handler calls into `collector`,
+    /// replacing the collector attached before; `None` stops recording.
+    pub fn set_trace_collector(&self, collector: Option<EventTraceCollector>) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.tracer = collector.clone();
+            bus.refresh_interest();
+            if let Ok(mut publishes) = bus.publish_tracer.write() {
+                *publishes = collector;
+            }
+        }
+    }
+
+    pub fn trace_collector(&self) -> Option<EventTraceCollector> {
+        self.inner.lock().ok().and_then(|bus| bus.tracer.clone())
+    }
+
+    pub(crate) fn trace_publish(&self, ev: &dyn Event, sender: Option<SenderId>) {
+        if let Ok(tracer) = self.tracer.read() {
+            if let Some(tracer) = &*tracer {
+                tracer.published(ev.event_name(), sender);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// Rejecting invalid events (negative health, NaN positions) where they
+// enter the bus instead of in every handler. An event type opts in by
+// implementing `Validate` and being declared with `#[validate]` in
+// `define_event!` (or registered with `validate_as`); only those types are
+// checked. `publish`, `emit` and `try_dispatch` fail with
+// `EventError::ValidationFailed`; `dispatch` has nowhere to report it and
+// drops the event. Either way the event goes to the dead-letter sink too
+// if the bus was built with `dead_letter_invalid`.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
+
+use super::{Event, EventError, EventResult, SharedEventBus};
+
+/// Checks an event's payload before the bus takes it.
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationError>;
+}
+
+/// Why an event was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    message: String,
+}
+
+impl ValidationError {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into() }
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+type Validator = fn(&dyn Event) -> Result<(), ValidationError>;
+
+fn check_as<E: Validate + 'static>(ev: &dyn Event) -> Result<(), ValidationError> {
+    match ev.as_any().downcast_ref::<E>() {
+        Some(ev) => ev.validate(),
+        None => Ok(()),
+    }
+}
+
+/// Submitted by `define_event!` for events declared with `#[validate]`.
+#[doc(hidden)]
+pub struct ValidatorLink {
+    event: fn() -> TypeId,
+    validator: Validator,
+}
+
+impl ValidatorLink {
+    pub const fn new<E: Validate + Event>() -> Self {
+        Self { event: TypeId::of::<E>, validator: check_as::<E> }
+    }
+}
+
+inventory::collect!(ValidatorLink);
+
+// Set once any type has a validator, so buses without any skip the lookup.
+static ANY_VALIDATORS: AtomicBool = AtomicBool::new(false);
+
+fn validators() -> &'static RwLock<HashMap<TypeId, Validator>> {
+    static VALIDATORS: OnceLock<RwLock<HashMap<TypeId, Validator>>> = OnceLock::new();
+    VALIDATORS.get_or_init(|| {
+        let validators: HashMap<TypeId, Validator> = inventory::iter::<ValidatorLink>
+            .into_iter()
+            .map(|link| ((link.event)(), link.validator))
+            .collect();
+        if !validators.is_empty() {
+            ANY_VALIDATORS.store(true, Ordering::Relaxed);
+        }
+        RwLock::new(validators)
+    })
+}
+
+/// Validates `E` on every bus from now on, for types not declared with
+/// `define_event!` (generic events, say).
+pub fn validate_as<E: Validate + Event>() {
+    if let Ok(mut validators) = validators().write() {
+        validators.insert(TypeId::of::<E>(), check_as::<E>);
+        ANY_VALIDATORS.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Runs the validator of `ev`'s type, if it has one.
+pub(crate) fn check(ev: &dyn Event) -> Result<(), ValidationError> {
+    let validators = validators();
+    if !ANY_VALIDATORS.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let validator = match validators.read() {
+        Ok(validators) => validators.get(&ev.as_any().type_id()).copied(),
+        Err(_) => None,
+    };
+    validator.map_or(Ok(()), |validate| validate(ev))
+}
+
+impl SharedEventBus {
+    /// Validates `ev` on its way in. A rejected event goes to the
+    /// dead-letter sink if the bus was built with `dead_letter_invalid`.
+    pub(crate) fn validate(&self, ev: &dyn Event) -> EventResult<()> {
+        let Err(source) = check(ev) else { return Ok(()) };
+        let sink = self.inner.lock().ok()
+            .filter(|bus| bus.dead_letter_invalid)
+            .and_then(|bus| bus.dead_letters.clone());
+        if let Some(sink) = sink {
+            sink(ev);
+        }
+        Err(EventError::ValidationFailed { event: ev.event_name(), source })
+    }
+}
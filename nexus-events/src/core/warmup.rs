@@ -0,0 +1,70 @@
+// Warming handlers up during a loading screen: every handler of an opted-in
+// event type is called once with a synthetic event, so lazy statics, script
+// JITs and allocator pools behind it are initialised before gameplay needs
+// them. Handlers that take an `EventContext` can recognise the stand-in
+// with `is_synthetic` and skip their side effects; plain handlers can't, so
+// only opt in types whose handlers tolerate a default event.
+
+use std::any::TypeId;
+use std::time::{Duration, Instant};
+
+use super::snapshot::BusLock;
+use super::{Event, EventContext, EventMeta, SharedEventBus};
+
+/// An event type that can produce a harmless stand-in for warming up.
+pub trait DefaultEvent: Event + Sized + 'static {
+    fn default_event() -> Self;
+}
+
+/// What `warm_up_all` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WarmUpReport {
+    pub handlers_called: usize,
+    pub elapsed: Duration,
+}
+
+pub(crate) type WarmUp = fn(&BusLock) -> usize;
+
+/// Calls every handler of `E` once, whatever its phase, and returns how
+/// many ran. Like any delivery, the handlers run with the bus unlocked.
+fn warm_up_type<E: DefaultEvent>(bus: &BusLock) -> usize {
+    let Ok(list) = bus.lock().map(|bus| bus.handlers.get(&TypeId::of::<E>()).cloned().unwrap_or_default()) else {
+        return 0;
+    };
+    let ev = E::default_event();
+    let meta = EventMeta { synthetic: true, ..EventMeta::now(None) };
+    let mut called = 0;
+    for h in list.iter().filter(|h| !h.is_retired()) {
+        h.handle(&ev, &mut EventContext { stopped: false, meta });
+        called += 1;
+    }
+    called
+}
+
+impl SharedEventBus {
+    /// Adds `E` to the types `warm_up_all` touches.
+    pub fn register_warm_up<E: DefaultEvent>(&self) {
+        if let Ok(mut bus) = self.inner.lock() {
+            let tid = TypeId::of::<E>();
+            if !bus.warmups.iter().any(|(t, _)| *t == tid) {
+                bus.warmups.push((tid, warm_up_type::<E>));
+            }
+        }
+    }
+
+    /// Calls every handler of `E` once with `E::default_event()`. Returns
+    /// how many handlers ran.
+    pub fn warm_up<E: DefaultEvent>(&self) -> usize {
+        warm_up_type::<E>(&self.inner)
+    }
+
+    /// Warms up every type registered with `register_warm_up`.
+    pub fn warm_up_all(&self) -> WarmUpReport {
+        let started = Instant::now();
+        let warmups = self.inner.lock()
+            .map(|bus| bus.warmups.iter().map(|(_, warm)| *warm).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let handlers_called = warmups.iter().map(|warm| warm(&self.inner)).sum();
+        WarmUpReport { handlers_called, elapsed: started.elapsed() }
+    }
+}
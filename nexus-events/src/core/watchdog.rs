@@ -0,0 +1,93 @@
+// Spotting handlers that eat the frame budget. With a time budget set,
+// every handler call the bus makes is checked against it afterwards (it is
+// never interrupted); a call that ran over is reported to the callback
+// given with the budget, or else as a `HandlerOverBudget` event, queued
+// for the next `process`. Handlers of `HandlerOverBudget` itself are not
+// watched, so a slow one can't keep reporting itself.
+
+use std::any::TypeId;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::backpressure::Inbox;
+use super::{Event, EventMeta, HandlerId, Queued, SharedEventBus};
+
+/// A handler call took longer than the bus's handler time budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerOverBudget {
+    pub handler: HandlerId,
+    /// The type name of the event it was handling.
+    pub event: &'static str,
+    pub elapsed: Duration,
+    pub budget: Duration,
+}
+
+type OverBudgetCallback = Arc<dyn Fn(&HandlerOverBudget) + Send + Sync>;
+
+#[derive(Clone)]
+enum Report {
+    Callback(OverBudgetCallback),
+    // The bus's inbox.
+    Dispatch(Inbox),
+}
+
+#[derive(Clone)]
+pub(crate) struct Watchdog {
+    budget: Duration,
+    report: Report,
+}
+
+impl Watchdog {
+    /// Reports the call of `handler` for `ev` if it took over budget.
+    pub(crate) fn check(&self, handler: u64, ev: &dyn Event, elapsed: Duration) {
+        if elapsed <= self.budget || ev.as_any().type_id() == TypeId::of::<HandlerOverBudget>() {
+            return;
+        }
+        let over = HandlerOverBudget {
+            handler: HandlerId(handler),
+            event: ev.event_name(),
+            elapsed,
+            budget: self.budget,
+        };
+        match &self.report {
+            Report::Callback(callback) => callback(&over),
+            Report::Dispatch(inbox) => {
+                inbox.send(Queued { ev: Box::new(over), meta: EventMeta::now(None) });
+            }
+        }
+    }
+}
+
+impl SharedEventBus {
+    /// Dispatches a `HandlerOverBudget` for every handler call that takes
+    /// longer than `budget`.
+    pub fn set_handler_time_budget(&self, budget: Duration) {
+        if let Ok(mut bus) = self.inner.lock() {
+            let report = Report::Dispatch(bus.inbox_tx.clone());
+            bus.watchdog = Some(Watchdog { budget, report });
+        }
+    }
+
+    /// Calls `callback` for every handler call that takes longer than
+    /// `budget`, instead of dispatching `HandlerOverBudget`. It runs right
+    /// after the call, on the thread that made it, with the bus unlocked.
+    pub fn set_handler_time_budget_with<F>(&self, budget: Duration, callback: F)
+    where
+        F: Fn(&HandlerOverBudget) + Send + Sync + 'static
+    {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.watchdog = Some(Watchdog { budget, report: Report::Callback(Arc::new(callback)) });
+        }
+    }
+
+    /// Stops checking handler calls against a time budget.
+    pub fn clear_handler_time_budget(&self) {
+        if let Ok(mut bus) = self.inner.lock() {
+            bus.watchdog = None;
+        }
+    }
+
+    pub fn handler_time_budget(&self) -> Option<Duration> {
+        self.inner.lock().ok().and_then(|bus| bus.watchdog.as_ref().map(|w| w.budget))
+    }
+}
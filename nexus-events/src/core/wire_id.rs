@@ -0,0 +1,30 @@
+// Compact numeric ids for event names, computed at compile time. A network
+// bridge can send the id instead of the name string; since the id is a hash
+// of the name alone, it is the same in every build that uses that name.
+
+use super::contract::fnv1a_from;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// 64-bit FNV-1a hash of `name`.
+pub const fn wire_id(name: &str) -> u64 {
+    fnv1a_from(FNV_OFFSET, name.as_bytes())
+}
+
+/// `wire_id` folded to 32 bits, for protocols where every byte counts. Ids
+/// of different names collide more easily at this width.
+pub const fn wire_id32(name: &str) -> u32 {
+    let id = wire_id(name);
+    (id ^ (id >> 32)) as u32
+}
+
+/// Implemented by `define_event!` for every event it declares.
+pub trait HasWireId {
+    /// The name the id is derived from (the bare struct name).
+    const WIRE_NAME: &'static str;
+    const WIRE_ID: u64 = wire_id(Self::WIRE_NAME);
+    const WIRE_ID32: u32 = wire_id32(Self::WIRE_NAME);
+    /// Schema version, set with `#[version(N)]` in `define_event!`. Codecs
+    /// record it with every payload and migrate older ones on decode.
+    const WIRE_VERSION: u32 = 1;
+}
@@ -0,0 +1,321 @@
+// `define_event!` declares event structs and enums with the derives every
+// event wants (`Debug`, `Clone`), plus `Serialize`/`Deserialize` when
+// nexus-events is built with the `serde` feature. Each non-generic one is
+// registered by name with `EventTypeRegistry`, gets a compile-time
+// `HasWireId` (carrying its schema version) and a dense `EventTypeId`, and
+// is linked to its category, if it names one, to its validator, if it is
+// declared `#[validate]`, and to its `EventClass`, if it is declared with
+// `#[class(..)]`. All of them are also listed, with their fields,
+// in `catalog`.
+
+/// Declares one or more event structs or enums.
+///
+/// ```ignore
+/// define_event! {
+///     pub struct PlayerMoved {
+///         /// World units.
+///         pub x: f32,
+///         pub y: f32,
+///     }
+///     pub struct PlayerDied;
+///     pub enum InputEvent {
+///         KeyDown { code: u32 },
+///         KeyUp { code: u32 },
+///     }
+///     pub struct ValueChanged<T: PartialEq + std::fmt::Debug> { pub old: T, pub new: T }
+///     pub struct DamageDealt { pub amount: u32 } : CombatEvent
+///     pub struct UnitDied: CombatEvent;
+///     #[version(3)]
+///     pub struct SaveSlotWritten { pub slot: u8, pub checksum: u64 }
+///     #[validate]
+///     pub struct HealthChanged { pub health: i32 }
+/// }
+/// ```
+///
+/// Don't add `Debug` or `Clone` yourself; extra attributes (other derives,
+/// doc comments) are passed through.
+///
+/// `: Category` after an event puts it in that category, so handlers
+/// registered with `subscribe_category::<Category>` receive it too. The
+/// category is any type, typically a unit struct that is never dispatched.
+///
+/// `#[version(N)]`, if present, must be the first attribute of an event. It
+/// sets `HasWireId::WIRE_VERSION` (1 otherwise), which `EventCodec` records
+/// with every payload; register `migrate_from` functions for the older
+/// versions still found in save files.
+///
+/// `#[validate]`, also before any other attribute (next to `#[version]` in
+/// either order), has the bus check the event with its `Validate` impl,
+/// which you write yourself, whenever it is published.
+///
+/// `#[class(telemetry)]` (or `control`, or `gameplay`, the default), also
+/// up front with the other two, puts the event in that `EventClass`: it
+/// waits in that class's queue, processed in the class's turn and bounded
+/// by its `ClassLimit` (see `BusConfig`).
+///
+/// Every event is listed in `catalog::all()` with its name, fields and
+/// `TypeId`, for tools that need to know which events exist.
+///
+/// Generic parameters take plain trait bounds (`T: Copy + fmt::Display`).
+/// A generic event has no single name or wire id, so register each
+/// instantiation you need with `EventTypeRegistry::register` yourself; it
+/// isn't in the catalog either.
+#[macro_export]
+macro_rules! define_event {
+    ($($rest:tt)*) => {
+        $crate::__event_attrs! { [] [] [] $($rest)* }
+    };
+}
+
+/// Takes `#[version(N)]`, `#[validate]` and `#[class(..)]`, in any order,
+/// off the front of the next item. Each is taken once; a repeated one is
+/// left on the item, where it doesn't compile.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_attrs {
+    ([] [] []) => {};
+    ([] [$($validate:ident)?] [$($class:ident)?] #[version($version:literal)] $($rest:tt)*) => {
+        $crate::__event_attrs! { [$version] [$($validate)?] [$($class)?] $($rest)* }
+    };
+    ([$($version:literal)?] [] [$($class:ident)?] #[validate] $($rest:tt)*) => {
+        $crate::__event_attrs! { [$($version)?] [validate] [$($class)?] $($rest)* }
+    };
+    ([$($version:literal)?] [$($validate:ident)?] [] #[class($class:ident)] $($rest:tt)*) => {
+        $crate::__event_attrs! { [$($version)?] [$($validate)?] [$class] $($rest)* }
+    };
+    ([$($version:literal)?] [$($validate:ident)?] [$($class:ident)?] $($rest:tt)+) => {
+        $crate::__event_def! { [$($version)?] [$($validate)?] [$($class)?] $($rest)* }
+    };
+}
+
+/// One `define_event!` item, with its version and `validate` if it
+/// declared them.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_def {
+    (
+        [$($version:literal)?] [$($validate:ident)?] [$($class:ident)?]
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident
+            $(< $($gen:ident $(: $($bound:ident)::+ $(+ $($more:ident)::+)*)?),+ $(,)? >)?
+        {
+            $($(#[$fmeta:meta])* $fvis:vis $field:ident : $ty:ty),* $(,)?
+        }
+        $($rest:tt)*
+    ) => {
+        $crate::__event_item! {
+            $(#[$meta])*
+            $vis struct $name
+                $(< $($gen $(: $($bound)::+ $(+ $($more)::+)*)?),+ >)?
+            {
+                $($(#[$fmeta])* $fvis $field: $ty),*
+            }
+        }
+        $crate::__event_register! { [$($version)?] [$($validate)?] $name $(< $($gen),+ >)? }
+        $crate::__event_class! { [$($class)?] $name $(< $($gen),+ >)? }
+        $crate::__event_describe! { $name $(< $($gen),+ >)? { $($field: $ty),* } }
+        $crate::__event_tail! { [$name $(< $($gen),+ >)?] $($rest)* }
+    };
+    (
+        [$($version:literal)?] [$($validate:ident)?] [$($class:ident)?]
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident $(: $($category:ident)::+)?;
+        $($rest:tt)*
+    ) => {
+        $crate::__event_item! {
+            $(#[$meta])*
+            $vis struct $name;
+        }
+        $crate::__event_register! { [$($version)?] [$($validate)?] $name }
+        $crate::__event_class! { [$($class)?] $name }
+        $crate::__event_describe! { $name {} }
+        $crate::__event_tail! { [$name] $(: $($category)::+)? $($rest)* }
+    };
+    (
+        [$($version:literal)?] [$($validate:ident)?] [$($class:ident)?]
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident
+            $(< $($gen:ident $(: $($bound:ident)::+ $(+ $($more:ident)::+)*)?),+ $(,)? >)?
+        {
+            $($variants:tt)*
+        }
+        $($rest:tt)*
+    ) => {
+        $crate::__event_item! {
+            $(#[$meta])*
+            $vis enum $name
+                $(< $($gen $(: $($bound)::+ $(+ $($more)::+)*)?),+ >)?
+            {
+                $($variants)*
+            }
+        }
+        $crate::__event_register! { [$($version)?] [$($validate)?] $name $(< $($gen),+ >)? }
+        $crate::__event_class! { [$($class)?] $name $(< $($gen),+ >)? }
+        $crate::__event_describe! { $name $(< $($gen),+ >)? enum { $($variants)* } }
+        $crate::__event_tail! { [$name $(< $($gen),+ >)?] $($rest)* }
+    };
+}
+
+/// Name registration, wire id, version, dense id and validator for a
+/// `define_event!` type. Generic types are skipped: every instantiation
+/// would claim the same name.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_register {
+    ([$($version:literal)?] [$($validate:ident)?] $name:ident) => {
+        $crate::__private::inventory::submit! {
+            $crate::core::EventRegistration::new::<$name>(stringify!($name))
+        }
+        impl $crate::core::HasWireId for $name {
+            const WIRE_NAME: &'static str = stringify!($name);
+            $(const WIRE_VERSION: u32 = $version;)?
+        }
+        impl $crate::core::EventType for $name {
+            fn event_type_id() -> ::core::option::Option<$crate::core::EventTypeId> {
+                static ID: $crate::core::EventTypeIdCell = $crate::core::EventTypeIdCell::new();
+                ID.get::<Self>()
+            }
+        }
+        $($crate::__event_validator! { $validate $name })?
+    };
+    ([] [] $name:ident < $($gen:ident),+ >) => {};
+    ([$version:literal] [$($validate:ident)?] $name:ident < $($gen:ident),+ >) => {
+        compile_error!(concat!(
+            "generic event `", stringify!($name), "` has no wire name to version; ",
+            "register each instantiation with `EventCodec::register_versioned` instead"
+        ));
+    };
+    ([] [validate] $name:ident < $($gen:ident),+ >) => {
+        compile_error!(concat!(
+            "generic event `", stringify!($name), "` can't be validated here; ",
+            "call `validate_as` for each instantiation instead"
+        ));
+    };
+}
+
+/// Adds a `define_event!` type and its fields to the catalog. Generic
+/// types are left out, like in `__event_register!`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_describe {
+    ($name:ident { $($field:ident : $ty:ty),* }) => {
+        $crate::__private::inventory::submit! {
+            $crate::catalog::CatalogEntry::new::<$name>(stringify!($name), &[
+                $($crate::catalog::FieldDescriptor { name: stringify!($field), ty: stringify!($ty) }),*
+            ], "")
+        }
+    };
+    ($name:ident enum { $($variants:tt)* }) => {
+        $crate::__private::inventory::submit! {
+            $crate::catalog::CatalogEntry::new::<$name>(stringify!($name), &[], stringify!($($variants)*))
+        }
+    };
+    ($name:ident < $($gen:ident),+ > $(enum)? { $($body:tt)* }) => {};
+}
+
+/// Links a `#[validate]` event to its `Validate` impl.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_validator {
+    (validate $name:ident) => {
+        $crate::__private::inventory::submit! {
+            $crate::core::ValidatorLink::new::<$name>()
+        }
+    };
+}
+
+/// Takes the optional `: Category` path off what follows an event's body,
+/// one segment at a time (a `$path` fragment can't be followed by more
+/// items), then carries on with the remaining items.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_tail {
+    (@path [$($head:tt)*] [$($path:tt)*] :: $seg:ident $($rest:tt)*) => {
+        $crate::__event_tail! { @path [$($head)*] [$($path)* :: $seg] $($rest)* }
+    };
+    (@path [$($head:tt)*] [$($path:tt)*] $($rest:tt)*) => {
+        $crate::__event_category! { $($head)* : $($path)* }
+        $crate::__event_attrs! { [] [] [] $($rest)* }
+    };
+    ([$($head:tt)*] : $seg:ident $($rest:tt)*) => {
+        $crate::__event_tail! { @path [$($head)*] [$seg] $($rest)* }
+    };
+    ([$($head:tt)*] $($rest:tt)*) => {
+        $crate::__event_attrs! { [] [] [] $($rest)* }
+    };
+}
+
+/// Puts a `define_event!` type in the class it was declared with.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_class {
+    ([] $($name:tt)*) => {};
+    ([control] $name:ident) => {
+        $crate::__event_class! { @link $name Control }
+    };
+    ([gameplay] $name:ident) => {
+        $crate::__event_class! { @link $name Gameplay }
+    };
+    ([telemetry] $name:ident) => {
+        $crate::__event_class! { @link $name Telemetry }
+    };
+    (@link $name:ident $class:ident) => {
+        $crate::__private::inventory::submit! {
+            $crate::core::ClassLink::new::<$name>($crate::core::EventClass::$class)
+        }
+    };
+    ([$class:ident] $name:ident < $($gen:ident),+ >) => {
+        compile_error!(concat!(
+            "generic event `", stringify!($name), "` can't take a class here; ",
+            "call `classify_as` for each instantiation instead"
+        ));
+    };
+    ([$class:ident] $name:ident) => {
+        compile_error!(concat!(
+            "unknown event class `", stringify!($class), "`; expected `control`, `gameplay` or `telemetry`"
+        ));
+    };
+}
+
+/// Links a `define_event!` type to its category.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_category {
+    ($name:ident : $($category:ident)::+) => {
+        $crate::__private::inventory::submit! {
+            $crate::core::CategoryLink::new::<$name, $($category)::+>()
+        }
+    };
+    ($name:ident < $($gen:ident),+ > : $($category:ident)::+) => {
+        compile_error!(concat!(
+            "generic event `", stringify!($name), "` can't take a category here; ",
+            "call `categorize_as` for each instantiation instead"
+        ));
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_item {
+    ($item:item) => {
+        #[derive(Debug, Clone)]
+        $item
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __event_item {
+    ($item:item) => {
+        #[derive(
+            Debug,
+            Clone,
+            $crate::__private::serde::Serialize,
+            $crate::__private::serde::Deserialize,
+        )]
+        #[serde(crate = "::nexus_events::__private::serde")]
+        $item
+    };
+}
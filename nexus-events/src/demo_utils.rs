@@ -0,0 +1,270 @@
+//! Live metrics and load generation, as used by the demo app (the
+//! `demo-utils` feature).
+//!
+//! `MetricsTracker` counts events, handler calls and frame times;
+//! `MetricsPanel` turns it into the numbers the demo's stats header shows,
+//! so any renderer (tui, egui, an in-game overlay) can draw the same panel.
+//! `LogPanel` is a bounded scrollback and `LoadGenerator` dispatches events
+//! at a fixed rate to see how a game holds up under load.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::core::{Event, SharedEventBus};
+
+/// Frame times kept for the moving average.
+const FRAME_WINDOW: usize = 120;
+/// Lines kept in the tracker's combined log.
+const LOG_WINDOW: usize = 16;
+
+/// Counters for events, handler calls and frame times since creation.
+#[derive(Debug)]
+pub struct MetricsTracker {
+    logs: Vec<String>,
+    events_processed: usize,
+    events_by_type: HashMap<String, usize>,
+    handler_calls: usize,
+    frame_times: Vec<Duration>,
+    max_frame_time: Duration,
+    min_frame_time: Duration,
+    start_time: Instant,
+    event_components: usize,
+}
+
+impl Default for MetricsTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsTracker {
+    pub fn new() -> Self {
+        Self {
+            logs: Vec::with_capacity(LOG_WINDOW),
+            events_processed: 0,
+            events_by_type: HashMap::new(),
+            handler_calls: 0,
+            frame_times: Vec::with_capacity(FRAME_WINDOW),
+            max_frame_time: Duration::ZERO,
+            min_frame_time: Duration::from_secs(1),
+            start_time: Instant::now(),
+            event_components: 0,
+        }
+    }
+
+    pub fn record_event(&mut self, event_type: &str) {
+        self.events_processed += 1;
+        *self.events_by_type.entry(event_type.to_string()).or_insert(0) += 1;
+    }
+
+    pub fn record_handler_call(&mut self) {
+        self.handler_calls += 1;
+    }
+
+    pub fn add_frame_time(&mut self, dt: Duration) {
+        self.frame_times.push(dt);
+        if self.frame_times.len() > FRAME_WINDOW {
+            self.frame_times.remove(0);
+        }
+        self.max_frame_time = self.max_frame_time.max(dt);
+        self.min_frame_time = self.min_frame_time.min(dt);
+    }
+
+    /// Sets the number of live event components shown on the panel.
+    pub fn set_components(&mut self, count: usize) {
+        self.event_components = count;
+    }
+
+    /// Average over the last 120 frames.
+    pub fn average_frame_time(&self) -> Duration {
+        if self.frame_times.is_empty() {
+            return Duration::ZERO;
+        }
+        let total: Duration = self.frame_times.iter().sum();
+        total / self.frame_times.len() as u32
+    }
+
+    pub fn min_frame_time(&self) -> Duration {
+        self.min_frame_time
+    }
+
+    pub fn max_frame_time(&self) -> Duration {
+        self.max_frame_time
+    }
+
+    pub fn runtime(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    pub fn events_processed(&self) -> usize {
+        self.events_processed
+    }
+
+    pub fn handler_calls(&self) -> usize {
+        self.handler_calls
+    }
+
+    /// Events recorded so far, by the name they were recorded under.
+    pub fn events_by_type(&self) -> &HashMap<String, usize> {
+        &self.events_by_type
+    }
+
+    pub fn push_log(&mut self, line: String) {
+        if self.logs.len() >= LOG_WINDOW {
+            self.logs.remove(0);
+        }
+        self.logs.push(line);
+    }
+
+    pub fn logs(&self) -> &[String] {
+        &self.logs
+    }
+
+    /// The numbers for a stats panel, as of now.
+    pub fn panel(&self) -> MetricsPanel {
+        let runtime = self.runtime().as_secs_f32();
+        let avg = self.average_frame_time();
+        MetricsPanel {
+            runtime_secs: runtime,
+            fps: 1.0 / avg.as_secs_f32().max(0.000001),
+            avg_frame_ms: avg.as_micros() as f32 / 1000.0,
+            min_frame_ms: self.min_frame_time.as_micros() as f32 / 1000.0,
+            max_frame_ms: self.max_frame_time.as_micros() as f32 / 1000.0,
+            events_total: self.events_processed,
+            events_per_sec: self.events_processed as f32 / runtime.max(0.001),
+            handler_calls_total: self.handler_calls,
+            handler_calls_per_sec: self.handler_calls as f32 / runtime.max(0.001),
+            components: self.event_components,
+        }
+    }
+}
+
+/// What the live stats panel shows, independent of how it is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MetricsPanel {
+    pub runtime_secs: f32,
+    pub fps: f32,
+    pub avg_frame_ms: f32,
+    pub min_frame_ms: f32,
+    pub max_frame_ms: f32,
+    pub events_total: usize,
+    pub events_per_sec: f32,
+    pub handler_calls_total: usize,
+    pub handler_calls_per_sec: f32,
+    pub components: usize,
+}
+
+impl MetricsPanel {
+    /// The panel as the two text lines the demo app prints.
+    pub fn lines(&self) -> [String; 2] {
+        [
+            format!(
+                "Runtime: {:.1}s | FPS: {:.1} | Frame: {:.2}ms (min: {:.2} max: {:.2})",
+                self.runtime_secs, self.fps, self.avg_frame_ms, self.min_frame_ms, self.max_frame_ms
+            ),
+            format!(
+                "Events: {} ({:.1}/sec) | Handler calls: {} ({:.1}/sec) | EventedComponents: {}",
+                self.events_total,
+                self.events_per_sec,
+                self.handler_calls_total,
+                self.handler_calls_per_sec,
+                self.components
+            ),
+        ]
+    }
+}
+
+/// A scrollback that keeps only the newest `capacity` lines.
+#[derive(Debug, Clone)]
+pub struct LogPanel {
+    lines: Vec<String>,
+    capacity: usize,
+}
+
+impl LogPanel {
+    pub fn new(capacity: usize) -> Self {
+        Self { lines: Vec::with_capacity(capacity), capacity }
+    }
+
+    pub fn push(&mut self, line: String) {
+        if self.lines.len() >= self.capacity {
+            self.lines.remove(0);
+        }
+        self.lines.push(line);
+    }
+
+    /// Oldest first.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+struct Source {
+    per_sec: f32,
+    owed: f32,
+    make: Box<dyn FnMut(&SharedEventBus) + Send>,
+}
+
+/// Dispatches synthetic events at fixed rates. Call `tick` once per frame;
+/// fractional events carry over, so low rates work at any frame rate.
+pub struct LoadGenerator {
+    bus: SharedEventBus,
+    sources: Vec<Source>,
+    paused: bool,
+}
+
+impl LoadGenerator {
+    /// Generates load on the global bus.
+    pub fn new() -> Self {
+        Self::with_bus(SharedEventBus::global())
+    }
+
+    pub fn with_bus(bus: SharedEventBus) -> Self {
+        Self { bus, sources: Vec::new(), paused: false }
+    }
+
+    /// Dispatches `per_sec` events a second, each made by `make`.
+    pub fn add<E: Event + 'static, F>(&mut self, per_sec: f32, mut make: F) -> &mut Self
+    where
+        F: FnMut() -> E + Send + 'static
+    {
+        self.sources.push(Source {
+            per_sec,
+            owed: 0.0,
+            make: Box::new(move |bus: &SharedEventBus| bus.dispatch(make())),
+        });
+        self
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Dispatches whatever is due after `dt` and returns how many events
+    /// that was. Time spent paused is not made up later.
+    pub fn tick(&mut self, dt: Duration) -> usize {
+        if self.paused {
+            return 0;
+        }
+        let mut sent = 0;
+        for source in &mut self.sources {
+            source.owed += source.per_sec * dt.as_secs_f32();
+            while source.owed >= 1.0 {
+                (source.make)(&self.bus);
+                source.owed -= 1.0;
+                sent += 1;
+            }
+        }
+        sent
+    }
+}
+
+impl Default for LoadGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,604 @@
+//! Event sourcing on top of the codec: an append-only log of the events a
+//! bus processes, replay into a fresh bus, and projections that fold events
+//! into state.
+//!
+//! ```ignore
+//! let mut codec = EventCodec::new();
+//! codec.register_event::<UnitSpawned>()?.register_event::<UnitDied>()?;
+//!
+//! // while playing
+//! let store = EventStore::open("world.log", &bus, codec)?
+//!     .record::<UnitSpawned>()
+//!     .record::<UnitDied>();
+//!
+//! // after loading
+//! let fresh = SharedEventBus::new();
+//! let units = Projection::new(&fresh, 0i32)
+//!     .on(|n, _: &UnitSpawned| *n += 1)
+//!     .on(|n, _: &UnitDied| *n -= 1);
+//! eventstore::replay("world.log", &codec, &fresh)?;
+//! fresh.process();
+//! ```
+//!
+//! The log is a short header followed by one frame per event, each the
+//! event in `WireFormat::Bincode` or how it differs from the last event of
+//! its type: a frame is a tag, the type's slot (numbered as types first
+//! appear), and then either the whole encoding, the bytes that changed since
+//! that type's last event (a delta), or a count of events that each changed
+//! by the same delta as the one before (a run). A stream of `GameTick`s
+//! whose counter goes up by one thus takes a few bytes per thousand ticks.
+//! Numbers are LEB128 varints. A frame cut short (the game crashed
+//! mid-write) ends the log. Don't replay into a bus that records into the
+//! same log; every event would be written again.
+//!
+//! With the `zstd` feature, `EventStore::open_compressed` also compresses
+//! the frames in blocks of about 64 KiB, each a 32-bit little-endian length
+//! and a zstd frame; every `flush` ends a block. A log keeps the framing it
+//! was created with when opened again. `LogReader` reads either kind one
+//! event at a time, decompressing as it goes, as do `read_log` and
+//! `replay`. Logs written before deltas (`NXEVLOG1`, a 32-bit length and
+//! the whole encoding per event) are still read, and appended to in their
+//! own format.
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::core::codec::{DecodedEvent, EventCodec, WireFormat};
+use crate::core::{Event, EventError, EventResult, EventResultExt, SharedEventBus, SubscriptionSet};
+
+const MAGIC: &[u8; 8] = b"NXEVLOG2";
+const MAGIC_V1: &[u8; 8] = b"NXEVLOG1";
+
+/// Header flag: the frames are compressed in zstd blocks.
+const ZSTD: u8 = 1;
+#[cfg(feature = "zstd")]
+const BLOCK_SIZE: usize = 64 * 1024;
+
+// Frame tags.
+const FULL: u8 = 0;
+const DELTA: u8 = 1;
+const RUN: u8 = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Framing {
+    V1,
+    Plain,
+    #[cfg(feature = "zstd")]
+    Zstd(i32),
+}
+
+impl Framing {
+    fn flags(self) -> u8 {
+        #[cfg(feature = "zstd")]
+        if let Framing::Zstd(_) = self {
+            return ZSTD;
+        }
+        0
+    }
+}
+
+/// Reads the header of the log at `path` from `file`: how its frames are
+/// written.
+fn read_header(file: &mut impl Read, path: &Path) -> EventResult<Framing> {
+    let not_a_log = || EventError::Store(format!("{} is not an event log", path.display()));
+    let mut magic = [0; MAGIC.len()];
+    file.read_exact(&mut magic).map_err(|_| not_a_log())?;
+    if &magic == MAGIC_V1 {
+        return Ok(Framing::V1);
+    }
+    let mut flags = [0];
+    if &magic != MAGIC || file.read_exact(&mut flags).is_err() {
+        return Err(not_a_log());
+    }
+    match flags[0] {
+        0 => Ok(Framing::Plain),
+        #[cfg(feature = "zstd")]
+        ZSTD => Ok(Framing::Zstd(zstd::DEFAULT_COMPRESSION_LEVEL)),
+        #[cfg(not(feature = "zstd"))]
+        ZSTD => Err(EventError::Store(format!("{} is compressed; reading it needs the `zstd` feature", path.display()))),
+        _ => Err(not_a_log()),
+    }
+}
+
+struct Log {
+    writer: Mutex<Writer>,
+    codec: EventCodec,
+    failures: AtomicU64,
+}
+
+impl Log {
+    fn append(&self, ev: &dyn Event) -> EventResult<()> {
+        let bytes = self.codec.encode(ev, WireFormat::Bincode)?;
+        if u32::try_from(bytes.len()).is_err() {
+            return Err(EventError::Store(format!("`{}` encodes to more than 4 GiB", ev.event_name())));
+        }
+        let mut writer = self.writer.lock().map_err(|_| EventError::BusPoisoned)?;
+        writer.append(ev.as_any().type_id(), bytes)?;
+        Ok(())
+    }
+}
+
+/// What a type's last event was written as, to write the next as a delta.
+struct Slot {
+    index: u64,
+    last: Vec<u8>,
+    /// The delta that produced `last`, if it was written as one.
+    delta: Option<Vec<u8>>,
+}
+
+struct Writer {
+    file: BufWriter<File>,
+    framing: Framing,
+    slots: HashMap<TypeId, Slot>,
+    /// A slot and how many of its events repeated its delta, not written
+    /// yet.
+    run: Option<(u64, u64)>,
+    /// Frames waiting to be compressed.
+    #[cfg(feature = "zstd")]
+    block: Vec<u8>,
+}
+
+impl Writer {
+    fn append(&mut self, tid: TypeId, bytes: Vec<u8>) -> io::Result<()> {
+        if self.framing == Framing::V1 {
+            self.file.write_all(&(bytes.len() as u32).to_le_bytes())?;
+            return self.file.write_all(&bytes);
+        }
+        let index = self.slots.len() as u64;
+        let slot = self.slots.entry(tid).or_insert(Slot { index, last: Vec::new(), delta: None });
+        let index = slot.index;
+        let delta = (slot.last.len() == bytes.len()).then(|| diff(&slot.last, &bytes));
+        let frame = match delta {
+            Some(delta) if slot.delta.as_ref() == Some(&delta) => None,
+            Some(delta) if delta.len() < bytes.len() => {
+                let frame = frame(DELTA, index, &delta);
+                slot.delta = Some(delta);
+                Some(frame)
+            }
+            _ => {
+                slot.delta = None;
+                Some(frame(FULL, index, &bytes))
+            }
+        };
+        slot.last = bytes;
+        match (frame, &mut self.run) {
+            (None, Some((slot, count))) if *slot == index => {
+                *count += 1;
+                Ok(())
+            }
+            (None, _) => {
+                self.end_run()?;
+                self.run = Some((index, 1));
+                Ok(())
+            }
+            (Some(frame), _) => {
+                self.end_run()?;
+                self.write(&frame)
+            }
+        }
+    }
+
+    fn end_run(&mut self) -> io::Result<()> {
+        let Some((index, count)) = self.run.take() else { return Ok(()) };
+        let mut frame = vec![RUN];
+        put_varint(&mut frame, index);
+        put_varint(&mut frame, count);
+        self.write(&frame)
+    }
+
+    fn write(&mut self, frame: &[u8]) -> io::Result<()> {
+        #[cfg(feature = "zstd")]
+        if let Framing::Zstd(_) = self.framing {
+            self.block.extend_from_slice(frame);
+            if self.block.len() >= BLOCK_SIZE {
+                self.write_block()?;
+            }
+            return Ok(());
+        }
+        self.file.write_all(frame)
+    }
+
+    #[cfg(feature = "zstd")]
+    fn write_block(&mut self) -> io::Result<()> {
+        let Framing::Zstd(level) = self.framing else { return Ok(()) };
+        if self.block.is_empty() {
+            return Ok(());
+        }
+        let compressed = zstd::bulk::compress(&self.block, level)?;
+        let len = u32::try_from(compressed.len()).map_err(|_| io::Error::other("block over 4 GiB"))?;
+        self.file.write_all(&len.to_le_bytes())?;
+        self.file.write_all(&compressed)?;
+        self.block.clear();
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.end_run()?;
+        #[cfg(feature = "zstd")]
+        self.write_block()?;
+        self.file.flush()
+    }
+}
+
+impl Drop for Writer {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+fn frame(tag: u8, index: u64, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![tag];
+    put_varint(&mut frame, index);
+    put_varint(&mut frame, payload.len() as u64);
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// The bytes that differ between two encodings of the same length: how
+/// many, then for each the distance from the one before and what was added
+/// to it.
+fn diff(old: &[u8], new: &[u8]) -> Vec<u8> {
+    let changed: Vec<(usize, u8)> = old.iter().zip(new).enumerate()
+        .filter(|(_, (old, new))| old != new)
+        .map(|(i, (old, new))| (i, new.wrapping_sub(*old)))
+        .collect();
+    let mut delta = Vec::new();
+    put_varint(&mut delta, changed.len() as u64);
+    let mut at = 0;
+    for (i, change) in changed {
+        put_varint(&mut delta, (i - at) as u64);
+        delta.push(change);
+        at = i + 1;
+    }
+    delta
+}
+
+/// Applies a delta from `diff` to `bytes`.
+fn patch(bytes: &mut [u8], mut delta: &[u8]) -> io::Result<()> {
+    let mut at = 0;
+    for _ in 0..read_varint(&mut delta)? {
+        at += read_varint(&mut delta)? as usize;
+        let mut change = [0];
+        delta.read_exact(&mut change)?;
+        let byte = bytes.get_mut(at).ok_or_else(corrupt)?;
+        *byte = byte.wrapping_add(change[0]);
+        at += 1;
+    }
+    Ok(())
+}
+
+fn put_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn read_varint(r: &mut impl Read) -> io::Result<u64> {
+    let mut n = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        r.read_exact(&mut byte)?;
+        n |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] < 0x80 {
+            return Ok(n);
+        }
+    }
+    Err(corrupt())
+}
+
+/// The next `len` bytes; `UnexpectedEof` if there aren't that many.
+fn read_bytes(r: &mut impl Read, len: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    r.take(len).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < len {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+fn corrupt() -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, "corrupt frame")
+}
+
+/// Appends the chosen event types to a log file as a bus processes them,
+/// for as long as it lives. Writes are buffered; `flush` (or dropping the
+/// store) puts them on disk.
+pub struct EventStore {
+    log: Arc<Log>,
+    subscriptions: SubscriptionSet,
+}
+
+impl EventStore {
+    /// Opens the log at `path`, creating it if needed, to append the events
+    /// `bus` processes. Every recorded type must be registered in `codec`.
+    pub fn open(path: impl AsRef<Path>, bus: &SharedEventBus, codec: EventCodec) -> EventResult<Self> {
+        Self::open_framed(path.as_ref(), bus, codec, Framing::Plain)
+    }
+
+    /// Like `open`, compressing a new log with zstd at `level` (1 to 22; 3
+    /// is zstd's default). An existing log keeps its own framing.
+    #[cfg(feature = "zstd")]
+    pub fn open_compressed(path: impl AsRef<Path>, bus: &SharedEventBus, codec: EventCodec, level: i32) -> EventResult<Self> {
+        Self::open_framed(path.as_ref(), bus, codec, Framing::Zstd(level))
+    }
+
+    fn open_framed(path: &Path, bus: &SharedEventBus, codec: EventCodec, framing: Framing) -> EventResult<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)
+            .with_context(|| format!("opening {}", path.display()))?;
+        let framing = if file.metadata()?.len() == 0 {
+            file.write_all(MAGIC).and_then(|_| file.write_all(&[framing.flags()]))?;
+            framing
+        } else {
+            file.seek(SeekFrom::Start(0))?;
+            match (read_header(&mut file, path)?, framing) {
+                #[cfg(feature = "zstd")]
+                (Framing::Zstd(_), Framing::Zstd(level)) => Framing::Zstd(level),
+                (found, _) => found,
+            }
+        };
+        let writer = Writer {
+            file: BufWriter::new(file),
+            framing,
+            slots: HashMap::new(),
+            run: None,
+            #[cfg(feature = "zstd")]
+            block: Vec::new(),
+        };
+        let log = Log { writer: Mutex::new(writer), codec, failures: AtomicU64::new(0) };
+        Ok(Self { log: Arc::new(log), subscriptions: SubscriptionSet::new(bus) })
+    }
+
+    /// Appends every `E` the bus processes.
+    pub fn record<E: Event + 'static>(mut self) -> Self {
+        let log = self.log.clone();
+        self.subscriptions.subscribe::<E, _>(move |ev: &E| {
+            if log.append(ev).is_err() {
+                log.failures.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+        self
+    }
+
+    /// Appends `ev` directly, whatever its type, as long as the codec knows
+    /// it.
+    pub fn append(&self, ev: &dyn Event) -> EventResult<()> {
+        self.log.append(ev)
+    }
+
+    /// Writes out everything appended so far, ending the current run and,
+    /// with zstd, the current block.
+    pub fn flush(&self) -> EventResult<()> {
+        let mut writer = self.log.writer.lock().map_err(|_| EventError::BusPoisoned)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Recorded events that couldn't be written (encoding or I/O failed).
+    pub fn failures(&self) -> u64 {
+        self.log.failures.load(Ordering::Relaxed)
+    }
+
+    pub fn codec(&self) -> &EventCodec {
+        &self.log.codec
+    }
+}
+
+impl Drop for EventStore {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Reads a log one event at a time, oldest first, so replaying an
+/// hour-long session doesn't load all of it. Ends after the first error.
+pub struct LogReader<'a> {
+    frames: Box<dyn Read + Send>,
+    codec: &'a EventCodec,
+    framing: Framing,
+    path: PathBuf,
+    /// Each slot's last encoding, and the delta that produced it.
+    slots: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+    /// A slot and how many more events its run has.
+    run: Option<(usize, u64)>,
+    read: usize,
+    done: bool,
+}
+
+impl<'a> LogReader<'a> {
+    pub fn open(path: impl AsRef<Path>, codec: &'a EventCodec) -> EventResult<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("reading {}", path.display()))?;
+        let mut file = BufReader::new(file);
+        let framing = read_header(&mut file, path)?;
+        let frames: Box<dyn Read + Send> = match framing {
+            #[cfg(feature = "zstd")]
+            Framing::Zstd(_) => Box::new(Blocks { file, block: Vec::new(), at: 0 }),
+            _ => Box::new(file),
+        };
+        Ok(Self {
+            frames,
+            codec,
+            framing,
+            path: path.to_path_buf(),
+            slots: Vec::new(),
+            run: None,
+            read: 0,
+            done: false,
+        })
+    }
+
+    /// How many events were read so far.
+    pub fn position(&self) -> usize {
+        self.read
+    }
+
+    /// The encoding of the next event.
+    fn next_encoded(&mut self) -> io::Result<Vec<u8>> {
+        if let Some((index, left)) = self.run {
+            self.run = (left > 1).then_some((index, left - 1));
+            return self.repeat(index);
+        }
+        let mut tag = [0];
+        self.frames.read_exact(&mut tag)?;
+        if self.framing == Framing::V1 {
+            // No tags: that was the first byte of the length.
+            let mut len = [tag[0], 0, 0, 0];
+            self.frames.read_exact(&mut len[1..])?;
+            return read_bytes(&mut self.frames, u32::from_le_bytes(len).into());
+        }
+        let index = read_varint(&mut self.frames)? as usize;
+        match tag[0] {
+            FULL => {
+                let len = read_varint(&mut self.frames)?;
+                let bytes = read_bytes(&mut self.frames, len)?;
+                // A store opened on the log again numbers types from 0.
+                match index.cmp(&self.slots.len()) {
+                    std::cmp::Ordering::Less => self.slots[index] = (bytes.clone(), None),
+                    std::cmp::Ordering::Equal => self.slots.push((bytes.clone(), None)),
+                    std::cmp::Ordering::Greater => return Err(corrupt()),
+                }
+                Ok(bytes)
+            }
+            DELTA => {
+                let len = read_varint(&mut self.frames)?;
+                let delta = read_bytes(&mut self.frames, len)?;
+                let (last, last_delta) = self.slots.get_mut(index).ok_or_else(corrupt)?;
+                patch(last, &delta)?;
+                *last_delta = Some(delta);
+                Ok(last.clone())
+            }
+            RUN => {
+                let count = read_varint(&mut self.frames)?;
+                if count == 0 {
+                    return Err(corrupt());
+                }
+                self.run = (count > 1).then_some((index, count - 1));
+                self.repeat(index)
+            }
+            _ => Err(corrupt()),
+        }
+    }
+
+    fn repeat(&mut self, index: usize) -> io::Result<Vec<u8>> {
+        let (last, delta) = self.slots.get_mut(index).ok_or_else(corrupt)?;
+        patch(last, delta.as_ref().ok_or_else(corrupt)?)?;
+        Ok(last.clone())
+    }
+}
+
+impl Iterator for LogReader<'_> {
+    type Item = EventResult<DecodedEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let event = match self.next_encoded() {
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                self.done = true;
+                return None;
+            }
+            Err(e) => Err(EventError::from(e)),
+            Ok(bytes) => self.codec.decode(&bytes, WireFormat::Bincode),
+        };
+        self.read += 1;
+        let event = event.with_context(|| format!("reading event {} of {}", self.read, self.path.display()));
+        self.done = event.is_err();
+        Some(event)
+    }
+}
+
+/// The frames of a zstd log, decompressed a block at a time. A block cut
+/// short ends them.
+#[cfg(feature = "zstd")]
+struct Blocks<R> {
+    file: R,
+    block: Vec<u8>,
+    at: usize,
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> Read for Blocks<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.at == self.block.len() {
+            let mut len = [0; 4];
+            let compressed = match self.file.read_exact(&mut len) {
+                Ok(()) => read_bytes(&mut self.file, u32::from_le_bytes(len).into()),
+                Err(e) => Err(e),
+            };
+            let compressed = match compressed {
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(0),
+                compressed => compressed?,
+            };
+            self.block = zstd::decode_all(&compressed[..])?;
+            self.at = 0;
+        }
+        let n = (&self.block[self.at..]).read(buf)?;
+        self.at += n;
+        Ok(n)
+    }
+}
+
+/// Decodes every event in the log at `path`, oldest first.
+pub fn read_log(path: impl AsRef<Path>, codec: &EventCodec) -> EventResult<Vec<DecodedEvent>> {
+    LogReader::open(path, codec)?.collect()
+}
+
+/// Dispatches every event in the log at `path` on `bus`, oldest first, and
+/// returns how many there were. They are delivered by the bus's next
+/// `process`. Events are read as they are dispatched, so one that can't be
+/// decoded fails the replay after those before it were dispatched.
+pub fn replay(path: impl AsRef<Path>, codec: &EventCodec, bus: &SharedEventBus) -> EventResult<usize> {
+    let mut count = 0;
+    for ev in LogReader::open(path, codec)? {
+        ev?.publish(bus);
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// State folded from events, kept up to date by handlers on a bus: replay a
+/// log into that bus to rebuild it, then keep it live from there.
+pub struct Projection<S> {
+    state: Arc<Mutex<S>>,
+    subscriptions: SubscriptionSet,
+}
+
+impl<S: Send + 'static> Projection<S> {
+    pub fn new(bus: &SharedEventBus, initial: S) -> Self {
+        Self { state: Arc::new(Mutex::new(initial)), subscriptions: SubscriptionSet::new(bus) }
+    }
+
+    /// Applies `f` to the state for every `E` the bus processes.
+    pub fn on<E: Event + 'static, F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut S, &E) + Send + Sync + 'static
+    {
+        let state = self.state.clone();
+        self.subscriptions.subscribe::<E, _>(move |ev: &E| {
+            f(&mut state.lock().unwrap_or_else(PoisonError::into_inner), ev)
+        });
+        self
+    }
+
+    pub fn read<R>(&self, f: impl FnOnce(&S) -> R) -> R {
+        f(&self.state.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    pub fn get(&self) -> S
+    where
+        S: Clone
+    {
+        self.read(S::clone)
+    }
+}
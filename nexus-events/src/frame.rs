@@ -0,0 +1,80 @@
+//! Driving a bus from a game loop, one call per frame.
+//!
+//! ```ignore
+//! let mut frames = FrameDriver::new(bus.clone());
+//! bus.subscribe(|f: &FrameStart| physics.step(f.dt));
+//! loop {
+//!     input.poll();
+//!     frames.run_frame(frame_time)?;
+//!     render();
+//! }
+//! ```
+//!
+//! A frame delivers `FrameStart` right away, then processes the queue (what
+//! was dispatched since the last frame, and what the `FrameStart` handlers
+//! dispatched), advances the bus clock so scheduled events fall due and
+//! processes those, and finally delivers `FrameEnd`. What `FrameEnd`
+//! handlers dispatch is processed in the next frame.
+
+use std::time::{Duration, Instant};
+
+use crate::core::{EventResult, SharedEventBus};
+
+/// Delivered at the start of every frame, before the queue is processed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameStart {
+    /// The time this frame covers.
+    pub dt: Duration,
+    /// Counts frames from 1.
+    pub frame: u64,
+}
+
+/// Delivered at the end of every frame, after scheduled events ran.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameEnd {
+    pub dt: Duration,
+    pub frame: u64,
+}
+
+/// Runs the frames of one bus.
+pub struct FrameDriver {
+    bus: SharedEventBus,
+    frame: u64,
+    last: Option<Instant>,
+}
+
+impl FrameDriver {
+    pub fn new(bus: SharedEventBus) -> Self {
+        Self { bus, frame: 0, last: None }
+    }
+
+    pub fn bus(&self) -> &SharedEventBus {
+        &self.bus
+    }
+
+    /// Frames run so far, which is also the number of the last one.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Runs one frame covering `dt`. Fails, without running the frame, once
+    /// the bus is shut down.
+    pub fn run_frame(&mut self, dt: Duration) -> EventResult<()> {
+        let frame = self.frame + 1;
+        self.bus.publish(FrameStart { dt, frame })?;
+        self.frame = frame;
+        self.last = Some(Instant::now());
+        self.bus.process();
+        self.bus.tick(dt);
+        self.bus.process();
+        self.bus.publish(FrameEnd { dt, frame })
+    }
+
+    /// Runs one frame covering the wall-clock time since the previous one
+    /// started (nothing, for the first), and returns that time.
+    pub fn run_frame_timed(&mut self) -> EventResult<Duration> {
+        let dt = self.last.map_or(Duration::ZERO, |last| last.elapsed());
+        self.run_frame(dt)?;
+        Ok(dt)
+    }
+}
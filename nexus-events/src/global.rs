@@ -0,0 +1,81 @@
+//! The process-wide bus, for code that can't pass a `SharedEventBus`
+//! around.
+//!
+//! ```ignore
+//! // at startup, before anything uses the global bus
+//! global::install(EventBusBuilder::new().max_queue_len(4096).build())?;
+//! global::publish(GameStarted)?;
+//!
+//! // in a test
+//! let bus = SharedEventBus::new();
+//! global::with_scoped_bus(&bus, || player.take_damage(3));
+//! assert_eq!(bus.drain_remaining().len(), 1);
+//! ```
+//!
+//! `with_scoped_bus` swaps the global bus for one thread only, so tests
+//! running in parallel each get their own. Everything that goes through
+//! `SharedEventBus::global()` follows the swap: the free functions in
+//! `nexus_events::core`, the macros and this module. Threads spawned inside
+//! the closure (including the bus's own workers) still see the real global
+//! bus.
+
+use std::cell::RefCell;
+
+use crate::core::{DeliveryReport, Event, EventResult, SharedEventBus};
+
+thread_local! {
+    // Innermost `with_scoped_bus` last.
+    static SCOPED: RefCell<Vec<SharedEventBus>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes `bus` the global bus. Fails with `EventError::GlobalBusInstalled`
+/// once the global bus exists, whether installed or created by a first use.
+pub fn install(bus: SharedEventBus) -> EventResult<()> {
+    SharedEventBus::install_global(bus)
+}
+
+/// The bus the global functions use on this thread: the innermost
+/// `with_scoped_bus` one, otherwise the global bus.
+pub fn bus() -> SharedEventBus {
+    SharedEventBus::global()
+}
+
+/// Runs `f` with `bus` standing in for the global bus on this thread.
+/// Calls nest, and the previous bus is restored even if `f` panics.
+pub fn with_scoped_bus<R>(bus: &SharedEventBus, f: impl FnOnce() -> R) -> R {
+    struct Restore;
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED.with(|scoped| scoped.borrow_mut().pop());
+        }
+    }
+
+    SCOPED.with(|scoped| scoped.borrow_mut().push(bus.clone()));
+    let _restore = Restore;
+    f()
+}
+
+/// The bus installed by the innermost `with_scoped_bus` on this thread.
+pub(crate) fn scoped_bus() -> Option<SharedEventBus> {
+    SCOPED.with(|scoped| scoped.borrow().last().cloned())
+}
+
+/// Same as `SharedEventBus::dispatch` on the global bus.
+pub fn dispatch<E: Event + 'static>(ev: E) {
+    bus().dispatch(ev)
+}
+
+/// Same as `SharedEventBus::publish` on the global bus.
+pub fn publish<E: Event + 'static>(ev: E) -> EventResult<()> {
+    bus().publish(ev)
+}
+
+/// Same as `SharedEventBus::emit` on the global bus.
+pub fn emit<E: Event + 'static>(ev: E) -> EventResult<DeliveryReport> {
+    bus().emit(ev)
+}
+
+/// Same as `SharedEventBus::process` on the global bus.
+pub fn process() {
+    bus().process()
+}
@@ -1,13 +1,60 @@
 pub mod core;
+pub mod catalog;
+mod define;
+mod with_args;
+pub mod observer;
+pub mod loader;
+pub mod subscriber;
+pub mod watched;
+pub mod bridge;
+pub mod topics;
+pub mod global;
+pub mod frame;
+pub mod testing;
+#[cfg(feature = "serde")]
+pub mod eventstore;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(feature = "bevy")]
+pub mod bevy;
+#[cfg(feature = "demo-utils")]
+pub mod demo_utils;
 
 // Re-export the macros so user code can do `use nexus_events::...`
-pub use nexus_events_macros::{event_component, event_handler, event_sender};
+pub use nexus_events_macros::{event_component, event_handler, global_event_handler, event_sender, query_handler, event_contract, event_handlers, EventSubscriber, EventEmitter};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use inventory;
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
 
 // A "prelude" for convenience
 pub mod prelude {
     pub use crate::core::{
-        EventBus, Event, HandlerId, subscribe, dispatch, process_events, unsubscribe,
+        EventBus, SharedEventBus, EventBusBuilder, ChildEdge, EventScope, System, Event, HandlerId, subscribe, dispatch, process_events, unsubscribe,
+        subscribe_coalesced,
+        emit, emit_ref, publish, publish_ref, DeliveryReport,
+        subscribe_category, unsubscribe_category, categorize_as,
+        EventClass, ClassLimit, classify_as,
+        EmitControl, EventEmitter, EventSink, EventContext, EventEnvelope, EventType, EventTypeRegistry, ProcessBudget, SenderId, SubscriptionSet, dispatch_from, subscribe_with_context, subscribe_with_priority,
+        subscribe_with_state, subscribe_for, Subscription,
+        Phase, subscribe_in_phase, advance_phase, current_phase, subscribe_global_handlers,
+        EventContract, HasContract, publish_contract, require_contract, verify_contracts,
+        Query, EventError, EventResult, EventResultExt, register_responder, unregister_responder, query, alias,
+        subscribe_returning, publish_collect,
+        Validate, ValidationError,
     };
 
-    pub use nexus_events_macros::{event_component, event_handler, event_sender};
+    #[cfg(feature = "local")]
+    pub use crate::core::{LocalContext, LocalSharedEventBus, LocalSubscriptionSet};
+
+    pub use crate::define_event;
+    pub use crate::subscribe_with_args;
+    pub use crate::subscriber::{ComponentActivated, ComponentDeactivated, EventSubscriber};
+    pub use crate::watched::{Changed, Watched};
+    pub use crate::frame::{FrameDriver, FrameEnd, FrameStart};
+
+    pub use nexus_events_macros::{event_component, event_handler, global_event_handler, event_sender, query_handler, event_contract, event_handlers, EventSubscriber, EventEmitter};
 }
@@ -0,0 +1,188 @@
+//! Event-driven resource loading.
+//!
+//! Dispatch `LoadRequested { path, kind }` on a bus that has a `Loader`
+//! attached. Each request is handed to exactly one worker thread registered
+//! for that `kind` (round-robin between them), which reports `LoadProgress`
+//! while it works and finishes with `LoadCompleted { handle, .. }` or
+//! `LoadFailed`. Fetch the loaded value with `Loader::get`.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::core::{HandlerId, SharedEventBus};
+
+#[derive(Debug, Clone)]
+pub struct LoadRequested {
+    pub path: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadProgress {
+    pub path: String,
+    /// Between 0.0 and 1.0, as reported by the worker.
+    pub progress: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadCompleted {
+    pub path: String,
+    pub kind: String,
+    pub handle: ResourceHandle,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadFailed {
+    pub path: String,
+    pub kind: String,
+    pub error: String,
+}
+
+/// Identifies a loaded resource inside its `Loader`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResourceHandle(pub u64);
+
+/// Handed to a worker so it can publish `LoadProgress` for the current path.
+pub struct ProgressReporter {
+    bus: SharedEventBus,
+    path: String,
+}
+impl ProgressReporter {
+    pub fn report(&self, progress: f32) {
+        self.bus.dispatch(LoadProgress { path: self.path.clone(), progress });
+    }
+}
+
+type Resource = Arc<dyn Any + Send + Sync>;
+type LoadFn = dyn Fn(&str, &ProgressReporter) -> Result<Resource, String> + Send + Sync;
+
+struct WorkerPool {
+    senders: Vec<Sender<LoadRequested>>,
+    next: usize,
+}
+
+struct Routes {
+    pools: HashMap<String, WorkerPool>,
+}
+
+pub struct Loader {
+    bus: SharedEventBus,
+    handler: HandlerId,
+    routes: Arc<Mutex<Routes>>,
+    resources: Arc<Mutex<HashMap<ResourceHandle, Resource>>>,
+    next_handle: Arc<AtomicU64>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Loader {
+    /// Attaches a loader to `bus`. Requests for kinds without workers are
+    /// answered with `LoadFailed`.
+    pub fn new(bus: SharedEventBus) -> Self {
+        let routes = Arc::new(Mutex::new(Routes { pools: HashMap::new() }));
+        let handler = {
+            let routes = routes.clone();
+            let bus_for_handler = bus.clone();
+            bus.subscribe::<LoadRequested, _>(move |req: &LoadRequested| {
+                let Ok(mut routes) = routes.lock() else { return };
+                match routes.pools.get_mut(&req.kind) {
+                    Some(pool) if !pool.senders.is_empty() => {
+                        let idx = pool.next % pool.senders.len();
+                        pool.next = pool.next.wrapping_add(1);
+                        let _ = pool.senders[idx].send(req.clone());
+                    }
+                    _ => {
+                        let error = format!("no loader worker registered for kind `{}`", req.kind);
+                        bus_for_handler.dispatch(LoadFailed { path: req.path.clone(), kind: req.kind.clone(), error });
+                    }
+                }
+            })
+        };
+        Self {
+            bus,
+            handler,
+            routes,
+            resources: Arc::default(),
+            next_handle: Arc::new(AtomicU64::new(0)),
+            threads: Vec::new(),
+        }
+    }
+
+    /// Starts a worker thread for `kind`. Call it several times to spread the
+    /// requests of one kind across several threads.
+    pub fn add_worker<T, F>(&mut self, kind: &str, load: F)
+    where
+        T: Any + Send + Sync,
+        F: Fn(&str, &ProgressReporter) -> Result<T, String> + Send + Sync + 'static
+    {
+        let load: Arc<LoadFn> = Arc::new(move |path: &str, reporter: &ProgressReporter| {
+            load(path, reporter).map(|v| Arc::new(v) as Resource)
+        });
+        let (tx, rx) = mpsc::channel::<LoadRequested>();
+        let bus = self.bus.clone();
+        let resources = self.resources.clone();
+        let next_handle = self.next_handle.clone();
+
+        let worker = self.bus.spawn_thread("loader", move || {
+            for req in rx {
+                let reporter = ProgressReporter { bus: bus.clone(), path: req.path.clone() };
+                match load(&req.path, &reporter) {
+                    Ok(resource) => {
+                        let handle = ResourceHandle(next_handle.fetch_add(1, Ordering::Relaxed));
+                        if let Ok(mut res) = resources.lock() {
+                            res.insert(handle, resource);
+                        }
+                        bus.dispatch(LoadCompleted { path: req.path, kind: req.kind, handle });
+                    }
+                    Err(error) => bus.dispatch(LoadFailed { path: req.path, kind: req.kind, error }),
+                }
+            }
+        });
+        self.threads.push(worker.expect("failed to spawn loader worker thread"));
+
+        if let Ok(mut routes) = self.routes.lock() {
+            routes.pools.entry(kind.to_string())
+                .or_insert_with(|| WorkerPool { senders: Vec::new(), next: 0 })
+                .senders.push(tx);
+        }
+    }
+
+    /// The resource behind `handle`, if it was loaded as a `T`.
+    pub fn get<T: Any + Send + Sync>(&self, handle: ResourceHandle) -> Option<Arc<T>> {
+        let res = self.resources.lock().ok()?;
+        res.get(&handle)?.clone().downcast::<T>().ok()
+    }
+
+    /// Drops a loaded resource. Outstanding `Arc`s returned by `get` stay valid.
+    pub fn release(&self, handle: ResourceHandle) {
+        if let Ok(mut res) = self.resources.lock() {
+            res.remove(&handle);
+        }
+    }
+
+    /// Stops accepting requests and waits for the workers to finish the ones
+    /// they already have.
+    pub fn shutdown(mut self) {
+        self.stop();
+        for t in self.threads.drain(..) {
+            let _ = t.join();
+        }
+    }
+
+    fn stop(&mut self) {
+        self.bus.unsubscribe::<LoadRequested>(self.handler);
+        if let Ok(mut routes) = self.routes.lock() {
+            routes.pools.clear();
+        }
+    }
+}
+
+impl Drop for Loader {
+    fn drop(&mut self) {
+        // Closing the channels lets the worker threads exit on their own.
+        self.stop();
+    }
+}
@@ -0,0 +1,576 @@
+//! Sharing events with a bus in another process, over TCP or WebSocket
+//! (`net` feature).
+//!
+//! ```ignore
+//! // server
+//! let server = RemoteBusEndpoint::builder(&bus, codec)
+//!     .send::<TurnCommitted>()
+//!     .listen("0.0.0.0:7000")?;
+//!
+//! // client
+//! let client = RemoteBusEndpoint::builder(&bus, codec)
+//!     .send::<PlayerInput>()
+//!     .transport(Transport::WebSocket)
+//!     .connect("game.example:7000")?;
+//! ```
+//!
+//! An endpoint encodes every event of the types it sends (in
+//! `WireFormat::Bincode`) as its bus processes them and ships them to the
+//! peer, in order; events from the peer are decoded with the same codec and
+//! dispatched on the bus. Both sides must register the shared types under
+//! the same names.
+//!
+//! When a connection opens, the two sides exchange the types they send and
+//! can decode. A type the peer can't decode, or only at an older schema
+//! version, is refused: `PeerConnected` lists it and its events are dropped
+//! instead of sent. A client reconnects after losing its peer (see
+//! `Reconnect`); a server goes back to accepting the next one. Events sent
+//! while there is no peer wait in a bounded outbox.
+//!
+//! Each endpoint runs one I/O thread, which picks up incoming events every
+//! couple of milliseconds.
+
+use std::collections::{HashSet, VecDeque};
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tungstenite::{Message, WebSocket};
+
+use crate::core::codec::{EventCodec, WireFormat};
+use crate::core::{Event, EventError, EventResult, EventResultExt, SharedEventBus, SubscriptionSet};
+
+/// Bumped when the handshake or framing changes.
+const PROTOCOL: u32 = 1;
+/// How long the I/O thread waits for incoming data before sending again.
+const POLL: Duration = Duration::from_millis(2);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_FRAME: usize = 64 << 20;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Transport {
+    /// Length-prefixed frames on a plain TCP stream.
+    #[default]
+    Tcp,
+    /// Binary WebSocket messages, for peers behind HTTP infrastructure.
+    WebSocket,
+}
+
+/// How a client retries after failing to connect or losing its peer: the
+/// delay starts at `initial` and doubles up to `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reconnect {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for Reconnect {
+    fn default() -> Self {
+        Self { initial: Duration::from_millis(250), max: Duration::from_secs(5) }
+    }
+}
+
+/// Dispatched on the local bus when a peer is connected and the handshake
+/// is done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerConnected {
+    pub peer: SocketAddr,
+    /// Sent types the peer accepts.
+    pub shared: Vec<String>,
+    /// Sent types the peer can't decode; their events are dropped.
+    pub refused: Vec<String>,
+    /// Types the peer sends that this side can't decode.
+    pub undecodable: Vec<String>,
+}
+
+/// Dispatched on the local bus when the connection to a peer is lost.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerDisconnected {
+    pub peer: SocketAddr,
+    pub reason: String,
+}
+
+// The first frame each way.
+#[derive(Serialize, Deserialize)]
+struct Hello {
+    protocol: u32,
+    sends: Vec<(String, u32)>,
+    accepts: Vec<(String, u32)>,
+}
+
+struct Outbox {
+    frames: VecDeque<(&'static str, Vec<u8>)>,
+    // Names the current peer accepts; `None` while there is no peer.
+    shared: Option<HashSet<&'static str>>,
+    capacity: usize,
+}
+
+struct Link {
+    bus: SharedEventBus,
+    codec: EventCodec,
+    transport: Transport,
+    // What this side sends, with versions.
+    sends: Vec<(&'static str, u32)>,
+    outbox: Mutex<Outbox>,
+    closed: AtomicBool,
+    connected: AtomicBool,
+    sent: AtomicU64,
+    received: AtomicU64,
+    dropped: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl Link {
+    fn push(&self, name: &'static str, ev: &dyn Event) {
+        let Ok(frame) = self.codec.encode(ev, WireFormat::Bincode) else {
+            self.failures.fetch_add(1, Ordering::Relaxed);
+            return;
+        };
+        let Ok(mut outbox) = self.outbox.lock() else { return };
+        if outbox.shared.as_ref().is_some_and(|shared| !shared.contains(name)) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        if outbox.frames.len() >= outbox.capacity {
+            outbox.frames.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        outbox.frames.push_back((name, frame));
+    }
+
+    /// Waits up to `delay`, waking early if the endpoint is dropped.
+    fn sleep(&self, delay: Duration) {
+        let until = Instant::now() + delay;
+        while !self.closed.load(Ordering::Relaxed) && Instant::now() < until {
+            thread::sleep(until.saturating_duration_since(Instant::now()).min(Duration::from_millis(10)));
+        }
+    }
+}
+
+/// One connection's worth of framing.
+trait Wire {
+    fn send(&mut self, frame: &[u8]) -> io::Result<()>;
+    /// The next frame, or `None` if nothing arrived within `POLL`.
+    fn recv(&mut self) -> io::Result<Option<Vec<u8>>>;
+    /// Tells the peer we are leaving, if the framing has a way to.
+    fn close(&mut self) {}
+}
+
+struct TcpWire {
+    stream: TcpStream,
+    buf: Vec<u8>,
+}
+
+impl TcpWire {
+    fn take_frame(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let Some(len) = self.buf.first_chunk::<4>() else { return Ok(None) };
+        let len = u32::from_le_bytes(*len) as usize;
+        if len > MAX_FRAME {
+            return Err(io::Error::new(ErrorKind::InvalidData, "frame too large"));
+        }
+        if self.buf.len() < 4 + len {
+            return Ok(None);
+        }
+        let frame = self.buf[4..4 + len].to_vec();
+        self.buf.drain(..4 + len);
+        Ok(Some(frame))
+    }
+}
+
+impl Wire for TcpWire {
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        let len = u32::try_from(frame.len()).map_err(|_| io::Error::new(ErrorKind::InvalidInput, "frame too large"))?;
+        self.stream.write_all(&len.to_le_bytes())?;
+        self.stream.write_all(frame)
+    }
+
+    fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        if let Some(frame) = self.take_frame()? {
+            return Ok(Some(frame));
+        }
+        let mut chunk = [0; 8192];
+        match self.stream.read(&mut chunk) {
+            Ok(0) => Err(io::Error::new(ErrorKind::UnexpectedEof, "peer closed the connection")),
+            Ok(n) => {
+                self.buf.extend_from_slice(&chunk[..n]);
+                self.take_frame()
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+struct WsWire(WebSocket<TcpStream>);
+
+impl Wire for WsWire {
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.0.send(Message::Binary(frame.to_vec())).map_err(ws_err)
+    }
+
+    fn recv(&mut self) -> io::Result<Option<Vec<u8>>> {
+        match self.0.read() {
+            Ok(Message::Binary(frame)) => Ok(Some(frame)),
+            Ok(Message::Close(_)) => Err(io::Error::new(ErrorKind::ConnectionAborted, "peer closed the connection")),
+            Ok(_) => Ok(None),
+            Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                Ok(None)
+            }
+            Err(e) => Err(ws_err(e)),
+        }
+    }
+
+    fn close(&mut self) {
+        let _ = self.0.close(None);
+        let _ = self.0.flush();
+    }
+}
+
+fn ws_err(e: tungstenite::Error) -> io::Error {
+    match e {
+        tungstenite::Error::Io(e) => e,
+        e => io::Error::other(e),
+    }
+}
+
+enum Role {
+    Connect(Vec<SocketAddr>, Reconnect),
+    Listen(TcpListener),
+}
+
+/// Ships events between a local bus and a peer's for as long as it lives.
+pub struct RemoteBusEndpoint {
+    link: Arc<Link>,
+    local_addr: Option<SocketAddr>,
+    io: Option<JoinHandle<()>>,
+    _subscriptions: SubscriptionSet,
+}
+
+type Installer = Box<dyn FnOnce(&mut SubscriptionSet, &Arc<Link>)>;
+
+/// Chooses what a `RemoteBusEndpoint` sends and how it connects.
+pub struct EndpointBuilder {
+    bus: SharedEventBus,
+    codec: EventCodec,
+    transport: Transport,
+    reconnect: Reconnect,
+    capacity: usize,
+    sends: Vec<(&'static str, u32)>,
+    installers: Vec<Installer>,
+    unregistered: Option<&'static str>,
+}
+
+impl RemoteBusEndpoint {
+    /// Starts an endpoint for `bus`; `codec` must know every type either
+    /// side sends.
+    pub fn builder(bus: &SharedEventBus, codec: EventCodec) -> EndpointBuilder {
+        EndpointBuilder {
+            bus: bus.clone(),
+            codec,
+            transport: Transport::default(),
+            reconnect: Reconnect::default(),
+            capacity: 1024,
+            sends: Vec::new(),
+            installers: Vec::new(),
+            unregistered: None,
+        }
+    }
+
+    /// The address a listening endpoint is bound to.
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.link.connected.load(Ordering::Relaxed)
+    }
+
+    /// Blocks until a peer is connected, or fails with
+    /// `EventError::Timeout` after `timeout`.
+    pub fn wait_connected(&self, timeout: Duration) -> EventResult<()> {
+        let deadline = Instant::now() + timeout;
+        while !self.is_connected() {
+            if Instant::now() >= deadline {
+                return Err(EventError::Timeout(timeout));
+            }
+            thread::sleep(POLL);
+        }
+        Ok(())
+    }
+
+    /// Events written to the peer so far.
+    pub fn sent(&self) -> u64 {
+        self.link.sent.load(Ordering::Relaxed)
+    }
+
+    /// Events received from the peer and dispatched so far.
+    pub fn received(&self) -> u64 {
+        self.link.received.load(Ordering::Relaxed)
+    }
+
+    /// Events not sent: refused by the peer, or pushed out of a full
+    /// outbox.
+    pub fn dropped(&self) -> u64 {
+        self.link.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Events that couldn't be encoded or decoded.
+    pub fn failures(&self) -> u64 {
+        self.link.failures.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for RemoteBusEndpoint {
+    fn drop(&mut self) {
+        self.link.closed.store(true, Ordering::Relaxed);
+        if let Some(io) = self.io.take() {
+            let _ = io.join();
+        }
+    }
+}
+
+impl EndpointBuilder {
+    /// Sends every `E` the bus processes to the peer.
+    pub fn send<E: Event + 'static>(mut self) -> Self {
+        let Some(name) = self.codec.name_of::<E>() else {
+            self.unregistered.get_or_insert(std::any::type_name::<E>());
+            return self;
+        };
+        if self.sends.iter().any(|(n, _)| *n == name) {
+            return self;
+        }
+        let version = self.codec.names().into_iter().find(|(n, _)| *n == name).map_or(1, |(_, v)| v);
+        self.sends.push((name, version));
+        self.installers.push(Box::new(move |subscriptions, link| {
+            let link = link.clone();
+            subscriptions.subscribe::<E, _>(move |ev: &E| link.push(name, ev));
+        }));
+        self
+    }
+
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Only used by `connect`.
+    pub fn reconnect(mut self, reconnect: Reconnect) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    /// How many events wait while there is no peer before the oldest are
+    /// dropped. Defaults to 1024.
+    pub fn outbox_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Connects to the peer at `addr`, retrying in the background until it
+    /// answers. Fails only if `addr` doesn't resolve.
+    pub fn connect(self, addr: impl ToSocketAddrs) -> EventResult<RemoteBusEndpoint> {
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs().context("resolving the peer address")?.collect();
+        if addrs.is_empty() {
+            let e = io::Error::new(ErrorKind::AddrNotAvailable, "address resolved to nothing");
+            return Err(EventError::from(e).context("resolving the peer address"));
+        }
+        let reconnect = self.reconnect;
+        self.start(Role::Connect(addrs, reconnect), None)
+    }
+
+    /// Listens on `addr` and serves one peer at a time.
+    pub fn listen(self, addr: impl ToSocketAddrs) -> EventResult<RemoteBusEndpoint> {
+        let listener = TcpListener::bind(addr).context("binding the listen socket")?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr().ok();
+        self.start(Role::Listen(listener), local_addr)
+    }
+
+    fn start(self, role: Role, local_addr: Option<SocketAddr>) -> EventResult<RemoteBusEndpoint> {
+        if let Some(name) = self.unregistered {
+            return Err(EventError::UnregisteredEvent(name.to_string()));
+        }
+        let link = Arc::new(Link {
+            bus: self.bus.clone(),
+            codec: self.codec,
+            transport: self.transport,
+            sends: self.sends,
+            outbox: Mutex::new(Outbox { frames: VecDeque::new(), shared: None, capacity: self.capacity }),
+            closed: AtomicBool::new(false),
+            connected: AtomicBool::new(false),
+            sent: AtomicU64::new(0),
+            received: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+        });
+        let mut subscriptions = SubscriptionSet::new(&self.bus);
+        for install in self.installers {
+            install(&mut subscriptions, &link);
+        }
+        let io = {
+            let link = link.clone();
+            self.bus.spawn_thread("net", move || run(&link, role)).context("starting the network thread")?
+        };
+        Ok(RemoteBusEndpoint { link, local_addr, io: Some(io), _subscriptions: subscriptions })
+    }
+}
+
+fn run(link: &Link, role: Role) {
+    let mut delay = None;
+    while !link.closed.load(Ordering::Relaxed) {
+        let stream = match &role {
+            Role::Connect(addrs, reconnect) => {
+                if let Some(delay) = delay {
+                    link.sleep(delay);
+                }
+                match addrs.iter().find_map(|addr| TcpStream::connect_timeout(addr, HANDSHAKE_TIMEOUT).ok()) {
+                    Some(stream) => stream,
+                    None => {
+                        delay = Some(delay.map_or(reconnect.initial, |d: Duration| (d * 2).min(reconnect.max)));
+                        continue;
+                    }
+                }
+            }
+            Role::Listen(listener) => match listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(_) => {
+                    link.sleep(Duration::from_millis(10));
+                    continue;
+                }
+            },
+        };
+        let Ok(peer) = stream.peer_addr() else { continue };
+        let reason = match open(link, stream, matches!(role, Role::Connect(..))) {
+            Ok(wire) => session(link, wire, peer),
+            Err(e) => e.to_string(),
+        };
+        let was_connected = link.connected.swap(false, Ordering::Relaxed);
+        if let Ok(mut outbox) = link.outbox.lock() {
+            outbox.shared = None;
+        }
+        if was_connected {
+            link.bus.dispatch(PeerDisconnected { peer, reason });
+            if let Role::Connect(_, reconnect) = &role {
+                delay = Some(reconnect.initial);
+            }
+        } else if let Role::Connect(_, reconnect) = &role {
+            delay = Some(delay.map_or(reconnect.initial, |d| (d * 2).min(reconnect.max)));
+        }
+    }
+}
+
+/// Sets up framing on a fresh connection.
+fn open(link: &Link, stream: TcpStream, client: bool) -> io::Result<Box<dyn Wire>> {
+    stream.set_nonblocking(false)?;
+    stream.set_nodelay(true)?;
+    if link.transport == Transport::Tcp {
+        stream.set_read_timeout(Some(POLL))?;
+        return Ok(Box::new(TcpWire { stream, buf: Vec::new() }));
+    }
+    // The WebSocket upgrade can't be interrupted by the poll timeout.
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let ws = if client {
+        let url = format!("ws://{}/", stream.peer_addr()?);
+        tungstenite::client(url, stream).map_err(|e| io::Error::other(e.to_string()))?.0
+    } else {
+        tungstenite::accept(stream).map_err(|e| io::Error::other(e.to_string()))?
+    };
+    ws.get_ref().set_read_timeout(Some(POLL))?;
+    Ok(Box::new(WsWire(ws)))
+}
+
+/// Runs one connection until it fails or the endpoint is dropped, and
+/// returns why it ended.
+fn session(link: &Link, mut wire: Box<dyn Wire>, peer: SocketAddr) -> String {
+    let hello = Hello {
+        protocol: PROTOCOL,
+        sends: link.sends.iter().map(|(n, v)| (n.to_string(), *v)).collect(),
+        accepts: link.codec.names().into_iter().map(|(n, v)| (n.to_string(), v)).collect(),
+    };
+    let theirs = match handshake(&mut *wire, &hello) {
+        Ok(theirs) => theirs,
+        Err(e) => return e.to_string(),
+    };
+    let (shared, refused): (Vec<_>, Vec<_>) = link.sends.iter()
+        .partition(|(name, version)| theirs.accepts.iter().any(|(n, v)| n == name && v >= version));
+    if let Ok(mut outbox) = link.outbox.lock() {
+        let shared: HashSet<&'static str> = shared.iter().map(|(n, _)| *n).collect();
+        let before = outbox.frames.len();
+        outbox.frames.retain(|(name, _)| shared.contains(name));
+        link.dropped.fetch_add((before - outbox.frames.len()) as u64, Ordering::Relaxed);
+        outbox.shared = Some(shared);
+    }
+    link.connected.store(true, Ordering::Relaxed);
+    let ours = &hello.accepts;
+    link.bus.dispatch(PeerConnected {
+        peer,
+        shared: shared.iter().map(|(n, _)| n.to_string()).collect(),
+        refused: refused.iter().map(|(n, _)| n.to_string()).collect(),
+        undecodable: theirs.sends.into_iter()
+            .filter(|(name, version)| !ours.iter().any(|(n, v)| n == name && v >= version))
+            .map(|(name, _)| name)
+            .collect(),
+    });
+
+    while !link.closed.load(Ordering::Relaxed) {
+        let frames = match link.outbox.lock() {
+            Ok(mut outbox) => std::mem::take(&mut outbox.frames),
+            Err(_) => return "outbox poisoned".to_string(),
+        };
+        let mut frames = frames.into_iter();
+        while let Some((name, frame)) = frames.next() {
+            if let Err(e) = wire.send(&frame) {
+                // Keep what wasn't sent for the next connection.
+                if let Ok(mut outbox) = link.outbox.lock() {
+                    let unsent: Vec<_> = std::iter::once((name, frame)).chain(frames).collect();
+                    for queued in unsent.into_iter().rev() {
+                        outbox.frames.push_front(queued);
+                    }
+                }
+                return e.to_string();
+            }
+            link.sent.fetch_add(1, Ordering::Relaxed);
+        }
+        match wire.recv() {
+            Ok(Some(frame)) => match link.codec.decode(&frame, WireFormat::Bincode) {
+                Ok(ev) => {
+                    ev.publish(&link.bus);
+                    link.received.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(_) => {
+                    link.failures.fetch_add(1, Ordering::Relaxed);
+                }
+            },
+            Ok(None) => {}
+            Err(e) => return e.to_string(),
+        }
+    }
+    wire.close();
+    "endpoint closed".to_string()
+}
+
+/// Swaps `Hello`s, then switches the connection to polling.
+fn handshake(wire: &mut dyn Wire, hello: &Hello) -> io::Result<Hello> {
+    let bytes = serde_json::to_vec(hello).map_err(io::Error::other)?;
+    wire.send(&bytes)?;
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    let theirs: Hello = loop {
+        if let Some(frame) = wire.recv()? {
+            break serde_json::from_slice(&frame).map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        }
+        if Instant::now() >= deadline {
+            return Err(io::Error::new(ErrorKind::TimedOut, "no handshake from peer"));
+        }
+    };
+    if theirs.protocol != PROTOCOL {
+        return Err(io::Error::new(
+            ErrorKind::InvalidData,
+            format!("peer speaks protocol {}, not {}", theirs.protocol, PROTOCOL),
+        ));
+    }
+    Ok(theirs)
+}
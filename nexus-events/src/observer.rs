@@ -0,0 +1,65 @@
+//! Adapters between nexus-events and classic observer-pattern code, so legacy
+//! systems can be moved onto the bus one at a time.
+//!
+//! - `SharedEventBus::observe` feeds bus events into an existing observer.
+//! - `BusForwarder` is an observer that republishes what it is notified of on
+//!   a bus, so a legacy subject can talk to bus subscribers.
+
+use std::sync::{Arc, Mutex};
+
+use crate::core::{Event, HandlerId, SharedEventBus};
+
+/// The usual observer interface. Implement it for your legacy types (or
+/// write a one-line blanket impl from your own observer trait).
+pub trait Observer<E> {
+    fn notify(&mut self, e: &E);
+}
+
+impl SharedEventBus {
+    /// Subscribes `observer` to `E`. The observer is locked for the duration
+    /// of each `notify`; unsubscribe with the returned id as usual.
+    pub fn observe<E, O>(&self, observer: Arc<Mutex<O>>) -> HandlerId
+    where
+        E: Event + 'static,
+        O: Observer<E> + Send + 'static,
+    {
+        self.subscribe::<E, _>(move |ev: &E| {
+            if let Ok(mut obs) = observer.lock() {
+                obs.notify(ev);
+            }
+        })
+    }
+}
+
+/// Same as `SharedEventBus::observe` on the global bus.
+pub fn observe<E, O>(observer: Arc<Mutex<O>>) -> HandlerId
+where
+    E: Event + 'static,
+    O: Observer<E> + Send + 'static,
+{
+    SharedEventBus::global().observe(observer)
+}
+
+/// An observer that dispatches a clone of every event it is notified of.
+/// Register it with a legacy subject in place of the observers that have
+/// already moved to bus subscriptions.
+#[derive(Clone)]
+pub struct BusForwarder {
+    bus: SharedEventBus,
+}
+
+impl BusForwarder {
+    pub fn new(bus: SharedEventBus) -> Self {
+        Self { bus }
+    }
+    /// A forwarder onto the global bus.
+    pub fn global() -> Self {
+        Self::new(SharedEventBus::global())
+    }
+}
+
+impl<E: Event + Clone + 'static> Observer<E> for BusForwarder {
+    fn notify(&mut self, e: &E) {
+        self.bus.dispatch(e.clone());
+    }
+}
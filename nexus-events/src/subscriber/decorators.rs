@@ -0,0 +1,88 @@
+//! Handler decorators for spammy events (mouse moves, ticks).
+//!
+//! - `throttle` lets an event through at most once per interval and drops
+//!   the rest. It runs inline, during `process`, like any handler.
+//! - `debounce` waits until `E` has been quiet for a whole window and then
+//!   handles only the last one. Because that moment doesn't coincide with a
+//!   delivery, the handler runs on a background thread of the bus.
+//!
+//! The `subscribe_throttled` and `subscribe_debounced` methods on
+//! `SharedEventBus` wrap and subscribe in one step.
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::core::{Event, HandlerId, SharedEventBus};
+
+/// Wraps `f` so it runs at most once per `min_interval`; events arriving
+/// sooner are dropped.
+pub fn throttle<E, F>(min_interval: Duration, f: F) -> impl Fn(&E) + Send + Sync + 'static
+where
+    E: Event + 'static,
+    F: Fn(&E) + Send + Sync + 'static
+{
+    let last: Mutex<Option<Instant>> = Mutex::new(None);
+    move |ev: &E| {
+        let Ok(mut last) = last.lock() else { return };
+        let now = Instant::now();
+        if last.is_none_or(|t| now.duration_since(t) >= min_interval) {
+            *last = Some(now);
+            drop(last);
+            f(ev);
+        }
+    }
+}
+
+/// Wraps `f` so it only sees the last `E` of a burst, once no other `E` has
+/// arrived for `window`. `f` runs on a thread spawned on `bus`, which exits
+/// when the returned handler is dropped (i.e. unsubscribed); a burst still
+/// waiting at that point is handled first.
+pub fn debounce<E, F>(bus: &SharedEventBus, window: Duration, f: F) -> impl Fn(&E) + Send + Sync + 'static
+where
+    E: Event + Clone + 'static,
+    F: Fn(&E) + Send + 'static
+{
+    let (tx, rx) = mpsc::channel::<E>();
+    bus.spawn_thread("debounce", move || {
+        while let Ok(mut latest) = rx.recv() {
+            loop {
+                match rx.recv_timeout(window) {
+                    Ok(newer) => latest = newer,
+                    Err(RecvTimeoutError::Timeout) => {
+                        f(&latest);
+                        break;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => {
+                        f(&latest);
+                        return;
+                    }
+                }
+            }
+        }
+    })
+    .expect("failed to spawn debounce thread");
+    move |ev: &E| {
+        let _ = tx.send(ev.clone());
+    }
+}
+
+impl SharedEventBus {
+    /// Subscribes `f`, called at most `max_per_sec` times per second.
+    pub fn subscribe_throttled<E: Event + 'static, F>(&self, max_per_sec: u32, f: F) -> HandlerId
+    where
+        F: Fn(&E) + Send + Sync + 'static
+    {
+        let interval = Duration::from_secs(1) / max_per_sec.max(1);
+        self.subscribe::<E, _>(throttle(interval, f))
+    }
+
+    /// Subscribes `f` to the last `E` of every burst (see `debounce`).
+    pub fn subscribe_debounced<E: Event + Clone + 'static, F>(&self, window: Duration, f: F) -> HandlerId
+    where
+        F: Fn(&E) + Send + 'static
+    {
+        let handler = debounce(self, window, f);
+        self.subscribe::<E, _>(handler)
+    }
+}
@@ -0,0 +1,148 @@
+//! Helpers layered on top of plain subscriptions.
+
+pub mod decorators;
+mod template;
+
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::DerefMut;
+use std::sync::{Mutex, MutexGuard, RwLock, RwLockWriteGuard};
+
+use crate::core::{address_of, SubscriptionSet};
+
+pub use template::{ComponentResolver, Resubscribe, SubscriptionPlan, SubscriptionTemplate};
+
+/// A component that owns its subscriptions. Usually derived:
+///
+/// ```ignore
+/// #[derive(EventSubscriber)]
+/// struct Enemy {
+///     id: u32,
+///     active: bool,
+///     subscriptions: SubscriptionSet,
+/// }
+/// ```
+///
+/// The derive reads the `id`, `active` and `subscriptions` fields. Structs
+/// that name them differently map them with
+/// `#[event_subscriber(id = "entity_id", active = "enabled", subscriptions = "subs")]`,
+/// and `#[event_subscriber(skip_active)]` drops the `active` field
+/// altogether (the component is then active unless paused with
+/// `set_active(false)`).
+///
+/// `#[event_subscriber(lifecycle)]` also generates inherent
+/// `activate(&mut self, bus)` and `deactivate(&mut self)` methods.
+/// `activate` moves the subscription set to `bus`, which subscribes the
+/// handlers registered with it again (see `SubscriptionSet::rebind`), marks
+/// the component active, calls the method named with `register = "..."` (a
+/// `fn(&mut self)` that subscribes the component's other handlers through
+/// its set), if any, and publishes `ComponentActivated`. So a component
+/// whose `#[event_handler]` methods were subscribed once with their
+/// `subscribe_<method>` companions needs no `register` method. `deactivate`
+/// removes every subscription, keeping the registrations, marks the
+/// component inactive and publishes `ComponentDeactivated`. Both need an
+/// `Id` that is `Clone`, and return the error of the rebind or publish, if
+/// any.
+///
+/// A register method that may be called again while the handlers are still
+/// there can subscribe with `SubscriptionSet::try_subscribe`, which fails
+/// with `EventError::DuplicateHandler` instead of subscribing a handler
+/// twice.
+pub trait EventSubscriber {
+    type Id;
+
+    fn subscriber_id(&self) -> &Self::Id;
+    fn is_active(&self) -> bool;
+    fn subscriptions(&self) -> &SubscriptionSet;
+    fn subscriptions_mut(&mut self) -> &mut SubscriptionSet;
+
+    /// Mutes (`false`) or unmutes the component: its subscriptions stay
+    /// registered, but the bus skips them while it is inactive. The derive
+    /// also stores the flag in the `active` field.
+    fn set_active(&mut self, active: bool) {
+        if active {
+            self.subscriptions_mut().resume();
+        } else {
+            self.subscriptions_mut().pause();
+        }
+    }
+
+    /// Registers the component's subscriptions, including ones made
+    /// later, under its id, so `SharedEventBus::dispatch_to(&id, ev)` and
+    /// `publish_to` reach this component and no other.
+    fn bind_address(&mut self)
+    where
+        Self::Id: Hash + 'static
+    {
+        let address = address_of(self.subscriber_id());
+        self.subscriptions_mut().set_address_hash(address);
+    }
+
+    /// Removes every subscription the component made.
+    fn unsubscribe_all(&mut self) {
+        self.subscriptions_mut().clear();
+    }
+
+    /// Records the component's type and id (as `Debug` formats it) with its
+    /// subscriptions, including ones made later, so
+    /// `SharedEventBus::export_subscription_plan` lists them.
+    fn record_owner(&mut self)
+    where
+        Self::Id: Debug
+    {
+        let id = format!("{:?}", self.subscriber_id());
+        self.subscriptions_mut().set_owner(std::any::type_name::<Self>(), &id);
+    }
+
+    /// Subscribes the component's handlers through its set, for
+    /// `SharedEventBus::rebind`. Does nothing unless implemented; the
+    /// derive calls the method named by `register = "..."`.
+    fn register_handlers(&mut self) {}
+}
+
+/// Published by the `activate` that `#[event_subscriber(lifecycle)]`
+/// generates, once the component's handlers are subscribed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentActivated<Id> {
+    /// Type name of the component.
+    pub component: &'static str,
+    pub id: Id,
+}
+
+/// Published by the generated `deactivate`, once the component's handlers
+/// are gone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentDeactivated<Id> {
+    pub component: &'static str,
+    pub id: Id,
+}
+
+/// A component field that `#[event_handler(.., uses(field))]` handlers lock
+/// on their own, so handlers using other fields aren't held up.
+pub trait FieldLock {
+    type Value: ?Sized;
+    type Guard<'a>: DerefMut<Target = Self::Value>
+    where
+        Self: 'a;
+
+    /// Locks the field for writing; `None` if it is poisoned.
+    fn lock_field(&self) -> Option<Self::Guard<'_>>;
+}
+
+impl<T: ?Sized> FieldLock for Mutex<T> {
+    type Value = T;
+    type Guard<'a> = MutexGuard<'a, T> where T: 'a;
+
+    fn lock_field(&self) -> Option<MutexGuard<'_, T>> {
+        self.lock().ok()
+    }
+}
+
+impl<T: ?Sized> FieldLock for RwLock<T> {
+    type Value = T;
+    type Guard<'a> = RwLockWriteGuard<'a, T> where T: 'a;
+
+    fn lock_field(&self) -> Option<RwLockWriteGuard<'_, T>> {
+        self.write().ok()
+    }
+}
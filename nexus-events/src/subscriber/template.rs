@@ -0,0 +1,108 @@
+//! Subscriptions that survive a scene reload.
+//!
+//! Before tearing a scene down, `bus.export_subscription_plan()` records
+//! which components (by type and id) were subscribed to which events. Once
+//! the new scene is built, `bus.rebind(&plan, &mut scene)` hands every
+//! component of the plan to the scene, which finds the new instance with
+//! that type and id, and subscribes it again:
+//!
+//! ```ignore
+//! let plan = bus.export_subscription_plan();
+//! drop(scene);
+//! let mut scene = Scene::load(path);
+//! let missing = bus.rebind(&plan, &mut scene);
+//! ```
+//!
+//! Only handlers subscribed through a `SubscriptionSet` with an owner are
+//! in the plan; `EventSubscriber::record_owner` sets it from the
+//! component's type and id. The set subscribes the handlers registered with
+//! it again (the `subscribe_<method>` companions of `#[event_handler]` do);
+//! anything else is up to the component's
+//! `EventSubscriber::register_handlers`, which the derive implements with
+//! the method named by `register = "..."`.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use super::EventSubscriber;
+use crate::core::SharedEventBus;
+
+/// One component of a `SubscriptionPlan`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SubscriptionTemplate {
+    /// Type name of the component.
+    pub component: &'static str,
+    /// The component's id, as `record_owner` formatted it.
+    pub id: String,
+    /// Type names of the events it was subscribed to, sorted, once each.
+    pub events: Vec<&'static str>,
+}
+
+/// Which components were subscribed to which events when
+/// `SharedEventBus::export_subscription_plan` was called.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SubscriptionPlan {
+    /// Sorted by component type, then id.
+    pub components: Vec<SubscriptionTemplate>,
+}
+
+/// A component that can be subscribed again on a bus. Implemented for
+/// every `EventSubscriber` with a `Debug` id.
+pub trait Resubscribe {
+    fn resubscribe(&mut self, bus: &SharedEventBus);
+}
+
+impl<C: EventSubscriber> Resubscribe for C
+where
+    C::Id: Debug
+{
+    /// Moves the component's subscription set to `bus`, dropping whatever
+    /// it still held, and registers its handlers again.
+    fn resubscribe(&mut self, bus: &SharedEventBus) {
+        // Fails only on a poisoned bus, which nothing can be subscribed to.
+        let _ = self.subscriptions_mut().rebind(bus);
+        self.record_owner();
+        self.register_handlers();
+    }
+}
+
+/// Finds the instance a `SubscriptionTemplate` stands for, typically by
+/// looking its type and id up in a scene.
+pub trait ComponentResolver {
+    fn resolve(&mut self, template: &SubscriptionTemplate) -> Option<&mut dyn Resubscribe>;
+}
+
+impl SharedEventBus {
+    /// The components subscribed on this bus and their events (see the top
+    /// of this file).
+    pub fn export_subscription_plan(&self) -> SubscriptionPlan {
+        let mut components: BTreeMap<(&'static str, String), Vec<&'static str>> = BTreeMap::new();
+        for (component, id, event) in self.owned_handlers() {
+            components.entry((component, id.to_string())).or_default().push(event);
+        }
+        SubscriptionPlan {
+            components: components.into_iter()
+                .map(|((component, id), mut events)| {
+                    events.sort_unstable();
+                    events.dedup();
+                    SubscriptionTemplate { component, id, events }
+                })
+                .collect(),
+        }
+    }
+
+    /// Subscribes every component of `plan` that `resolver` finds on this
+    /// bus again, and returns the ones it didn't find.
+    pub fn rebind<R: ComponentResolver + ?Sized>(&self, plan: &SubscriptionPlan, resolver: &mut R) -> Vec<SubscriptionTemplate> {
+        let mut missing = Vec::new();
+        for template in &plan.components {
+            match resolver.resolve(template) {
+                Some(component) => component.resubscribe(self),
+                None => missing.push(template.clone()),
+            }
+        }
+        missing
+    }
+}
@@ -0,0 +1,256 @@
+//! Helpers for unit-testing components that publish and handle events.
+//!
+//! ```ignore
+//! let bus = TestBus::new();
+//! bus.run(|| player.move_to(10.0, 0.0));
+//! assert_published!(bus, PlayerMoved { x: 10.0, .. });
+//! ```
+//!
+//! A `TestBus` keeps every event published on it, whether delivered
+//! already (`publish`, `process`) or still queued (`dispatch`), so a test
+//! can check what a component sent without subscribing first. `run` makes
+//! it the global bus for the closure, for components that use the free
+//! functions or the macros. `Spy` records the calls a handler would get,
+//! and `FakeClock` moves the scheduler's clock by hand.
+
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::core::{Event, EventLog, HandlerId, SharedEventBus};
+use crate::global;
+
+/// A bus that remembers what was published on it.
+pub struct TestBus {
+    bus: SharedEventBus,
+    log: EventLog,
+}
+
+impl Default for TestBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestBus {
+    pub fn new() -> Self {
+        Self::with_bus(SharedEventBus::new())
+    }
+
+    /// Records what is published on `bus` from now on, for a bus built
+    /// with a particular configuration.
+    pub fn with_bus(bus: SharedEventBus) -> Self {
+        let log = bus.record_delivered();
+        Self { bus, log }
+    }
+
+    pub fn bus(&self) -> &SharedEventBus {
+        &self.bus
+    }
+
+    /// Runs `f` with this bus standing in for the global one on this
+    /// thread (see `global::with_scoped_bus`).
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        global::with_scoped_bus(&self.bus, f)
+    }
+
+    /// Every `E` published so far, delivered ones first and then the
+    /// queued ones, each in order.
+    pub fn published<E: Event + Clone + 'static>(&self) -> Vec<E> {
+        let mut events = Vec::new();
+        self.each(|ev: &E| events.push(ev.clone()));
+        events
+    }
+
+    /// How many `E` were published so far.
+    pub fn count<E: Event + 'static>(&self) -> usize {
+        let mut count = 0;
+        self.each(|_: &E| count += 1);
+        count
+    }
+
+    /// Whether any `E` published so far satisfies `f`; what
+    /// `assert_published!` checks.
+    pub fn any_published<E: Event + 'static>(&self, mut f: impl FnMut(&E) -> bool) -> bool {
+        let mut found = false;
+        self.each(|ev: &E| found = found || f(ev));
+        found
+    }
+
+    /// The type names of everything published so far, in the order of
+    /// `published`, for failure messages.
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        self.each_dyn(|ev| names.push(ev.event_name()));
+        names
+    }
+
+    /// Forgets the delivered events. Queued ones are still reported until
+    /// they are processed.
+    pub fn clear(&self) {
+        let old = self.log.lock().map(|mut log| std::mem::take(&mut *log)).unwrap_or_default();
+        drop(old);
+    }
+
+    /// Subscribes a handler that records every `E` it is called with.
+    pub fn spy<E: Event + Clone + 'static>(&self) -> Spy<E> {
+        Spy::new(&self.bus)
+    }
+
+    /// A clock for the bus's scheduler, starting at zero.
+    pub fn clock(&self) -> FakeClock {
+        FakeClock::new(&self.bus)
+    }
+
+    fn each<E: Event + 'static>(&self, mut f: impl FnMut(&E)) {
+        self.each_dyn(|ev| {
+            if let Some(ev) = ev.as_any().downcast_ref::<E>() {
+                f(ev);
+            }
+        });
+    }
+
+    fn each_dyn(&self, mut f: impl FnMut(&dyn Event)) {
+        if let Ok(log) = self.log.lock() {
+            log.iter().for_each(|ev| f(&**ev));
+        }
+        self.bus.with_queued(|queued| queued.for_each(&mut f));
+    }
+}
+
+impl Deref for TestBus {
+    type Target = SharedEventBus;
+
+    fn deref(&self) -> &SharedEventBus {
+        &self.bus
+    }
+}
+
+/// A handler that records the events it is called with, standing in for
+/// the real one. Subscribed like any handler at priority 0, so it sees what
+/// that handler would: nothing consumed before it, nothing of a paused type.
+/// Unsubscribed when dropped.
+#[must_use = "the spy unsubscribes when dropped"]
+pub struct Spy<E: Event + 'static> {
+    bus: SharedEventBus,
+    id: HandlerId,
+    calls: Arc<Mutex<Vec<E>>>,
+}
+
+impl<E: Event + Clone + 'static> Spy<E> {
+    pub fn new(bus: &SharedEventBus) -> Self {
+        let calls: Arc<Mutex<Vec<E>>> = Arc::default();
+        let seen = calls.clone();
+        let id = bus.subscribe(move |ev: &E| {
+            if let Ok(mut seen) = seen.lock() {
+                seen.push(ev.clone());
+            }
+        });
+        Self { bus: bus.clone(), id, calls }
+    }
+
+    /// How many times the handler was called.
+    pub fn calls(&self) -> usize {
+        self.calls.lock().map(|calls| calls.len()).unwrap_or(0)
+    }
+
+    pub fn was_called(&self) -> bool {
+        self.calls() > 0
+    }
+
+    /// The events the handler was called with, in order.
+    pub fn events(&self) -> Vec<E> {
+        self.calls.lock().map(|calls| calls.clone()).unwrap_or_default()
+    }
+
+    pub fn last(&self) -> Option<E> {
+        self.calls.lock().ok().and_then(|calls| calls.last().cloned())
+    }
+}
+
+impl<E: Event + 'static> Drop for Spy<E> {
+    fn drop(&mut self) {
+        self.bus.unsubscribe::<E>(self.id);
+    }
+}
+
+/// Drives a bus's scheduler (`publish_after`, `publish_every`) by hand
+/// instead of with the frame time or `start_scheduler`, so a test can skip
+/// ahead three seconds without waiting for them.
+pub struct FakeClock {
+    bus: SharedEventBus,
+    elapsed: Duration,
+}
+
+impl FakeClock {
+    pub fn new(bus: &SharedEventBus) -> Self {
+        Self { bus: bus.clone(), elapsed: Duration::ZERO }
+    }
+
+    /// Moves the clock forward by `dt`, then processes the bus so the
+    /// events that fell due reach their handlers.
+    pub fn advance(&mut self, dt: Duration) {
+        self.elapsed += dt;
+        self.bus.tick(dt);
+        self.bus.process();
+    }
+
+    /// The time advanced so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Asserts that an event matching the pattern was published on a
+/// `TestBus`, delivered or still queued. A type name alone matches any
+/// event of that type:
+///
+/// ```ignore
+/// assert_published!(bus, PlayerMoved { x: 10.0, .. });
+/// assert_published!(bus, Respawned);
+/// assert_published!(bus, ScoreChanged(n) if *n > 100);
+/// ```
+#[macro_export]
+macro_rules! assert_published {
+    ($bus:expr, $($ty:ident)::+ { $($fields:tt)* } $(if $guard:expr)?) => {
+        $crate::__assert_published!(true, $bus, [$($ty)::+] $($ty)::+ { $($fields)* } $(if $guard)?)
+    };
+    ($bus:expr, $($ty:ident)::+ ( $($fields:tt)* ) $(if $guard:expr)?) => {
+        $crate::__assert_published!(true, $bus, [$($ty)::+] $($ty)::+ ( $($fields)* ) $(if $guard)?)
+    };
+    ($bus:expr, $($ty:ident)::+) => {
+        $crate::__assert_published!(true, $bus, [$($ty)::+] _)
+    };
+}
+
+/// `assert_published!` the other way round: no event matching the pattern
+/// was published.
+#[macro_export]
+macro_rules! assert_not_published {
+    ($bus:expr, $($ty:ident)::+ { $($fields:tt)* } $(if $guard:expr)?) => {
+        $crate::__assert_published!(false, $bus, [$($ty)::+] $($ty)::+ { $($fields)* } $(if $guard)?)
+    };
+    ($bus:expr, $($ty:ident)::+ ( $($fields:tt)* ) $(if $guard:expr)?) => {
+        $crate::__assert_published!(false, $bus, [$($ty)::+] $($ty)::+ ( $($fields)* ) $(if $guard)?)
+    };
+    ($bus:expr, $($ty:ident)::+) => {
+        $crate::__assert_published!(false, $bus, [$($ty)::+] _)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_published {
+    ($expected:literal, $bus:expr, [$($ty:tt)*] $pat:pat $(if $guard:expr)?) => {{
+        let bus: &$crate::testing::TestBus = &$bus;
+        let found = bus.any_published(|ev: &$($ty)*| matches!(ev, $pat $(if $guard)?));
+        if found != $expected {
+            panic!(
+                "expected {}`{}` to be published; published: {:?}",
+                if $expected { "" } else { "no " },
+                stringify!($pat $(if $guard)?),
+                bus.names(),
+            );
+        }
+    }};
+}
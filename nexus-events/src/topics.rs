@@ -0,0 +1,197 @@
+//! String-addressed events for scripting and modding layers that can't
+//! define Rust types.
+//!
+//! ```ignore
+//! bus.subscribe_topic("combat.*", |ev: &TopicEvent| log(&ev.topic, &ev.payload));
+//! bus.publish_topic("combat.hit", Value::from(12));
+//! ```
+//!
+//! A published topic is an ordinary `TopicEvent` on the bus, queued and
+//! delivered in order with typed events. Patterns match whole topics: `*`
+//! stands for any run of characters within one segment (segments are
+//! separated by `.` or `/`), `**` for any run across segments.
+
+use std::collections::BTreeMap;
+
+use crate::core::{HandlerId, SharedEventBus};
+
+/// A dynamically typed payload, shaped like JSON.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum Value {
+    #[default]
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    List(Vec<Value>),
+    Map(BTreeMap<String, Value>),
+}
+
+impl Value {
+    /// The entry `key` of a map.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Map(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match *self {
+            Value::Int(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Ints convert too.
+    pub fn as_float(&self) -> Option<f64> {
+        match *self {
+            Value::Float(f) => Some(f),
+            Value::Int(i) => Some(i as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for Value {
+    fn from(b: bool) -> Self {
+        Value::Bool(b)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(i: i64) -> Self {
+        Value::Int(i)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(i: i32) -> Self {
+        Value::Int(i.into())
+    }
+}
+
+impl From<f64> for Value {
+    fn from(f: f64) -> Self {
+        Value::Float(f)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(f: f32) -> Self {
+        Value::Float(f.into())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(s: &str) -> Self {
+        Value::String(s.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Self {
+        Value::String(s)
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(list: Vec<T>) -> Self {
+        Value::List(list.into_iter().map(Into::into).collect())
+    }
+}
+
+impl From<BTreeMap<String, Value>> for Value {
+    fn from(map: BTreeMap<String, Value>) -> Self {
+        Value::Map(map)
+    }
+}
+
+/// Dispatched by `publish_topic`. Typed code can subscribe to it directly
+/// to see every topic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopicEvent {
+    pub topic: String,
+    pub payload: Value,
+}
+
+/// Whether `topic` matches `pattern` (see the module docs).
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    matches(pattern.as_bytes(), topic.as_bytes())
+}
+
+fn is_separator(b: u8) -> bool {
+    b == b'.' || b == b'/'
+}
+
+fn matches(pattern: &[u8], topic: &[u8]) -> bool {
+    match pattern {
+        [] => topic.is_empty(),
+        [b'*', b'*', rest @ ..] => (0..=topic.len()).any(|i| matches(rest, &topic[i..])),
+        [b'*', rest @ ..] => {
+            let segment = topic.iter().position(|&b| is_separator(b)).unwrap_or(topic.len());
+            (0..=segment).any(|i| matches(rest, &topic[i..]))
+        }
+        [p, rest @ ..] => topic.first() == Some(p) && matches(rest, &topic[1..]),
+    }
+}
+
+impl SharedEventBus {
+    /// Dispatches a `TopicEvent`.
+    pub fn publish_topic(&self, topic: impl Into<String>, payload: impl Into<Value>) {
+        self.dispatch(TopicEvent { topic: topic.into(), payload: payload.into() });
+    }
+
+    /// Subscribes to the topics matching `pattern`. Unsubscribe with
+    /// `unsubscribe_topic`.
+    pub fn subscribe_topic<F>(&self, pattern: impl Into<String>, f: F) -> HandlerId
+    where
+        F: Fn(&TopicEvent) + Send + Sync + 'static
+    {
+        let pattern = pattern.into();
+        self.subscribe::<TopicEvent, _>(move |ev: &TopicEvent| {
+            if topic_matches(&pattern, &ev.topic) {
+                f(ev);
+            }
+        })
+    }
+
+    pub fn unsubscribe_topic(&self, handler_id: HandlerId) {
+        self.unsubscribe::<TopicEvent>(handler_id);
+    }
+}
+
+/// Same as `SharedEventBus::publish_topic` on the global bus.
+pub fn publish_topic(topic: impl Into<String>, payload: impl Into<Value>) {
+    SharedEventBus::global().publish_topic(topic, payload)
+}
+
+/// Same as `SharedEventBus::subscribe_topic` on the global bus.
+pub fn subscribe_topic<F>(pattern: impl Into<String>, f: F) -> HandlerId
+where
+    F: Fn(&TopicEvent) + Send + Sync + 'static
+{
+    SharedEventBus::global().subscribe_topic(pattern, f)
+}
+
+/// Same as `SharedEventBus::unsubscribe_topic` on the global bus.
+pub fn unsubscribe_topic(handler_id: HandlerId) {
+    SharedEventBus::global().unsubscribe_topic(handler_id)
+}
@@ -0,0 +1,80 @@
+//! Values that announce their own changes, for HUD-style data binding.
+//!
+//! A `Watched<T>` dispatches `Changed<T> { old, new }` on its bus whenever it
+//! is changed through `set` or `update`, so the health bar subscribes to
+//! `Changed<Health>` instead of polling the player every frame.
+
+use std::fmt;
+use std::ops::Deref;
+
+use crate::core::SharedEventBus;
+
+/// Dispatched by `Watched<T>` after its value changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Changed<T> {
+    pub old: T,
+    pub new: T,
+}
+
+/// A value that dispatches `Changed<T>` when it changes. Reading goes
+/// through `Deref`; writing only through the methods here, so no change can
+/// slip by unannounced. Setting an equal value announces nothing.
+pub struct Watched<T> {
+    value: T,
+    bus: SharedEventBus,
+}
+
+impl<T> Watched<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static
+{
+    /// Watches `value` on the global bus.
+    pub fn new(value: T) -> Self {
+        Self::with_bus(SharedEventBus::global(), value)
+    }
+
+    pub fn with_bus(bus: SharedEventBus, value: T) -> Self {
+        Self { value, bus }
+    }
+
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn set(&mut self, new: T) -> T {
+        let old = std::mem::replace(&mut self.value, new);
+        if old != self.value {
+            self.bus.dispatch(Changed { old: old.clone(), new: self.value.clone() });
+        }
+        old
+    }
+
+    /// Changes the value in place; one `Changed` is dispatched afterwards if
+    /// it ended up different.
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        let old = self.value.clone();
+        let result = f(&mut self.value);
+        if old != self.value {
+            self.bus.dispatch(Changed { old, new: self.value.clone() });
+        }
+        result
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Watched<T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Watched<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Watched").field(&self.value).finish()
+    }
+}
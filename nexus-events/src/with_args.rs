@@ -0,0 +1,46 @@
+// `subscribe_with_args!` declares the state of a `subscribe_with_state`
+// handler inline, as named arguments with initial values, instead of as a
+// separate struct or tuple the handler has to take apart.
+
+/// Subscribes a handler with state of its own, declared as named arguments.
+///
+/// ```ignore
+/// // On the global bus:
+/// subscribe_with_args!(PlayerMoved, [moves: u32 = 0, last_x: f32 = 0.0], |ev| {
+///     *moves += 1;
+///     *last_x = ev.x;
+/// });
+///
+/// // On a bus of your own:
+/// let id = subscribe_with_args!(bus, EnemyAttack, [total: u64 = 0], |ev| {
+///     *total += ev.damage as u64;
+/// });
+/// ```
+///
+/// Each argument starts at its initial value and keeps what the handler
+/// left in it from one event to the next; in the body it is a `&mut` to
+/// the value. The handler gets the event by reference. Expands to
+/// `subscribe_with_state` and returns the `HandlerId`.
+#[macro_export]
+macro_rules! subscribe_with_args {
+    ($event:ty, [$($arg:ident : $ty:ty = $init:expr),* $(,)?], |$ev:pat_param| $body:expr $(,)?) => {
+        $crate::core::subscribe_with_state::<$event, ($($ty,)*), _>(
+            ($($init,)*),
+            #[allow(unused_variables)]
+            move |$ev: &$event, state: &mut ($($ty,)*)| {
+                let ($($arg,)*) = state;
+                $body
+            },
+        )
+    };
+    ($bus:expr, $event:ty, [$($arg:ident : $ty:ty = $init:expr),* $(,)?], |$ev:pat_param| $body:expr $(,)?) => {
+        $bus.subscribe_with_state::<$event, ($($ty,)*), _>(
+            ($($init,)*),
+            #[allow(unused_variables)]
+            move |$ev: &$event, state: &mut ($($ty,)*)| {
+                let ($($arg,)*) = state;
+                $body
+            },
+        )
+    };
+}
@@ -0,0 +1,90 @@
+// Aliases: publishing a deprecated event type reaches the handlers of its
+// replacement with the converted event, through chains of aliases, while
+// handlers still on the old type keep getting it.
+
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct OldPlayerMoved {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PlayerMoved {
+    position: (i32, i32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct PlayerMovedV3 {
+    position: (f32, f32),
+}
+
+fn collect<E: Event + Clone>(bus: &SharedEventBus) -> Arc<Mutex<Vec<E>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |ev: &E| s.lock().unwrap().push(ev.clone()));
+    seen
+}
+
+#[test]
+fn a_deprecated_event_reaches_the_handlers_of_its_replacement() {
+    let bus = SharedEventBus::new();
+    bus.alias::<OldPlayerMoved, PlayerMoved, _>(|old| PlayerMoved { position: (old.x, old.y) }).unwrap();
+    let moved = collect::<PlayerMoved>(&bus);
+
+    bus.publish(OldPlayerMoved { x: 1, y: 2 }).unwrap();
+    bus.dispatch(OldPlayerMoved { x: 3, y: 4 });
+    bus.process();
+    bus.publish(PlayerMoved { position: (5, 6) }).unwrap();
+
+    assert_eq!(
+        *moved.lock().unwrap(),
+        [PlayerMoved { position: (1, 2) }, PlayerMoved { position: (3, 4) }, PlayerMoved { position: (5, 6) }]
+    );
+}
+
+#[test]
+fn handlers_of_the_deprecated_type_still_get_it() {
+    let bus = SharedEventBus::new();
+    bus.alias::<OldPlayerMoved, PlayerMoved, _>(|old| PlayerMoved { position: (old.x, old.y) }).unwrap();
+    let old = collect::<OldPlayerMoved>(&bus);
+    let moved = collect::<PlayerMoved>(&bus);
+
+    let report = bus.emit(OldPlayerMoved { x: 1, y: 1 }).unwrap();
+    assert_eq!(report.handlers, 2);
+    assert_eq!(old.lock().unwrap().len(), 1);
+    assert_eq!(moved.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn aliases_chain_through_every_replacement() {
+    let bus = SharedEventBus::new();
+    bus.alias::<OldPlayerMoved, PlayerMoved, _>(|old| PlayerMoved { position: (old.x, old.y) }).unwrap();
+    bus.alias::<PlayerMoved, PlayerMovedV3, _>(|ev| PlayerMovedV3 {
+        position: (ev.position.0 as f32, ev.position.1 as f32),
+    })
+    .unwrap();
+    let moved = collect::<PlayerMoved>(&bus);
+    let latest = collect::<PlayerMovedV3>(&bus);
+
+    bus.publish(OldPlayerMoved { x: 2, y: -1 }).unwrap();
+    assert_eq!(*moved.lock().unwrap(), [PlayerMoved { position: (2, -1) }]);
+    assert_eq!(*latest.lock().unwrap(), [PlayerMovedV3 { position: (2.0, -1.0) }]);
+}
+
+#[test]
+fn an_alias_that_would_close_a_cycle_is_refused() {
+    let bus = SharedEventBus::new();
+    bus.alias::<OldPlayerMoved, PlayerMoved, _>(|old| PlayerMoved { position: (old.x, old.y) }).unwrap();
+    let err = bus.alias::<PlayerMoved, OldPlayerMoved, _>(|ev| OldPlayerMoved { x: ev.position.0, y: ev.position.1 })
+        .unwrap_err();
+    assert!(matches!(err, EventError::AliasCycle(name) if name.ends_with("PlayerMoved")));
+
+    // The refused alias isn't there: a new event doesn't bounce back.
+    let old = collect::<OldPlayerMoved>(&bus);
+    bus.publish(PlayerMoved { position: (0, 0) }).unwrap();
+    assert!(old.lock().unwrap().is_empty());
+}
@@ -0,0 +1,146 @@
+// Bounded queues and their overflow policies.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use nexus_events::core::{BusConfig, ConfigChange, EventClass, EventError, OverflowPolicy, SharedEventBus};
+
+#[derive(Debug, Clone)]
+struct Spawned(u32);
+
+fn bus_with(policy: OverflowPolicy) -> (SharedEventBus, Arc<Mutex<Vec<u32>>>) {
+    let bus = SharedEventBus::builder().max_queue_len(2).queue_policy(policy).build();
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe::<Spawned, _>(move |ev| s.lock().unwrap().push(ev.0));
+    (bus, seen)
+}
+
+// A kilobyte of event that counts how many of its kind were dropped.
+struct Payload {
+    _bytes: Vec<u8>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl Payload {
+    fn new(dropped: &Arc<AtomicUsize>) -> Self {
+        Self { _bytes: vec![0; 1024], dropped: dropped.clone() }
+    }
+}
+
+impl Drop for Payload {
+    fn drop(&mut self) {
+        self.dropped.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn error_fails_try_dispatch_and_drops_dispatches() {
+    let (bus, seen) = bus_with(OverflowPolicy::default());
+    bus.dispatch(Spawned(1));
+    bus.dispatch(Spawned(2));
+    bus.dispatch(Spawned(3));
+    assert!(matches!(bus.try_dispatch(Spawned(4)), Err(EventError::QueueFull { capacity: 2 })));
+    assert_eq!(bus.overflowed_events(), 1);
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [1, 2]);
+}
+
+#[test]
+fn drop_newest_keeps_the_first_events() {
+    let (bus, seen) = bus_with(OverflowPolicy::DropNewest);
+    for n in 1..=4 {
+        bus.try_dispatch(Spawned(n)).unwrap();
+    }
+    assert_eq!(bus.overflowed_events(), 2);
+    assert_eq!(bus.dropped_events(), 2);
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [1, 2]);
+}
+
+#[test]
+fn drop_oldest_keeps_the_last_events() {
+    let (bus, seen) = bus_with(OverflowPolicy::DropOldest);
+    for n in 1..=4 {
+        bus.dispatch(Spawned(n));
+    }
+    assert_eq!(bus.dropped_in_class(EventClass::Gameplay), 2);
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [3, 4]);
+}
+
+#[test]
+fn block_holds_producers_back_until_there_is_room() {
+    let (bus, seen) = bus_with(OverflowPolicy::Block);
+    let producer = {
+        let bus = bus.clone();
+        thread::spawn(move || {
+            for n in 1..=20 {
+                bus.dispatch(Spawned(n));
+            }
+        })
+    };
+    while seen.lock().unwrap().len() < 20 {
+        bus.process();
+        thread::yield_now();
+    }
+    producer.join().unwrap();
+    assert_eq!(*seen.lock().unwrap(), (1..=20).collect::<Vec<_>>());
+    assert_eq!(bus.overflowed_events(), 0);
+
+    // A handler of the bus can't wait for itself; its extra events go.
+    bus.subscribe::<Spawned, _>({
+        let bus = bus.clone();
+        move |ev| {
+            if ev.0 == 0 {
+                for n in 100..103 {
+                    bus.dispatch(Spawned(n));
+                }
+            }
+        }
+    });
+    bus.dispatch(Spawned(0));
+    bus.process();
+    assert_eq!(bus.overflowed_events(), 1);
+}
+
+#[test]
+fn the_policy_can_be_changed_on_a_live_bus() {
+    let (bus, _) = bus_with(OverflowPolicy::Block);
+    let changes = bus.apply_config(BusConfig { max_queue_len: Some(2), ..BusConfig::default() }).unwrap();
+    assert_eq!(changes, [ConfigChange::QueuePolicy { from: OverflowPolicy::Block, to: OverflowPolicy::Error }]);
+}
+
+#[test]
+fn events_over_the_limit_are_dropped_at_dispatch() {
+    for policy in [OverflowPolicy::Error, OverflowPolicy::DropNewest, OverflowPolicy::DropOldest] {
+        let bus = SharedEventBus::builder().max_queue_len(10).queue_policy(policy).build();
+        let dropped = Arc::new(AtomicUsize::new(0));
+        for _ in 0..1000 {
+            bus.dispatch(Payload::new(&dropped));
+        }
+        // Nothing has processed the bus or looked at its queue, so only
+        // the dispatches can have dropped them.
+        assert_eq!(dropped.load(Ordering::SeqCst), 990, "{:?}", policy);
+        assert_eq!(bus.overflowed_events(), 990, "{:?}", policy);
+    }
+}
+
+#[test]
+fn try_dispatch_fails_as_soon_as_the_queue_is_full() {
+    let bus = SharedEventBus::builder().max_queue_len(10).build();
+    let dropped = Arc::new(AtomicUsize::new(0));
+    for _ in 0..10 {
+        bus.try_dispatch(Payload::new(&dropped)).unwrap();
+    }
+    for _ in 0..5 {
+        assert!(matches!(
+            bus.try_dispatch(Payload::new(&dropped)),
+            Err(EventError::QueueFull { capacity: 10 })
+        ));
+    }
+    assert_eq!(dropped.load(Ordering::SeqCst), 5);
+    bus.process();
+    assert_eq!(dropped.load(Ordering::SeqCst), 15);
+}
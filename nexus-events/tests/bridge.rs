@@ -0,0 +1,119 @@
+// Bus bridges: the forwarded event types reach the target bus's handlers,
+// others stay on the source bus, and buffered bridges apply their
+// backpressure policy to what gets through.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use nexus_events::bridge::{Backpressure, EventBridge};
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+struct RenderCommand {
+    frame: u32,
+}
+
+#[derive(Debug, Clone)]
+struct PhysicsStep;
+
+fn frames(bus: &SharedEventBus) -> Arc<Mutex<Vec<u32>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |ev: &RenderCommand| s.lock().unwrap().push(ev.frame));
+    seen
+}
+
+#[test]
+fn forwarded_events_reach_the_target_on_its_next_process() {
+    let (sim, render) = (SharedEventBus::new(), SharedEventBus::new());
+    let seen = frames(&render);
+    let steps = Arc::new(Mutex::new(0));
+    let s = steps.clone();
+    render.subscribe(move |_: &PhysicsStep| *s.lock().unwrap() += 1);
+    let _bridge = EventBridge::connect(&sim, &render).forward::<RenderCommand>();
+
+    sim.publish(RenderCommand { frame: 1 }).unwrap();
+    sim.publish(PhysicsStep).unwrap();
+    assert!(seen.lock().unwrap().is_empty());
+
+    render.process();
+    assert_eq!(*seen.lock().unwrap(), [1]);
+    assert_eq!(*steps.lock().unwrap(), 0);
+}
+
+#[test]
+fn forward_if_only_forwards_what_the_filter_accepts() {
+    let (sim, render) = (SharedEventBus::new(), SharedEventBus::new());
+    let seen = frames(&render);
+    let _bridge = EventBridge::connect(&sim, &render).forward_if(|ev: &RenderCommand| ev.frame.is_multiple_of(2));
+
+    for frame in 1..=4 {
+        sim.publish(RenderCommand { frame }).unwrap();
+    }
+    render.process();
+    assert_eq!(*seen.lock().unwrap(), [2, 4]);
+}
+
+#[test]
+fn dropping_the_bridge_stops_forwarding() {
+    let (sim, render) = (SharedEventBus::new(), SharedEventBus::new());
+    let seen = frames(&render);
+    let bridge = EventBridge::connect(&sim, &render).forward::<RenderCommand>();
+    drop(bridge);
+
+    sim.publish(RenderCommand { frame: 1 }).unwrap();
+    render.process();
+    assert!(seen.lock().unwrap().is_empty());
+}
+
+fn buffered(policy: Backpressure) -> (Vec<u32>, u64) {
+    let (sim, render) = (SharedEventBus::new(), SharedEventBus::new());
+    let seen = frames(&render);
+    let bridge = EventBridge::connect_buffered(&sim, &render, 2, policy).forward::<RenderCommand>();
+
+    for frame in 1..=4 {
+        sim.publish(RenderCommand { frame }).unwrap();
+    }
+    assert_eq!(bridge.pending(), 2);
+    assert_eq!(bridge.pump(), 2);
+    render.process();
+    let seen = seen.lock().unwrap().clone();
+    (seen, bridge.dropped())
+}
+
+#[test]
+fn a_full_buffer_drops_the_newest_events() {
+    assert_eq!(buffered(Backpressure::DropNewest), (vec![1, 2], 2));
+}
+
+#[test]
+fn a_full_buffer_drops_the_oldest_events() {
+    assert_eq!(buffered(Backpressure::DropOldest), (vec![3, 4], 2));
+}
+
+#[test]
+fn a_blocking_bridge_holds_the_source_until_the_target_pumps() {
+    let (sim, render) = (SharedEventBus::new(), SharedEventBus::new());
+    let seen = frames(&render);
+    let bridge = EventBridge::connect_buffered(&sim, &render, 1, Backpressure::Block).forward::<RenderCommand>();
+
+    let source = {
+        let sim = sim.clone();
+        thread::spawn(move || {
+            for frame in 1..=3 {
+                sim.publish(RenderCommand { frame }).unwrap();
+            }
+        })
+    };
+    let mut pumped = 0;
+    while pumped < 3 {
+        pumped += bridge.pump();
+        thread::sleep(Duration::from_millis(1));
+    }
+    source.join().unwrap();
+    render.process();
+
+    assert_eq!(*seen.lock().unwrap(), [1, 2, 3]);
+    assert_eq!(bridge.dropped(), 0);
+}
@@ -0,0 +1,296 @@
+// Random sequences of subscribe/unsubscribe/clear/publish/dispatch/process
+// run against a bus and against a plain model of it. After every step the
+// handler calls made so far must be exactly the ones the model predicts, in
+// the same order, and `handler_counts` must agree with it. In particular no
+// handler is called after it was unsubscribed or its subscription set
+// cleared, even by a handler of the same event that ran just before it, and
+// removing a handler that owns a subscription set (which unsubscribes when
+// dropped) doesn't deadlock.
+//
+// Two of the event types come from `define_event!` and have dense ids; the
+// third is a plain struct, looked up by `TypeId`.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use nexus_events::core::{EventContext, HandlerId, Phase, SharedEventBus, SubscriptionSet};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+nexus_events::define_event! {
+    #[derive(Default)]
+    struct Spawned;
+    #[derive(Default)]
+    struct Despawned;
+}
+
+#[derive(Debug, Clone, Default)]
+struct Tick;
+
+// Only subscribed to by the sets handlers own; never published.
+struct Owned;
+
+const TYPES: usize = 3;
+const SETS: usize = 3;
+
+/// Runs `$body` with `$e` standing for event type number `$ty`.
+macro_rules! with_type {
+    ($ty:expr, $e:ident => $body:expr) => {
+        match $ty {
+            0 => { type $e = Spawned; $body }
+            1 => { type $e = Despawned; $body }
+            _ => { type $e = Tick; $body }
+        }
+    };
+}
+
+fn type_name(ty: usize) -> &'static str {
+    with_type!(ty, E => std::any::type_name::<E>())
+}
+
+#[derive(Debug, Clone)]
+enum Op {
+    /// Subscribes a handler at `priority`, directly or through
+    /// subscription set `set`.
+    Subscribe { ty: usize, set: Option<usize>, priority: i32 },
+    /// Subscribes a handler (directly) that clears subscription set
+    /// `clears` whenever it runs.
+    SubscribeClearer { ty: usize, clears: usize },
+    /// Subscribes a handler (directly) that owns a subscription set with a
+    /// handler of `Owned` in it.
+    SubscribeOwner { ty: usize },
+    /// Unsubscribes one of the handlers subscribed so far, directly, even
+    /// if it is already gone.
+    Unsubscribe(Index),
+    Clear(usize),
+    Publish(usize),
+    Dispatch(usize),
+    Process,
+}
+
+/// Mostly the default priority, so ties are common.
+fn priority() -> impl Strategy<Value = i32> {
+    prop_oneof![3 => Just(0), 1 => -2..=2]
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    prop_oneof![
+        3 => (0..TYPES, proptest::option::of(0..SETS), priority())
+            .prop_map(|(ty, set, priority)| Op::Subscribe { ty, set, priority }),
+        1 => (0..TYPES, 0..SETS).prop_map(|(ty, clears)| Op::SubscribeClearer { ty, clears }),
+        1 => (0..TYPES).prop_map(|ty| Op::SubscribeOwner { ty }),
+        2 => any::<Index>().prop_map(Op::Unsubscribe),
+        1 => (0..SETS).prop_map(Op::Clear),
+        2 => (0..TYPES).prop_map(Op::Publish),
+        2 => (0..TYPES).prop_map(Op::Dispatch),
+        1 => Just(Op::Process),
+    ]
+}
+
+struct Handler {
+    ty: usize,
+    priority: i32,
+    set: Option<usize>,
+    clears: Option<usize>,
+    live: bool,
+}
+
+/// What the bus should do: handlers run by descending priority and in
+/// subscription order among equals, those subscribed during a delivery miss
+/// it, those removed during it miss the rest of it.
+#[derive(Default)]
+struct Model {
+    handlers: Vec<Handler>,
+    queue: VecDeque<usize>,
+    calls: Vec<usize>,
+}
+
+impl Model {
+    fn deliver(&mut self, ty: usize) {
+        let mut reached: Vec<usize> = (0..self.handlers.len())
+            .filter(|&i| self.handlers[i].ty == ty && self.handlers[i].live)
+            .collect();
+        reached.sort_by_key(|&i| std::cmp::Reverse(self.handlers[i].priority));
+        for i in reached {
+            if !self.handlers[i].live {
+                continue;
+            }
+            self.calls.push(i);
+            if let Some(set) = self.handlers[i].clears {
+                self.clear(set);
+            }
+        }
+    }
+
+    fn clear(&mut self, set: usize) {
+        for h in self.handlers.iter_mut().filter(|h| h.set == Some(set)) {
+            h.live = false;
+        }
+    }
+
+    fn counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for h in self.handlers.iter().filter(|h| h.live) {
+            *counts.entry(type_name(h.ty)).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+struct Harness {
+    bus: SharedEventBus,
+    sets: Vec<Arc<Mutex<SubscriptionSet>>>,
+    ids: Vec<HandlerId>,
+    calls: Arc<Mutex<Vec<usize>>>,
+    model: Model,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let bus = SharedEventBus::new();
+        let sets = (0..SETS).map(|_| Arc::new(Mutex::new(SubscriptionSet::new(&bus)))).collect();
+        Self { bus, sets, ids: Vec::new(), calls: Arc::default(), model: Model::default() }
+    }
+
+    fn apply(&mut self, op: &Op) {
+        match *op {
+            Op::Subscribe { ty, set, priority } => {
+                let (index, calls) = (self.ids.len(), self.calls.clone());
+                let id = with_type!(ty, E => {
+                    let handler = move |_: &E, _: &mut EventContext| calls.lock().unwrap().push(index);
+                    match set {
+                        Some(set) => self.sets[set].lock().unwrap()
+                            .subscribe_in_phase::<E, _>(Phase::PreInit, priority, handler),
+                        None => self.bus.subscribe_with_priority::<E, _>(priority, handler),
+                    }
+                });
+                self.ids.push(id);
+                self.model.handlers.push(Handler { ty, priority, set, clears: None, live: true });
+            }
+            Op::SubscribeClearer { ty, clears } => {
+                let (index, calls) = (self.ids.len(), self.calls.clone());
+                let set = self.sets[clears].clone();
+                let id = with_type!(ty, E => self.bus.subscribe::<E, _>(move |_: &E| {
+                    calls.lock().unwrap().push(index);
+                    set.lock().unwrap().clear();
+                }));
+                self.ids.push(id);
+                self.model.handlers.push(Handler { ty, priority: 0, set: None, clears: Some(clears), live: true });
+            }
+            Op::SubscribeOwner { ty } => {
+                let (index, calls) = (self.ids.len(), self.calls.clone());
+                let mut owned = SubscriptionSet::new(&self.bus);
+                owned.subscribe::<Owned, _>(|_| {});
+                let id = with_type!(ty, E => self.bus.subscribe::<E, _>(move |_: &E| {
+                    calls.lock().unwrap().push(index);
+                    assert_eq!(owned.len(), 1);
+                }));
+                self.ids.push(id);
+                self.model.handlers.push(Handler { ty, priority: 0, set: None, clears: None, live: true });
+            }
+            Op::Unsubscribe(pick) => {
+                if self.ids.is_empty() {
+                    return;
+                }
+                let i = pick.index(self.ids.len());
+                let ty = self.model.handlers[i].ty;
+                with_type!(ty, E => self.bus.unsubscribe::<E>(self.ids[i]));
+                self.model.handlers[i].live = false;
+            }
+            Op::Clear(set) => {
+                self.sets[set].lock().unwrap().clear();
+                self.model.clear(set);
+            }
+            Op::Publish(ty) => {
+                with_type!(ty, E => self.bus.publish(E::default())).unwrap();
+                self.model.deliver(ty);
+            }
+            Op::Dispatch(ty) => {
+                with_type!(ty, E => self.bus.dispatch(E::default()));
+                self.model.queue.push_back(ty);
+            }
+            Op::Process => {
+                self.bus.process();
+                while let Some(ty) = self.model.queue.pop_front() {
+                    self.model.deliver(ty);
+                }
+            }
+        }
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig { failure_persistence: None, ..ProptestConfig::default() })]
+
+    #[test]
+    fn bus_matches_model(ops in proptest::collection::vec(op(), 1..60)) {
+        let mut harness = Harness::new();
+        for op in &ops {
+            harness.apply(op);
+            prop_assert_eq!(&*harness.calls.lock().unwrap(), &harness.model.calls, "after {:?}", op);
+            let mut counts = harness.bus.handler_counts();
+            counts.remove(std::any::type_name::<Owned>());
+            prop_assert_eq!(counts, harness.model.counts(), "after {:?}", op);
+        }
+    }
+
+    #[test]
+    fn order_survives_churn(
+        rounds in proptest::collection::vec(
+            (proptest::collection::vec(priority(), 0..8), proptest::collection::vec(any::<Index>(), 0..4)),
+            1..12,
+        ),
+    ) {
+        // Handlers run by descending priority, then in subscription order,
+        // however many were unsubscribed in between.
+        let bus = SharedEventBus::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut handlers: Vec<(i32, HandlerId, bool)> = Vec::new();
+        for (subscribes, removes) in &rounds {
+            for &priority in subscribes {
+                let (index, calls) = (handlers.len(), calls.clone());
+                let id = bus.subscribe_with_priority::<Tick, _>(priority, move |_, _| calls.lock().unwrap().push(index));
+                handlers.push((priority, id, true));
+            }
+            for pick in removes {
+                if handlers.is_empty() {
+                    break;
+                }
+                let i = pick.index(handlers.len());
+                bus.unsubscribe::<Tick>(handlers[i].1);
+                handlers[i].2 = false;
+            }
+            calls.lock().unwrap().clear();
+            bus.publish(Tick).unwrap();
+            let mut expected: Vec<usize> = (0..handlers.len()).filter(|&i| handlers[i].2).collect();
+            expected.sort_by_key(|&i| std::cmp::Reverse(handlers[i].0));
+            prop_assert_eq!(&*calls.lock().unwrap(), &expected);
+        }
+    }
+
+    #[test]
+    fn removed_ids_stay_removed(subscribes in 1..20usize, removes in proptest::collection::vec(any::<Index>(), 1..20)) {
+        // Slots of removed handlers are reused; an old id must never
+        // reach the handler that took its slot.
+        let bus = SharedEventBus::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut ids = Vec::new();
+        let mut live = Vec::new();
+        for round in 0..2 {
+            for _ in 0..subscribes {
+                let (index, calls) = (ids.len(), calls.clone());
+                ids.push(bus.subscribe::<Tick, _>(move |_: &Tick| calls.lock().unwrap().push(index)));
+                live.push(true);
+            }
+            for pick in &removes {
+                let i = pick.index(ids.len());
+                bus.unsubscribe::<Tick>(ids[i]);
+                live[i] = false;
+            }
+            calls.lock().unwrap().clear();
+            bus.publish(Tick).unwrap();
+            let expected: Vec<usize> = (0..ids.len()).filter(|&i| live[i]).collect();
+            prop_assert_eq!(&*calls.lock().unwrap(), &expected, "round {}", round);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+// Catalog conflicts: two define_event! types registered under the same name
+// are reported, with whether their schemas agree.
+
+use nexus_events::catalog;
+use nexus_events::prelude::*;
+
+mod movement {
+    use nexus_events::prelude::*;
+
+    define_event! {
+        pub struct PlayerMoved { pub x: f32, pub y: f32 }
+        pub enum Stance { Standing, Crouching }
+    }
+}
+
+// Another crate's idea of the same events.
+mod netcode {
+    use nexus_events::prelude::*;
+
+    define_event! {
+        pub struct PlayerMoved { pub x: f32, pub y: f32, pub z: f32 }
+        pub enum Stance { Standing, Crouching }
+    }
+}
+
+define_event! {
+    pub struct PlayerJumped { pub height: f32 }
+}
+
+#[test]
+fn conflicts_report_both_types_and_whether_their_schemas_match() {
+    let conflicts = catalog::conflicts();
+    assert_eq!(conflicts.len(), 2);
+    let moved = conflicts.iter().find(|c| c.name == "PlayerMoved").unwrap();
+    assert!(!moved.same_schema());
+    assert_ne!(moved.registered.type_id, moved.other.type_id);
+    // The registry resolves the name to the type the conflict says it does.
+    assert_eq!(EventTypeRegistry::type_id("PlayerMoved"), Some(moved.registered.type_id));
+    let paths = [moved.registered.type_name, moved.other.type_name];
+    assert!(paths.iter().any(|p| p.ends_with("movement::PlayerMoved")));
+    assert!(paths.iter().any(|p| p.ends_with("netcode::PlayerMoved")));
+
+    let stance = conflicts.iter().find(|c| c.name == "Stance").unwrap();
+    assert!(stance.same_schema());
+    assert!(conflicts.iter().all(|c| c.name != "PlayerJumped"));
+
+    let _ = (movement::PlayerMoved { x: 0.0, y: 0.0 }, netcode::PlayerMoved { x: 0.0, y: 0.0, z: 0.0 });
+    let _ = (movement::Stance::Standing, movement::Stance::Crouching);
+    let _ = (netcode::Stance::Standing, netcode::Stance::Crouching, PlayerJumped { height: 1.0 });
+}
+
+#[test]
+fn check_fails_on_a_conflict_with_different_fields() {
+    let err = catalog::check().unwrap_err();
+    assert!(matches!(err, EventError::ConflictingEventName { name: "PlayerMoved", same_schema: false, .. }));
+    assert!(err.to_string().contains("with different fields"), "{err}");
+}
@@ -0,0 +1,45 @@
+// Registering event types with a codec: names that collide on the wire are
+// refused, and registering the same type again is fine.
+
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+
+use nexus_events::core::codec::{EventCodec, WireFormat};
+use nexus_events::core::{wire_id, EventError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct GameTick {
+    frame: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LegacyTick {
+    frame: u64,
+}
+
+#[test]
+fn a_name_already_on_the_wire_for_another_type_is_refused() {
+    let mut codec = EventCodec::new();
+    codec.register::<GameTick>("Tick").unwrap();
+    let Err(err) = codec.register::<LegacyTick>("Tick") else {
+        panic!("registering `Tick` for a second type succeeded");
+    };
+    assert!(matches!(
+        err,
+        EventError::WireIdCollision { name: "Tick", existing: "Tick", id } if id == wire_id("Tick")
+    ));
+
+    // The first registration still stands.
+    let bytes = codec.encode(&GameTick { frame: 7 }, WireFormat::Compact).unwrap();
+    let decoded = codec.decode(&bytes, WireFormat::Compact).unwrap();
+    assert_eq!(decoded.event().as_any().downcast_ref::<GameTick>(), Some(&GameTick { frame: 7 }));
+    assert!(codec.encode(&LegacyTick { frame: 7 }, WireFormat::Compact).is_err());
+}
+
+#[test]
+fn registering_a_type_again_bumps_its_version() {
+    let mut codec = EventCodec::new();
+    codec.register::<GameTick>("Tick").unwrap().register_versioned::<GameTick>("Tick", 2).unwrap();
+    assert_eq!(codec.names(), [("Tick", 2)]);
+}
@@ -0,0 +1,60 @@
+// Command queues: events that go to exactly one consumer, in order.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+struct SpawnEnemy {
+    at: (i32, i32),
+}
+
+#[derive(Debug, Clone)]
+struct OpenDoor;
+
+#[test]
+fn commands_go_only_to_their_queue_in_order() {
+    let bus = SharedEventBus::new();
+    let heard = Arc::new(AtomicU32::new(0));
+    let h = heard.clone();
+    bus.subscribe(move |_: &SpawnEnemy| {
+        h.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let spawns = bus.command_queue::<SpawnEnemy>().unwrap();
+    assert!(matches!(bus.command_queue::<SpawnEnemy>(), Err(EventError::CommandQueueTaken(_))));
+    bus.dispatch(SpawnEnemy { at: (1, 1) });
+    bus.publish(SpawnEnemy { at: (2, 2) }).unwrap();
+    bus.try_dispatch(SpawnEnemy { at: (3, 3) }).unwrap();
+    bus.process();
+    assert_eq!(spawns.len(), 3);
+    assert_eq!(spawns.pop(), Some(SpawnEnemy { at: (1, 1) }));
+    assert_eq!(spawns.drain(), [SpawnEnemy { at: (2, 2) }, SpawnEnemy { at: (3, 3) }]);
+    assert!(spawns.is_empty());
+    assert_eq!(heard.load(Ordering::SeqCst), 0);
+    assert!(matches!(
+        bus.publish_ref(&SpawnEnemy { at: (0, 0) }),
+        Err(EventError::CommandByReference(_))
+    ));
+}
+
+#[test]
+fn publishing_a_command_without_a_queue_fails() {
+    let bus = SharedEventBus::new();
+    let spawns = bus.command_queue::<SpawnEnemy>().unwrap();
+    drop(spawns);
+    assert!(matches!(bus.publish(SpawnEnemy { at: (4, 4) }), Err(EventError::NoCommandQueue(_))));
+    let spawns = bus.command_queue::<SpawnEnemy>().unwrap();
+    bus.publish(SpawnEnemy { at: (5, 5) }).unwrap();
+    assert_eq!(spawns.drain().len(), 1);
+}
+
+#[test]
+fn a_declared_command_needs_a_queue() {
+    let bus = SharedEventBus::new();
+    assert!(!bus.is_command::<OpenDoor>());
+    bus.declare_command::<OpenDoor>();
+    assert!(bus.is_command::<OpenDoor>());
+    assert!(matches!(bus.try_dispatch(OpenDoor), Err(EventError::NoCommandQueue(_))));
+}
@@ -0,0 +1,80 @@
+// Event contracts: a subscriber built against the publisher's definition of
+// an event verifies and then gets its events; one built against another
+// definition fails at startup.
+
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+// The publishing crate's events.
+mod combat {
+    use nexus_events::prelude::*;
+
+    #[event_contract]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct PlayerHit {
+        pub player: u32,
+        pub damage: u32,
+    }
+}
+
+// A subscribing crate built against an older definition.
+mod stale_combat {
+    use nexus_events::prelude::*;
+
+    #[event_contract]
+    #[derive(Debug, Clone)]
+    pub struct PlayerHit {
+        pub player: u32,
+    }
+}
+
+use combat::PlayerHit;
+
+#[test]
+fn a_subscriber_with_the_publishers_definition_verifies_and_gets_its_events() {
+    let bus = SharedEventBus::new();
+    bus.publish_contract(PlayerHit::CONTRACT).unwrap();
+    bus.require_contract(PlayerHit::CONTRACT);
+    bus.verify_contracts().unwrap();
+
+    let hits = Arc::new(Mutex::new(Vec::new()));
+    let h = hits.clone();
+    bus.subscribe(move |ev: &PlayerHit| h.lock().unwrap().push(ev.clone()));
+    bus.publish(PlayerHit { player: 1, damage: 7 }).unwrap();
+    assert_eq!(*hits.lock().unwrap(), [PlayerHit { player: 1, damage: 7 }]);
+}
+
+#[test]
+fn a_subscriber_with_another_definition_fails_verification() {
+    let bus = SharedEventBus::new();
+    bus.publish_contract(PlayerHit::CONTRACT).unwrap();
+    bus.require_contract(stale_combat::PlayerHit::CONTRACT);
+
+    let stale = stale_combat::PlayerHit { player: 1 };
+    assert_eq!(stale.player, 1);
+    match bus.verify_contracts() {
+        Err(EventError::ContractMismatch { name, expected, found }) => {
+            assert_eq!(name, "PlayerHit");
+            assert_eq!(expected, stale_combat::PlayerHit::CONTRACT.schema_hash);
+            assert_eq!(found, PlayerHit::CONTRACT.schema_hash);
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn a_contract_nobody_published_is_missing() {
+    let bus = SharedEventBus::new();
+    bus.require_contract(PlayerHit::CONTRACT);
+    assert!(matches!(bus.verify_contracts(), Err(EventError::MissingContract("PlayerHit"))));
+}
+
+#[test]
+fn two_publishers_disagreeing_on_a_definition_are_caught() {
+    let bus = SharedEventBus::new();
+    bus.publish_contract(PlayerHit::CONTRACT).unwrap();
+    bus.publish_contract(PlayerHit::CONTRACT).unwrap();
+    let err = bus.publish_contract(stale_combat::PlayerHit::CONTRACT).unwrap_err();
+    assert!(matches!(err, EventError::ContractMismatch { name: "PlayerHit", .. }));
+}
@@ -0,0 +1,64 @@
+// `debug_snapshot`: what a bus is doing, without waiting for its lock.
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use nexus_events::prelude::*;
+use nexus_events::core::BusSnapshot;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(Debug, Clone)]
+struct Save;
+
+#[test]
+fn an_idle_bus_lists_its_queue_and_handlers() {
+    let bus = SharedEventBus::new();
+    bus.subscribe::<Tick, _>(|_| {});
+    bus.subscribe::<Tick, _>(|_| {});
+    bus.dispatch(Tick);
+    bus.dispatch(Tick);
+    bus.dispatch(Save);
+
+    let snapshot: BusSnapshot = bus.debug_snapshot();
+    assert!(!snapshot.locked);
+    assert_eq!(snapshot.lock_held_for, None);
+    assert_eq!(snapshot.queued.iter().find(|(name, _)| name.ends_with("Tick")).map(|(_, n)| *n), Some(2));
+    assert_eq!(snapshot.queued.iter().find(|(name, _)| name.ends_with("Save")).map(|(_, n)| *n), Some(1));
+    assert_eq!(snapshot.handlers.values().sum::<usize>(), 2);
+    assert!(snapshot.running.is_empty());
+    bus.process();
+    assert!(bus.debug_snapshot().queued.is_empty());
+}
+
+#[test]
+fn a_stuck_handler_shows_up_as_running_on_its_thread() {
+    let bus = SharedEventBus::new();
+    let (entered_tx, entered) = mpsc::channel();
+    let (release, release_rx) = mpsc::channel::<()>();
+    let release_rx = Arc::new(Mutex::new(release_rx));
+    let stuck = bus.subscribe::<Save, _>(move |_| {
+        entered_tx.send(()).unwrap();
+        release_rx.lock().unwrap().recv().unwrap();
+    });
+    let worker = {
+        let bus = bus.clone();
+        thread::Builder::new().name("saver".into()).spawn(move || bus.publish(Save).unwrap()).unwrap()
+    };
+    entered.recv().unwrap();
+
+    let snapshot = bus.debug_snapshot();
+    assert!(!snapshot.locked, "handlers run with the bus unlocked");
+    assert_eq!(snapshot.running.len(), 1);
+    assert!(snapshot.running[0].event.ends_with("Save"));
+    assert_eq!(snapshot.running[0].handler, stuck);
+    assert_eq!(snapshot.running[0].thread, "saver");
+    // Other buses don't see it.
+    assert!(SharedEventBus::new().debug_snapshot().running.is_empty());
+
+    release.send(()).unwrap();
+    worker.join().unwrap();
+    assert!(bus.debug_snapshot().running.is_empty());
+}
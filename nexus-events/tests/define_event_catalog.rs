@@ -0,0 +1,38 @@
+// The event catalog: define_event! types are listed by name with their
+// fields, and generic ones are left out.
+
+use std::any::TypeId;
+
+use nexus_events::catalog::{self, FieldDescriptor};
+use nexus_events::prelude::*;
+
+define_event! {
+    pub struct ItemPicked { pub item: u32, pub slots: Vec<u8> }
+    pub struct Paused;
+    pub enum Input { Down { key: u32 }, Up { key: u32 } }
+    pub struct Wrapped<T: Clone> { pub value: T }
+}
+
+#[test]
+fn cataloged_events_are_found_by_name_with_their_fields() {
+    let picked = catalog::find("ItemPicked").expect("ItemPicked is cataloged");
+    assert_eq!(picked.type_id, TypeId::of::<ItemPicked>());
+    assert_eq!(picked.fields, &[
+        FieldDescriptor { name: "item", ty: "u32" },
+        FieldDescriptor { name: "slots", ty: "Vec<u8>" },
+    ]);
+    assert!(catalog::find("Paused").unwrap().fields.is_empty());
+    assert_eq!(catalog::find("Input").unwrap().type_id, TypeId::of::<Input>());
+}
+
+#[test]
+fn generic_events_are_not_cataloged() {
+    assert!(catalog::find("Wrapped").is_none());
+    let _ = Wrapped { value: 1 };
+}
+
+#[test]
+fn the_catalog_lists_events_sorted_by_name() {
+    let names: Vec<&str> = catalog::all().iter().map(|ev| ev.name).collect();
+    assert!(names.windows(2).all(|w| w[0] <= w[1]));
+}
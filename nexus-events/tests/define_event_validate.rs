@@ -0,0 +1,80 @@
+// Event validation: `#[validate]` events are checked on every publish path,
+// and rejected ones can go to the dead-letter handler.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+define_event! {
+    #[validate]
+    pub struct HealthChanged { pub health: i32 }
+    #[version(2)]
+    #[validate]
+    pub struct Moved { pub x: f32 }
+    pub struct Unchecked { pub health: i32 }
+}
+
+impl Validate for HealthChanged {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.health < 0 {
+            return Err(ValidationError::new("health is negative"));
+        }
+        Ok(())
+    }
+}
+
+impl Validate for Moved {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.x.is_nan() {
+            return Err(ValidationError::new("x is NaN"));
+        }
+        Ok(())
+    }
+}
+
+fn bus_counting_rejects() -> (SharedEventBus, Arc<AtomicU32>) {
+    let rejected = Arc::new(AtomicU32::new(0));
+    let r = rejected.clone();
+    let bus = SharedEventBus::builder()
+        .dead_letter_invalid(true)
+        .dead_letters(move |_| {
+            r.fetch_add(1, Ordering::SeqCst);
+        })
+        .build();
+    (bus, rejected)
+}
+
+#[test]
+fn invalid_events_are_rejected_on_every_publish_path() {
+    let (bus, _) = bus_counting_rejects();
+    let handled = Arc::new(AtomicU32::new(0));
+    let h = handled.clone();
+    bus.subscribe(move |_: &HealthChanged| {
+        h.fetch_add(1, Ordering::SeqCst);
+    });
+    bus.subscribe(|_: &Moved| {});
+
+    assert!(bus.publish(HealthChanged { health: 10 }).is_ok());
+    let err = bus.publish(HealthChanged { health: -1 }).unwrap_err();
+    assert!(matches!(err, EventError::ValidationFailed { .. }));
+    assert!(bus.try_dispatch(Moved { x: f32::NAN }).is_err());
+    bus.dispatch(HealthChanged { health: -5 });
+    bus.process();
+    assert_eq!(handled.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn rejected_events_are_dead_lettered_and_unchecked_ones_are_not() {
+    let (bus, rejected) = bus_counting_rejects();
+    bus.subscribe(|_: &HealthChanged| {});
+    bus.subscribe(|_: &Moved| {});
+    bus.subscribe(|_: &Unchecked| {});
+
+    let _ = bus.publish(HealthChanged { health: -1 });
+    let _ = bus.try_dispatch(Moved { x: f32::NAN });
+    assert!(bus.publish(Unchecked { health: -1 }).is_ok());
+    bus.dispatch(HealthChanged { health: -5 });
+    bus.process();
+    assert_eq!(rejected.load(Ordering::SeqCst), 3);
+}
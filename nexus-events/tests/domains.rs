@@ -0,0 +1,82 @@
+// Domains: named sub-buses that only share the event types they export
+// and import.
+
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Collision(u32);
+
+#[derive(Debug, Clone)]
+struct Loaded(&'static str);
+
+fn recorder<E: Event + Clone>(bus: &SharedEventBus) -> Arc<Mutex<Vec<E>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |ev: &E| s.lock().unwrap().push(ev.clone()));
+    seen
+}
+
+fn collisions(seen: &Arc<Mutex<Vec<Collision>>>) -> Vec<u32> {
+    seen.lock().unwrap().iter().map(|c| c.0).collect()
+}
+
+#[test]
+fn domains_using_the_same_type_dont_hear_each_other() {
+    let bus = SharedEventBus::new();
+    let physics = bus.domain("physics");
+    let audio = bus.domain("audio");
+    assert_eq!(physics.name(), "physics");
+
+    let physics_loaded = recorder::<Loaded>(&physics);
+    let audio_loaded = recorder::<Loaded>(&audio);
+    let root_loaded = recorder::<Loaded>(&bus);
+    physics.publish(Loaded("physics")).unwrap();
+    audio.dispatch(Loaded("audio"));
+    bus.process();
+    assert_eq!(physics_loaded.lock().unwrap().len(), 1);
+    assert_eq!(audio_loaded.lock().unwrap()[0].0, "audio");
+    assert!(root_loaded.lock().unwrap().is_empty());
+
+    // The same name is the same domain.
+    let again = bus.domain("physics");
+    again.publish(Loaded("again")).unwrap();
+    assert_eq!(physics_loaded.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn exported_events_reach_the_root_and_importers() {
+    let bus = SharedEventBus::new();
+    let physics = bus.domain("physics");
+    let audio = bus.domain("audio");
+    let ui = bus.domain("ui");
+
+    // Exporting twice is exporting once.
+    physics.export::<Collision>().export::<Collision>();
+    audio.import::<Collision>();
+    let heard = recorder::<Collision>(&audio);
+    let root_heard = recorder::<Collision>(&bus);
+    let ui_heard = recorder::<Collision>(&ui);
+    physics.dispatch(Collision(7));
+    bus.process();
+    assert_eq!(collisions(&heard), [7]);
+    assert_eq!(root_heard.lock().unwrap().len(), 1);
+    assert!(ui_heard.lock().unwrap().is_empty());
+}
+
+#[test]
+fn root_events_reach_importers_without_echoing_back() {
+    let bus = SharedEventBus::new();
+    let physics = bus.domain("physics");
+    let audio = bus.domain("audio");
+    physics.export::<Collision>().import::<Collision>();
+    audio.import::<Collision>();
+    let audio_heard = recorder::<Collision>(&audio);
+    let physics_heard = recorder::<Collision>(&physics);
+
+    bus.publish(Collision(8)).unwrap();
+    physics.publish(Collision(9)).unwrap();
+    assert_eq!(collisions(&audio_heard), [8, 9]);
+    assert_eq!(collisions(&physics_heard), [8, 9]);
+}
@@ -0,0 +1,87 @@
+// Event classes: queue order, budgets and per-class limits.
+
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+define_event! {
+    #[class(control)]
+    pub struct Paused;
+    pub struct Moved { pub x: i32 }
+    #[validate]
+    #[class(telemetry)]
+    pub struct FrameTime { pub ms: u32 }
+}
+
+impl Validate for FrameTime {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Sampled<T>(T);
+
+fn log(bus: &SharedEventBus) -> Arc<Mutex<Vec<String>>> {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let l = log.clone();
+    bus.subscribe::<Paused, _>(move |_| l.lock().unwrap().push("paused".to_string()));
+    let l = log.clone();
+    bus.subscribe::<Moved, _>(move |m| l.lock().unwrap().push(format!("moved {}", m.x)));
+    let l = log.clone();
+    bus.subscribe::<FrameTime, _>(move |f| l.lock().unwrap().push(format!("frame {}", f.ms)));
+    let l = log.clone();
+    bus.subscribe::<Sampled<u32>, _>(move |s| l.lock().unwrap().push(format!("sampled {}", s.0)));
+    log
+}
+
+#[test]
+fn classes_are_processed_in_order() {
+    classify_as::<Sampled<u32>>(EventClass::Telemetry);
+
+    // Control first, then gameplay, then telemetry, each in dispatch order.
+    let bus = SharedEventBus::new();
+    let seen = log(&bus);
+    bus.dispatch(FrameTime { ms: 16 });
+    bus.dispatch(Sampled(1u32));
+    bus.dispatch(Moved { x: 1 });
+    bus.dispatch(Paused);
+    bus.dispatch(Moved { x: 2 });
+    assert_eq!(bus.queued_in_class(EventClass::Telemetry), 2);
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), ["paused", "moved 1", "moved 2", "frame 16", "sampled 1"]);
+}
+
+#[test]
+fn a_spent_budget_leaves_later_classes_queued() {
+    let bus = SharedEventBus::new();
+    let seen = log(&bus);
+    bus.dispatch(FrameTime { ms: 17 });
+    bus.dispatch(Moved { x: 3 });
+    let outcome = bus.process_with_budget(ProcessBudget::events(1));
+    assert_eq!((outcome.processed, outcome.carried_over), (1, 1));
+    assert_eq!(*seen.lock().unwrap(), ["moved 3"]);
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), ["moved 3", "frame 17"]);
+}
+
+#[test]
+fn limits_drop_events_of_their_class_only() {
+    let bus = SharedEventBus::builder()
+        .class_limit(EventClass::Telemetry, ClassLimit::drop_oldest(2))
+        .class_limit(EventClass::Gameplay, ClassLimit::drop_newest(1))
+        .class_order([EventClass::Telemetry])
+        .build();
+    let seen = log(&bus);
+    for ms in 1..=4 {
+        bus.dispatch(FrameTime { ms });
+    }
+    bus.dispatch(Moved { x: 1 });
+    bus.dispatch(Moved { x: 2 });
+    bus.dispatch(Paused);
+    assert_eq!(bus.dropped_in_class(EventClass::Telemetry), 2);
+    assert_eq!(bus.dropped_in_class(EventClass::Gameplay), 1);
+    assert_eq!(bus.dropped_in_class(EventClass::Control), 0);
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), ["frame 3", "frame 4", "paused", "moved 1"]);
+}
@@ -0,0 +1,25 @@
+// Dense event type ids: every non-generic `define_event!` type gets its own,
+// and looking one up never panics.
+
+use nexus_events::core::EventTypeId;
+use nexus_events::prelude::*;
+
+define_event! {
+    pub struct Spawned { pub id: u32 }
+    pub struct Despawned { pub id: u32 }
+}
+
+#[test]
+fn defined_events_have_distinct_ids() {
+    let spawned = EventTypeId::of::<Spawned>().expect("Spawned has an id");
+    let despawned = EventTypeId::of::<Despawned>().expect("Despawned has an id");
+    assert_ne!(spawned, despawned);
+    // Cached after the first lookup.
+    assert_eq!(EventTypeId::of::<Spawned>(), Some(spawned));
+}
+
+#[test]
+fn types_outside_the_table_have_no_id() {
+    struct Plain;
+    assert_eq!(EventTypeId::lookup(std::any::TypeId::of::<Plain>()), None);
+}
@@ -0,0 +1,139 @@
+// Recording and replaying event logs: ticks written as deltas and runs,
+// logs cut short, and zstd blocks.
+
+#![cfg(feature = "serde")]
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use nexus_events::core::codec::EventCodec;
+use nexus_events::core::SharedEventBus;
+use nexus_events::eventstore::{self, EventStore, LogReader};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct GameTick {
+    frame: u64,
+    dt: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Chat {
+    text: String,
+}
+
+fn codec() -> EventCodec {
+    let mut codec = EventCodec::new();
+    codec.register::<GameTick>("GameTick").unwrap().register::<Chat>("Chat").unwrap();
+    codec
+}
+
+fn temp_log(name: &str) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    let path = std::env::temp_dir().join(format!("nexus-{}-{}-{name}.log", std::process::id(), n));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+/// Records 10,000 ticks with a chat message every 1,000, and returns what
+/// was recorded in order.
+fn record(store: &EventStore) -> Vec<(Option<u64>, Option<String>)> {
+    let mut expected = Vec::new();
+    for frame in 0..10_000 {
+        store.append(&GameTick { frame, dt: 1.0 / 60.0 }).unwrap();
+        expected.push((Some(frame), None));
+        if frame % 1000 == 999 {
+            let text = "x".repeat(frame as usize / 1000);
+            store.append(&Chat { text: text.clone() }).unwrap();
+            expected.push((None, Some(text)));
+        }
+    }
+    store.flush().unwrap();
+    expected
+}
+
+fn read(path: &PathBuf, codec: &EventCodec) -> Vec<(Option<u64>, Option<String>)> {
+    LogReader::open(path, codec).unwrap()
+        .map(|ev| {
+            let ev = ev.unwrap();
+            let any = ev.event().as_any();
+            (any.downcast_ref::<GameTick>().map(|t| t.frame), any.downcast_ref::<Chat>().map(|c| c.text.clone()))
+        })
+        .collect()
+}
+
+#[test]
+fn ticks_are_written_as_runs_and_read_back_in_order() {
+    let path = temp_log("ticks");
+    let bus = SharedEventBus::new();
+    let store = EventStore::open(&path, &bus, codec()).unwrap();
+    let expected = record(&store);
+
+    // A full encoding of a tick alone is over 20 bytes.
+    assert!(std::fs::metadata(&path).unwrap().len() < 10_000, "log is {} bytes", std::fs::metadata(&path).unwrap().len());
+    assert_eq!(read(&path, &codec()), expected);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn reopening_a_log_appends_after_it() {
+    let path = temp_log("reopen");
+    let bus = SharedEventBus::new();
+    let mut expected = record(&EventStore::open(&path, &bus, codec()).unwrap());
+    expected.extend(record(&EventStore::open(&path, &bus, codec()).unwrap()));
+
+    assert_eq!(read(&path, &codec()), expected);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn a_log_cut_short_ends_at_the_last_whole_frame() {
+    let path = temp_log("cut");
+    let bus = SharedEventBus::new();
+    let store = EventStore::open(&path, &bus, codec()).unwrap();
+    store.append(&Chat { text: "first".into() }).unwrap();
+    store.append(&Chat { text: "second message".into() }).unwrap();
+    drop(store);
+    let len = std::fs::metadata(&path).unwrap().len();
+    std::fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 3).unwrap();
+
+    assert_eq!(read(&path, &codec()), vec![(None, Some("first".to_string()))]);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn replay_dispatches_the_recorded_events() {
+    let path = temp_log("replay");
+    let live = SharedEventBus::new();
+    let store = EventStore::open(&path, &live, codec()).unwrap().record::<GameTick>();
+    for frame in 0..100 {
+        live.publish(GameTick { frame, dt: 0.5 }).unwrap();
+    }
+    drop(store);
+
+    let fresh = SharedEventBus::new();
+    let total = std::sync::Arc::new(AtomicU64::new(0));
+    let seen = total.clone();
+    fresh.subscribe(move |tick: &GameTick| {
+        seen.fetch_add(tick.frame, Ordering::Relaxed);
+    });
+    assert_eq!(eventstore::replay(&path, &codec(), &fresh).unwrap(), 100);
+    fresh.process();
+    assert_eq!(total.load(Ordering::Relaxed), (0..100).sum::<u64>());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn compressed_logs_are_read_a_block_at_a_time() {
+    let path = temp_log("zstd");
+    let bus = SharedEventBus::new();
+    let mut expected = record(&EventStore::open_compressed(&path, &bus, codec(), 3).unwrap());
+    // Opened again without asking for compression, it stays compressed.
+    expected.extend(record(&EventStore::open(&path, &bus, codec()).unwrap()));
+
+    assert_eq!(read(&path, &codec()), expected);
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,136 @@
+// Generated handler companions: `subscribe_<method>` subscribes a method of a
+// shared component through a `SubscriptionSet`, once, and keeps the
+// registration in the set under a key of its own.
+
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Damage {
+    amount: u32,
+}
+
+struct Player {
+    hp: u32,
+    hits: u32,
+}
+
+impl Player {
+    #[event_handler(Damage)]
+    fn on_damage(&mut self, evt: &Damage) {
+        self.hp = self.hp.saturating_sub(evt.amount);
+        self.hits += 1;
+    }
+}
+
+// Same handler name as the player's.
+struct Crate {
+    broken: bool,
+}
+
+impl Crate {
+    #[event_handler(Damage)]
+    fn on_damage(&mut self, _evt: &Damage) {
+        self.broken = true;
+    }
+}
+
+struct Laser;
+struct Cannon;
+
+struct Turret<Kind> {
+    hits: u32,
+    _kind: Kind,
+}
+
+impl Turret<Laser> {
+    #[event_handler(Damage)]
+    fn on_damage(&mut self, _evt: &Damage) {
+        self.hits += 1;
+    }
+}
+
+impl Turret<Cannon> {
+    #[event_handler(Damage)]
+    fn on_damage(&mut self, evt: &Damage) {
+        self.hits += evt.amount;
+    }
+}
+
+fn player() -> Arc<Mutex<Player>> {
+    Arc::new(Mutex::new(Player { hp: 100, hits: 0 }))
+}
+
+#[test]
+fn the_companion_subscribes_the_method() {
+    let bus = SharedEventBus::new();
+    let player = player();
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Player::subscribe_on_damage(&player, &mut subscriptions).unwrap();
+
+    bus.publish(Damage { amount: 30 }).unwrap();
+    assert_eq!(player.lock().unwrap().hp, 70);
+}
+
+#[test]
+fn calling_the_companion_twice_delivers_once() {
+    let bus = SharedEventBus::new();
+    let player = player();
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Player::subscribe_on_damage(&player, &mut subscriptions).unwrap();
+    let err = Player::subscribe_on_damage(&player, &mut subscriptions).unwrap_err();
+    assert!(matches!(err, EventError::DuplicateHandler { event, .. } if event.ends_with("Damage")));
+    assert_eq!(subscriptions.len(), 1);
+
+    bus.publish(Damage { amount: 10 }).unwrap();
+    let player = player.lock().unwrap();
+    assert_eq!(player.hits, 1);
+    assert_eq!(player.hp, 90);
+}
+
+#[test]
+fn the_companion_subscribes_again_once_the_set_is_cleared() {
+    let bus = SharedEventBus::new();
+    let player = player();
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Player::subscribe_on_damage(&player, &mut subscriptions).unwrap();
+    subscriptions.clear();
+    Player::subscribe_on_damage(&player, &mut subscriptions).unwrap();
+
+    bus.publish(Damage { amount: 1 }).unwrap();
+    assert_eq!(player.lock().unwrap().hits, 1);
+}
+
+#[test]
+fn same_named_handlers_of_different_types_share_a_set() {
+    let bus = SharedEventBus::new();
+    let player = player();
+    let crate_ = Arc::new(Mutex::new(Crate { broken: false }));
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Player::subscribe_on_damage(&player, &mut subscriptions).unwrap();
+    Crate::subscribe_on_damage(&crate_, &mut subscriptions).unwrap();
+    let keys: Vec<&str> = subscriptions.registrations().collect();
+    assert_eq!(keys.len(), 2);
+    assert!(keys[0].contains("Player::on_damage@"));
+    assert!(keys[1].contains("Crate::on_damage@"));
+
+    bus.publish(Damage { amount: 5 }).unwrap();
+    assert_eq!(player.lock().unwrap().hits, 1);
+    assert!(crate_.lock().unwrap().broken);
+}
+
+#[test]
+fn same_named_handlers_in_impl_blocks_of_one_generic_type_share_a_set() {
+    let bus = SharedEventBus::new();
+    let laser = Arc::new(Mutex::new(Turret { hits: 0, _kind: Laser }));
+    let cannon = Arc::new(Mutex::new(Turret { hits: 0, _kind: Cannon }));
+    let mut subscriptions = SubscriptionSet::new(&bus);
+    Turret::<Laser>::subscribe_on_damage(&laser, &mut subscriptions).unwrap();
+    Turret::<Cannon>::subscribe_on_damage(&cannon, &mut subscriptions).unwrap();
+    assert_eq!(subscriptions.registrations().count(), 2);
+
+    bus.publish(Damage { amount: 4 }).unwrap();
+    assert_eq!(laser.lock().unwrap().hits, 1);
+    assert_eq!(cannon.lock().unwrap().hits, 4);
+}
@@ -0,0 +1,131 @@
+// Field-level handlers: a `uses(...)` handler locks only the fields it lists,
+// so handlers using disjoint fields of one component run at the same time.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
+
+use nexus_events::prelude::*;
+
+/// Where two handlers wait for each other, for up to a second.
+#[derive(Default)]
+struct Rendezvous {
+    arrived: Mutex<u32>,
+    all_here: Condvar,
+    met: AtomicUsize,
+}
+
+impl Rendezvous {
+    fn meet(&self) {
+        let mut arrived = self.arrived.lock().unwrap();
+        *arrived += 1;
+        self.all_here.notify_all();
+        let (arrived, _) = self.all_here
+            .wait_timeout_while(arrived, Duration::from_secs(1), |n| *n < 2)
+            .unwrap();
+        if *arrived == 2 {
+            self.met.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Tick {
+    dt: f32,
+    meet: Option<Arc<Rendezvous>>,
+}
+
+#[derive(Clone)]
+struct Hit {
+    damage: u32,
+    meet: Option<Arc<Rendezvous>>,
+}
+
+#[derive(Debug, Clone)]
+struct Brake;
+
+struct Ship {
+    position: Mutex<f32>,
+    velocity: Mutex<f32>,
+    shields: RwLock<u32>,
+}
+
+impl Ship {
+    #[event_handler(Tick, uses(position, velocity))]
+    fn on_tick(evt: &Tick, position: &mut f32, velocity: &mut f32) {
+        if let Some(meet) = &evt.meet {
+            meet.meet();
+        }
+        *position += *velocity * evt.dt;
+    }
+
+    #[event_handler(Hit, uses(shields))]
+    fn on_hit(evt: &Hit, shields: &mut u32) {
+        if let Some(meet) = &evt.meet {
+            meet.meet();
+        }
+        *shields = shields.saturating_sub(evt.damage);
+    }
+
+    // Listed out of order: fields are locked by name all the same.
+    #[event_handler(Brake, uses(velocity, position))]
+    fn on_brake(_evt: &Brake, velocity: &mut f32, position: &mut f32, ctx: &mut EventContext) {
+        *velocity = 0.0;
+        *position = position.round();
+        ctx.stop_propagation();
+    }
+}
+
+fn ship(bus: &SharedEventBus) -> (Arc<Ship>, SubscriptionSet) {
+    let ship = Arc::new(Ship { position: Mutex::new(0.0), velocity: Mutex::new(2.0), shields: RwLock::new(10) });
+    let mut subscriptions = SubscriptionSet::new(bus);
+    Ship::subscribe_on_tick(&ship, &mut subscriptions).unwrap();
+    Ship::subscribe_on_hit(&ship, &mut subscriptions).unwrap();
+    Ship::subscribe_on_brake(&ship, &mut subscriptions).unwrap();
+    (ship, subscriptions)
+}
+
+#[test]
+fn each_handler_gets_the_fields_it_uses() {
+    let bus = SharedEventBus::new();
+    let (ship, _subscriptions) = ship(&bus);
+
+    bus.publish(Tick { dt: 0.25, meet: None }).unwrap();
+    bus.publish(Hit { damage: 3, meet: None }).unwrap();
+    assert_eq!(*ship.position.lock().unwrap(), 0.5);
+    assert_eq!(*ship.shields.read().unwrap(), 7);
+
+    bus.publish(Brake).unwrap();
+    assert_eq!(*ship.velocity.lock().unwrap(), 0.0);
+    assert_eq!(*ship.position.lock().unwrap(), 1.0);
+}
+
+#[test]
+fn handlers_using_disjoint_fields_run_at_the_same_time() {
+    let bus = SharedEventBus::new();
+    let (ship, _subscriptions) = ship(&bus);
+    let meet = Arc::new(Rendezvous::default());
+
+    let (b, m) = (bus.clone(), meet.clone());
+    let ticking = thread::spawn(move || b.publish(Tick { dt: 1.0, meet: Some(m) }).unwrap());
+    let (b, m) = (bus.clone(), meet.clone());
+    let hitting = thread::spawn(move || b.publish(Hit { damage: 4, meet: Some(m) }).unwrap());
+    ticking.join().unwrap();
+    hitting.join().unwrap();
+
+    // Each waited inside its handler until the other got there too.
+    assert_eq!(meet.met.load(Ordering::SeqCst), 2);
+    assert_eq!(*ship.position.lock().unwrap(), 2.0);
+    assert_eq!(*ship.shields.read().unwrap(), 6);
+}
+
+#[test]
+fn the_handlers_stop_once_the_component_is_gone() {
+    let bus = SharedEventBus::new();
+    let (ship, subscriptions) = ship(&bus);
+    drop(ship);
+
+    bus.publish(Hit { damage: 1, meet: None }).unwrap();
+    assert_eq!(subscriptions.len(), 3);
+}
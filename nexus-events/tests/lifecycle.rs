@@ -0,0 +1,90 @@
+// Generated lifecycle: `activate` subscribes the handlers registered through
+// the `subscribe_<method>` companions again on the bus it is given, and
+// `deactivate` takes them off.
+
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Intruder;
+
+#[derive(EventSubscriber)]
+#[event_subscriber(lifecycle)]
+struct Guard {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+    alarms: u32,
+}
+
+impl Guard {
+    #[event_handler(Intruder)]
+    fn on_intruder(&mut self, _evt: &Intruder) {
+        self.alarms += 1;
+    }
+}
+
+/// A guard whose handler is registered on a bus of its own, inactive.
+fn guard() -> Arc<Mutex<Guard>> {
+    let guard = Arc::new(Mutex::new(Guard {
+        id: 1,
+        active: false,
+        subscriptions: SubscriptionSet::new(&SharedEventBus::new()),
+        alarms: 0,
+    }));
+    let mut g = guard.lock().unwrap();
+    Guard::subscribe_on_intruder(&guard, &mut g.subscriptions).unwrap();
+    drop(g);
+    guard
+}
+
+#[test]
+fn an_activated_component_receives_events() {
+    let bus = SharedEventBus::new();
+    let guard = guard();
+    guard.lock().unwrap().activate(&bus).unwrap();
+    assert!(guard.lock().unwrap().is_active());
+
+    bus.publish(Intruder).unwrap();
+    assert_eq!(guard.lock().unwrap().alarms, 1);
+}
+
+#[test]
+fn a_deactivated_component_receives_nothing() {
+    let bus = SharedEventBus::new();
+    let guard = guard();
+    guard.lock().unwrap().activate(&bus).unwrap();
+    guard.lock().unwrap().deactivate().unwrap();
+
+    bus.publish(Intruder).unwrap();
+    assert_eq!(guard.lock().unwrap().alarms, 0);
+    assert!(guard.lock().unwrap().subscriptions().is_empty());
+}
+
+#[test]
+fn activating_again_does_not_double_the_handlers() {
+    let bus = SharedEventBus::new();
+    let guard = guard();
+    guard.lock().unwrap().activate(&bus).unwrap();
+    guard.lock().unwrap().deactivate().unwrap();
+    guard.lock().unwrap().activate(&bus).unwrap();
+    guard.lock().unwrap().activate(&bus).unwrap();
+
+    bus.publish(Intruder).unwrap();
+    assert_eq!(guard.lock().unwrap().alarms, 1);
+    assert_eq!(guard.lock().unwrap().subscriptions().len(), 1);
+}
+
+#[test]
+fn activating_on_another_bus_moves_the_handlers() {
+    let (first, second) = (SharedEventBus::new(), SharedEventBus::new());
+    let guard = guard();
+    guard.lock().unwrap().activate(&first).unwrap();
+    guard.lock().unwrap().activate(&second).unwrap();
+
+    first.publish(Intruder).unwrap();
+    assert_eq!(guard.lock().unwrap().alarms, 0);
+    second.publish(Intruder).unwrap();
+    assert_eq!(guard.lock().unwrap().alarms, 1);
+}
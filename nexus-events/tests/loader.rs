@@ -0,0 +1,116 @@
+// Resource loading: each `LoadRequested` is loaded by one worker for its
+// kind, which reports progress and then completion or failure on the bus.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nexus_events::loader::{LoadCompleted, LoadFailed, LoadProgress, LoadRequested, Loader};
+use nexus_events::prelude::*;
+
+#[derive(Debug, PartialEq)]
+struct Texture {
+    path: String,
+}
+
+fn collect<E: Event + Clone>(bus: &SharedEventBus) -> Arc<Mutex<Vec<E>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |ev: &E| s.lock().unwrap().push(ev.clone()));
+    seen
+}
+
+/// Processes `bus` until `done` holds, for up to five seconds.
+fn process_until(bus: &SharedEventBus, done: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() && Instant::now() < deadline {
+        bus.process();
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn request(bus: &SharedEventBus, path: &str, kind: &str) {
+    bus.dispatch(LoadRequested { path: path.to_string(), kind: kind.to_string() });
+}
+
+#[test]
+fn a_request_reports_progress_then_completes_with_a_handle_to_the_resource() {
+    let bus = SharedEventBus::new();
+    let mut loader = Loader::new(bus.clone());
+    loader.add_worker("texture", |path, progress| {
+        progress.report(0.5);
+        progress.report(1.0);
+        Ok(Texture { path: path.to_string() })
+    });
+    let progress = collect::<LoadProgress>(&bus);
+    let completed = collect::<LoadCompleted>(&bus);
+
+    request(&bus, "hero.png", "texture");
+    process_until(&bus, || !completed.lock().unwrap().is_empty());
+
+    let steps: Vec<f32> = progress.lock().unwrap().iter().map(|p| p.progress).collect();
+    assert_eq!(steps, [0.5, 1.0]);
+    let done = completed.lock().unwrap()[0].clone();
+    assert_eq!((done.path.as_str(), done.kind.as_str()), ("hero.png", "texture"));
+    let texture = loader.get::<Texture>(done.handle).unwrap();
+    assert_eq!(*texture, Texture { path: "hero.png".to_string() });
+    assert!(loader.get::<String>(done.handle).is_none());
+
+    loader.release(done.handle);
+    assert!(loader.get::<Texture>(done.handle).is_none());
+}
+
+#[test]
+fn a_kind_without_workers_fails() {
+    let bus = SharedEventBus::new();
+    let _loader = Loader::new(bus.clone());
+    let failed = collect::<LoadFailed>(&bus);
+
+    request(&bus, "theme.ogg", "audio");
+    process_until(&bus, || !failed.lock().unwrap().is_empty());
+
+    let failed = failed.lock().unwrap();
+    assert_eq!(failed[0].path, "theme.ogg");
+    assert!(failed[0].error.contains("audio"));
+}
+
+#[test]
+fn a_worker_error_is_reported_as_a_failure() {
+    let bus = SharedEventBus::new();
+    let mut loader = Loader::new(bus.clone());
+    loader.add_worker::<Texture, _>("texture", |path, _| Err(format!("{} is corrupt", path)));
+    let failed = collect::<LoadFailed>(&bus);
+    let completed = collect::<LoadCompleted>(&bus);
+
+    request(&bus, "broken.png", "texture");
+    process_until(&bus, || !failed.lock().unwrap().is_empty());
+
+    assert_eq!(failed.lock().unwrap()[0].error, "broken.png is corrupt");
+    assert!(completed.lock().unwrap().is_empty());
+}
+
+#[test]
+fn requests_of_one_kind_are_shared_between_its_workers() {
+    let bus = SharedEventBus::new();
+    let mut loader = Loader::new(bus.clone());
+    let loads: Vec<Arc<AtomicUsize>> = (0..2).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+    for count in &loads {
+        let count = count.clone();
+        loader.add_worker("texture", move |path, _| {
+            count.fetch_add(1, Ordering::SeqCst);
+            Ok(path.to_string())
+        });
+    }
+    let completed = collect::<LoadCompleted>(&bus);
+
+    for i in 0..4 {
+        request(&bus, &format!("tile{}.png", i), "texture");
+    }
+    process_until(&bus, || completed.lock().unwrap().len() == 4);
+    loader.shutdown();
+
+    assert_eq!(completed.lock().unwrap().len(), 4);
+    assert_eq!(loads[0].load(Ordering::SeqCst), 2);
+    assert_eq!(loads[1].load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,90 @@
+// The single-threaded bus: handlers that own non-`Send` state, and
+// `#[event_sender]` methods sending on a local bus.
+
+#![cfg(feature = "local")]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use nexus_events::core::{EventEmitter, LocalSharedEventBus, LocalSubscriptionSet};
+use nexus_events::event_sender;
+
+#[derive(Debug, Clone)]
+struct ScoreChanged {
+    score: u32,
+}
+
+#[derive(Default)]
+struct UiModel {
+    shown: Vec<u32>,
+}
+
+#[test]
+fn mutable_handlers_own_their_state() {
+    let bus = LocalSharedEventBus::new();
+    let renders = Rc::new(Cell::new(0));
+    let r = renders.clone();
+    let mut model = UiModel::default();
+    bus.subscribe_mut(move |ev: &ScoreChanged| {
+        model.shown.push(ev.score);
+        r.set(model.shown.len());
+    });
+
+    bus.dispatch(ScoreChanged { score: 1 });
+    bus.dispatch(ScoreChanged { score: 2 });
+    bus.process();
+    assert_eq!(renders.get(), 2);
+}
+
+#[test]
+fn reentrant_calls_skip_the_running_handler() {
+    let bus = LocalSharedEventBus::new();
+    let calls = Rc::new(Cell::new(0));
+    let (b, c) = (bus.clone(), calls.clone());
+    let mut set = LocalSubscriptionSet::new(&bus);
+    set.subscribe_mut(move |ev: &ScoreChanged| {
+        c.set(c.get() + 1);
+        if ev.score == 0 {
+            b.dispatch(ScoreChanged { score: 1 });
+            b.process();
+        }
+    });
+
+    bus.dispatch(ScoreChanged { score: 0 });
+    bus.process();
+    assert_eq!(calls.get(), 1);
+    drop(set);
+    bus.dispatch(ScoreChanged { score: 0 });
+    bus.process();
+    assert_eq!(calls.get(), 1);
+}
+
+struct Hud {
+    bus: LocalSharedEventBus,
+}
+
+impl EventEmitter for Hud {
+    type Bus = LocalSharedEventBus;
+
+    fn sender_named(&self, name: &str) -> Option<&LocalSharedEventBus> {
+        (name == "ui").then_some(&self.bus)
+    }
+}
+
+impl Hud {
+    #[event_sender(ScoreChanged, bus = "ui")]
+    fn show(&self, score: u32) {}
+}
+
+#[test]
+fn senders_dispatch_on_a_local_bus() {
+    let hud = Hud { bus: LocalSharedEventBus::new() };
+    let seen = Rc::new(Cell::new(0));
+    let s = seen.clone();
+    hud.bus.subscribe(move |ev: &ScoreChanged| s.set(ev.score));
+
+    hud.show(42);
+    assert_eq!(hud.bus.pending(), 1);
+    hud.bus.process();
+    assert_eq!(seen.get(), 42);
+}
@@ -0,0 +1,83 @@
+// Mapping stages: chains, cycle detection and loops through handlers.
+
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct RawInput { key: char }
+
+#[derive(Debug, Clone, PartialEq)]
+enum GameAction { Jump, Fire }
+
+#[derive(Debug, Clone, PartialEq)]
+struct Sound(&'static str);
+
+#[derive(Debug, Clone)]
+struct Ping;
+
+#[derive(Debug, Clone)]
+struct Pong;
+
+/// A bus mapping input to actions and actions to sounds. Returns the id of
+/// the second stage.
+fn pipeline() -> (SharedEventBus, HandlerId) {
+    let bus = SharedEventBus::new();
+    bus.map::<RawInput, GameAction, _>(|raw| match raw.key {
+        ' ' => Some(GameAction::Jump),
+        'f' => Some(GameAction::Fire),
+        _ => None,
+    }).unwrap();
+    let stage = bus.map::<GameAction, Sound, _>(|action| Some(match action {
+        GameAction::Jump => Sound("boing"),
+        GameAction::Fire => Sound("bang"),
+    })).unwrap();
+    (bus, stage)
+}
+
+#[test]
+fn events_go_through_chained_stages() {
+    let (bus, _) = pipeline();
+    let actions = Arc::new(Mutex::new(Vec::new()));
+    let a = actions.clone();
+    bus.subscribe::<GameAction, _>(move |action| a.lock().unwrap().push(action.clone()));
+    let sounds = Arc::new(Mutex::new(Vec::new()));
+    let s = sounds.clone();
+    bus.subscribe::<Sound, _>(move |sound| s.lock().unwrap().push(sound.0));
+
+    // Queued and immediate publishes both go through the stages.
+    bus.dispatch(RawInput { key: ' ' });
+    bus.process();
+    bus.publish(RawInput { key: 'f' }).unwrap();
+    bus.publish(RawInput { key: 'x' }).unwrap();
+    assert_eq!(*actions.lock().unwrap(), [GameAction::Jump, GameAction::Fire]);
+    assert_eq!(*sounds.lock().unwrap(), ["boing", "bang"]);
+}
+
+#[test]
+fn a_stage_closing_a_loop_is_refused() {
+    let (bus, stage) = pipeline();
+    let err = bus.map::<Sound, RawInput, _>(|_| Some(RawInput { key: ' ' })).unwrap_err();
+    assert!(matches!(&err, EventError::MapCycle { path } if path.len() == 4));
+    assert!(err.to_string().contains("Sound -> "), "{err}");
+    assert!(matches!(bus.map::<Ping, Ping, _>(|_| Some(Ping)), Err(EventError::MapCycle { .. })));
+
+    // Once the stage closing it is gone, the loop is open again.
+    bus.unsubscribe::<GameAction>(stage);
+    assert!(bus.map::<Sound, RawInput, _>(|_| None).is_ok());
+}
+
+#[test]
+fn a_loop_through_a_handler_stops_when_the_event_comes_round() {
+    let bus = SharedEventBus::new();
+    let pongs = Arc::new(Mutex::new(0));
+    let p = pongs.clone();
+    let handler_bus = bus.clone();
+    bus.map::<Ping, Pong, _>(|_| Some(Pong)).unwrap();
+    bus.subscribe::<Pong, _>(move |_| {
+        *p.lock().unwrap() += 1;
+        handler_bus.publish(Ping).unwrap();
+    });
+    bus.publish(Ping).unwrap();
+    assert_eq!(*pongs.lock().unwrap(), 1);
+}
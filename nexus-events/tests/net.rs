@@ -0,0 +1,116 @@
+// Network endpoints: events of the types one side sends reach handlers on
+// the peer's bus, over TCP or WebSocket, and types the peer can't decode are
+// refused at the handshake.
+
+#![cfg(feature = "net")]
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use nexus_events::core::codec::EventCodec;
+use nexus_events::net::{PeerConnected, RemoteBusEndpoint, Transport};
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct TurnCommitted {
+    turn: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DebugDump {
+    text: String,
+}
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn codec(with_dump: bool) -> EventCodec {
+    let mut codec = EventCodec::new();
+    codec.register::<TurnCommitted>("turn_committed").unwrap();
+    if with_dump {
+        codec.register::<DebugDump>("debug_dump").unwrap();
+    }
+    codec
+}
+
+fn collect<E: Event + Clone>(bus: &SharedEventBus) -> Arc<Mutex<Vec<E>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |ev: &E| s.lock().unwrap().push(ev.clone()));
+    seen
+}
+
+/// Processes `bus` until `done` holds, for up to `TIMEOUT`.
+fn process_until(bus: &SharedEventBus, done: impl Fn() -> bool) {
+    let deadline = Instant::now() + TIMEOUT;
+    while !done() && Instant::now() < deadline {
+        bus.process();
+        thread::sleep(Duration::from_millis(2));
+    }
+}
+
+fn turns_reach_the_client(transport: Transport) {
+    let (server_bus, client_bus) = (SharedEventBus::new(), SharedEventBus::new());
+    let server = RemoteBusEndpoint::builder(&server_bus, codec(false))
+        .send::<TurnCommitted>()
+        .transport(transport)
+        .listen("127.0.0.1:0")
+        .unwrap();
+    let client = RemoteBusEndpoint::builder(&client_bus, codec(false))
+        .transport(transport)
+        .connect(server.local_addr().unwrap())
+        .unwrap();
+    let turns = collect::<TurnCommitted>(&client_bus);
+    server.wait_connected(TIMEOUT).unwrap();
+    client.wait_connected(TIMEOUT).unwrap();
+
+    for turn in 1..=3 {
+        server_bus.publish(TurnCommitted { turn }).unwrap();
+    }
+    process_until(&client_bus, || turns.lock().unwrap().len() == 3);
+
+    let turns: Vec<u32> = turns.lock().unwrap().iter().map(|t| t.turn).collect();
+    assert_eq!(turns, [1, 2, 3]);
+    assert_eq!(server.sent(), 3);
+    assert_eq!(client.received(), 3);
+}
+
+#[test]
+fn events_sent_over_tcp_reach_the_peers_handlers_in_order() {
+    turns_reach_the_client(Transport::Tcp);
+}
+
+#[test]
+fn events_sent_over_websocket_reach_the_peers_handlers_in_order() {
+    turns_reach_the_client(Transport::WebSocket);
+}
+
+#[test]
+fn a_type_the_peer_cannot_decode_is_refused_and_not_sent() {
+    let (server_bus, client_bus) = (SharedEventBus::new(), SharedEventBus::new());
+    let connected = collect::<PeerConnected>(&server_bus);
+    let server = RemoteBusEndpoint::builder(&server_bus, codec(true))
+        .send::<TurnCommitted>()
+        .send::<DebugDump>()
+        .listen("127.0.0.1:0")
+        .unwrap();
+    let client = RemoteBusEndpoint::builder(&client_bus, codec(false))
+        .connect(server.local_addr().unwrap())
+        .unwrap();
+    let turns = collect::<TurnCommitted>(&client_bus);
+    process_until(&server_bus, || !connected.lock().unwrap().is_empty());
+    client.wait_connected(TIMEOUT).unwrap();
+
+    let peer = connected.lock().unwrap()[0].clone();
+    assert_eq!(peer.shared, ["turn_committed"]);
+    assert_eq!(peer.refused, ["debug_dump"]);
+
+    server_bus.publish(DebugDump { text: "state".into() }).unwrap();
+    server_bus.publish(TurnCommitted { turn: 9 }).unwrap();
+    process_until(&client_bus, || !turns.lock().unwrap().is_empty());
+    assert_eq!(*turns.lock().unwrap(), [TurnCommitted { turn: 9 }]);
+    assert_eq!(server.dropped(), 1);
+    assert_eq!(client.received(), 1);
+}
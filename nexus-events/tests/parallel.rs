@@ -0,0 +1,80 @@
+// `publish_parallel` reaches the same handlers as `publish`, and a
+// context-aware handler still runs after every handler before it and
+// before every handler after it.
+
+#![cfg(feature = "rayon")]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use nexus_events::core::{EventContext, SharedEventBus};
+
+#[derive(Clone)]
+struct GameTick {
+    stop: bool,
+}
+
+fn counting(bus: &SharedEventBus, handlers: usize, count: &Arc<AtomicUsize>) {
+    for _ in 0..handlers {
+        let count = count.clone();
+        bus.subscribe(move |_: &GameTick| {
+            count.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+}
+
+#[test]
+fn every_handler_runs_once() {
+    let bus = SharedEventBus::new();
+    let count = Arc::new(AtomicUsize::new(0));
+    counting(&bus, 1000, &count);
+
+    let report = bus.emit_parallel(GameTick { stop: false }).unwrap();
+    assert_eq!(report.handlers, 1000);
+    assert_eq!(count.load(Ordering::SeqCst), 1000);
+}
+
+#[test]
+fn context_handlers_keep_their_place() {
+    let bus = SharedEventBus::new();
+    let count = Arc::new(AtomicUsize::new(0));
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    counting(&bus, 100, &count);
+    let (c, s) = (count.clone(), seen.clone());
+    bus.subscribe_with_context(move |ev: &GameTick, ctx: &mut EventContext| {
+        s.lock().unwrap().push(c.load(Ordering::SeqCst));
+        if ev.stop {
+            ctx.stop_propagation();
+        }
+    });
+    counting(&bus, 100, &count);
+    let (c, s) = (count.clone(), seen.clone());
+    bus.subscribe_with_priority(-1, move |_: &GameTick, _: &mut EventContext| {
+        s.lock().unwrap().push(c.load(Ordering::SeqCst));
+    });
+
+    bus.publish_parallel(GameTick { stop: false }).unwrap();
+    assert_eq!(*seen.lock().unwrap(), [100, 200]);
+
+    count.store(0, Ordering::SeqCst);
+    seen.lock().unwrap().clear();
+    let report = bus.emit_parallel(GameTick { stop: true }).unwrap();
+    assert!(report.consumed);
+    assert_eq!(*seen.lock().unwrap(), [100]);
+    assert_eq!(count.load(Ordering::SeqCst), 100);
+}
+
+#[test]
+fn unsubscribed_handlers_are_left_out() {
+    let bus = SharedEventBus::new();
+    let count = Arc::new(AtomicUsize::new(0));
+    counting(&bus, 10, &count);
+    let c = count.clone();
+    let id = bus.subscribe(move |_: &GameTick| {
+        c.fetch_add(100, Ordering::SeqCst);
+    });
+    bus.unsubscribe::<GameTick>(id);
+
+    bus.publish_parallel(GameTick { stop: false }).unwrap();
+    assert_eq!(count.load(Ordering::SeqCst), 10);
+}
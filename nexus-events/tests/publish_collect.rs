@@ -0,0 +1,52 @@
+// Handlers that return a result, gathered by `publish_collect`.
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct CanPlayerMove { to: (i32, i32) }
+
+#[derive(Debug, PartialEq)]
+enum Vote { Allow, Deny(&'static str) }
+
+#[test]
+fn results_are_collected_per_result_type() {
+    let bus = SharedEventBus::new();
+    assert!(bus.publish_collect::<CanPlayerMove, Vote>(CanPlayerMove { to: (0, 0) }).unwrap().is_empty());
+
+    bus.subscribe_returning::<CanPlayerMove, Vote, _>(|ev| {
+        if ev.to.0 < 0 { Vote::Deny("off the map") } else { Vote::Allow }
+    });
+    bus.subscribe_returning::<CanPlayerMove, Vote, _>(|ev| {
+        if ev.to == (1, 1) { Vote::Deny("wall") } else { Vote::Allow }
+    });
+    // Handlers returning another type are kept apart from these.
+    bus.subscribe_returning::<CanPlayerMove, u32, _>(|ev| ev.to.0.unsigned_abs());
+
+    let votes = bus.publish_collect::<CanPlayerMove, Vote>(CanPlayerMove { to: (1, 1) }).unwrap();
+    assert_eq!(votes, [Vote::Allow, Vote::Deny("wall")]);
+    let costs = bus.publish_collect::<CanPlayerMove, u32>(CanPlayerMove { to: (-3, 0) }).unwrap();
+    assert_eq!(costs, [3]);
+}
+
+#[test]
+fn returning_and_ordinary_handlers_stay_apart() {
+    let bus = SharedEventBus::new();
+    bus.subscribe_returning::<CanPlayerMove, Vote, _>(|_| Vote::Allow);
+    let walls = bus.subscribe_returning::<CanPlayerMove, Vote, _>(|_| Vote::Deny("wall"));
+
+    // The fire-and-forget path doesn't call them, nor they it.
+    assert!(!bus.emit(CanPlayerMove { to: (1, 1) }).unwrap().delivered());
+    bus.subscribe::<CanPlayerMove, _>(|_| panic!("not a returning handler"));
+    bus.unsubscribe_returning::<CanPlayerMove, Vote>(walls);
+    let votes = bus.publish_collect::<CanPlayerMove, Vote>(CanPlayerMove { to: (1, 1) }).unwrap();
+    assert_eq!(votes, [Vote::Allow]);
+}
+
+#[test]
+fn unsubscribing_with_the_wrong_result_type_does_nothing() {
+    let bus = SharedEventBus::new();
+    bus.subscribe_returning::<CanPlayerMove, u32, _>(|ev| ev.to.0.unsigned_abs());
+    let cost = bus.subscribe_returning::<CanPlayerMove, u32, _>(|_| 1);
+    bus.unsubscribe_returning::<CanPlayerMove, Vote>(cost);
+    assert_eq!(bus.publish_collect::<CanPlayerMove, u32>(CanPlayerMove { to: (2, 0) }).unwrap(), [2, 1]);
+}
@@ -0,0 +1,30 @@
+// Borrowed publishing: publish_ref and emit_ref deliver events that are not
+// `Clone` without taking them from the caller.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+// Deliberately not `Clone`.
+struct NavGrid {
+    cells: Vec<u8>,
+}
+
+#[test]
+fn a_borrowed_event_reaches_handlers_and_stays_with_the_caller() {
+    let bus = SharedEventBus::new();
+    let seen = Arc::new(AtomicUsize::new(0));
+    let s = seen.clone();
+    bus.subscribe::<NavGrid, _>(move |grid| {
+        s.fetch_add(grid.cells.len(), Ordering::SeqCst);
+    });
+
+    let grid = NavGrid { cells: vec![0; 4096] };
+    bus.publish_ref(&grid).unwrap();
+    let report = bus.emit_ref(&grid).unwrap();
+    assert_eq!(report.handlers, 1);
+    assert_eq!(seen.load(Ordering::SeqCst), 2 * 4096);
+    // Still ours.
+    assert_eq!(grid.cells.len(), 4096);
+}
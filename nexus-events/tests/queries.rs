@@ -0,0 +1,73 @@
+// Queries: one responder per query type answers `query`, and registering
+// another replaces it.
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerHealth {
+    player: u32,
+}
+
+impl Query for PlayerHealth {
+    type Response = i32;
+}
+
+#[test]
+fn a_query_without_a_responder_fails() {
+    let bus = SharedEventBus::new();
+    let err = bus.query(PlayerHealth { player: 1 }).unwrap_err();
+    assert!(matches!(err, EventError::NoResponder(name) if name.ends_with("PlayerHealth")));
+}
+
+#[test]
+fn the_responder_answers_the_query() {
+    let bus = SharedEventBus::new();
+    bus.register_responder(|q: &PlayerHealth| q.player as i32 * 10);
+    assert_eq!(bus.query(PlayerHealth { player: 3 }).unwrap(), 30);
+}
+
+#[test]
+fn a_later_responder_replaces_the_earlier_one() {
+    let bus = SharedEventBus::new();
+    let first = bus.register_responder(|_: &PlayerHealth| 1);
+    bus.register_responder(|_: &PlayerHealth| 2);
+    assert_eq!(bus.query(PlayerHealth { player: 1 }).unwrap(), 2);
+
+    // The replaced id no longer refers to anything.
+    bus.unregister_responder::<PlayerHealth>(first);
+    assert_eq!(bus.query(PlayerHealth { player: 1 }).unwrap(), 2);
+}
+
+#[test]
+fn an_unregistered_responder_no_longer_answers() {
+    let bus = SharedEventBus::new();
+    let id = bus.register_responder(|_: &PlayerHealth| 100);
+    bus.unregister_responder::<PlayerHealth>(id);
+    assert!(matches!(bus.query(PlayerHealth { player: 1 }), Err(EventError::NoResponder(_))));
+}
+
+#[derive(Debug, Clone)]
+struct GetPotionCount;
+
+impl Query for GetPotionCount {
+    type Response = u32;
+}
+
+struct Inventory;
+
+impl Inventory {
+    const POTIONS: u32 = 5;
+
+    #[query_handler(GetPotionCount)]
+    fn potion_count(_q: &GetPotionCount) -> u32 {
+        Self::POTIONS
+    }
+}
+
+#[test]
+fn a_query_handler_in_an_impl_block_answers_through_its_register_function() {
+    Inventory::register_potion_count();
+    assert_eq!(query(GetPotionCount).unwrap(), 5);
+    // The function itself is still there to call.
+    assert_eq!(Inventory::potion_count(&GetPotionCount), 5);
+}
@@ -0,0 +1,58 @@
+// Reentrant handlers: a handler may subscribe, unsubscribe and dispatch while
+// its event is being delivered, and the changes apply from the next event.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(Debug, Clone)]
+struct Echo;
+
+#[test]
+fn changes_made_during_delivery_apply_from_the_next_event() {
+    let bus = SharedEventBus::new();
+    let late = Arc::new(AtomicUsize::new(0));
+    let doomed = Arc::new(AtomicUsize::new(0));
+    let echoes = Arc::new(AtomicUsize::new(0));
+    let doomed_id = Arc::new(Mutex::new(None::<HandlerId>));
+
+    // Runs first; subscribes a handler, unsubscribes another and dispatches,
+    // all while `Tick` is being delivered.
+    let (b, l, id) = (bus.clone(), late.clone(), doomed_id.clone());
+    bus.subscribe_with_priority::<Tick, _>(10, move |_, _| {
+        if let Some(doomed) = id.lock().unwrap().take() {
+            let l = l.clone();
+            b.subscribe::<Tick, _>(move |_| {
+                l.fetch_add(1, Ordering::SeqCst);
+            });
+            b.unsubscribe::<Tick>(doomed);
+            b.dispatch(Echo);
+        }
+    });
+    let d = doomed.clone();
+    *doomed_id.lock().unwrap() = Some(bus.subscribe::<Tick, _>(move |_| {
+        d.fetch_add(1, Ordering::SeqCst);
+    }));
+    let e = echoes.clone();
+    bus.subscribe::<Echo, _>(move |_| {
+        e.fetch_add(1, Ordering::SeqCst);
+    });
+
+    bus.dispatch(Tick);
+    bus.process();
+    // Neither the unsubscribed handler nor the new one got the tick being
+    // delivered, and the echo waits for the next `process`.
+    assert_eq!(doomed.load(Ordering::SeqCst), 0);
+    assert_eq!(late.load(Ordering::SeqCst), 0);
+    assert_eq!(echoes.load(Ordering::SeqCst), 0);
+
+    bus.dispatch(Tick);
+    bus.process();
+    assert_eq!(doomed.load(Ordering::SeqCst), 0);
+    assert_eq!(late.load(Ordering::SeqCst), 1);
+    assert_eq!(echoes.load(Ordering::SeqCst), 1);
+}
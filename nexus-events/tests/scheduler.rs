@@ -0,0 +1,129 @@
+// Scheduled publishes: events reach their handlers once the bus clock has
+// moved past their delay, on the next `process`, and cancelled ones never
+// do.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct RespawnPlayer {
+    player: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Heartbeat {
+    beat: u32,
+}
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn respawns(bus: &SharedEventBus) -> Arc<Mutex<Vec<u32>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |ev: &RespawnPlayer| s.lock().unwrap().push(ev.player));
+    seen
+}
+
+fn heartbeats(bus: &SharedEventBus) -> Arc<Mutex<Vec<u32>>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let s = seen.clone();
+    bus.subscribe(move |ev: &Heartbeat| s.lock().unwrap().push(ev.beat));
+    seen
+}
+
+#[test]
+fn a_delayed_publish_is_delivered_once_its_delay_has_passed() {
+    let bus = SharedEventBus::new();
+    let seen = respawns(&bus);
+    let handle = bus.publish_after(ms(3000), RespawnPlayer { player: 1 });
+
+    bus.tick(ms(2999));
+    bus.process();
+    assert!(seen.lock().unwrap().is_empty());
+    assert!(!handle.is_done());
+
+    bus.tick(ms(1));
+    assert!(seen.lock().unwrap().is_empty());
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [1]);
+    assert!(handle.is_done());
+
+    bus.tick(ms(5000));
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [1]);
+}
+
+#[test]
+fn events_falling_due_in_one_tick_arrive_in_due_order() {
+    let bus = SharedEventBus::new();
+    let seen = respawns(&bus);
+    bus.publish_after(ms(30), RespawnPlayer { player: 3 });
+    bus.publish_after(ms(10), RespawnPlayer { player: 1 });
+    bus.publish_after(ms(20), RespawnPlayer { player: 2 });
+
+    bus.tick(ms(50));
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn a_repeating_publish_fires_every_interval_including_missed_ones() {
+    let bus = SharedEventBus::new();
+    let seen = heartbeats(&bus);
+    let mut beat = 0;
+    bus.publish_every(ms(100), move || {
+        beat += 1;
+        Heartbeat { beat }
+    });
+
+    bus.tick(ms(100));
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [1]);
+
+    bus.tick(ms(250));
+    bus.process();
+    assert_eq!(*seen.lock().unwrap(), [1, 2, 3]);
+}
+
+#[test]
+fn a_cancelled_publish_is_never_delivered() {
+    let bus = SharedEventBus::new();
+    let (respawned, beats) = (respawns(&bus), heartbeats(&bus));
+    let respawn = bus.publish_after(ms(10), RespawnPlayer { player: 1 });
+    let heartbeat = bus.publish_every(ms(10), || Heartbeat { beat: 0 });
+
+    // Both are queued by now: cancelling only stops what comes later.
+    bus.tick(ms(10));
+    heartbeat.cancel();
+    respawn.cancel();
+    bus.process();
+    assert_eq!(*beats.lock().unwrap(), [0]);
+    assert_eq!(*respawned.lock().unwrap(), [1]);
+
+    let late = bus.publish_after(ms(10), RespawnPlayer { player: 2 });
+    late.cancel();
+    bus.tick(ms(100));
+    bus.process();
+    assert_eq!(*beats.lock().unwrap(), [0]);
+    assert_eq!(*respawned.lock().unwrap(), [1]);
+}
+
+#[test]
+fn the_scheduler_thread_moves_the_clock_without_a_game_loop() {
+    let bus = SharedEventBus::new();
+    let seen = respawns(&bus);
+    let _scheduler = bus.start_scheduler(ms(1));
+    bus.publish_after(ms(20), RespawnPlayer { player: 7 });
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while seen.lock().unwrap().is_empty() && Instant::now() < deadline {
+        bus.process();
+        thread::sleep(ms(1));
+    }
+    assert_eq!(*seen.lock().unwrap(), [7]);
+}
@@ -0,0 +1,136 @@
+// Streams: a bus subscription polled as a futures `Stream`, woken when an
+// event arrives, with the overflow policy deciding what a slow consumer
+// loses, and ended by the bus shutting down.
+#![cfg(feature = "futures")]
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+use futures_core::Stream;
+use nexus_events::bridge::Backpressure;
+use nexus_events::core::EventStream;
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+struct ScoreChanged {
+    score: u32,
+}
+
+/// Wakes the thread that polled, and counts how often it was woken.
+struct Unpark {
+    thread: Thread,
+    wakes: AtomicUsize,
+}
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.wakes.fetch_add(1, Ordering::SeqCst);
+        self.thread.unpark();
+    }
+}
+
+fn unpark() -> Arc<Unpark> {
+    Arc::new(Unpark { thread: thread::current(), wakes: AtomicUsize::new(0) })
+}
+
+fn poll(stream: &mut EventStream<ScoreChanged>, waker: &Arc<Unpark>) -> Poll<Option<ScoreChanged>> {
+    let waker = Waker::from(waker.clone());
+    Pin::new(stream).poll_next(&mut Context::from_waker(&waker))
+}
+
+/// Waits for the stream's next item, for up to five seconds.
+fn next(stream: &mut EventStream<ScoreChanged>) -> Option<ScoreChanged> {
+    let waker = unpark();
+    for _ in 0..5000 {
+        if let Poll::Ready(item) = poll(stream, &waker) {
+            return item;
+        }
+        thread::park_timeout(Duration::from_millis(1));
+    }
+    panic!("the stream never produced an item");
+}
+
+fn scores(stream: &mut EventStream<ScoreChanged>, waker: &Arc<Unpark>) -> Vec<u32> {
+    let mut seen = Vec::new();
+    while let Poll::Ready(Some(ev)) = poll(stream, waker) {
+        seen.push(ev.score);
+    }
+    seen
+}
+
+#[test]
+fn a_stream_yields_events_in_order_and_is_woken_when_one_arrives() {
+    let bus = SharedEventBus::new();
+    let mut stream = bus.stream::<ScoreChanged>();
+    let waker = unpark();
+
+    assert_eq!(poll(&mut stream, &waker), Poll::Pending);
+    bus.publish(ScoreChanged { score: 1 }).unwrap();
+    assert_eq!(waker.wakes.load(Ordering::SeqCst), 1);
+    bus.dispatch(ScoreChanged { score: 2 });
+    bus.process();
+    assert_eq!(stream.pending(), 2);
+
+    assert_eq!(scores(&mut stream, &waker), [1, 2]);
+    assert_eq!(stream.pending(), 0);
+}
+
+#[test]
+fn a_full_stream_drops_what_its_policy_says() {
+    let bus = SharedEventBus::new();
+    let mut oldest = bus.stream_with::<ScoreChanged>(2, Backpressure::DropOldest);
+    let mut newest = bus.stream_with::<ScoreChanged>(2, Backpressure::DropNewest);
+    for score in 1..=5 {
+        bus.publish(ScoreChanged { score }).unwrap();
+    }
+
+    let waker = unpark();
+    assert_eq!(scores(&mut oldest, &waker), [4, 5]);
+    assert_eq!(oldest.dropped(), 3);
+    assert_eq!(scores(&mut newest, &waker), [1, 2]);
+    assert_eq!(newest.dropped(), 3);
+}
+
+#[test]
+fn a_blocking_stream_holds_up_the_publisher_until_it_is_polled() {
+    let bus = SharedEventBus::new();
+    let mut stream = bus.stream_with::<ScoreChanged>(1, Backpressure::Block);
+    let publisher = {
+        let bus = bus.clone();
+        thread::spawn(move || {
+            for score in 1..=20 {
+                bus.publish(ScoreChanged { score }).unwrap();
+            }
+        })
+    };
+
+    let received: Vec<u32> = (0..20).map(|_| next(&mut stream).unwrap().score).collect();
+    publisher.join().unwrap();
+    assert_eq!(received, (1..=20).collect::<Vec<_>>());
+    assert_eq!(stream.dropped(), 0);
+}
+
+#[test]
+fn a_stream_ends_after_its_queued_events_when_the_bus_shuts_down() {
+    let bus = SharedEventBus::new();
+    let mut stream = bus.stream::<ScoreChanged>();
+    bus.publish(ScoreChanged { score: 9 }).unwrap();
+    bus.shutdown();
+
+    assert_eq!(next(&mut stream), Some(ScoreChanged { score: 9 }));
+    assert_eq!(next(&mut stream), None);
+}
+
+#[test]
+fn dropping_a_stream_removes_its_subscription() {
+    let bus = SharedEventBus::new();
+    let stream = bus.stream::<ScoreChanged>();
+    assert_eq!(bus.emit(ScoreChanged { score: 1 }).unwrap().handlers, 1);
+
+    drop(stream);
+    assert_eq!(bus.emit(ScoreChanged { score: 2 }).unwrap().handlers, 0);
+}
@@ -0,0 +1,65 @@
+// Leased handlers: subscribe_for handlers drop off the bus once their lease
+// runs out.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct GameTick;
+
+fn counter(bus: &SharedEventBus, lease: Duration) -> (Subscription, Arc<AtomicU32>) {
+    let count = Arc::new(AtomicU32::new(0));
+    let c = count.clone();
+    let sub = bus.subscribe_for(lease, move |_: &GameTick| {
+        c.fetch_add(1, Ordering::SeqCst);
+    });
+    (sub, count)
+}
+
+fn count(n: &AtomicU32) -> u32 {
+    n.load(Ordering::SeqCst)
+}
+
+#[test]
+fn an_expired_lease_is_pruned_before_the_next_delivery() {
+    let bus = SharedEventBus::new();
+    let (_haste, hasted) = counter(&bus, Duration::from_secs(600));
+    let (_, stunned) = counter(&bus, Duration::ZERO);
+    bus.publish(GameTick).unwrap();
+    assert_eq!((count(&hasted), count(&stunned)), (1, 0));
+}
+
+#[test]
+fn moving_the_expiry_into_the_past_ends_the_lease_on_process() {
+    let bus = SharedEventBus::new();
+    let (haste, hasted) = counter(&bus, Duration::from_secs(600));
+    let (_shield, shielded) = counter(&bus, Duration::from_secs(600));
+    haste.expire_at(Instant::now());
+    bus.dispatch(GameTick);
+    bus.process();
+    assert_eq!(count(&hasted), 0);
+    assert_eq!(haste.expires_at(), None);
+    assert_eq!(count(&shielded), 1);
+}
+
+#[test]
+fn expired_leases_can_be_pruned_without_publishing() {
+    let bus = SharedEventBus::new();
+    let (shield, _) = counter(&bus, Duration::from_secs(600));
+    shield.expire_at(Instant::now());
+    assert_eq!(bus.prune_expired(), 1);
+    assert_eq!(bus.prune_expired(), 0);
+    assert_eq!(bus.handler_counts().get(std::any::type_name::<GameTick>()), None);
+}
+
+#[test]
+fn a_leased_subscription_can_be_cancelled_early() {
+    let bus = SharedEventBus::new();
+    let (regen, _) = counter(&bus, Duration::from_secs(600));
+    assert!(regen.expires_at().is_some());
+    regen.cancel();
+    assert!(bus.handler_counts().is_empty());
+}
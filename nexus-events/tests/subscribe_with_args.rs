@@ -0,0 +1,45 @@
+// Stateful handlers: subscribe_with_args! and subscribe_with_state keep
+// state from one event to the next.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Hit {
+    damage: u32,
+}
+
+#[test]
+fn subscribe_with_args_carries_its_state_between_events() {
+    let bus = SharedEventBus::new();
+    let seen = Arc::new(AtomicU32::new(0));
+
+    let s = seen.clone();
+    subscribe_with_args!(bus, Hit, [hits: u32 = 0, total: u32 = 0], |ev| {
+        *hits += 1;
+        *total += ev.damage;
+        s.store(*hits * 1000 + *total, Ordering::SeqCst);
+    });
+
+    bus.dispatch(Hit { damage: 5 });
+    bus.dispatch(Hit { damage: 7 });
+    bus.process();
+    assert_eq!(seen.load(Ordering::SeqCst), 2012);
+}
+
+#[test]
+fn subscribe_with_state_carries_its_state_between_events() {
+    let bus = SharedEventBus::new();
+    let largest = Arc::new(AtomicU32::new(0));
+    let l = largest.clone();
+    bus.subscribe_with_state(0u32, move |ev: &Hit, max: &mut u32| {
+        *max = (*max).max(ev.damage);
+        l.store(*max, Ordering::SeqCst);
+    });
+    bus.dispatch(Hit { damage: 9 });
+    bus.dispatch(Hit { damage: 3 });
+    bus.process();
+    assert_eq!(largest.load(Ordering::SeqCst), 9);
+}
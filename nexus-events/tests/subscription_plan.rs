@@ -0,0 +1,97 @@
+// Subscription plans: exporting which components own which subscriptions
+// and rebinding them onto a rebuilt scene.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+use nexus_events::subscriber::{ComponentResolver, Resubscribe, SubscriptionTemplate};
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(Debug, Clone)]
+struct Hit;
+
+#[derive(EventSubscriber)]
+#[event_subscriber(lifecycle, register = "subscribe_handlers")]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+    ticks: Arc<AtomicUsize>,
+}
+
+impl Enemy {
+    fn new(id: u32, bus: &SharedEventBus) -> Self {
+        Enemy { id, active: false, subscriptions: SubscriptionSet::new(bus), ticks: Arc::default() }
+    }
+
+    fn subscribe_handlers(&mut self) {
+        let ticks = self.ticks.clone();
+        self.subscriptions.subscribe::<Tick, _>(move |_| {
+            ticks.fetch_add(1, Ordering::SeqCst);
+        });
+        self.subscriptions.subscribe::<Hit, _>(|_| {});
+    }
+}
+
+struct Scene {
+    enemies: Vec<Enemy>,
+}
+
+impl ComponentResolver for Scene {
+    fn resolve(&mut self, template: &SubscriptionTemplate) -> Option<&mut dyn Resubscribe> {
+        if template.component != std::any::type_name::<Enemy>() {
+            return None;
+        }
+        let enemy = self.enemies.iter_mut().find(|e| format!("{:?}", e.id) == template.id)?;
+        Some(enemy)
+    }
+}
+
+fn activated(ids: &[u32], bus: &SharedEventBus) -> Scene {
+    let mut scene = Scene { enemies: ids.iter().map(|&id| Enemy::new(id, bus)).collect() };
+    for enemy in &mut scene.enemies {
+        enemy.activate(bus).unwrap();
+        enemy.record_owner();
+    }
+    scene
+}
+
+#[test]
+fn the_plan_lists_component_owned_subscriptions_only() {
+    let bus = SharedEventBus::new();
+    let scene = activated(&[1, 2], &bus);
+    bus.subscribe::<Tick, _>(|_| {});
+
+    let plan = bus.export_subscription_plan();
+    assert_eq!(plan.components.len(), 2);
+    assert_eq!(plan.components[0].id, "1");
+    assert_eq!(plan.components[1].id, "2");
+    assert!(plan.components[0].component.ends_with("Enemy"));
+    assert_eq!(plan.components[0].events.len(), 2);
+
+    drop(scene);
+    assert!(bus.export_subscription_plan().components.is_empty());
+}
+
+#[test]
+fn rebinding_resubscribes_present_components_and_reports_missing_ones() {
+    let bus = SharedEventBus::new();
+    let scene = activated(&[1, 2], &bus);
+    let plan = bus.export_subscription_plan();
+    drop(scene);
+    let mut scene = Scene { enemies: vec![Enemy::new(2, &bus), Enemy::new(3, &bus)] };
+    let missing = bus.rebind(&plan, &mut scene);
+    assert_eq!(missing.len(), 1);
+    assert_eq!(missing[0].id, "1");
+
+    bus.dispatch(Tick);
+    bus.process();
+    assert_eq!(scene.enemies[0].ticks.load(Ordering::SeqCst), 1);
+    assert_eq!(scene.enemies[1].ticks.load(Ordering::SeqCst), 0);
+
+    let next = bus.export_subscription_plan();
+    assert_eq!(next.components, vec![plan.components[1].clone()]);
+}
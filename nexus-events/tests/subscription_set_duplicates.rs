@@ -0,0 +1,101 @@
+// Duplicate handlers: `try_subscribe` refuses a closure the set already has on
+// the bus, while `subscribe` takes whatever it is given.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(EventSubscriber)]
+struct Enemy {
+    id: u32,
+    active: bool,
+    subscriptions: SubscriptionSet,
+    ticks: Arc<AtomicUsize>,
+}
+
+impl Enemy {
+    // Called on every re-activation, whether or not the handlers are
+    // still there.
+    fn subscribe_handlers(&mut self) -> EventResult<()> {
+        let ticks = self.ticks.clone();
+        match self.subscriptions.try_subscribe::<Tick, _>(move |_| {
+            ticks.fetch_add(1, Ordering::SeqCst);
+        }) {
+            Ok(_) | Err(EventError::DuplicateHandler { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn counting(set: &mut SubscriptionSet, ticks: &Arc<AtomicUsize>) -> EventResult<HandlerId> {
+    let ticks = ticks.clone();
+    set.try_subscribe::<Tick, _>(move |_| {
+        ticks.fetch_add(1, Ordering::SeqCst);
+    })
+}
+
+#[test]
+fn subscribing_the_same_handler_twice_delivers_once() {
+    let bus = SharedEventBus::new();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut enemy = Enemy { id: 1, active: true, subscriptions: SubscriptionSet::new(&bus), ticks: ticks.clone() };
+
+    enemy.subscribe_handlers().unwrap();
+    enemy.subscribe_handlers().unwrap();
+    assert_eq!(enemy.id, 1);
+    assert!(enemy.active);
+    assert_eq!(enemy.subscriptions().len(), 1);
+    bus.publish(Tick).unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn the_error_names_the_handler_already_subscribed() {
+    let bus = SharedEventBus::new();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut set = SubscriptionSet::new(&bus);
+    let first = counting(&mut set, &ticks).unwrap();
+    match counting(&mut set, &ticks) {
+        Err(EventError::DuplicateHandler { event, handler, id }) => {
+            assert!(event.ends_with("Tick"));
+            assert!(handler.contains("counting"));
+            assert_eq!(id, first);
+        }
+        other => panic!("expected a duplicate, got {:?}", other),
+    }
+}
+
+#[test]
+fn a_handler_gone_from_the_bus_may_be_subscribed_again() {
+    let bus = SharedEventBus::new();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut set = SubscriptionSet::new(&bus);
+    let first = counting(&mut set, &ticks).unwrap();
+    bus.unsubscribe::<Tick>(first);
+    assert!(counting(&mut set, &ticks).is_ok());
+
+    bus.publish(Tick).unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn plain_subscribe_does_not_check() {
+    let bus = SharedEventBus::new();
+    let ticks = Arc::new(AtomicUsize::new(0));
+    let mut set = SubscriptionSet::new(&bus);
+    // Handlers made in a loop are one closure type.
+    for _ in 0..3 {
+        let ticks = ticks.clone();
+        set.subscribe::<Tick, _>(move |_| {
+            ticks.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    assert_eq!(set.len(), 3);
+
+    bus.publish(Tick).unwrap();
+    assert_eq!(ticks.load(Ordering::SeqCst), 3);
+}
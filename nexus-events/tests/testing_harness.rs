@@ -0,0 +1,84 @@
+// The testing harness: TestBus records what is published, including through
+// the global functions, with a spy and a manual clock alongside.
+
+use std::time::Duration;
+
+use nexus_events::prelude::*;
+use nexus_events::testing::TestBus;
+use nexus_events::{assert_not_published, assert_published};
+
+#[derive(Debug, Clone, PartialEq)]
+struct PlayerMoved {
+    x: f32,
+    y: f32,
+}
+
+#[derive(Debug, Clone)]
+struct Respawned(u32);
+
+struct Player {
+    x: f32,
+    y: f32,
+}
+
+impl Player {
+    fn move_to(&mut self, x: f32, y: f32) {
+        (self.x, self.y) = (x, y);
+        publish(PlayerMoved { x, y }).unwrap();
+    }
+}
+
+#[test]
+fn the_global_functions_publish_to_the_test_bus_inside_run() {
+    let bus = TestBus::new();
+    let mut player = Player { x: 0.0, y: 0.0 };
+    bus.run(|| player.move_to(10.0, 2.0));
+    assert_published!(bus, PlayerMoved { x: 10.0, .. });
+    assert_published!(bus, PlayerMoved { y, .. } if *y > 1.0);
+    assert_not_published!(bus, PlayerMoved { x: 3.0, .. });
+    assert_not_published!(bus, Respawned);
+}
+
+#[test]
+fn queued_events_count_before_and_after_processing() {
+    let bus = TestBus::new();
+    bus.publish(PlayerMoved { x: 10.0, y: 2.0 }).unwrap();
+    bus.dispatch(PlayerMoved { x: 3.0, y: 0.0 });
+    assert_eq!(bus.count::<PlayerMoved>(), 2);
+    bus.process();
+    assert_eq!(
+        bus.published::<PlayerMoved>(),
+        [PlayerMoved { x: 10.0, y: 2.0 }, PlayerMoved { x: 3.0, y: 0.0 }]
+    );
+
+    bus.clear();
+    assert_eq!(bus.count::<PlayerMoved>(), 0);
+}
+
+#[test]
+#[should_panic]
+fn assert_published_panics_when_nothing_matches() {
+    let bus = TestBus::new();
+    bus.publish(PlayerMoved { x: 10.0, y: 2.0 }).unwrap();
+    assert_published!(bus, PlayerMoved { x: 5.0, .. });
+}
+
+#[test]
+fn the_spy_sees_events_the_clock_releases() {
+    let bus = TestBus::new();
+    let spy = bus.spy::<Respawned>();
+    let mut clock = bus.clock();
+    bus.publish_after(Duration::from_secs(3), Respawned(1));
+    clock.advance(Duration::from_secs(2));
+    assert!(!spy.was_called());
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(spy.calls(), 1);
+    assert_eq!(spy.last().map(|r| r.0), Some(1));
+    assert_published!(bus, Respawned(1));
+    assert_eq!(clock.elapsed(), Duration::from_secs(3));
+
+    drop(spy);
+    bus.publish(Respawned(2)).unwrap();
+    assert_eq!(bus.count::<Respawned>(), 2);
+    assert!(bus.handler_counts().is_empty());
+}
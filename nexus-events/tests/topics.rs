@@ -0,0 +1,90 @@
+// Topics: string-addressed events reach the handlers whose pattern matches
+// the topic, queued in order with typed events.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use nexus_events::prelude::*;
+use nexus_events::topics::{TopicEvent, Value};
+
+#[derive(Debug, Clone)]
+struct GameTick;
+
+type Log = Arc<Mutex<Vec<String>>>;
+
+fn listen(bus: &SharedEventBus, pattern: &'static str, log: &Log) -> HandlerId {
+    let log = log.clone();
+    bus.subscribe_topic(pattern, move |ev: &TopicEvent| {
+        log.lock().unwrap().push(format!("{} {}", pattern, ev.topic));
+    })
+}
+
+#[test]
+fn a_topic_reaches_the_handlers_whose_pattern_matches() {
+    let bus = SharedEventBus::new();
+    let log = Log::default();
+    listen(&bus, "combat.hit", &log);
+    listen(&bus, "combat.*", &log);
+    listen(&bus, "ui/**", &log);
+    listen(&bus, "ui/*", &log);
+
+    bus.publish_topic("combat.hit", 12);
+    bus.publish_topic("combat.miss", Value::Null);
+    bus.publish_topic("ui/button/click", "start");
+    bus.publish_topic("inventory.full", true);
+    bus.process();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        ["combat.hit combat.hit", "combat.* combat.hit", "combat.* combat.miss", "ui/** ui/button/click"]
+    );
+}
+
+#[test]
+fn handlers_get_the_payload() {
+    let bus = SharedEventBus::new();
+    let damage = Arc::new(Mutex::new(Vec::new()));
+    let d = damage.clone();
+    bus.subscribe_topic("combat.hit", move |ev: &TopicEvent| {
+        let target = ev.payload.get("target").and_then(Value::as_str).unwrap().to_string();
+        let amount = ev.payload.get("amount").and_then(Value::as_int).unwrap();
+        d.lock().unwrap().push((target, amount));
+    });
+
+    let mut payload = BTreeMap::new();
+    payload.insert("target".to_string(), Value::from("orc"));
+    payload.insert("amount".to_string(), Value::from(7));
+    bus.publish_topic("combat.hit", payload);
+    bus.process();
+
+    assert_eq!(*damage.lock().unwrap(), [("orc".to_string(), 7)]);
+}
+
+#[test]
+fn topics_are_delivered_in_order_with_typed_events() {
+    let bus = SharedEventBus::new();
+    let log = Log::default();
+    listen(&bus, "**", &log);
+    let l = log.clone();
+    bus.subscribe(move |_: &GameTick| l.lock().unwrap().push("tick".to_string()));
+
+    bus.publish_topic("a", Value::Null);
+    bus.dispatch(GameTick);
+    bus.publish_topic("b", Value::Null);
+    assert!(log.lock().unwrap().is_empty());
+    bus.process();
+
+    assert_eq!(*log.lock().unwrap(), ["** a", "tick", "** b"]);
+}
+
+#[test]
+fn an_unsubscribed_topic_handler_hears_nothing() {
+    let bus = SharedEventBus::new();
+    let log = Log::default();
+    let id = listen(&bus, "combat.*", &log);
+    bus.unsubscribe_topic(id);
+
+    bus.publish_topic("combat.hit", 1);
+    bus.process();
+    assert!(log.lock().unwrap().is_empty());
+}
@@ -0,0 +1,143 @@
+// Tracing: a collector attached to a bus records every publish, with who
+// published it, and every handler call, and renders them for chrome://tracing
+// and Graphviz.
+
+use nexus_events::core::{Emitter, EventTraceCollector, TraceRecord};
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct PlayerHit {
+    damage: u32,
+}
+
+#[derive(Debug, Clone)]
+struct PlayerDied;
+
+fn published(records: &[TraceRecord]) -> Vec<(&'static str, Emitter)> {
+    records
+        .iter()
+        .filter_map(|record| match record {
+            TraceRecord::Published { event, emitter, .. } => Some((*event, *emitter)),
+            TraceRecord::Handled { .. } => None,
+        })
+        .collect()
+}
+
+fn handled(records: &[TraceRecord]) -> Vec<(&'static str, HandlerId)> {
+    records
+        .iter()
+        .filter_map(|record| match record {
+            TraceRecord::Handled { event, handler, .. } => Some((*event, *handler)),
+            TraceRecord::Published { .. } => None,
+        })
+        .collect()
+}
+
+/// A bus where a big enough hit kills the player, traced by the collector
+/// it returns with the ids of the two handlers.
+fn traced_bus() -> (SharedEventBus, EventTraceCollector, HandlerId, HandlerId) {
+    let bus = SharedEventBus::new();
+    let b = bus.clone();
+    let on_hit = bus.subscribe(move |ev: &PlayerHit| {
+        if ev.damage >= 100 {
+            b.publish(PlayerDied).unwrap();
+        }
+    });
+    let on_died = bus.subscribe(|_: &PlayerDied| {});
+    let collector = EventTraceCollector::new();
+    bus.set_trace_collector(Some(collector.clone()));
+    (bus, collector, on_hit, on_died)
+}
+
+const HIT: &str = "tracer::PlayerHit";
+const DIED: &str = "tracer::PlayerDied";
+
+#[test]
+fn publishes_are_recorded_with_who_published_them() {
+    let (bus, collector, on_hit, _) = traced_bus();
+
+    bus.publish(PlayerHit { damage: 1 }).unwrap();
+    bus.dispatch_from(SenderId(7), PlayerHit { damage: 100 });
+    bus.process();
+
+    let trace = collector.flush();
+    assert_eq!(
+        published(&trace.records),
+        [
+            (HIT, Emitter::Outside),
+            (HIT, Emitter::Sender(SenderId(7))),
+            (DIED, Emitter::Handler(on_hit)),
+        ]
+    );
+    assert_eq!(trace.dropped, 0);
+}
+
+#[test]
+fn handler_calls_are_recorded_after_what_they_published() {
+    let (bus, collector, on_hit, on_died) = traced_bus();
+
+    bus.publish(PlayerHit { damage: 100 }).unwrap();
+
+    let trace = collector.flush();
+    // The death is handled while the hit handler is still running.
+    assert_eq!(handled(&trace.records), [(DIED, on_died), (HIT, on_hit)]);
+    for record in &trace.records {
+        if let TraceRecord::Handled { thread, panicked, .. } = record {
+            assert!(!panicked);
+            assert!(*thread < trace.threads.len());
+        }
+    }
+}
+
+#[test]
+fn flushing_empties_the_collector_and_detaching_stops_recording() {
+    let (bus, collector, _, _) = traced_bus();
+
+    bus.publish(PlayerHit { damage: 1 }).unwrap();
+    assert!(!collector.is_empty());
+    assert_eq!(collector.flush().records.len(), 2);
+    assert!(collector.is_empty());
+
+    bus.set_trace_collector(None);
+    assert!(bus.trace_collector().is_none());
+    bus.publish(PlayerHit { damage: 1 }).unwrap();
+    assert!(collector.is_empty());
+}
+
+#[test]
+fn a_full_collector_drops_its_oldest_records() {
+    let bus = SharedEventBus::new();
+    let collector = EventTraceCollector::with_capacity(2);
+    bus.set_trace_collector(Some(collector.clone()));
+
+    for damage in 0..5 {
+        bus.publish(PlayerHit { damage }).unwrap();
+    }
+    assert_eq!(collector.len(), 2);
+    let trace = collector.flush();
+    assert_eq!(trace.records.len(), 2);
+    assert_eq!(trace.dropped, 3);
+}
+
+#[test]
+fn a_trace_renders_for_chrome_and_graphviz() {
+    let (bus, collector, on_hit, on_died) = traced_bus();
+
+    bus.publish(PlayerHit { damage: 100 }).unwrap();
+    bus.publish(PlayerHit { damage: 100 }).unwrap();
+    let trace = collector.flush();
+
+    let json = trace.to_chrome_json();
+    assert!(json.starts_with("{\"traceEvents\":["));
+    assert_eq!(json.matches("\"cat\":\"publish\"").count(), 4);
+    assert_eq!(json.matches("\"cat\":\"handler\"").count(), 4);
+    assert!(json.contains(&format!("\"from\":\"handler {}\"", on_hit)));
+    assert!(json.ends_with("\"otherData\":{\"dropped\":0}}"));
+
+    let dot = trace.to_dot();
+    assert!(dot.starts_with("digraph events {"));
+    assert!(dot.contains(&format!("\"outside\" -> \"event {}\" [label=\"2\"];", HIT)));
+    assert!(dot.contains(&format!("\"event {}\" -> \"handler {}\" [label=\"2\"];", HIT, on_hit)));
+    assert!(dot.contains(&format!("\"handler {}\" -> \"event {}\" [label=\"2\"];", on_hit, DIED)));
+    assert!(dot.contains(&format!("\"event {}\" -> \"handler {}\" [label=\"2\"];", DIED, on_died)));
+}
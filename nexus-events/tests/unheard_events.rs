@@ -0,0 +1,61 @@
+// Unheard events: publishing a type nobody listens to skips delivery, but
+// aliases, command queues, metrics and parent buses still see it.
+
+use nexus_events::prelude::*;
+
+#[derive(Debug, Clone)]
+struct Tick;
+
+#[derive(Debug, Clone)]
+struct OldTick;
+
+#[derive(Debug, Clone)]
+struct Move;
+
+#[derive(Debug, Clone)]
+struct Nobody;
+
+#[test]
+fn a_type_is_heard_only_while_it_has_handlers() {
+    let bus = SharedEventBus::new();
+    assert!(!bus.emit(Tick).unwrap().delivered());
+
+    let first = bus.subscribe::<Tick, _>(|_| {});
+    assert_eq!(bus.emit(Tick).unwrap().handlers, 1);
+    let second = bus.subscribe::<Tick, _>(|_| {});
+    bus.unsubscribe::<Tick>(first);
+    assert_eq!(bus.emit(Tick).unwrap().handlers, 1);
+    bus.unsubscribe::<Tick>(second);
+    assert!(!bus.emit(Tick).unwrap().delivered());
+
+    bus.subscribe::<Tick, _>(|_| {});
+    assert_eq!(bus.emit_ref(&Tick).unwrap().handlers, 1);
+}
+
+#[test]
+fn aliases_and_command_queues_receive_types_without_handlers() {
+    let bus = SharedEventBus::new();
+    bus.subscribe::<Tick, _>(|_| {});
+    bus.alias::<OldTick, Tick, _>(|_| Tick).unwrap();
+    assert_eq!(bus.emit(OldTick).unwrap().handlers, 1);
+
+    let moves = bus.command_queue::<Move>().unwrap();
+    bus.publish(Move).unwrap();
+    assert_eq!(moves.len(), 1);
+}
+
+#[test]
+fn metrics_count_unheard_publishes() {
+    let bus = SharedEventBus::new();
+    bus.set_metrics_enabled(true);
+    bus.publish(Nobody).unwrap();
+    assert_eq!(bus.metrics().total_published(), 1);
+}
+
+#[test]
+fn a_childs_unheard_events_bubble_up_to_its_parent() {
+    let bus = SharedEventBus::new();
+    bus.subscribe::<Tick, _>(|_| {});
+    let child = bus.create_child();
+    assert_eq!(child.emit(Tick).unwrap().handlers, 1);
+}